@@ -0,0 +1,238 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! Runtime capability detection.
+//!
+//! # Overview
+//!
+//! The PA version feature flags (`pa_v6`, `pa_v8`, etc) select the minimum version of the PA client
+//! system library that a program supports at build time, with functionality introduced in newer
+//! versions only being available when the matching flag is enabled. This module instead allows a
+//! program to discover at runtime which functionality is actually available, combining the version
+//! of the client library in use ([`version::get_library_version_numbers()`]), the presence of the
+//! relevant library functions, and where relevant, the protocol version of the connected server
+//! ([`Context::get_server_protocol_version()`]).
+//!
+//! This allows a single binary, built against an older minimum version, to make use of newer
+//! functionality when present and degrade gracefully otherwise, together with the entry points
+//! that are resolved at runtime, available regardless of the feature flags enabled:
+//!
+//! * [`Introspector::try_send_message_to_object()`](crate::context::introspect::Introspector::try_send_message_to_object).
+//! * [`util::try_make_thread_realtime()`](crate::util::try_make_thread_realtime).
+//! * [`Encoding::try_from_string()`](crate::format::Encoding::try_from_string).
+//! * The `try_get_*()` methods of [`format::Info`](crate::format::Info).
+//!
+//! The port availability group and type introspection information is likewise filled in at runtime
+//! when the client library in use provides it.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use libpulse_binding::capability::{Capabilities, Feature};
+//!
+//! let caps = Capabilities::for_context(&context).unwrap();
+//! if caps.supports(Feature::Messaging) {
+//!     // Make use of the messaging API
+//! }
+//! ```
+//!
+//! [`Context::get_server_protocol_version()`]: crate::context::Context::get_server_protocol_version
+
+use std::fmt;
+use capi;
+use crate::context::Context;
+use crate::version;
+
+/// Functionality that is not available with all supported PA versions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Feature {
+    /// Direction validation and conversion to string (PA v6).
+    Direction,
+    /// Stream writing with a free callback taking user data (PA v6).
+    StreamWriteExtFree,
+    /// LFE balance handling of channel maps and volumes (PA v8).
+    LfeBalance,
+    /// Conversion of encoding strings back to encodings (PA v12).
+    EncodingFromString,
+    /// Sample format, rate, channel count and channel map format info accessors (PA v13).
+    FormatInfoAccessors,
+    /// Making the current thread realtime (PA v13).
+    ThreadRealtime,
+    /// Port availability group and type introspection information (PA v14).
+    PortAvailabilityGroup,
+    /// The messaging API (PA v15).
+    Messaging,
+}
+
+impl Feature {
+    /// All features, in order of introduction.
+    pub const ALL: &'static [Feature] = &[
+        Feature::Direction,
+        Feature::StreamWriteExtFree,
+        Feature::LfeBalance,
+        Feature::EncodingFromString,
+        Feature::FormatInfoAccessors,
+        Feature::ThreadRealtime,
+        Feature::PortAvailabilityGroup,
+        Feature::Messaging,
+    ];
+
+    /// Gets the PA client library version (major, minor) that introduced this feature.
+    pub fn min_library_version(self) -> (u8, u8) {
+        match self {
+            Feature::Direction => (6, 0),
+            Feature::StreamWriteExtFree => (6, 0),
+            Feature::LfeBalance => (8, 0),
+            Feature::EncodingFromString => (12, 0),
+            Feature::FormatInfoAccessors => (13, 0),
+            Feature::ThreadRealtime => (13, 0),
+            Feature::PortAvailabilityGroup => (14, 0),
+            Feature::Messaging => (15, 0),
+        }
+    }
+
+    /// Gets the server protocol version required for this feature, if it depends upon the server.
+    pub fn min_protocol_version(self) -> Option<u32> {
+        match self {
+            Feature::PortAvailabilityGroup => Some(34),
+            Feature::Messaging => Some(35),
+            _ => None,
+        }
+    }
+
+    /// Gets the name of a client library function that must be present for this feature, if any.
+    ///
+    /// Features consisting only of additions to data structures have no such function.
+    pub fn symbol(self) -> Option<&'static str> {
+        match self {
+            Feature::Direction => Some("pa_direction_valid"),
+            Feature::StreamWriteExtFree => Some("pa_stream_write_ext_free"),
+            Feature::LfeBalance => Some("pa_channel_map_can_lfe_balance"),
+            Feature::EncodingFromString => Some("pa_encoding_from_string"),
+            Feature::FormatInfoAccessors => Some("pa_format_info_get_sample_format"),
+            Feature::ThreadRealtime => Some("pa_thread_make_realtime"),
+            Feature::PortAvailabilityGroup => None,
+            Feature::Messaging => Some("pa_context_send_message_to_object"),
+        }
+    }
+
+    /// Checks whether the API for this feature was compiled in, per the PA version feature flags.
+    ///
+    /// If not, the feature may still be usable through entry points resolved at runtime, where
+    /// provided.
+    pub fn is_compiled(self) -> bool {
+        match self {
+            Feature::Direction | Feature::StreamWriteExtFree => cfg!(feature = "pa_v6"),
+            Feature::LfeBalance => cfg!(feature = "pa_v8"),
+            Feature::EncodingFromString => cfg!(feature = "pa_v12"),
+            Feature::FormatInfoAccessors | Feature::ThreadRealtime => cfg!(feature = "pa_v13"),
+            Feature::PortAvailabilityGroup => cfg!(feature = "pa_v14"),
+            Feature::Messaging => cfg!(feature = "pa_v15"),
+        }
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (major, minor) = self.min_library_version();
+        write!(f, "{:?} (PA v{}.{})", self, major, minor)
+    }
+}
+
+/// The capabilities of the client library in use, and optionally of a connected server.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    library_version: (u8, u8, u8),
+    server_protocol_version: Option<u32>,
+}
+
+impl Capabilities {
+    /// Detects the capabilities of the client library in use.
+    ///
+    /// Features depending upon the server are reported as unsupported, since there is no server
+    /// to check; use [`for_context()`](Self::for_context) for those.
+    pub fn detect() -> Result<Self, version::Error> {
+        Ok(Self {
+            library_version: version::get_library_version_numbers()?,
+            server_protocol_version: None,
+        })
+    }
+
+    /// Detects the capabilities of the client library in use, and of the server the given context
+    /// is connected to, if any.
+    pub fn for_context(context: &Context) -> Result<Self, version::Error> {
+        Ok(Self {
+            library_version: version::get_library_version_numbers()?,
+            server_protocol_version: context.get_server_protocol_version(),
+        })
+    }
+
+    /// Gets the version (major, minor, micro) of the client library in use.
+    #[inline]
+    pub fn library_version(&self) -> (u8, u8, u8) {
+        self.library_version
+    }
+
+    /// Gets the protocol version of the connected server, if known.
+    #[inline]
+    pub fn server_protocol_version(&self) -> Option<u32> {
+        self.server_protocol_version
+    }
+
+    /// Checks whether the given feature is supported.
+    ///
+    /// This requires that the client library be new enough, that any function required for it can
+    /// be found, and where relevant, that the connected server speaks a new enough protocol.
+    pub fn supports(&self, feature: Feature) -> bool {
+        self.supports_version(feature)
+            && feature.symbol().map_or(true, |name| capi::find_symbol(name).is_some())
+    }
+
+    /// Gets the list of supported features.
+    pub fn features(&self) -> Vec<Feature> {
+        Feature::ALL.iter().copied().filter(|f| self.supports(*f)).collect()
+    }
+
+    /// Checks the library and server version requirements of a feature.
+    fn supports_version(&self, feature: Feature) -> bool {
+        let (major, minor, _) = self.library_version;
+        if (major, minor) < feature.min_library_version() {
+            return false;
+        }
+        match feature.min_protocol_version() {
+            None => true,
+            Some(min) => self.server_protocol_version.map_or(false, |v| v >= min),
+        }
+    }
+}
+
+#[test]
+fn version_requirements() {
+    let caps = Capabilities { library_version: (13, 99, 0), server_protocol_version: None };
+    assert!(caps.supports_version(Feature::LfeBalance));
+    assert!(caps.supports_version(Feature::ThreadRealtime));
+    assert!(!caps.supports_version(Feature::PortAvailabilityGroup));
+
+    let caps = Capabilities { library_version: (15, 0, 0), server_protocol_version: None };
+    assert!(!caps.supports_version(Feature::Messaging));
+    assert!(caps.supports_version(Feature::FormatInfoAccessors));
+
+    let caps = Capabilities { library_version: (15, 0, 0), server_protocol_version: Some(34) };
+    assert!(caps.supports_version(Feature::PortAvailabilityGroup));
+    assert!(!caps.supports_version(Feature::Messaging));
+
+    let caps = Capabilities { library_version: (14, 0, 0), server_protocol_version: Some(35) };
+    assert!(caps.supports_version(Feature::PortAvailabilityGroup));
+    assert!(!caps.supports_version(Feature::Messaging));
+
+    let caps = Capabilities { library_version: (16, 1, 0), server_protocol_version: Some(35) };
+    assert!(Feature::ALL.iter().all(|f| caps.supports_version(*f)));
+}
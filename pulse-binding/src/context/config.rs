@@ -0,0 +1,394 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+//
+// Portions of documentation are copied from the LGPL 2.1+ licensed PulseAudio C headers on a
+// fair-use basis, as discussed in the overall project readme (available in the git repository).
+
+//! Client configuration.
+//!
+//! # Overview
+//!
+//! The behaviour of [`Context::connect()`](super::Context::connect) is influenced by the client
+//! configuration file (`client.conf`) and a number of environment variables. This module reads
+//! these in the same way as the PulseAudio client library, producing a [`ClientConfig`], which
+//! records where each setting came from. It does not influence the behaviour of the library
+//! itself; it is intended for inspection and diagnostics.
+//!
+//! The configuration file used is the first of the following found:
+//!
+//! 1. The file named by the `PULSE_CLIENTCONFIG` environment variable.
+//! 2. `$XDG_CONFIG_HOME/pulse/client.conf` (with `XDG_CONFIG_HOME` defaulting to `~/.config`).
+//! 3. `~/.pulse/client.conf` (legacy location).
+//! 4. `/etc/pulse/client.conf`.
+//!
+//! Any `*.conf` files in a `client.conf.d` directory alongside the file chosen are then applied in
+//! alphabetical order, followed lastly by the environment variables `PULSE_SERVER`, `PULSE_SINK`,
+//! `PULSE_SOURCE`, `PULSE_BINARY` and `PULSE_COOKIE`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use libpulse_binding::context::config::ClientConfig;
+//!
+//! let config = ClientConfig::load().unwrap();
+//! if let Some(server) = &config.default_server {
+//!     println!("Default server {} (from {})", server, config.origin("default-server").unwrap());
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use super::server::{self, ParseError, ServerList};
+
+/// Environment variable naming an alternate configuration file.
+pub const ENV_CONFIG_FILE: &str = "PULSE_CLIENTCONFIG";
+/// Environment variable overriding the default sink.
+pub const ENV_SINK: &str = "PULSE_SINK";
+/// Environment variable overriding the default source.
+pub const ENV_SOURCE: &str = "PULSE_SOURCE";
+/// Environment variable overriding the daemon binary used for autospawning.
+pub const ENV_BINARY: &str = "PULSE_BINARY";
+/// Environment variable overriding the cookie file.
+pub const ENV_COOKIE: &str = "PULSE_COOKIE";
+
+/// Location of the system-wide configuration file.
+pub const SYSTEM_CONFIG_FILE: &str = "/etc/pulse/client.conf";
+
+/// Where a configuration setting came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+    /// A configuration file, with line number.
+    File(PathBuf, usize),
+    /// An environment variable.
+    Env(&'static str),
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Origin::File(path, line) => write!(f, "{}:{}", path.display(), line),
+            Origin::Env(var) => write!(f, "${}", var),
+        }
+    }
+}
+
+/// Error in loading the client configuration.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// A file could not be read.
+    Io(PathBuf, io::Error),
+    /// A line could not be parsed, or held an invalid value.
+    Parse {
+        /// The file, if any.
+        path: Option<PathBuf>,
+        /// The line number.
+        line: usize,
+        /// Description of the problem.
+        message: String,
+    },
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io(_, e) => Some(e),
+            ConfigError::Parse { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            ConfigError::Parse { path: Some(path), line, message } => {
+                write!(f, "{}:{}: {}", path.display(), line, message)
+            },
+            ConfigError::Parse { path: None, line, message } => {
+                write!(f, "line {}: {}", line, message)
+            },
+        }
+    }
+}
+
+/// Client configuration.
+///
+/// The defaults match those of the PulseAudio client library.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientConfig {
+    /// The default server specification (`default-server`, `PULSE_SERVER`).
+    pub default_server: Option<String>,
+    /// The default sink (`default-sink`, `PULSE_SINK`).
+    pub default_sink: Option<String>,
+    /// The default source (`default-source`, `PULSE_SOURCE`).
+    pub default_source: Option<String>,
+    /// The authentication cookie file (`cookie-file`, `PULSE_COOKIE`).
+    pub cookie_file: Option<PathBuf>,
+    /// Whether to autospawn a daemon if none is running (`autospawn`).
+    pub autospawn: bool,
+    /// The daemon binary used for autospawning (`daemon-binary`, `PULSE_BINARY`).
+    pub daemon_binary: Option<String>,
+    /// Extra arguments passed to the daemon when autospawning (`extra-arguments`).
+    pub extra_arguments: Option<String>,
+    /// Whether to use shared memory for data transfer (`enable-shm`).
+    pub enable_shm: bool,
+    /// Whether to use memfd shared memory (`enable-memfd`).
+    pub enable_memfd: bool,
+    /// Size of the shared memory pool, or `None` for the default (`shm-size-bytes`).
+    pub shm_size: Option<usize>,
+    /// Whether to attempt connection to a daemon on localhost by TCP
+    /// (`auto-connect-localhost`).
+    pub auto_connect_localhost: bool,
+    /// Whether to attempt connection to a daemon on the host of `$DISPLAY`
+    /// (`auto-connect-display`).
+    pub auto_connect_display: bool,
+    /// Files applied, in order.
+    pub files: Vec<PathBuf>,
+    origins: BTreeMap<&'static str, Origin>,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            default_server: None,
+            default_sink: None,
+            default_source: None,
+            cookie_file: None,
+            autospawn: true,
+            daemon_binary: None,
+            extra_arguments: None,
+            enable_shm: true,
+            enable_memfd: true,
+            shm_size: None,
+            auto_connect_localhost: false,
+            auto_connect_display: false,
+            files: Vec::new(),
+            origins: BTreeMap::new(),
+        }
+    }
+}
+
+impl ClientConfig {
+    /// Loads the configuration from the configuration file(s) and environment, as the client
+    /// library would.
+    ///
+    /// It is not an error for no configuration file to exist.
+    pub fn load() -> Result<Self, ConfigError> {
+        let mut config = Self::default();
+        if let Some(path) = find_config_file() {
+            config.apply_file(&path)?;
+            config.apply_dot_d(&path)?;
+        }
+        config.apply_env()?;
+        Ok(config)
+    }
+
+    /// Parses the given configuration file contents, applying settings over the defaults.
+    pub fn parse(text: &str) -> Result<Self, ConfigError> {
+        let mut config = Self::default();
+        config.apply_str(text, None)?;
+        Ok(config)
+    }
+
+    /// Applies the settings from the given file.
+    pub fn apply_file(&mut self, path: &Path) -> Result<(), ConfigError> {
+        let text = fs::read_to_string(path).map_err(|e| ConfigError::Io(path.to_path_buf(), e))?;
+        self.apply_str(&text, Some(path))?;
+        self.files.push(path.to_path_buf());
+        Ok(())
+    }
+
+    /// Applies the settings from the given configuration file contents.
+    ///
+    /// `path` is used only for recording the origin of settings and in errors. Unknown keys are
+    /// ignored, as with the client library.
+    pub fn apply_str(&mut self, text: &str, path: Option<&Path>) -> Result<(), ConfigError> {
+        for (i, line) in text.lines().enumerate() {
+            let line_no = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            let error = |message: String| ConfigError::Parse {
+                path: path.map(Path::to_path_buf),
+                line: line_no,
+                message,
+            };
+            let (key, value) = match line.find('=') {
+                Some(i) => (line[..i].trim(), line[i + 1..].trim()),
+                None => return Err(error(format!("Missing '=' in line: {}", line))),
+            };
+            let origin = match path {
+                Some(p) => Origin::File(p.to_path_buf(), line_no),
+                None => Origin::File(PathBuf::new(), line_no),
+            };
+            self.set(key, value, origin).map_err(error)?;
+        }
+        Ok(())
+    }
+
+    /// Applies the settings from environment variables.
+    pub fn apply_env(&mut self) -> Result<(), ConfigError> {
+        let vars = [
+            (server::ENV_VAR, "default-server"),
+            (ENV_SINK, "default-sink"),
+            (ENV_SOURCE, "default-source"),
+            (ENV_BINARY, "daemon-binary"),
+            (ENV_COOKIE, "cookie-file"),
+        ];
+        for (var, key) in vars.iter() {
+            if let Ok(value) = std::env::var(var) {
+                if !value.is_empty() {
+                    self.set(key, &value, Origin::Env(var)).map_err(|message| {
+                        ConfigError::Parse { path: None, line: 0, message }
+                    })?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Gets where the setting with the given key (as used in the configuration file) came from.
+    ///
+    /// Returns `None` if the setting has its default value.
+    pub fn origin(&self, key: &str) -> Option<&Origin> {
+        self.origins.get(key)
+    }
+
+    /// Parses the default server specification, if any.
+    pub fn server_list(&self) -> Option<Result<ServerList, ParseError>> {
+        self.default_server.as_ref().map(|s| s.parse())
+    }
+
+    /// Applies files from the `.d` directory accompanying the given configuration file.
+    fn apply_dot_d(&mut self, path: &Path) -> Result<(), ConfigError> {
+        let mut dir = path.as_os_str().to_owned();
+        dir.push(".d");
+        let mut files: Vec<PathBuf> = match fs::read_dir(&dir) {
+            Ok(entries) => entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.extension().map_or(false, |e| e == "conf"))
+                .collect(),
+            Err(_) => return Ok(()),
+        };
+        files.sort();
+        for file in files {
+            self.apply_file(&file)?;
+        }
+        Ok(())
+    }
+
+    /// Sets a single setting by key.
+    fn set(&mut self, key: &str, value: &str, origin: Origin) -> Result<(), String> {
+        let key: &'static str = match key {
+            "default-server" => { self.default_server = non_empty(value); "default-server" },
+            "default-sink" => { self.default_sink = non_empty(value); "default-sink" },
+            "default-source" => { self.default_source = non_empty(value); "default-source" },
+            "cookie-file" => { self.cookie_file = non_empty(value).map(PathBuf::from); "cookie-file" },
+            "daemon-binary" => { self.daemon_binary = non_empty(value); "daemon-binary" },
+            "extra-arguments" => { self.extra_arguments = non_empty(value); "extra-arguments" },
+            "autospawn" => { self.autospawn = parse_bool(key, value)?; "autospawn" },
+            "enable-shm" => { self.enable_shm = parse_bool(key, value)?; "enable-shm" },
+            "enable-memfd" => { self.enable_memfd = parse_bool(key, value)?; "enable-memfd" },
+            "auto-connect-localhost" => {
+                self.auto_connect_localhost = parse_bool(key, value)?;
+                "auto-connect-localhost"
+            },
+            "auto-connect-display" => {
+                self.auto_connect_display = parse_bool(key, value)?;
+                "auto-connect-display"
+            },
+            "shm-size-bytes" => {
+                let size = value.parse::<usize>()
+                    .map_err(|_| format!("Invalid value for {}: {}", key, value))?;
+                self.shm_size = match size { 0 => None, s => Some(s) };
+                "shm-size-bytes"
+            },
+            _ => return Ok(()),
+        };
+        self.origins.insert(key, origin);
+        Ok(())
+    }
+}
+
+/// Gets the paths searched for the configuration file, in priority order.
+///
+/// Only the first of these found is used.
+pub fn config_file_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    if let Some(path) = std::env::var_os(ENV_CONFIG_FILE) {
+        if !path.is_empty() {
+            paths.push(PathBuf::from(path));
+        }
+    }
+    let home = crate::util::get_home_dir(4096).map(PathBuf::from);
+    let config_home = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => Some(PathBuf::from(dir)),
+        _ => home.as_ref().map(|h| h.join(".config")),
+    };
+    if let Some(dir) = config_home {
+        paths.push(dir.join("pulse").join("client.conf"));
+    }
+    if let Some(home) = home {
+        paths.push(home.join(".pulse").join("client.conf"));
+    }
+    paths.push(PathBuf::from(SYSTEM_CONFIG_FILE));
+    paths
+}
+
+/// Finds the configuration file to be used, if any.
+fn find_config_file() -> Option<PathBuf> {
+    config_file_paths().into_iter().find(|p| p.is_file())
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    match value.is_empty() {
+        true => None,
+        false => Some(value.to_string()),
+    }
+}
+
+/// Parses a boolean value as the client library does.
+fn parse_bool(key: &str, value: &str) -> Result<bool, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "1" | "y" | "t" | "yes" | "true" | "on" => Ok(true),
+        "0" | "n" | "f" | "no" | "false" | "off" => Ok(false),
+        _ => Err(format!("Invalid value for {}: {}", key, value)),
+    }
+}
+
+#[test]
+fn parse_config() {
+    let config = ClientConfig::parse("\
+        ; comment\n\
+        # another comment\n\
+        default-server = tcp:host:4713\n\
+        default-sink=speakers\n\
+        autospawn = no\n\
+        enable-shm = off\n\
+        shm-size-bytes = 65536\n\
+        unknown-key = 1\n").unwrap();
+    assert_eq!(config.default_server.as_deref(), Some("tcp:host:4713"));
+    assert_eq!(config.default_sink.as_deref(), Some("speakers"));
+    assert_eq!(config.default_source, None);
+    assert!(!config.autospawn);
+    assert!(!config.enable_shm);
+    assert!(config.enable_memfd);
+    assert_eq!(config.shm_size, Some(65536));
+    assert_eq!(config.origin("autospawn"), Some(&Origin::File(PathBuf::new(), 5)));
+    assert_eq!(config.origin("enable-memfd"), None);
+    assert!(config.server_list().unwrap().is_ok());
+
+    assert!(ClientConfig::parse("autospawn = maybe").is_err());
+    assert!(ClientConfig::parse("autospawn").is_err());
+}
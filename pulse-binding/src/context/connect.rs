@@ -0,0 +1,356 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+//
+// Portions of documentation are copied from the LGPL 2.1+ licensed PulseAudio C headers on a
+// fair-use basis, as discussed in the overall project readme (available in the git repository).
+
+//! Connect-and-wait helpers.
+//!
+//! # Overview
+//!
+//! Establishing a connection with [`Context::connect()`] requires waiting for the context to reach
+//! the [`State::Ready`] state (or otherwise [`State::Failed`] or [`State::Terminated`]), and then
+//! inspecting [`Context::errno()`] upon failure. The helpers here take care of this:
+//!
+//! * [`Context::connect_blocking()`]: For use with the [standard mainloop], iterating it until the
+//!   connection attempt completes.
+//! * [`Context::connect_blocking_threaded()`]: For use with the [threaded mainloop], waiting for
+//!   the connection attempt to complete.
+//! * [`Context::connect_async()`]: For use with any mainloop (including the GLIB mainloop),
+//!   executing a callback upon completion.
+//!
+//! All of them accept an optional timeout, implemented with a timer event created with
+//! [`Context::rttime_new()`], after which the connection attempt is abandoned. Failure is described
+//! with a [`ConnectError`].
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use libpulse_binding::context::{Context, FlagSet};
+//! use libpulse_binding::context::connect::ConnectError;
+//! use libpulse_binding::time::MicroSeconds;
+//!
+//! match context.connect_blocking(&mut mainloop, None, FlagSet::NOFLAGS,
+//!     Some(MicroSeconds::from_secs(5).unwrap()))
+//! {
+//!     Ok(()) => println!("Connected to {:?}", context.get_server()),
+//!     Err(ConnectError::ConnectionRefused) => eprintln!("Is the daemon running?"),
+//!     Err(e) => eprintln!("Failed to connect: {}", e),
+//! }
+//! ```
+//!
+//! [standard mainloop]: mod@crate::mainloop::standard
+//! [threaded mainloop]: mod@crate::mainloop::threaded
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::mem::ManuallyDrop;
+use std::rc::Rc;
+use super::{Context, ContextInternal, FlagSet, State};
+use crate::error::{Code, PAErr};
+use crate::mainloop::api::{Mainloop, MainloopInnerType, MainloopRef};
+use crate::mainloop::events::timer::TimeEvent;
+use crate::mainloop::{standard, threaded};
+use crate::mainloop::standard::IterateResult;
+use crate::time::{MicroSeconds, MonotonicTs};
+
+/// Reasons for failure to establish a connection.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ConnectError {
+    /// The connection was refused, typically because no daemon is running.
+    ConnectionRefused,
+    /// Access was denied by the daemon.
+    AccessDenied,
+    /// No (valid) authentication key was available.
+    NoAuthKey,
+    /// The protocol versions of the client library and the daemon are incompatible.
+    IncompatibleProtocol {
+        /// Protocol version of the client library.
+        client: u32,
+        /// Protocol version of the server, if known.
+        server: Option<u32>,
+    },
+    /// The server string was invalid.
+    InvalidServer,
+    /// The connection attempt did not complete within the timeout period, and was abandoned.
+    Timeout,
+    /// The mainloop was asked to quit before the connection attempt completed.
+    Interrupted,
+    /// Any other failure, with the error reported.
+    Other(PAErr),
+}
+
+impl ConnectError {
+    /// Creates a `ConnectError` from the error reported for a context.
+    pub fn from_context(context: &Context, err: PAErr) -> Self {
+        match Code::try_from(err) {
+            Ok(Code::ConnectionRefused) => ConnectError::ConnectionRefused,
+            Ok(Code::Access) => ConnectError::AccessDenied,
+            Ok(Code::AuthKey) => ConnectError::NoAuthKey,
+            Ok(Code::Version) => ConnectError::IncompatibleProtocol {
+                client: context.get_protocol_version(),
+                server: context.get_server_protocol_version(),
+            },
+            Ok(Code::InvalidServer) => ConnectError::InvalidServer,
+            Ok(Code::Timeout) => ConnectError::Timeout,
+            _ => ConnectError::Other(err),
+        }
+    }
+
+    /// Gets the closest equivalent error code.
+    pub fn code(&self) -> Code {
+        match *self {
+            ConnectError::ConnectionRefused => Code::ConnectionRefused,
+            ConnectError::AccessDenied => Code::Access,
+            ConnectError::NoAuthKey => Code::AuthKey,
+            ConnectError::IncompatibleProtocol { .. } => Code::Version,
+            ConnectError::InvalidServer => Code::InvalidServer,
+            ConnectError::Timeout => Code::Timeout,
+            ConnectError::Interrupted => Code::Killed,
+            ConnectError::Other(e) => Code::try_from(e).unwrap_or(Code::Unknown),
+        }
+    }
+}
+
+impl std::error::Error for ConnectError {}
+
+impl fmt::Display for ConnectError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ConnectError::ConnectionRefused => write!(f, "Connection refused"),
+            ConnectError::AccessDenied => write!(f, "Access denied"),
+            ConnectError::NoAuthKey => write!(f, "No authentication key"),
+            ConnectError::IncompatibleProtocol { client, server: Some(server) } => {
+                write!(f, "Incompatible protocol version (client {}, server {})", client, server)
+            },
+            ConnectError::IncompatibleProtocol { client, server: None } => {
+                write!(f, "Incompatible protocol version (client {})", client)
+            },
+            ConnectError::InvalidServer => write!(f, "Invalid server"),
+            ConnectError::Timeout => write!(f, "Timeout"),
+            ConnectError::Interrupted => write!(f, "Interrupted by mainloop quit"),
+            ConnectError::Other(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<ConnectError> for PAErr {
+    #[inline]
+    fn from(e: ConnectError) -> Self {
+        match e {
+            ConnectError::Other(e) => e,
+            e => e.code().into(),
+        }
+    }
+}
+
+impl Context {
+    /// Connects the context to the specified server, iterating the given [standard mainloop] until
+    /// the connection is ready or has failed.
+    ///
+    /// See [`connect()`](Self::connect) for the meaning of the `server` and `flags` parameters. If
+    /// `timeout` is not `None`, the attempt is abandoned (and the context disconnected) if not
+    /// complete within that time.
+    ///
+    /// [standard mainloop]: mod@crate::mainloop::standard
+    pub fn connect_blocking(&mut self, mainloop: &mut standard::Mainloop, server: Option<&str>,
+        flags: FlagSet, timeout: Option<MicroSeconds>) -> Result<(), ConnectError>
+    {
+        if let Err(e) = self.connect(server, flags, None) {
+            return Err(ConnectError::from_context(self, e));
+        }
+        let timed_out = Rc::new(Cell::new(false));
+        let _timer = timeout.and_then(|t| {
+            let flag = Rc::clone(&timed_out);
+            self.rttime_new::<standard::Mainloop, _>(mainloop, MonotonicTs::now() + t,
+                move |_| flag.set(true))
+        });
+        self.wait_connected(&timed_out, || {
+            match mainloop.iterate(true) {
+                IterateResult::Success(_) => Ok(()),
+                IterateResult::Quit(_) => Err(ConnectError::Interrupted),
+                IterateResult::Err(e) => Err(ConnectError::Other(e)),
+            }
+        })
+    }
+
+    /// Connects the context to the specified server, waiting on the given [threaded mainloop] until
+    /// the connection is ready or has failed.
+    ///
+    /// The mainloop must be running, and its lock must be held when calling this function.
+    ///
+    /// Note that waiting requires the context’s state callback, thus any previously set state
+    /// callback will be removed.
+    ///
+    /// See [`connect()`](Self::connect) for the meaning of the `server` and `flags` parameters. If
+    /// `timeout` is not `None`, the attempt is abandoned (and the context disconnected) if not
+    /// complete within that time.
+    ///
+    /// [threaded mainloop]: mod@crate::mainloop::threaded
+    pub fn connect_blocking_threaded(&mut self, mainloop: &mut threaded::Mainloop,
+        server: Option<&str>, flags: FlagSet, timeout: Option<MicroSeconds>)
+        -> Result<(), ConnectError>
+    {
+        let ml_ptr = mainloop._inner.get_ptr();
+        self.set_state_callback(Some(Box::new(move || {
+            unsafe { capi::pa_threaded_mainloop_signal(ml_ptr, 0) };
+        })));
+        if let Err(e) = self.connect(server, flags, None) {
+            self.set_state_callback(None);
+            return Err(ConnectError::from_context(self, e));
+        }
+        let timed_out = Rc::new(Cell::new(false));
+        let timer = timeout.and_then(|t| {
+            let flag = Rc::clone(&timed_out);
+            self.rttime_new::<threaded::Mainloop, _>(mainloop, MonotonicTs::now() + t,
+                move |_| {
+                    flag.set(true);
+                    unsafe { capi::pa_threaded_mainloop_signal(ml_ptr, 0) };
+                })
+        });
+        let result = self.wait_connected(&timed_out, || {
+            mainloop.wait();
+            Ok(())
+        });
+        drop(timer);
+        self.set_state_callback(None);
+        result
+    }
+
+    /// Connects the context to the specified server, executing the given callback once the
+    /// connection is ready or has failed.
+    ///
+    /// This works with any mainloop implementation, and is the only option provided for use with
+    /// the GLIB mainloop.
+    ///
+    /// Note that this requires the context’s state callback, thus any previously set state callback
+    /// will be replaced. It is safe to set a new state callback from within `callback`.
+    ///
+    /// See [`connect()`](Self::connect) for the meaning of the `server` and `flags` parameters. If
+    /// `timeout` is not `None`, the attempt is abandoned (and the context disconnected) if not
+    /// complete within that time.
+    ///
+    /// If the connection attempt fails immediately, the error is returned directly, and `callback`
+    /// is not executed.
+    pub fn connect_async<M, F>(&mut self, mainloop: &M, server: Option<&str>, flags: FlagSet,
+        timeout: Option<MicroSeconds>, callback: F) -> Result<(), ConnectError>
+        where M: Mainloop + 'static,
+              F: FnOnce(Result<(), ConnectError>) + 'static
+    {
+        let pending = Rc::new(RefCell::new(PendingConnect::<M> {
+            ptr: self.ptr,
+            mainloop: MainloopRef(mainloop.inner()),
+            callback: Some(Box::new(callback)),
+            timer: None,
+            timed_out: false,
+        }));
+
+        let pending_ref = Rc::clone(&pending);
+        self.set_state_callback(Some(Box::new(move || PendingConnect::check(&pending_ref))));
+        if let Err(e) = self.connect(server, flags, None) {
+            pending.borrow_mut().callback = None;
+            return Err(ConnectError::from_context(self, e));
+        }
+
+        if let Some(t) = timeout {
+            // Note, the timer only holds a weak reference, since the shared state holds the timer.
+            let weak = Rc::downgrade(&pending);
+            let timer = self.rttime_new::<M, _>(mainloop, MonotonicTs::now() + t, move |_| {
+                if let Some(pending) = weak.upgrade() {
+                    let ptr = {
+                        let mut p = pending.borrow_mut();
+                        p.timed_out = true;
+                        p.ptr
+                    };
+                    // Executes the state callback synchronously, which completes the attempt
+                    unsafe { capi::pa_context_disconnect(ptr) };
+                }
+            });
+            pending.borrow_mut().timer = timer;
+        }
+        Ok(())
+    }
+
+    /// Waits until the connection attempt completes, using the given function to wait for events.
+    fn wait_connected<W>(&mut self, timed_out: &Cell<bool>, mut wait: W) -> Result<(), ConnectError>
+        where W: FnMut() -> Result<(), ConnectError>
+    {
+        loop {
+            match self.get_state() {
+                State::Ready => return Ok(()),
+                State::Failed | State::Terminated => {
+                    return Err(ConnectError::from_context(self, self.errno()));
+                },
+                _ => {},
+            }
+            if timed_out.get() {
+                self.disconnect();
+                return Err(ConnectError::Timeout);
+            }
+            wait()?;
+        }
+    }
+}
+
+type ConnectCb = dyn FnOnce(Result<(), ConnectError>);
+
+/// State of a connection attempt made with [`Context::connect_async()`].
+struct PendingConnect<M>
+    where M: Mainloop + 'static
+{
+    ptr: *mut ContextInternal,
+    mainloop: MainloopRef<M::MI>,
+    callback: Option<Box<ConnectCb>>,
+    timer: Option<TimeEvent<M::MI>>,
+    timed_out: bool,
+}
+
+impl<M> PendingConnect<M>
+    where M: Mainloop + 'static
+{
+    /// Checks the context state, completing the attempt if no longer in progress.
+    fn check(rc: &Rc<RefCell<Self>>) {
+        let mut pending = rc.borrow_mut();
+        if pending.callback.is_none() {
+            return;
+        }
+        let ptr = pending.ptr;
+        let result = match State::from(unsafe { capi::pa_context_get_state(ptr) }) {
+            State::Ready => Ok(()),
+            State::Failed | State::Terminated if pending.timed_out => Err(ConnectError::Timeout),
+            State::Failed | State::Terminated => {
+                let err = PAErr(unsafe { capi::pa_context_errno(ptr) });
+                // A temporary weak wrapper, for access to the context methods used for mapping
+                let context = ManuallyDrop::new(Context::from_raw(ptr));
+                Err(ConnectError::from_context(&context, err))
+            },
+            _ => return,
+        };
+        let mut callback = pending.callback.take();
+        // The user callback and destruction of the timer are deferred, since neither the state
+        // callback nor the timer may be destroyed from within their own execution.
+        let mut timer = pending.timer.take();
+        pending.mainloop.once_event(Box::new(move || {
+            timer.take();
+            if let Some(callback) = callback.take() {
+                callback(result);
+            }
+        }));
+    }
+}
+
+#[test]
+fn error_codes() {
+    assert_eq!(PAErr::from(ConnectError::ConnectionRefused), Code::ConnectionRefused.into());
+    assert_eq!(PAErr::from(ConnectError::Timeout), Code::Timeout.into());
+    let other = PAErr::from(Code::Protocol);
+    assert_eq!(ConnectError::Other(other).code(), Code::Protocol);
+    assert_eq!(PAErr::from(ConnectError::Other(other)), other);
+}
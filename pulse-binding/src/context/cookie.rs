@@ -0,0 +1,276 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+//
+// Portions of documentation are copied from the LGPL 2.1+ licensed PulseAudio C headers on a
+// fair-use basis, as discussed in the overall project readme (available in the git repository).
+
+//! Authentication cookies.
+//!
+//! # Overview
+//!
+//! The PulseAudio daemon authenticates clients with a shared secret, the “cookie”, a 256 byte
+//! value usually stored in `~/.config/pulse/cookie`. The [`Cookie`] type holds such a value in
+//! memory, and can be loaded from raw bytes or hexadecimal text (as received through secrets
+//! management or environment variables, for instance), freshly generated, and read from or written
+//! to cookie files (which simply hold the raw bytes).
+//!
+//! A cookie held in memory can be given to a context with [`Context::load_cookie()`], which on
+//! Linux does so without it touching the filesystem.
+//!
+//! Cookie memory is zeroed upon drop, and the [`Debug`](std::fmt::Debug) implementation does not
+//! reveal its content.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use libpulse_binding::context::cookie::Cookie;
+//!
+//! let cookie = Cookie::from_hex(&std::env::var("PA_COOKIE_HEX").unwrap()).unwrap();
+//! context.load_cookie(&cookie).unwrap();
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use super::Context;
+use crate::error::PAErr;
+
+/// Length of an authentication cookie, in bytes.
+pub const COOKIE_LENGTH: usize = 256;
+
+/// An authentication cookie.
+///
+/// Comparison takes constant time, such that it does not reveal where cookies differ.
+#[derive(Clone)]
+pub struct Cookie([u8; COOKIE_LENGTH]);
+
+/// Error in obtaining a cookie.
+#[derive(Debug)]
+pub enum CookieError {
+    /// The data was not [`COOKIE_LENGTH`] bytes long. Holds the length found.
+    InvalidLength(usize),
+    /// Hexadecimal text contained a non-hexadecimal character, or an odd number of digits.
+    InvalidHex,
+    /// An I/O error occurred.
+    Io(io::Error),
+}
+
+impl std::error::Error for CookieError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CookieError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CookieError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CookieError::InvalidLength(l) => {
+                write!(f, "Invalid cookie length {} (expected {})", l, COOKIE_LENGTH)
+            },
+            CookieError::InvalidHex => write!(f, "Invalid hexadecimal cookie data"),
+            CookieError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<io::Error> for CookieError {
+    #[inline]
+    fn from(e: io::Error) -> Self {
+        CookieError::Io(e)
+    }
+}
+
+impl Cookie {
+    /// Creates a cookie from raw bytes, which must be exactly [`COOKIE_LENGTH`] long.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CookieError> {
+        if bytes.len() != COOKIE_LENGTH {
+            return Err(CookieError::InvalidLength(bytes.len()));
+        }
+        let mut data = [0; COOKIE_LENGTH];
+        data.copy_from_slice(bytes);
+        Ok(Cookie(data))
+    }
+
+    /// Creates a cookie from hexadecimal text (either case), as used in the `PULSE_COOKIE` X11
+    /// property. Surrounding whitespace is ignored.
+    pub fn from_hex(hex: &str) -> Result<Self, CookieError> {
+        let hex = hex.trim().as_bytes();
+        if hex.len() % 2 != 0 {
+            return Err(CookieError::InvalidHex);
+        }
+        if hex.len() != COOKIE_LENGTH * 2 {
+            return Err(CookieError::InvalidLength(hex.len() / 2));
+        }
+        let mut data = [0; COOKIE_LENGTH];
+        for (byte, pair) in data.iter_mut().zip(hex.chunks(2)) {
+            *byte = (hex_value(pair[0])? << 4) | hex_value(pair[1])?;
+        }
+        Ok(Cookie(data))
+    }
+
+    /// Generates a fresh random cookie, from the system random source (`/dev/urandom`).
+    pub fn generate() -> Result<Self, CookieError> {
+        let mut data = [0; COOKIE_LENGTH];
+        fs::File::open("/dev/urandom")?.read_exact(&mut data)?;
+        Ok(Cookie(data))
+    }
+
+    /// Reads a cookie from a cookie file.
+    pub fn read_file<P: AsRef<Path>>(path: P) -> Result<Self, CookieError> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    /// Writes the cookie to a cookie file, creating or replacing it.
+    ///
+    /// On Unix the file is created readable and writable by the owner only.
+    pub fn write_file<P: AsRef<Path>>(&self, path: P) -> Result<(), CookieError> {
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(path)?;
+        file.write_all(&self.0)?;
+        Ok(())
+    }
+
+    /// Gets the raw bytes of the cookie.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Gets the cookie as lower-case hexadecimal text.
+    pub fn to_hex(&self) -> String {
+        const DIGITS: &[u8; 16] = b"0123456789abcdef";
+        let mut s = String::with_capacity(COOKIE_LENGTH * 2);
+        for byte in self.0.iter() {
+            s.push(DIGITS[(byte >> 4) as usize] as char);
+            s.push(DIGITS[(byte & 0xf) as usize] as char);
+        }
+        s
+    }
+}
+
+impl PartialEq for Cookie {
+    fn eq(&self, other: &Self) -> bool {
+        let diff = self.0.iter().zip(other.0.iter()).fold(0, |diff, (a, b)| diff | (a ^ b));
+        // Volatile, to avoid the comparison being cut short
+        unsafe { std::ptr::read_volatile(&diff) == 0 }
+    }
+}
+
+impl Eq for Cookie {}
+
+impl fmt::Debug for Cookie {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Cookie(..)")
+    }
+}
+
+impl Drop for Cookie {
+    fn drop(&mut self) {
+        for byte in self.0.iter_mut() {
+            // Volatile, to avoid the zeroing being optimised away
+            unsafe { std::ptr::write_volatile(byte, 0) };
+        }
+    }
+}
+
+/// Gets the default cookie file location, `$XDG_CONFIG_HOME/pulse/cookie` (with
+/// `XDG_CONFIG_HOME` defaulting to `~/.config`).
+///
+/// Note that the legacy location `~/.pulse-cookie` is also used by the client library if present.
+pub fn default_path() -> Option<PathBuf> {
+    match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => Some(PathBuf::from(dir).join("pulse").join("cookie")),
+        _ => crate::util::get_home_dir(4096)
+            .map(|home| PathBuf::from(home).join(".config").join("pulse").join("cookie")),
+    }
+}
+
+fn hex_value(c: u8) -> Result<u8, CookieError> {
+    match c {
+        b'0'..=b'9' => Ok(c - b'0'),
+        b'a'..=b'f' => Ok(c - b'a' + 10),
+        b'A'..=b'F' => Ok(c - b'A' + 10),
+        _ => Err(CookieError::InvalidHex),
+    }
+}
+
+impl Context {
+    /// Loads the given authentication cookie.
+    ///
+    /// This is an in-memory alternative to
+    /// [`load_cookie_from_file()`](Self::load_cookie_from_file). On Linux, the cookie is passed to
+    /// the library through an anonymous memory-backed file, thus it never touches the filesystem.
+    /// On other platforms this returns [`Code::NotSupported`](crate::error::Code::NotSupported);
+    /// write the cookie to a file and load that instead.
+    ///
+    /// The library only reads the cookie upon connecting, so the file is kept open for the lifetime
+    /// of the context.
+    #[cfg(target_os = "linux")]
+    pub fn load_cookie(&mut self, cookie: &Cookie) -> Result<(), PAErr> {
+        use std::os::unix::io::{AsRawFd, FromRawFd};
+
+        let fd = unsafe { libc::memfd_create(b"pulse-cookie\0".as_ptr() as *const _,
+            libc::MFD_CLOEXEC) };
+        if fd < 0 {
+            return Err(crate::error::Code::IO.into());
+        }
+        // Takes ownership, closing the descriptor upon drop
+        let mut file = unsafe { fs::File::from_raw_fd(fd) };
+        if file.write_all(cookie.as_bytes()).is_err() {
+            return Err(crate::error::Code::IO.into());
+        }
+        self.load_cookie_from_file(&format!("/proc/self/fd/{}", file.as_raw_fd()))?;
+        // Replacing any previous one, which is no longer referenced
+        self._cookie_file = Some(file);
+        Ok(())
+    }
+
+    /// Loads the given authentication cookie.
+    ///
+    /// This is an in-memory alternative to
+    /// [`load_cookie_from_file()`](Self::load_cookie_from_file). On Linux, the cookie is passed to
+    /// the library through an anonymous memory-backed file, thus it never touches the filesystem.
+    /// On other platforms this returns [`Code::NotSupported`](crate::error::Code::NotSupported);
+    /// write the cookie to a file and load that instead.
+    #[cfg(not(target_os = "linux"))]
+    pub fn load_cookie(&mut self, _cookie: &Cookie) -> Result<(), PAErr> {
+        Err(crate::error::Code::NotSupported.into())
+    }
+}
+
+#[test]
+fn hex_round_trip() {
+    let mut bytes = [0u8; COOKIE_LENGTH];
+    for (i, b) in bytes.iter_mut().enumerate() {
+        *b = i as u8;
+    }
+    let cookie = Cookie::from_bytes(&bytes).unwrap();
+    let hex = cookie.to_hex();
+    assert!(hex.starts_with("000102"));
+    assert_eq!(Cookie::from_hex(&hex).unwrap(), cookie);
+    assert_eq!(Cookie::from_hex(&hex.to_uppercase()).unwrap(), cookie);
+    assert!(matches!(Cookie::from_bytes(&bytes[1..]), Err(CookieError::InvalidLength(255))));
+    assert!(matches!(Cookie::from_hex(&hex.replace("00", "zz")), Err(CookieError::InvalidHex)));
+    assert!(matches!(Cookie::from_hex(&hex[1..]), Err(CookieError::InvalidHex)));
+    assert!(matches!(Cookie::from_hex(&hex[2..]), Err(CookieError::InvalidLength(255))));
+    bytes[255] ^= 1;
+    assert_ne!(Cookie::from_bytes(&bytes).unwrap(), cookie);
+    assert_eq!(format!("{:?}", cookie), "Cookie(..)");
+}
@@ -0,0 +1,706 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! Event sounds from XDG sound themes.
+//!
+//! # Overview
+//!
+//! Desktop event sounds, such as for notifications, are identified by event IDs (for instance
+//! `message-new-instant`), as defined by the freedesktop.org sound naming specification, and
+//! provided by sound themes installed according to the freedesktop.org sound theme specification.
+//!
+//! A [`SoundTheme`] resolves an event ID to a sound file. Themes are looked up in the `sounds`
+//! directory of each of the XDG data directories; a theme may inherit from others, and the
+//! `freedesktop` theme is used as a final fallback, followed by sounds not belonging to any theme.
+//! Within a theme, only directories for the requested output profile (by default `stereo`) are
+//! used, with sounds specific to the locale preferred. Should no sound exist for an event ID, its
+//! trailing dash-separated components are removed one at a time, such that `dialog-warning-auth`
+//! may be served by a `dialog-warning` sound. A `.disabled` file for an event marks its sound as
+//! deliberately disabled.
+//!
+//! [`EventSounds`] plays event sounds through the [sample cache]. Each sound is uploaded upon
+//! first use, and from then on played directly from the cache. Playback streams are given the
+//! event ID in the [`EVENT_ID`] property, and the `event` [`MEDIA_ROLE`], along with any further
+//! `event.*` properties provided, such as the [`EVENT_DESCRIPTION`].
+//!
+//! This binding can only read WAV files itself. Themes commonly provide Ogg Vorbis (`.oga`)
+//! sounds, which require a [`Decoder`] to be supplied; without one, only WAV sounds are used.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use libpulse_binding::context::event_sound::{EventSounds, SoundTheme};
+//! use libpulse_binding::proplist::{properties, Proplist};
+//!
+//! let mut sounds = EventSounds::new(&context, SoundTheme::new("freedesktop"));
+//! let mut proplist = Proplist::new().unwrap();
+//! proplist.set_str(properties::EVENT_DESCRIPTION, "New message received").unwrap();
+//! sounds.play("message-new-instant", Some(&proplist), None)?;
+//! ```
+//!
+//! [sample cache]: mod@crate::context::scache
+//! [`EVENT_ID`]: crate::proplist::properties::EVENT_ID
+//! [`EVENT_DESCRIPTION`]: crate::proplist::properties::EVENT_DESCRIPTION
+//! [`MEDIA_ROLE`]: crate::proplist::properties::MEDIA_ROLE
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::rc::{Rc, Weak};
+use super::Context;
+use super::scache::{SampleCache, Upload, UploadFileError};
+use crate::channelmap::Map;
+use crate::error::{Code, Error};
+use crate::proplist::{properties, Proplist};
+use crate::sample::Spec;
+use crate::wav;
+
+/// Extensions of the sound files defined by the sound theme specification, in order of
+/// preference.
+const STANDARD_EXTENSIONS: [&str; 3] = ["oga", "ogg", "wav"];
+
+/// Extension of files marking a sound as disabled.
+const DISABLED_EXTENSION: &str = "disabled";
+
+/// The output profile used where none is specified, and as a fallback for others.
+const DEFAULT_PROFILE: &str = "stereo";
+
+/// The media role given to event sounds.
+const EVENT_ROLE: &str = "event";
+
+/// The result of looking up an event ID in a [`SoundTheme`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Lookup {
+    /// A sound file was found.
+    Found(PathBuf),
+    /// The sound is disabled.
+    Disabled,
+}
+
+/// An XDG sound theme, for resolving event IDs to sound files.
+#[derive(Debug, Clone)]
+pub struct SoundTheme {
+    name: String,
+    /// The `sounds` directories to look in, in priority order.
+    base_dirs: Vec<PathBuf>,
+    /// The locale, in the usual `language_TERRITORY.codeset@modifier` form.
+    locale: Option<String>,
+    profile: String,
+}
+
+/// The content of a theme’s `index.theme` file of relevance.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ThemeIndex {
+    /// Names of the themes inherited from.
+    inherits: Vec<String>,
+    /// The sub-directories, along with their output profile.
+    directories: Vec<(String, String)>,
+}
+
+impl SoundTheme {
+    /// Name of the theme used as a fallback for all others.
+    pub const FALLBACK: &'static str = "freedesktop";
+
+    /// Creates a theme of the given name.
+    ///
+    /// Themes are looked up in the XDG data directories, as given by the `XDG_DATA_HOME` and
+    /// `XDG_DATA_DIRS` environment variables, and the locale is taken from the `LC_ALL`,
+    /// `LC_MESSAGES` or `LANG` environment variable.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            base_dirs: default_base_dirs(),
+            locale: default_locale(),
+            profile: DEFAULT_PROFILE.to_owned(),
+        }
+    }
+
+    /// Sets the `sounds` directories to look for themes in, in priority order.
+    pub fn with_base_dirs(mut self, base_dirs: Vec<PathBuf>) -> Self {
+        self.base_dirs = base_dirs;
+        self
+    }
+
+    /// Sets the locale, or with `None`, uses no locale specific sounds.
+    pub fn with_locale(mut self, locale: Option<&str>) -> Self {
+        self.locale = locale.map(str::to_owned);
+        self
+    }
+
+    /// Sets the output profile, such as `stereo` or `5.1`.
+    ///
+    /// Sounds for the `stereo` profile are used where none exist for the profile given.
+    pub fn with_profile(mut self, profile: &str) -> Self {
+        self.profile = profile.to_owned();
+        self
+    }
+
+    /// Gets the name of the theme.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the `sounds` directories themes are looked for in, in priority order.
+    #[inline]
+    pub fn base_dirs(&self) -> &[PathBuf] {
+        &self.base_dirs
+    }
+
+    /// Looks up the sound for an event ID, considering sound files with the given extensions only,
+    /// in order of preference.
+    ///
+    /// Returns `None` if there is no such sound.
+    pub fn lookup(&self, event_id: &str, extensions: &[&str]) -> Option<Lookup> {
+        let themes = self.themes();
+        let locales = locale_dirs(self.locale.as_deref());
+        let mut profiles = vec![self.profile.as_str()];
+        if self.profile != DEFAULT_PROFILE {
+            profiles.push(DEFAULT_PROFILE);
+        }
+
+        for name in event_names(event_id) {
+            for (theme, index) in &themes {
+                for locale in &locales {
+                    for profile in &profiles {
+                        let dirs = index.directories.iter().filter(|(_, p)| p == profile);
+                        for (dir, _) in dirs {
+                            for base in &self.base_dirs {
+                                let path = base.join(theme).join(dir).join(locale);
+                                if let Some(found) = probe(&path, name, extensions) {
+                                    return Some(found);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            // Sounds not belonging to any theme
+            for base in &self.base_dirs {
+                if let Some(found) = probe(base, name, extensions) {
+                    return Some(found);
+                }
+            }
+        }
+        None
+    }
+
+    /// Gets the themes to look in, in order: this one, those it inherits from, depth first, then
+    /// the fallback theme. Themes not installed are left out.
+    fn themes(&self) -> Vec<(String, ThemeIndex)> {
+        let mut themes = Vec::new();
+        let mut seen = HashSet::new();
+        self.add_theme(&self.name, &mut themes, &mut seen);
+        self.add_theme(Self::FALLBACK, &mut themes, &mut seen);
+        themes
+    }
+
+    fn add_theme(&self, name: &str, themes: &mut Vec<(String, ThemeIndex)>,
+        seen: &mut HashSet<String>)
+    {
+        if !seen.insert(name.to_owned()) {
+            return;
+        }
+        let index = self.base_dirs.iter()
+            .find_map(|base| std::fs::read_to_string(base.join(name).join("index.theme")).ok())
+            .map(|content| ThemeIndex::parse(&content));
+        if let Some(index) = index {
+            let inherits = index.inherits.clone();
+            themes.push((name.to_owned(), index));
+            for parent in &inherits {
+                self.add_theme(parent, themes, seen);
+            }
+        }
+    }
+}
+
+impl ThemeIndex {
+    /// Parses the content of an `index.theme` file.
+    fn parse(content: &str) -> Self {
+        let list = |value: &str| -> Vec<String> {
+            value.split(',').map(str::trim).filter(|v| !v.is_empty()).map(str::to_owned).collect()
+        };
+        let mut section = "";
+        let mut inherits = Vec::new();
+        let mut directories = Vec::new();
+        let mut profiles = HashMap::new();
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line.starts_with('[') && line.ends_with(']') {
+                section = &line[1..line.len() - 1];
+                continue;
+            }
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => continue,
+            };
+            match (section, key) {
+                ("Sound Theme", "Inherits") => inherits = list(value),
+                ("Sound Theme", "Directories") => directories = list(value),
+                (dir, "OutputProfile") => {
+                    profiles.insert(dir, value);
+                },
+                _ => {},
+            }
+        }
+        let directories = directories.into_iter().map(|dir| {
+            let profile = profiles.get(dir.as_str()).copied().unwrap_or(DEFAULT_PROFILE);
+            let profile = profile.to_owned();
+            (dir, profile)
+        }).collect();
+        Self { inherits, directories }
+    }
+}
+
+/// Looks for a sound in the given directory.
+fn probe(dir: &Path, name: &str, extensions: &[&str]) -> Option<Lookup> {
+    if dir.join(format!("{}.{}", name, DISABLED_EXTENSION)).exists() {
+        return Some(Lookup::Disabled);
+    }
+    extensions.iter()
+        .map(|ext| dir.join(format!("{}.{}", name, ext)))
+        .find(|path| path.is_file())
+        .map(Lookup::Found)
+}
+
+/// Gets the event ID, followed by those with trailing components removed.
+fn event_names(event_id: &str) -> Vec<&str> {
+    let mut names = vec![event_id];
+    let mut name = event_id;
+    while let Some(i) = name.rfind('-') {
+        name = &name[..i];
+        if !name.is_empty() {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Gets the locale sub-directories to look in, in order, ending with the `C` locale and then none.
+fn locale_dirs(locale: Option<&str>) -> Vec<String> {
+    let mut dirs = Vec::new();
+    if let Some(locale) = locale {
+        let (locale, modifier) = match locale.split_once('@') {
+            Some((locale, modifier)) => (locale, Some(modifier)),
+            None => (locale, None),
+        };
+        let locale = locale.split('.').next().unwrap_or("");
+        let language = locale.split('_').next().unwrap_or("");
+        for candidate in [locale, language] {
+            if candidate.is_empty() || candidate == "C" || candidate == "POSIX" {
+                continue;
+            }
+            if let Some(modifier) = modifier {
+                dirs.push(format!("{}@{}", candidate, modifier));
+            }
+            dirs.push(candidate.to_owned());
+        }
+        dirs.dedup();
+    }
+    dirs.push("C".to_owned());
+    dirs.push(String::new());
+    dirs
+}
+
+/// Gets the `sounds` directories of the XDG data directories, in priority order.
+fn default_base_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    match std::env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => dirs.push(PathBuf::from(dir)),
+        _ => {
+            if let Some(home) = crate::util::get_home_dir(4096) {
+                dirs.push(PathBuf::from(home).join(".local").join("share"));
+            }
+        },
+    }
+    match std::env::var("XDG_DATA_DIRS") {
+        Ok(list) if !list.is_empty() => {
+            dirs.extend(list.split(':').filter(|d| !d.is_empty()).map(PathBuf::from));
+        },
+        _ => dirs.extend(["/usr/local/share", "/usr/share"].iter().map(PathBuf::from)),
+    }
+    dirs.into_iter().map(|dir| dir.join("sounds")).collect()
+}
+
+/// Gets the locale of messages from the environment.
+fn default_locale() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"].iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+}
+
+/// Decoded audio data of a sound file.
+#[derive(Debug, Clone)]
+pub struct Sound {
+    /// Sample specification of the data.
+    pub spec: Spec,
+    /// Channel map of the data.
+    pub map: Map,
+    /// The audio data, made up of whole frames.
+    pub data: Vec<u8>,
+}
+
+/// A decoder of sound files in formats other than WAV, such as Ogg Vorbis.
+pub trait Decoder {
+    /// Gets the extensions of the files decoded, such as `oga`, in order of preference.
+    fn extensions(&self) -> &[&'static str];
+
+    /// Decodes the given file.
+    fn decode(&self, path: &Path) -> io::Result<Sound>;
+}
+
+/// Error in playing an event sound.
+#[derive(Debug)]
+pub enum EventSoundError {
+    /// No sound exists for the event ID.
+    NotFound(String),
+    /// The sound for the event ID is disabled.
+    Disabled(String),
+    /// The sound file found is of a format that cannot be read, lacking a suitable [`Decoder`].
+    Unsupported(PathBuf),
+    /// Reading the WAV file failed.
+    File(wav::Error),
+    /// Decoding the sound file failed.
+    Decode(PathBuf, io::Error),
+    /// Starting the upload failed.
+    Upload(Error),
+}
+
+impl std::error::Error for EventSoundError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EventSoundError::File(e) => Some(e),
+            EventSoundError::Decode(_, e) => Some(e),
+            EventSoundError::Upload(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for EventSoundError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EventSoundError::NotFound(id) => write!(f, "No sound for event `{}`", id),
+            EventSoundError::Disabled(id) => write!(f, "Sound for event `{}` disabled", id),
+            EventSoundError::Unsupported(path) => {
+                write!(f, "No decoder for sound file {}", path.display())
+            },
+            EventSoundError::File(e) => write!(f, "Failed to read sound file: {}", e),
+            EventSoundError::Decode(path, e) => {
+                write!(f, "Failed to decode sound file {}: {}", path.display(), e)
+            },
+            EventSoundError::Upload(e) => e.fmt(f),
+        }
+    }
+}
+
+impl From<UploadFileError> for EventSoundError {
+    fn from(e: UploadFileError) -> Self {
+        match e {
+            UploadFileError::File(e) => EventSoundError::File(e),
+            UploadFileError::Upload(e) => EventSoundError::Upload(e),
+        }
+    }
+}
+
+impl From<Error> for EventSoundError {
+    #[inline]
+    fn from(e: Error) -> Self {
+        EventSoundError::Upload(e)
+    }
+}
+
+type PlayCb = Box<dyn FnMut(Result<u32, ()>) + 'static>;
+
+/// A player of event sounds, through the sample cache.
+///
+/// See the [module level documentation](self).
+pub struct EventSounds {
+    theme: SoundTheme,
+    cache: Rc<SampleCache>,
+    device: Option<String>,
+    decoder: Option<Box<dyn Decoder>>,
+    /// State shared with the upload and playback callbacks.
+    state: Rc<RefCell<PlayerState>>,
+}
+
+/// State of an [`EventSounds`] player, shared with callbacks.
+#[derive(Default)]
+struct PlayerState {
+    /// Names of the samples believed to be in the sample cache.
+    cached: HashSet<String>,
+    /// Uploads in progress, or complete but yet to be dropped, by sample name.
+    uploads: HashMap<String, PendingUpload>,
+}
+
+struct PendingUpload {
+    /// The upload, kept until complete.
+    _upload: Upload,
+    /// Plays awaiting completion of the upload.
+    plays: Vec<Play>,
+    done: bool,
+}
+
+/// A request to play a sample.
+struct Play {
+    proplist: Proplist,
+    callback: Option<PlayCb>,
+}
+
+impl EventSounds {
+    /// Creates a player of the sounds of the given theme, through the sample cache of the given
+    /// context.
+    pub fn new(context: &Context, theme: SoundTheme) -> Self {
+        Self {
+            theme,
+            cache: Rc::new(context.sample_cache()),
+            device: None,
+            decoder: None,
+            state: Rc::new(RefCell::new(PlayerState::default())),
+        }
+    }
+
+    /// Gets the theme.
+    #[inline]
+    pub fn theme(&self) -> &SoundTheme {
+        &self.theme
+    }
+
+    /// Sets the sink to play sounds on, or with `None`, the default sink.
+    pub fn set_device(&mut self, device: Option<&str>) {
+        self.device = device.map(str::to_owned);
+    }
+
+    /// Sets a decoder for sound files in formats other than WAV.
+    pub fn set_decoder(&mut self, decoder: Option<Box<dyn Decoder>>) {
+        self.decoder = decoder;
+    }
+
+    /// Gets the name of the sample used for an event ID.
+    pub fn sample_name(&self, event_id: &str) -> String {
+        format!("{}:{}", self.theme.name(), event_id)
+    }
+
+    /// Resolves an event ID to the sound file to play, among those that can be read.
+    pub fn resolve(&self, event_id: &str) -> Result<PathBuf, EventSoundError> {
+        let mut extensions = self.decoder.as_ref().map_or(Vec::new(), |d| d.extensions().to_vec());
+        extensions.push("wav");
+        match self.theme.lookup(event_id, &extensions) {
+            Some(Lookup::Found(path)) => Ok(path),
+            Some(Lookup::Disabled) => Err(EventSoundError::Disabled(event_id.to_owned())),
+            None => match self.theme.lookup(event_id, &STANDARD_EXTENSIONS) {
+                Some(Lookup::Found(path)) => Err(EventSoundError::Unsupported(path)),
+                _ => Err(EventSoundError::NotFound(event_id.to_owned())),
+            },
+        }
+    }
+
+    /// Plays the sound for an event ID.
+    ///
+    /// The sound is uploaded to the sample cache upon first use, with playback started once the
+    /// upload completes. The given property list, which may hold further `event.*` properties, is
+    /// used for the playback stream, with the event ID and media role added.
+    ///
+    /// The optional callback is given the index of the sink input upon success, and `Err` should
+    /// playback or the upload fail. It is not executed should an error be returned here.
+    pub fn play(&mut self, event_id: &str, proplist: Option<&Proplist>, callback: Option<PlayCb>)
+        -> Result<(), EventSoundError>
+    {
+        let name = self.sample_name(event_id);
+        let mut proplist = match proplist {
+            Some(proplist) => proplist.clone(),
+            None => new_proplist()?,
+        };
+        let _ = proplist.set_str(properties::EVENT_ID, event_id);
+        if proplist.contains(properties::MEDIA_ROLE) != Some(true) {
+            let _ = proplist.set_str(properties::MEDIA_ROLE, EVENT_ROLE);
+        }
+        let play = Play { proplist, callback };
+
+        {
+            let mut state = self.state.borrow_mut();
+            state.uploads.retain(|_, upload| !upload.done);
+            if let Some(upload) = state.uploads.get_mut(&name) {
+                upload.plays.push(play);
+                return Ok(());
+            }
+            if !state.cached.contains(&name) {
+                drop(state);
+                return self.upload(event_id, name, play);
+            }
+        }
+        start_play(&self.cache, &Rc::downgrade(&self.state), self.device.as_deref(), &name, play);
+        Ok(())
+    }
+
+    /// Uploads the sound for an event ID, playing it once complete.
+    fn upload(&mut self, event_id: &str, name: String, play: Play)
+        -> Result<(), EventSoundError>
+    {
+        let path = self.resolve(event_id)?;
+        let mut proplist = new_proplist()?;
+        let _ = proplist.set_str(properties::EVENT_ID, event_id);
+        let _ = proplist.set_str(properties::MEDIA_ROLE, EVENT_ROLE);
+        let _ = proplist.set_str(properties::MEDIA_FILENAME, &path.to_string_lossy());
+        let mut upload = match (path.extension().map_or(false, |ext| ext == "wav"), &self.decoder) {
+            (true, _) => self.cache.replace_from_file(&name, &path, Some(&proplist))?,
+            (false, Some(decoder)) => {
+                let sound = decoder.decode(&path)
+                    .map_err(|e| EventSoundError::Decode(path.clone(), e))?;
+                self.cache.replace(&name, &sound.spec, Some(&sound.map), Some(&proplist),
+                    sound.data)?
+            },
+            (false, None) => return Err(EventSoundError::Unsupported(path)),
+        };
+
+        let weak_state = Rc::downgrade(&self.state);
+        let cache = Rc::clone(&self.cache);
+        let device = self.device.clone();
+        let sample = name.clone();
+        upload.set_completion_callback(Some(Box::new(move |outcome| {
+            let state = match weak_state.upgrade() {
+                Some(state) => state,
+                None => return,
+            };
+            // Note, the upload itself cannot be dropped here, from within its own callback, so is
+            // only marked as done, to be dropped later.
+            let plays = {
+                let mut state = state.borrow_mut();
+                if outcome.is_ok() {
+                    state.cached.insert(sample.clone());
+                }
+                match state.uploads.get_mut(&sample) {
+                    Some(upload) => {
+                        upload.done = true;
+                        std::mem::take(&mut upload.plays)
+                    },
+                    None => Vec::new(),
+                }
+            };
+            for play in plays {
+                match outcome {
+                    Ok(()) => start_play(&cache, &weak_state, device.as_deref(), &sample, play),
+                    Err(_) => {
+                        if let Some(mut callback) = play.callback {
+                            callback(Err(()));
+                        }
+                    },
+                }
+            }
+        })));
+        let pending = PendingUpload { _upload: upload, plays: vec![play], done: false };
+        self.state.borrow_mut().uploads.insert(name, pending);
+        Ok(())
+    }
+}
+
+/// Starts playback of a cached sample.
+fn start_play(cache: &SampleCache, state: &Weak<RefCell<PlayerState>>, device: Option<&str>,
+    name: &str, play: Play)
+{
+    let Play { proplist, mut callback } = play;
+    let state = Weak::clone(state);
+    let sample = name.to_owned();
+    let _ = cache.play(name, device, None, &proplist, Some(Box::new(move |result| {
+        // The sample may since have been removed from the cache, so is uploaded afresh next time
+        if result.is_err() {
+            if let Some(state) = state.upgrade() {
+                if let Ok(mut state) = state.try_borrow_mut() {
+                    state.cached.remove(&sample);
+                }
+            }
+        }
+        if let Some(callback) = callback.as_mut() {
+            callback(result);
+        }
+    })));
+}
+
+fn new_proplist() -> Result<Proplist, Error> {
+    Proplist::new().ok_or_else(|| Error::new(Code::Internal.into(), "create proplist"))
+}
+
+#[test]
+fn theme_index() {
+    let index = ThemeIndex::parse("\
+        [Sound Theme]\n\
+        Name=Test\n\
+        Name[de]=Probe\n\
+        Inherits=parent, freedesktop\n\
+        Directories=stereo,5.1\n\
+        \n\
+        # comment\n\
+        [5.1]\n\
+        OutputProfile=5.1\n");
+    assert_eq!(index.inherits, ["parent", "freedesktop"]);
+    assert_eq!(index.directories, [("stereo".to_owned(), "stereo".to_owned()),
+        ("5.1".to_owned(), "5.1".to_owned())]);
+}
+
+#[test]
+fn names_and_locales() {
+    assert_eq!(event_names("dialog-warning-auth"), ["dialog-warning-auth", "dialog-warning",
+        "dialog"]);
+    assert_eq!(event_names("bell"), ["bell"]);
+    assert_eq!(locale_dirs(Some("de_DE.UTF-8@euro")), ["de_DE@euro", "de_DE", "de@euro", "de",
+        "C", ""]);
+    assert_eq!(locale_dirs(Some("fr")), ["fr", "C", ""]);
+    assert_eq!(locale_dirs(Some("C.UTF-8")), ["C", ""]);
+    assert_eq!(locale_dirs(None), ["C", ""]);
+}
+
+#[test]
+fn theme_lookup() {
+    let base = std::env::temp_dir().join(format!("pulse-binding-sounds-{}", std::process::id()));
+    let files = [
+        ("child/index.theme", "[Sound Theme]\nInherits=parent\nDirectories=stereo\n"),
+        ("child/stereo/de/bell.wav", ""),
+        ("child/stereo/dialog-warning.disabled", ""),
+        ("parent/index.theme", "[Sound Theme]\nDirectories=stereo,5.1\n[5.1]\nOutputProfile=5.1\n"),
+        ("parent/stereo/bell.oga", ""),
+        ("parent/stereo/message.wav", ""),
+        ("parent/5.1/message.wav", ""),
+        ("freedesktop/index.theme", "[Sound Theme]\nDirectories=stereo\n"),
+        ("freedesktop/stereo/complete.oga", ""),
+        ("alarm.wav", ""),
+    ];
+    for (path, content) in &files {
+        let path = base.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    let theme = SoundTheme::new("child").with_base_dirs(vec![base.clone()]).with_locale(None);
+    let found = |theme: &SoundTheme, id, exts: &[&str]| match theme.lookup(id, exts) {
+        Some(Lookup::Found(path)) => Some(path.strip_prefix(&base).unwrap().to_owned()),
+        _ => None,
+    };
+    let german = theme.clone().with_locale(Some("de_DE.UTF-8"));
+    assert_eq!(found(&german, "bell", &["wav"]), Some(PathBuf::from("child/stereo/de/bell.wav")));
+    assert_eq!(found(&theme, "bell", &["oga", "wav"]),
+        Some(PathBuf::from("parent/stereo/bell.oga")));
+    assert_eq!(found(&theme, "bell", &["wav"]), None);
+    assert_eq!(theme.lookup("dialog-warning-auth", &["wav"]), Some(Lookup::Disabled));
+    assert_eq!(found(&theme, "message-new-instant", &["wav"]),
+        Some(PathBuf::from("parent/stereo/message.wav")));
+    let surround = theme.clone().with_profile("5.1");
+    assert_eq!(found(&surround, "message", &["wav"]),
+        Some(PathBuf::from("parent/5.1/message.wav")));
+    assert_eq!(found(&theme, "complete", &STANDARD_EXTENSIONS),
+        Some(PathBuf::from("freedesktop/stereo/complete.oga")));
+    assert_eq!(found(&theme, "alarm-clock-elapsed", &["wav"]), Some(PathBuf::from("alarm.wav")));
+    assert_eq!(theme.lookup("missing", &STANDARD_EXTENSIONS), None);
+
+    std::fs::remove_dir_all(&base).unwrap();
+}
@@ -113,8 +113,7 @@ pub struct DeviceManager {
     cb_ptrs: CallbackPointers,
 }
 
-unsafe impl Send for DeviceManager {}
-unsafe impl Sync for DeviceManager {}
+unsafe impl crate::mainloop::threaded::LockBound for DeviceManager {}
 
 /// Holds copies of callback closure pointers, for those that are “multi-use” (may be fired multiple
 /// times), for freeing at the appropriate time.
@@ -305,7 +304,7 @@ extern "C"
 fn read_list_cb_proxy(_: *mut ContextInternal, i: *const InfoInternal, eol: i32,
     userdata: *mut c_void)
 {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         callback_for_list_instance(i, eol, userdata, Info::new_from_raw);
     });
 }
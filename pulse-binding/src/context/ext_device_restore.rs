@@ -63,8 +63,7 @@ pub struct DeviceRestore {
     cb_ptrs: CallbackPointers,
 }
 
-unsafe impl Send for DeviceRestore {}
-unsafe impl Sync for DeviceRestore {}
+unsafe impl crate::mainloop::threaded::LockBound for DeviceRestore {}
 
 /// Holds copies of callback closure pointers, for those that are “multi-use” (may be fired multiple
 /// times), for freeing at the appropriate time.
@@ -202,7 +201,7 @@ extern "C"
 fn ext_subscribe_cb_proxy(_: *mut ContextInternal, type_: def::Device, index: u32,
     userdata: *mut c_void)
 {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         let callback = SubscribeCb::get_callback(userdata);
         (callback)(type_, index);
     });
@@ -215,7 +214,7 @@ extern "C"
 fn read_list_cb_proxy(_: *mut ContextInternal, i: *const InfoInternal, eol: i32,
     userdata: *mut c_void)
 {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         callback_for_list_instance(i, eol, userdata, Info::new_from_raw);
     });
 }
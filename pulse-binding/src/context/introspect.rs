@@ -69,6 +69,10 @@
 //! All three methods use the same callback and will provide a [`SinkInfo`] or [`SourceInfo`]
 //! structure.
 //!
+//! Where either an index or a name may be given, such as one supplied by a user, a [`DeviceRef`]
+//! can be used instead with the likes of [`Introspector::get_sink_info()`], which accept either
+//! form, as well as the special names of the default devices.
+//!
 //! # Sink Inputs and Source Outputs
 //!
 //! Sink inputs and source outputs are the representations of the client ends of streams inside the
@@ -174,16 +178,15 @@
 //!
 //! Server objects like sinks, sink inputs or modules can register a message handler to communicate
 //! with clients. A message can be sent to a named message handler using
-//! [`Introspector::send_message_to_object()`].
+//! [`Introspector::send_message_to_object()`]. Parameters and responses can be encoded and decoded
+//! with the [`message`](super::message) module, which can also list the available handlers.
 //!
 //! # Clients
 //!
 //! The only operation supported on clients is the possibility of kicking them off the server using
 //! [`Introspector::kill_client()`].
 
-use std::os::raw::c_void;
-#[cfg(any(doc, feature = "pa_v15"))]
-use std::os::raw::c_char;
+use std::os::raw::{c_char, c_void};
 use std::ffi::{CStr, CString};
 use std::borrow::Cow;
 use std::ptr::null_mut;
@@ -210,18 +213,83 @@ use crate::callbacks::{
 };
 use crate::volume::{ChannelVolumes, Volume};
 use crate::{operation::Operation, proplist::Proplist};
-#[cfg(any(doc, feature = "pa_v14"))]
+use crate::mainloop::lockcheck;
 use crate::def::DevicePortType;
 
 pub use capi::pa_stat_info as StatInfo;
 
+/// Port information added in PA v14, read at runtime when built without the `pa_v14` feature.
+///
+/// The structures the client library passes are those of the version in use, thus with a new
+/// enough library the fields added are present beyond the end of the structures as compiled, and
+/// can be read through these copies of the newer layouts.
+#[cfg(not(any(doc, feature = "pa_v14")))]
+mod v14 {
+    use std::os::raw::{c_char, c_void};
+    use crate::capability::Feature;
+    use crate::version;
+
+    /// Layout of `pa_sink_port_info` and `pa_source_port_info`.
+    #[repr(C)]
+    pub struct PortInfo {
+        pub name: *const c_char,
+        pub description: *const c_char,
+        pub priority: u32,
+        pub available: i32,
+        pub availability_group: *const c_char,
+        pub r#type: u32,
+    }
+
+    /// Layout of `pa_card_port_info`.
+    #[repr(C)]
+    pub struct CardPortInfo {
+        pub name: *const c_char,
+        pub description: *const c_char,
+        pub priority: u32,
+        pub available: i32,
+        pub direction: i32,
+        pub n_profiles: u32,
+        pub profiles: *mut *mut c_void,
+        pub proplist: *mut c_void,
+        pub latency_offset: i64,
+        pub profiles2: *mut *mut c_void,
+        pub availability_group: *const c_char,
+        pub r#type: u32,
+    }
+
+    /// Checks whether the client library in use provides the fields.
+    fn is_available() -> bool {
+        match version::get_library_version_numbers() {
+            Ok((major, minor, _)) => {
+                (major, minor) >= Feature::PortAvailabilityGroup.min_library_version()
+            },
+            Err(_) => false,
+        }
+    }
+
+    /// Gets the availability group and type of a sink or source port, if available.
+    pub unsafe fn port_fields(p: *const PortInfo) -> (*const c_char, u32) {
+        match is_available() {
+            true => ((*p).availability_group, (*p).r#type),
+            false => (std::ptr::null(), 0),
+        }
+    }
+
+    /// Gets the availability group and type of a card port, if available.
+    pub unsafe fn card_port_fields(p: *const CardPortInfo) -> (*const c_char, u32) {
+        match is_available() {
+            true => ((*p).availability_group, (*p).r#type),
+            false => (std::ptr::null(), 0),
+        }
+    }
+}
+
 /// A wrapper object providing introspection routines to a context.
 pub struct Introspector {
     context: *mut super::ContextInternal,
 }
 
-unsafe impl Send for Introspector {}
-unsafe impl Sync for Introspector {}
+unsafe impl crate::mainloop::threaded::LockBound for Introspector {}
 
 impl Context {
     /// Gets an introspection object linked to the current context, giving access to introspection
@@ -230,6 +298,7 @@ impl Context {
     /// See [`context::introspect`](mod@crate::context::introspect).
     #[inline]
     pub fn introspect(&self) -> Introspector {
+        self.check_lock();
         unsafe { capi::pa_context_ref(self.ptr) };
         Introspector::from_raw(self.ptr)
     }
@@ -243,6 +312,15 @@ impl Introspector {
     }
 }
 
+impl Introspector {
+    /// Asserts, in debug builds, that the introspector may be used from the current thread.
+    #[inline(always)]
+    #[track_caller]
+    fn check_lock(&self) {
+        lockcheck::check_context(self.context);
+    }
+}
+
 impl Drop for Introspector {
     fn drop(&mut self) {
         unsafe { capi::pa_context_unref(self.context) };
@@ -250,6 +328,324 @@ impl Drop for Introspector {
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Device selection
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A reference to a sink, source or card, either by index or by name.
+///
+/// This is accepted by introspection methods such as [`Introspector::set_sink_volume()`], in place
+/// of the separate `_by_index` and `_by_name` variants. Besides the names of the devices themselves,
+/// the server accepts the special names [`DEFAULT_SINK`](Self::DEFAULT_SINK),
+/// [`DEFAULT_SOURCE`](Self::DEFAULT_SOURCE) and [`DEFAULT_MONITOR`](Self::DEFAULT_MONITOR).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DeviceRef<'a> {
+    /// By index.
+    Index(u32),
+    /// By name.
+    Name(Cow<'a, str>),
+}
+
+impl<'a> DeviceRef<'a> {
+    /// The default sink.
+    pub const DEFAULT_SINK: DeviceRef<'static> = DeviceRef::Name(Cow::Borrowed("@DEFAULT_SINK@"));
+    /// The default source.
+    pub const DEFAULT_SOURCE: DeviceRef<'static> =
+        DeviceRef::Name(Cow::Borrowed("@DEFAULT_SOURCE@"));
+    /// The monitor source of the default sink.
+    pub const DEFAULT_MONITOR: DeviceRef<'static> =
+        DeviceRef::Name(Cow::Borrowed("@DEFAULT_MONITOR@"));
+
+    /// Interprets a device given as text, such as on the command line.
+    ///
+    /// As with `pactl`, text consisting only of digits is taken as an index, and anything else as a
+    /// name.
+    pub fn parse(s: &'a str) -> Self {
+        match s.parse::<u32>() {
+            Ok(index) if s.bytes().all(|b| b.is_ascii_digit()) => DeviceRef::Index(index),
+            _ => DeviceRef::Name(Cow::Borrowed(s)),
+        }
+    }
+
+    /// Gets the index, if referenced by index.
+    #[inline]
+    pub fn index(&self) -> Option<u32> {
+        match self {
+            DeviceRef::Index(index) => Some(*index),
+            DeviceRef::Name(_) => None,
+        }
+    }
+
+    /// Gets the name, if referenced by name.
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            DeviceRef::Index(_) => None,
+            DeviceRef::Name(name) => Some(name),
+        }
+    }
+
+    /// Converts to a reference not borrowing the name, if any.
+    pub fn into_owned(self) -> DeviceRef<'static> {
+        match self {
+            DeviceRef::Index(index) => DeviceRef::Index(index),
+            DeviceRef::Name(name) => DeviceRef::Name(Cow::Owned(name.into_owned())),
+        }
+    }
+}
+
+impl std::fmt::Display for DeviceRef<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DeviceRef::Index(index) => write!(f, "{}", index),
+            DeviceRef::Name(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl From<u32> for DeviceRef<'_> {
+    #[inline]
+    fn from(index: u32) -> Self {
+        DeviceRef::Index(index)
+    }
+}
+
+impl<'a> From<&'a str> for DeviceRef<'a> {
+    #[inline]
+    fn from(name: &'a str) -> Self {
+        DeviceRef::Name(Cow::Borrowed(name))
+    }
+}
+
+impl From<String> for DeviceRef<'_> {
+    #[inline]
+    fn from(name: String) -> Self {
+        DeviceRef::Name(Cow::Owned(name))
+    }
+}
+
+impl From<&SinkInfo<'_>> for DeviceRef<'static> {
+    #[inline]
+    fn from(info: &SinkInfo) -> Self {
+        DeviceRef::Index(info.index)
+    }
+}
+
+impl From<&SourceInfo<'_>> for DeviceRef<'static> {
+    #[inline]
+    fn from(info: &SourceInfo) -> Self {
+        DeviceRef::Index(info.index)
+    }
+}
+
+impl From<&CardInfo<'_>> for DeviceRef<'static> {
+    #[inline]
+    fn from(info: &CardInfo) -> Self {
+        DeviceRef::Index(info.index)
+    }
+}
+
+impl Introspector {
+    /// Gets information about a sink, by index or name.
+    ///
+    /// See [`get_sink_info_by_index()`](Self::get_sink_info_by_index) and
+    /// [`get_sink_info_by_name()`](Self::get_sink_info_by_name).
+    pub fn get_sink_info<'d, D, F>(&self, sink: D, callback: F)
+        -> Operation<dyn FnMut(ListResult<&SinkInfo>)>
+        where D: Into<DeviceRef<'d>>,
+              F: FnMut(ListResult<&SinkInfo>) + 'static
+    {
+        match sink.into() {
+            DeviceRef::Index(index) => self.get_sink_info_by_index(index, callback),
+            DeviceRef::Name(name) => self.get_sink_info_by_name(&name, callback),
+        }
+    }
+
+    /// Sets the volume of a sink, by index or name.
+    ///
+    /// See [`set_sink_volume_by_index()`](Self::set_sink_volume_by_index) and
+    /// [`set_sink_volume_by_name()`](Self::set_sink_volume_by_name).
+    pub fn set_sink_volume<'d, D>(&mut self, sink: D, volume: &ChannelVolumes,
+        callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
+        where D: Into<DeviceRef<'d>>
+    {
+        match sink.into() {
+            DeviceRef::Index(index) => self.set_sink_volume_by_index(index, volume, callback),
+            DeviceRef::Name(name) => self.set_sink_volume_by_name(&name, volume, callback),
+        }
+    }
+
+    /// Sets the mute switch of a sink, by index or name.
+    ///
+    /// See [`set_sink_mute_by_index()`](Self::set_sink_mute_by_index) and
+    /// [`set_sink_mute_by_name()`](Self::set_sink_mute_by_name).
+    pub fn set_sink_mute<'d, D>(&mut self, sink: D, mute: bool,
+        callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
+        where D: Into<DeviceRef<'d>>
+    {
+        match sink.into() {
+            DeviceRef::Index(index) => self.set_sink_mute_by_index(index, mute, callback),
+            DeviceRef::Name(name) => self.set_sink_mute_by_name(&name, mute, callback),
+        }
+    }
+
+    /// Suspends/Resumes a sink, by index or name.
+    ///
+    /// See [`suspend_sink_by_index()`](Self::suspend_sink_by_index) and
+    /// [`suspend_sink_by_name()`](Self::suspend_sink_by_name).
+    pub fn suspend_sink<'d, D>(&mut self, sink: D, suspend: bool,
+        callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
+        where D: Into<DeviceRef<'d>>
+    {
+        match sink.into() {
+            DeviceRef::Index(index) => self.suspend_sink_by_index(index, suspend, callback),
+            DeviceRef::Name(name) => self.suspend_sink_by_name(&name, suspend, callback),
+        }
+    }
+
+    /// Changes the port of a sink, by index or name.
+    ///
+    /// See [`set_sink_port_by_index()`](Self::set_sink_port_by_index) and
+    /// [`set_sink_port_by_name()`](Self::set_sink_port_by_name).
+    pub fn set_sink_port<'d, D>(&mut self, sink: D, port: &str,
+        callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
+        where D: Into<DeviceRef<'d>>
+    {
+        match sink.into() {
+            DeviceRef::Index(index) => self.set_sink_port_by_index(index, port, callback),
+            DeviceRef::Name(name) => self.set_sink_port_by_name(&name, port, callback),
+        }
+    }
+
+    /// Gets information about a source, by index or name.
+    ///
+    /// See [`get_source_info_by_index()`](Self::get_source_info_by_index) and
+    /// [`get_source_info_by_name()`](Self::get_source_info_by_name).
+    pub fn get_source_info<'d, D, F>(&self, source: D, callback: F)
+        -> Operation<dyn FnMut(ListResult<&SourceInfo>)>
+        where D: Into<DeviceRef<'d>>,
+              F: FnMut(ListResult<&SourceInfo>) + 'static
+    {
+        match source.into() {
+            DeviceRef::Index(index) => self.get_source_info_by_index(index, callback),
+            DeviceRef::Name(name) => self.get_source_info_by_name(&name, callback),
+        }
+    }
+
+    /// Sets the volume of a source, by index or name.
+    ///
+    /// See [`set_source_volume_by_index()`](Self::set_source_volume_by_index) and
+    /// [`set_source_volume_by_name()`](Self::set_source_volume_by_name).
+    pub fn set_source_volume<'d, D>(&mut self, source: D, volume: &ChannelVolumes,
+        callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
+        where D: Into<DeviceRef<'d>>
+    {
+        match source.into() {
+            DeviceRef::Index(index) => self.set_source_volume_by_index(index, volume, callback),
+            DeviceRef::Name(name) => self.set_source_volume_by_name(&name, volume, callback),
+        }
+    }
+
+    /// Sets the mute switch of a source, by index or name.
+    ///
+    /// See [`set_source_mute_by_index()`](Self::set_source_mute_by_index) and
+    /// [`set_source_mute_by_name()`](Self::set_source_mute_by_name).
+    pub fn set_source_mute<'d, D>(&mut self, source: D, mute: bool,
+        callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
+        where D: Into<DeviceRef<'d>>
+    {
+        match source.into() {
+            DeviceRef::Index(index) => self.set_source_mute_by_index(index, mute, callback),
+            DeviceRef::Name(name) => self.set_source_mute_by_name(&name, mute, callback),
+        }
+    }
+
+    /// Suspends/Resumes a source, by index or name.
+    ///
+    /// See [`suspend_source_by_index()`](Self::suspend_source_by_index) and
+    /// [`suspend_source_by_name()`](Self::suspend_source_by_name).
+    pub fn suspend_source<'d, D>(&mut self, source: D, suspend: bool,
+        callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
+        where D: Into<DeviceRef<'d>>
+    {
+        match source.into() {
+            DeviceRef::Index(index) => self.suspend_source_by_index(index, suspend, callback),
+            DeviceRef::Name(name) => self.suspend_source_by_name(&name, suspend, callback),
+        }
+    }
+
+    /// Changes the port of a source, by index or name.
+    ///
+    /// See [`set_source_port_by_index()`](Self::set_source_port_by_index) and
+    /// [`set_source_port_by_name()`](Self::set_source_port_by_name).
+    pub fn set_source_port<'d, D>(&mut self, source: D, port: &str,
+        callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
+        where D: Into<DeviceRef<'d>>
+    {
+        match source.into() {
+            DeviceRef::Index(index) => self.set_source_port_by_index(index, port, callback),
+            DeviceRef::Name(name) => self.set_source_port_by_name(&name, port, callback),
+        }
+    }
+
+    /// Gets information about a card, by index or name.
+    ///
+    /// See [`get_card_info_by_index()`](Self::get_card_info_by_index) and
+    /// [`get_card_info_by_name()`](Self::get_card_info_by_name).
+    pub fn get_card_info<'d, D, F>(&self, card: D, callback: F)
+        -> Operation<dyn FnMut(ListResult<&CardInfo>)>
+        where D: Into<DeviceRef<'d>>,
+              F: FnMut(ListResult<&CardInfo>) + 'static
+    {
+        match card.into() {
+            DeviceRef::Index(index) => self.get_card_info_by_index(index, callback),
+            DeviceRef::Name(name) => self.get_card_info_by_name(&name, callback),
+        }
+    }
+
+    /// Changes the profile of a card, by index or name.
+    ///
+    /// See [`set_card_profile_by_index()`](Self::set_card_profile_by_index) and
+    /// [`set_card_profile_by_name()`](Self::set_card_profile_by_name).
+    pub fn set_card_profile<'d, D>(&mut self, card: D, profile: &str,
+        callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
+        where D: Into<DeviceRef<'d>>
+    {
+        match card.into() {
+            DeviceRef::Index(index) => self.set_card_profile_by_index(index, profile, callback),
+            DeviceRef::Name(name) => self.set_card_profile_by_name(&name, profile, callback),
+        }
+    }
+
+    /// Moves the specified sink input to a different sink, given by index or name.
+    ///
+    /// See [`move_sink_input_by_index()`](Self::move_sink_input_by_index) and
+    /// [`move_sink_input_by_name()`](Self::move_sink_input_by_name).
+    pub fn move_sink_input<'d, D>(&mut self, index: u32, sink: D,
+        callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
+        where D: Into<DeviceRef<'d>>
+    {
+        match sink.into() {
+            DeviceRef::Index(sink) => self.move_sink_input_by_index(index, sink, callback),
+            DeviceRef::Name(sink) => self.move_sink_input_by_name(index, &sink, callback),
+        }
+    }
+
+    /// Moves the specified source output to a different source, given by index or name.
+    ///
+    /// See [`move_source_output_by_index()`](Self::move_source_output_by_index) and
+    /// [`move_source_output_by_name()`](Self::move_source_output_by_name).
+    pub fn move_source_output<'d, D>(&mut self, index: u32, source: D,
+        callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
+        where D: Into<DeviceRef<'d>>
+    {
+        match source.into() {
+            DeviceRef::Index(source) => self.move_source_output_by_index(index, source, callback),
+            DeviceRef::Name(source) => self.move_source_output_by_name(index, &source, callback),
+        }
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // Sink info
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -289,12 +685,14 @@ pub struct SinkPortInfo<'a> {
     /// Since one group can include both input and output ports, the grouping should be done using
     /// `CardPortInfo` instead of `SinkPortInfo`, but this field is duplicated also in
     /// `SinkPortInfo` (and `SourcePortInfo`) in case someone finds that convenient.
-    #[cfg(any(doc, feature = "pa_v14"))]
-    #[cfg_attr(docsrs, doc(cfg(feature = "pa_v14")))]
+    ///
+    /// This was introduced in PA v14, and is provided regardless of the PA version feature flags
+    /// enabled, as long as the client library in use is new enough; otherwise it is `None`.
     pub availability_group: Option<Cow<'a, str>>,
     /// Port device type.
-    #[cfg(any(doc, feature = "pa_v14"))]
-    #[cfg_attr(docsrs, doc(cfg(feature = "pa_v14")))]
+    ///
+    /// This was introduced in PA v14, and is provided regardless of the PA version feature flags
+    /// enabled, as long as the client library in use is new enough; otherwise it is `Unknown`.
     pub r#type: DevicePortType,
 }
 
@@ -302,6 +700,10 @@ impl<'a> SinkPortInfo<'a> {
     fn new_from_raw(p: *const SinkPortInfoInternal) -> Self {
         assert!(!p.is_null());
         let src = unsafe { &*p };
+        #[cfg(any(doc, feature = "pa_v14"))]
+        let (group, port_type) = (src.availability_group, src.r#type);
+        #[cfg(not(any(doc, feature = "pa_v14")))]
+        let (group, port_type) = unsafe { v14::port_fields(p as *const v14::PortInfo) };
         unsafe {
             SinkPortInfo {
                 name: match src.name.is_null() {
@@ -314,13 +716,11 @@ impl<'a> SinkPortInfo<'a> {
                 },
                 priority: src.priority,
                 available: def::PortAvailable::from_i32(src.available).unwrap(),
-                #[cfg(any(doc, feature = "pa_v14"))]
-                availability_group: match src.availability_group.is_null() {
-                    false => Some(CStr::from_ptr(src.availability_group).to_string_lossy()),
+                availability_group: match group.is_null() {
+                    false => Some(CStr::from_ptr(group).to_string_lossy()),
                     true => None,
                 },
-                #[cfg(any(doc, feature = "pa_v14"))]
-                r#type: DevicePortType::from_u32(src.r#type).unwrap(),
+                r#type: DevicePortType::from_u32(port_type).unwrap_or(DevicePortType::Unknown),
             }
         }
     }
@@ -462,6 +862,7 @@ impl Introspector {
         -> Operation<dyn FnMut(ListResult<&SinkInfo>)>
         where F: FnMut(ListResult<&SinkInfo>) + 'static
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_name = CString::new(name.clone()).unwrap();
@@ -479,6 +880,7 @@ impl Introspector {
         -> Operation<dyn FnMut(ListResult<&SinkInfo>)>
         where F: FnMut(ListResult<&SinkInfo>) + 'static
     {
+        self.check_lock();
         let cb_data = box_closure_get_capi_ptr::<dyn FnMut(ListResult<&SinkInfo>)>(Box::new(callback));
         let ptr = unsafe { capi::pa_context_get_sink_info_by_index(self.context, index,
             Some(get_sink_info_list_cb_proxy), cb_data) };
@@ -491,6 +893,7 @@ impl Introspector {
     pub fn get_sink_info_list<F>(&self, callback: F) -> Operation<dyn FnMut(ListResult<&SinkInfo>)>
         where F: FnMut(ListResult<&SinkInfo>) + 'static
     {
+        self.check_lock();
         let cb_data = box_closure_get_capi_ptr::<dyn FnMut(ListResult<&SinkInfo>)>(Box::new(callback));
         let ptr = unsafe { capi::pa_context_get_sink_info_list(self.context,
             Some(get_sink_info_list_cb_proxy), cb_data) };
@@ -505,6 +908,7 @@ impl Introspector {
     pub fn set_sink_volume_by_index(&mut self, index: u32, volume: &ChannelVolumes,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         let (cb_fn, cb_data): (Option<extern "C" fn(_, _, _)>, _) =
             get_su_capi_params::<_, _>(callback, super::success_cb_proxy);
         let ptr = unsafe { capi::pa_context_set_sink_volume_by_index(self.context, index,
@@ -520,6 +924,7 @@ impl Introspector {
     pub fn set_sink_volume_by_name(&mut self, name: &str, volume: &ChannelVolumes,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_name = CString::new(name.clone()).unwrap();
@@ -539,6 +944,7 @@ impl Introspector {
     pub fn set_sink_mute_by_index(&mut self, index: u32, mute: bool,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         let (cb_fn, cb_data): (Option<extern "C" fn(_, _, _)>, _) =
             get_su_capi_params::<_, _>(callback, super::success_cb_proxy);
         let ptr = unsafe { capi::pa_context_set_sink_mute_by_index(self.context, index, mute as i32,
@@ -554,6 +960,7 @@ impl Introspector {
     pub fn set_sink_mute_by_name(&mut self, name: &str, mute: bool,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_name = CString::new(name.clone()).unwrap();
@@ -573,6 +980,7 @@ impl Introspector {
     pub fn suspend_sink_by_name(&mut self, sink_name: &str, suspend: bool,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_name = CString::new(sink_name.clone()).unwrap();
@@ -593,6 +1001,7 @@ impl Introspector {
     pub fn suspend_sink_by_index(&mut self, index: u32, suspend: bool,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         let (cb_fn, cb_data): (Option<extern "C" fn(_, _, _)>, _) =
             get_su_capi_params::<_, _>(callback, super::success_cb_proxy);
         let ptr = unsafe { capi::pa_context_suspend_sink_by_index(self.context, index,
@@ -608,6 +1017,7 @@ impl Introspector {
     pub fn set_sink_port_by_index(&mut self, index: u32, port: &str,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_port = CString::new(port.clone()).unwrap();
@@ -627,6 +1037,7 @@ impl Introspector {
     pub fn set_sink_port_by_name(&mut self, name: &str, port: &str,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_name = CString::new(name.clone()).unwrap();
@@ -646,7 +1057,7 @@ extern "C"
 fn get_sink_info_list_cb_proxy(_: *mut ContextInternal, i: *const SinkInfoInternal, eol: i32,
     userdata: *mut c_void)
 {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         callback_for_list_instance(i, eol, userdata, SinkInfo::new_from_raw);
     });
 }
@@ -690,12 +1101,14 @@ pub struct SourcePortInfo<'a> {
     /// Since one group can include both input and output ports, the grouping should be done using
     /// `CardPortInfo` instead of `SourcePortInfo`, but this field is duplicated also in
     /// `SourcePortInfo` (and `SinkPortInfo`) in case someone finds that convenient.
-    #[cfg(any(doc, feature = "pa_v14"))]
-    #[cfg_attr(docsrs, doc(cfg(feature = "pa_v14")))]
+    ///
+    /// This was introduced in PA v14, and is provided regardless of the PA version feature flags
+    /// enabled, as long as the client library in use is new enough; otherwise it is `None`.
     pub availability_group: Option<Cow<'a, str>>,
     /// Port device type.
-    #[cfg(any(doc, feature = "pa_v14"))]
-    #[cfg_attr(docsrs, doc(cfg(feature = "pa_v14")))]
+    ///
+    /// This was introduced in PA v14, and is provided regardless of the PA version feature flags
+    /// enabled, as long as the client library in use is new enough; otherwise it is `Unknown`.
     pub r#type: DevicePortType,
 }
 
@@ -703,6 +1116,10 @@ impl<'a> SourcePortInfo<'a> {
     fn new_from_raw(p: *const SourcePortInfoInternal) -> Self {
         assert!(!p.is_null());
         let src = unsafe { &*p };
+        #[cfg(any(doc, feature = "pa_v14"))]
+        let (group, port_type) = (src.availability_group, src.r#type);
+        #[cfg(not(any(doc, feature = "pa_v14")))]
+        let (group, port_type) = unsafe { v14::port_fields(p as *const v14::PortInfo) };
         unsafe {
             SourcePortInfo {
                 name: match src.name.is_null() {
@@ -715,13 +1132,11 @@ impl<'a> SourcePortInfo<'a> {
                 },
                 priority: src.priority,
                 available: def::PortAvailable::from_i32(src.available).unwrap(),
-                #[cfg(any(doc, feature = "pa_v14"))]
-                availability_group: match src.availability_group.is_null() {
-                    false => Some(CStr::from_ptr(src.availability_group).to_string_lossy()),
+                availability_group: match group.is_null() {
+                    false => Some(CStr::from_ptr(group).to_string_lossy()),
                     true => None,
                 },
-                #[cfg(any(doc, feature = "pa_v14"))]
-                r#type: DevicePortType::from_u32(src.r#type).unwrap(),
+                r#type: DevicePortType::from_u32(port_type).unwrap_or(DevicePortType::Unknown),
             }
         }
     }
@@ -866,6 +1281,7 @@ impl Introspector {
         -> Operation<dyn FnMut(ListResult<&SourceInfo>)>
         where F: FnMut(ListResult<&SourceInfo>) + 'static
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_name = CString::new(name.clone()).unwrap();
@@ -883,6 +1299,7 @@ impl Introspector {
         -> Operation<dyn FnMut(ListResult<&SourceInfo>)>
         where F: FnMut(ListResult<&SourceInfo>) + 'static
     {
+        self.check_lock();
         let cb_data = box_closure_get_capi_ptr::<dyn FnMut(ListResult<&SourceInfo>)>(Box::new(callback));
         let ptr = unsafe { capi::pa_context_get_source_info_by_index(self.context, index,
             Some(get_source_info_list_cb_proxy), cb_data) };
@@ -896,6 +1313,7 @@ impl Introspector {
         -> Operation<dyn FnMut(ListResult<&SourceInfo>)>
         where F: FnMut(ListResult<&SourceInfo>) + 'static
     {
+        self.check_lock();
         let cb_data = box_closure_get_capi_ptr::<dyn FnMut(ListResult<&SourceInfo>)>(Box::new(callback));
         let ptr = unsafe { capi::pa_context_get_source_info_list(self.context,
             Some(get_source_info_list_cb_proxy), cb_data) };
@@ -910,6 +1328,7 @@ impl Introspector {
     pub fn set_source_volume_by_index(&mut self, index: u32, volume: &ChannelVolumes,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         let (cb_fn, cb_data): (Option<extern "C" fn(_, _, _)>, _) =
             get_su_capi_params::<_, _>(callback, super::success_cb_proxy);
         let ptr = unsafe { capi::pa_context_set_source_volume_by_index(self.context, index,
@@ -925,6 +1344,7 @@ impl Introspector {
     pub fn set_source_volume_by_name(&mut self, name: &str, volume: &ChannelVolumes,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_name = CString::new(name.clone()).unwrap();
@@ -944,6 +1364,7 @@ impl Introspector {
     pub fn set_source_mute_by_index(&mut self, index: u32, mute: bool,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         let (cb_fn, cb_data): (Option<extern "C" fn(_, _, _)>, _) =
             get_su_capi_params::<_, _>(callback, super::success_cb_proxy);
         let ptr = unsafe { capi::pa_context_set_source_mute_by_index(self.context, index,
@@ -959,6 +1380,7 @@ impl Introspector {
     pub fn set_source_mute_by_name(&mut self, name: &str, mute: bool,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_name = CString::new(name.clone()).unwrap();
@@ -978,6 +1400,7 @@ impl Introspector {
     pub fn suspend_source_by_name(&mut self, name: &str, suspend: bool,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_name = CString::new(name.clone()).unwrap();
@@ -998,6 +1421,7 @@ impl Introspector {
     pub fn suspend_source_by_index(&mut self, index: u32, suspend: bool,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         let (cb_fn, cb_data): (Option<extern "C" fn(_, _, _)>, _) =
             get_su_capi_params::<_, _>(callback, super::success_cb_proxy);
         let ptr = unsafe { capi::pa_context_suspend_source_by_index(self.context, index,
@@ -1013,6 +1437,7 @@ impl Introspector {
     pub fn set_source_port_by_index(&mut self, index: u32, port: &str,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_port = CString::new(port.clone()).unwrap();
@@ -1032,6 +1457,7 @@ impl Introspector {
     pub fn set_source_port_by_name(&mut self, name: &str, port: &str,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_name = CString::new(name.clone()).unwrap();
@@ -1052,7 +1478,7 @@ extern "C"
 fn get_source_info_list_cb_proxy(_: *mut ContextInternal, i: *const SourceInfoInternal, eol: i32,
     userdata: *mut c_void)
 {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         callback_for_list_instance(i, eol, userdata, SourceInfo::new_from_raw);
     });
 }
@@ -1132,6 +1558,7 @@ impl Introspector {
     pub fn get_server_info<F>(&self, callback: F) -> Operation<dyn FnMut(&ServerInfo)>
         where F: FnMut(&ServerInfo) + 'static
     {
+        self.check_lock();
         let cb_data = box_closure_get_capi_ptr::<dyn FnMut(&ServerInfo)>(Box::new(callback));
         let ptr = unsafe { capi::pa_context_get_server_info(self.context,
             Some(get_server_info_cb_proxy), cb_data) };
@@ -1145,7 +1572,7 @@ extern "C"
 fn get_server_info_cb_proxy(_: *mut ContextInternal, i: *const ServerInfoInternal,
     userdata: *mut c_void)
 {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         assert!(!i.is_null());
         let obj = ServerInfo::new_from_raw(i);
 
@@ -1210,6 +1637,7 @@ impl Introspector {
         -> Operation<dyn FnMut(ListResult<&ModuleInfo>)>
         where F: FnMut(ListResult<&ModuleInfo>) + 'static
     {
+        self.check_lock();
         let cb_data = box_closure_get_capi_ptr::<dyn FnMut(ListResult<&ModuleInfo>)>(Box::new(callback));
         let ptr = unsafe { capi::pa_context_get_module_info(self.context, index,
             Some(mod_info_list_cb_proxy), cb_data) };
@@ -1223,6 +1651,7 @@ impl Introspector {
         -> Operation<dyn FnMut(ListResult<&ModuleInfo>)>
         where F: FnMut(ListResult<&ModuleInfo>) + 'static
     {
+        self.check_lock();
         let cb_data = box_closure_get_capi_ptr::<dyn FnMut(ListResult<&ModuleInfo>)>(Box::new(callback));
         let ptr = unsafe { capi::pa_context_get_module_info_list(self.context,
             Some(mod_info_list_cb_proxy), cb_data) };
@@ -1237,6 +1666,7 @@ impl Introspector {
         -> Operation<dyn FnMut(u32)>
         where F: FnMut(u32) + 'static
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_name = CString::new(name.clone()).unwrap();
@@ -1256,6 +1686,7 @@ impl Introspector {
     pub fn unload_module<F>(&mut self, index: u32, callback: F) -> Operation<dyn FnMut(bool)>
         where F: FnMut(bool) + 'static
     {
+        self.check_lock();
         let cb_data = box_closure_get_capi_ptr::<dyn FnMut(bool)>(Box::new(callback));
         let ptr = unsafe { capi::pa_context_unload_module(self.context, index,
             Some(super::success_cb_proxy), cb_data) };
@@ -1270,7 +1701,7 @@ extern "C"
 fn mod_info_list_cb_proxy(_: *mut ContextInternal, i: *const ModuleInfoInternal, eol: i32,
     userdata: *mut c_void)
 {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         callback_for_list_instance(i, eol, userdata, ModuleInfo::new_from_raw);
     });
 }
@@ -1280,7 +1711,7 @@ fn mod_info_list_cb_proxy(_: *mut ContextInternal, i: *const ModuleInfoInternal,
 /// Warning: This is for single-use cases only! It destroys the actual closure callback.
 extern "C"
 fn context_index_cb_proxy(_: *mut ContextInternal, index: u32, userdata: *mut c_void) {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         // Note, destroys closure callback after use - restoring outer box means it gets dropped
         let mut callback = get_su_callback::<dyn FnMut(u32)>(userdata);
         (callback)(index);
@@ -1307,6 +1738,7 @@ impl Introspector {
         message_parameters: &str, callback: F) -> Operation<dyn FnMut(bool, Option<String>)>
         where F: FnMut(bool, Option<String>) + 'static
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_recipient_name = CString::new(recipient_name.clone()).unwrap();
@@ -1319,12 +1751,43 @@ impl Introspector {
             Some(send_message_to_object_cb_proxy), cb_data) };
         Operation::from_raw(ptr, cb_data as *mut Box<dyn FnMut(bool, Option<String>)>)
     }
+
+    /// Send a message to an object that registered a message handler, if supported.
+    ///
+    /// This is the same as [`send_message_to_object()`](Self::send_message_to_object), but is
+    /// available regardless of the PA version feature flags enabled, with the function being
+    /// resolved at runtime. Returns `None` if the client library in use is too old to provide it.
+    ///
+    /// Note that the server must also be new enough to support messaging, which can be checked with
+    /// [`Capabilities`](crate::capability::Capabilities).
+    pub fn try_send_message_to_object<F>(&mut self, recipient_name: &str, message: &str,
+        message_parameters: &str, callback: F) -> Option<Operation<dyn FnMut(bool, Option<String>)>>
+        where F: FnMut(bool, Option<String>) + 'static
+    {
+        self.check_lock();
+        type SendMessageFn = unsafe extern "C" fn(*mut ContextInternal, *const c_char,
+            *const c_char, *const c_char, capi::pa_context_string_cb_t, *mut c_void)
+            -> *mut capi::pa_operation;
+
+        let symbol = capi::find_symbol("pa_context_send_message_to_object")?;
+        let send_message: SendMessageFn = unsafe { std::mem::transmute(symbol) };
+
+        // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
+        // as_ptr() giving dangling pointers!
+        let c_recipient_name = CString::new(recipient_name).unwrap();
+        let c_message = CString::new(message).unwrap();
+        let c_message_parameters = CString::new(message_parameters).unwrap();
+
+        let cb_data = box_closure_get_capi_ptr::<dyn FnMut(bool, Option<String>)>(Box::new(callback));
+        let ptr = unsafe { send_message(self.context, c_recipient_name.as_ptr(), c_message.as_ptr(),
+            c_message_parameters.as_ptr(), Some(send_message_to_object_cb_proxy), cb_data) };
+        Some(Operation::from_raw(ptr, cb_data as *mut Box<dyn FnMut(bool, Option<String>)>))
+    }
 }
 
 /// Proxy for send message to object callbacks.
 ///
 /// Warning: This is for single-use cases only! It destroys the actual closure callback.
-#[cfg(any(doc, feature = "pa_v15"))]
 extern "C"
 fn send_message_to_object_cb_proxy(_: *mut ContextInternal, success: i32, response: *const c_char,
     userdata: *mut c_void)
@@ -1333,7 +1796,7 @@ fn send_message_to_object_cb_proxy(_: *mut ContextInternal, success: i32, respon
         0 => false,
         _ => true,
     };
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         let r = match response.is_null() {
             true => None,
             false => {
@@ -1402,6 +1865,7 @@ impl Introspector {
         -> Operation<dyn FnMut(ListResult<&ClientInfo>)>
         where F: FnMut(ListResult<&ClientInfo>) + 'static
     {
+        self.check_lock();
         let cb_data = box_closure_get_capi_ptr::<dyn FnMut(ListResult<&ClientInfo>)>(Box::new(callback));
         let ptr = unsafe { capi::pa_context_get_client_info(self.context, index,
             Some(get_client_info_list_cb_proxy), cb_data) };
@@ -1415,6 +1879,7 @@ impl Introspector {
         -> Operation<dyn FnMut(ListResult<&ClientInfo>)>
         where F: FnMut(ListResult<&ClientInfo>) + 'static
     {
+        self.check_lock();
         let cb_data = box_closure_get_capi_ptr::<dyn FnMut(ListResult<&ClientInfo>)>(Box::new(callback));
         let ptr = unsafe { capi::pa_context_get_client_info_list(self.context,
             Some(get_client_info_list_cb_proxy), cb_data) };
@@ -1429,6 +1894,7 @@ impl Introspector {
     pub fn kill_client<F>(&mut self, index: u32, callback: F) -> Operation<dyn FnMut(bool)>
         where F: FnMut(bool) + 'static
     {
+        self.check_lock();
         let cb_data = box_closure_get_capi_ptr::<dyn FnMut(bool)>(Box::new(callback));
         let ptr = unsafe { capi::pa_context_kill_client(self.context, index,
             Some(super::success_cb_proxy), cb_data) };
@@ -1443,7 +1909,7 @@ extern "C"
 fn get_client_info_list_cb_proxy(_: *mut ContextInternal, i: *const ClientInfoInternal, eol: i32,
     userdata: *mut c_void)
 {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         callback_for_list_instance(i, eol, userdata, ClientInfo::new_from_raw);
     });
 }
@@ -1545,12 +2011,14 @@ pub struct CardPortInfo<'a> {
     /// The group identifier must be treated as an opaque identifier. The string may look like an
     /// ALSA control name, but applications must not assume any such relationship. The group naming
     /// scheme can change without a warning.
-    #[cfg(any(doc, feature = "pa_v14"))]
-    #[cfg_attr(docsrs, doc(cfg(feature = "pa_v14")))]
+    ///
+    /// This was introduced in PA v14, and is provided regardless of the PA version feature flags
+    /// enabled, as long as the client library in use is new enough; otherwise it is `None`.
     pub availability_group: Option<Cow<'a, str>>,
     /// Port device type.
-    #[cfg(any(doc, feature = "pa_v14"))]
-    #[cfg_attr(docsrs, doc(cfg(feature = "pa_v14")))]
+    ///
+    /// This was introduced in PA v14, and is provided regardless of the PA version feature flags
+    /// enabled, as long as the client library in use is new enough; otherwise it is `Unknown`.
     pub r#type: DevicePortType,
 }
 
@@ -1570,6 +2038,11 @@ impl<'a> CardPortInfo<'a> {
             }
         }
 
+        #[cfg(any(doc, feature = "pa_v14"))]
+        let (group, port_type) = (src.availability_group, src.r#type);
+        #[cfg(not(any(doc, feature = "pa_v14")))]
+        let (group, port_type) = unsafe { v14::card_port_fields(p as *const v14::CardPortInfo) };
+
         unsafe {
             CardPortInfo {
                 name: match src.name.is_null() {
@@ -1586,13 +2059,11 @@ impl<'a> CardPortInfo<'a> {
                 proplist: Proplist::from_raw_weak(src.proplist),
                 latency_offset: src.latency_offset,
                 profiles: profiles_vec,
-                #[cfg(any(doc, feature = "pa_v14"))]
-                availability_group: match src.availability_group.is_null() {
-                    false => Some(CStr::from_ptr(src.availability_group).to_string_lossy()),
+                availability_group: match group.is_null() {
+                    false => Some(CStr::from_ptr(group).to_string_lossy()),
                     true => None,
                 },
-                #[cfg(any(doc, feature = "pa_v14"))]
-                r#type: DevicePortType::from_u32(src.r#type).unwrap(),
+                r#type: DevicePortType::from_u32(port_type).unwrap_or(DevicePortType::Unknown),
             }
         }
     }
@@ -1681,6 +2152,7 @@ impl Introspector {
         -> Operation<dyn FnMut(ListResult<&CardInfo>)>
         where F: FnMut(ListResult<&CardInfo>) + 'static
     {
+        self.check_lock();
         let cb_data = box_closure_get_capi_ptr::<dyn FnMut(ListResult<&CardInfo>)>(Box::new(callback));
         let ptr = unsafe { capi::pa_context_get_card_info_by_index(self.context, index,
             Some(get_card_info_list_cb_proxy), cb_data) };
@@ -1694,6 +2166,7 @@ impl Introspector {
         -> Operation<dyn FnMut(ListResult<&CardInfo>)>
         where F: FnMut(ListResult<&CardInfo>) + 'static
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_name = CString::new(name.clone()).unwrap();
@@ -1710,6 +2183,7 @@ impl Introspector {
     pub fn get_card_info_list<F>(&self, callback: F) -> Operation<dyn FnMut(ListResult<&CardInfo>)>
         where F: FnMut(ListResult<&CardInfo>) + 'static
     {
+        self.check_lock();
         let cb_data = box_closure_get_capi_ptr::<dyn FnMut(ListResult<&CardInfo>)>(Box::new(callback));
         let ptr = unsafe { capi::pa_context_get_card_info_list(self.context,
             Some(get_card_info_list_cb_proxy), cb_data) };
@@ -1724,6 +2198,7 @@ impl Introspector {
     pub fn set_card_profile_by_index(&mut self, index: u32, profile: &str,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_profile = CString::new(profile.clone()).unwrap();
@@ -1743,6 +2218,7 @@ impl Introspector {
     pub fn set_card_profile_by_name(&mut self, name: &str, profile: &str,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_name = CString::new(name.clone()).unwrap();
@@ -1763,6 +2239,7 @@ impl Introspector {
     pub fn set_port_latency_offset(&mut self, card_name: &str, port_name: &str, offset: i64,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_name = CString::new(card_name.clone()).unwrap();
@@ -1783,7 +2260,7 @@ extern "C"
 fn get_card_info_list_cb_proxy(_: *mut ContextInternal, i: *const CardInfoInternal, eol: i32,
     userdata: *mut c_void)
 {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         callback_for_list_instance(i, eol, userdata, CardInfo::new_from_raw);
     });
 }
@@ -1905,6 +2382,7 @@ impl Introspector {
         -> Operation<dyn FnMut(ListResult<&SinkInputInfo>)>
         where F: FnMut(ListResult<&SinkInputInfo>) + 'static
     {
+        self.check_lock();
         let cb_data =
             box_closure_get_capi_ptr::<dyn FnMut(ListResult<&SinkInputInfo>)>(Box::new(callback));
         let ptr = unsafe { capi::pa_context_get_sink_input_info(self.context, index,
@@ -1919,6 +2397,7 @@ impl Introspector {
         -> Operation<dyn FnMut(ListResult<&SinkInputInfo>)>
         where F: FnMut(ListResult<&SinkInputInfo>) + 'static
     {
+        self.check_lock();
         let cb_data =
             box_closure_get_capi_ptr::<dyn FnMut(ListResult<&SinkInputInfo>)>(Box::new(callback));
         let ptr = unsafe { capi::pa_context_get_sink_input_info_list(self.context,
@@ -1934,6 +2413,7 @@ impl Introspector {
     pub fn move_sink_input_by_name(&mut self, index: u32, sink_name: &str,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_name = CString::new(sink_name.clone()).unwrap();
@@ -1953,6 +2433,7 @@ impl Introspector {
     pub fn move_sink_input_by_index(&mut self, index: u32, sink_index: u32,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         let (cb_fn, cb_data): (Option<extern "C" fn(_, _, _)>, _) =
             get_su_capi_params::<_, _>(callback, super::success_cb_proxy);
         let ptr = unsafe { capi::pa_context_move_sink_input_by_index(self.context, index,
@@ -1968,6 +2449,7 @@ impl Introspector {
     pub fn set_sink_input_volume(&mut self, index: u32, volume: &ChannelVolumes,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         let (cb_fn, cb_data): (Option<extern "C" fn(_, _, _)>, _) =
             get_su_capi_params::<_, _>(callback, super::success_cb_proxy);
         let ptr = unsafe { capi::pa_context_set_sink_input_volume(self.context, index,
@@ -1983,6 +2465,7 @@ impl Introspector {
     pub fn set_sink_input_mute(&mut self, index: u32, mute: bool,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         let (cb_fn, cb_data): (Option<extern "C" fn(_, _, _)>, _) =
             get_su_capi_params::<_, _>(callback, super::success_cb_proxy);
         let ptr = unsafe { capi::pa_context_set_sink_input_mute(self.context, index, mute as i32,
@@ -1998,6 +2481,7 @@ impl Introspector {
     pub fn kill_sink_input<F>(&mut self, index: u32, callback: F) -> Operation<dyn FnMut(bool)>
         where F: FnMut(bool) + 'static
     {
+        self.check_lock();
         let cb_data = box_closure_get_capi_ptr::<dyn FnMut(bool)>(Box::new(callback));
         let ptr = unsafe { capi::pa_context_kill_sink_input(self.context, index,
             Some(super::success_cb_proxy), cb_data) };
@@ -2012,7 +2496,7 @@ extern "C"
 fn get_sink_input_info_list_cb_proxy(_: *mut ContextInternal, i: *const SinkInputInfoInternal,
     eol: i32, userdata: *mut c_void)
 {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         callback_for_list_instance(i, eol, userdata, SinkInputInfo::new_from_raw);
     });
 }
@@ -2134,6 +2618,7 @@ impl Introspector {
         -> Operation<dyn FnMut(ListResult<&SourceOutputInfo>)>
         where F: FnMut(ListResult<&SourceOutputInfo>) + 'static
     {
+        self.check_lock();
         let cb_data =
             box_closure_get_capi_ptr::<dyn FnMut(ListResult<&SourceOutputInfo>)>(Box::new(callback));
         let ptr = unsafe { capi::pa_context_get_source_output_info(self.context, index,
@@ -2148,6 +2633,7 @@ impl Introspector {
         -> Operation<dyn FnMut(ListResult<&SourceOutputInfo>)>
         where F: FnMut(ListResult<&SourceOutputInfo>) + 'static
     {
+        self.check_lock();
         let cb_data =
             box_closure_get_capi_ptr::<dyn FnMut(ListResult<&SourceOutputInfo>)>(Box::new(callback));
         let ptr = unsafe { capi::pa_context_get_source_output_info_list(self.context,
@@ -2163,6 +2649,7 @@ impl Introspector {
     pub fn move_source_output_by_name(&mut self, index: u32, source_name: &str,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_name = CString::new(source_name.clone()).unwrap();
@@ -2182,6 +2669,7 @@ impl Introspector {
     pub fn move_source_output_by_index(&mut self, index: u32, source_index: u32,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         let (cb_fn, cb_data): (Option<extern "C" fn(_, _, _)>, _) =
             get_su_capi_params::<_, _>(callback, super::success_cb_proxy);
         let ptr = unsafe { capi::pa_context_move_source_output_by_index(self.context, index,
@@ -2197,6 +2685,7 @@ impl Introspector {
     pub fn set_source_output_volume(&mut self, index: u32, volume: &ChannelVolumes,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         let (cb_fn, cb_data): (Option<extern "C" fn(_, _, _)>, _) =
             get_su_capi_params::<_, _>(callback, super::success_cb_proxy);
         let ptr = unsafe { capi::pa_context_set_source_output_volume(self.context, index,
@@ -2212,6 +2701,7 @@ impl Introspector {
     pub fn set_source_output_mute(&mut self, index: u32, mute: bool,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         let (cb_fn, cb_data): (Option<extern "C" fn(_, _, _)>, _) =
             get_su_capi_params::<_, _>(callback, super::success_cb_proxy);
         let ptr = unsafe { capi::pa_context_set_source_output_mute(self.context, index, mute as i32,
@@ -2227,6 +2717,7 @@ impl Introspector {
     pub fn kill_source_output<F>(&mut self, index: u32, callback: F) -> Operation<dyn FnMut(bool)>
         where F: FnMut(bool) + 'static
     {
+        self.check_lock();
         let cb_data = box_closure_get_capi_ptr::<dyn FnMut(bool)>(Box::new(callback));
         let ptr = unsafe { capi::pa_context_kill_source_output(self.context, index,
             Some(super::success_cb_proxy), cb_data) };
@@ -2241,7 +2732,7 @@ extern "C"
 fn get_source_output_info_list_cb_proxy(_: *mut ContextInternal, i: *const SourceOutputInfoInternal,
     eol: i32, userdata: *mut c_void)
 {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         callback_for_list_instance(i, eol, userdata, SourceOutputInfo::new_from_raw);
     });
 }
@@ -2257,6 +2748,7 @@ impl Introspector {
     pub fn stat<F>(&self, callback: F) -> Operation<dyn FnMut(&StatInfo)>
         where F: FnMut(&StatInfo) + 'static
     {
+        self.check_lock();
         let cb_data = box_closure_get_capi_ptr::<dyn FnMut(&StatInfo)>(Box::new(callback));
         let ptr =
             unsafe { capi::pa_context_stat(self.context, Some(get_stat_info_cb_proxy), cb_data) };
@@ -2269,7 +2761,7 @@ impl Introspector {
 /// Warning: This is for single-use cases only! It destroys the actual closure callback.
 extern "C"
 fn get_stat_info_cb_proxy(_: *mut ContextInternal, i: *const StatInfo, userdata: *mut c_void) {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         assert!(!i.is_null());
         // Note, destroys closure callback after use - restoring outer box means it gets dropped
         let mut callback = get_su_callback::<dyn FnMut(&StatInfo)>(userdata);
@@ -2347,6 +2839,7 @@ impl Introspector {
         -> Operation<dyn FnMut(ListResult<&SampleInfo>)>
         where F: FnMut(ListResult<&SampleInfo>) + 'static
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_name = CString::new(name.clone()).unwrap();
@@ -2364,6 +2857,7 @@ impl Introspector {
         -> Operation<dyn FnMut(ListResult<&SampleInfo>)>
         where F: FnMut(ListResult<&SampleInfo>) + 'static
     {
+        self.check_lock();
         let cb_data = box_closure_get_capi_ptr::<dyn FnMut(ListResult<&SampleInfo>)>(Box::new(callback));
         let ptr = unsafe { capi::pa_context_get_sample_info_by_index(self.context, index,
             Some(get_sample_info_list_cb_proxy), cb_data) };
@@ -2377,6 +2871,7 @@ impl Introspector {
         -> Operation<dyn FnMut(ListResult<&SampleInfo>)>
         where F: FnMut(ListResult<&SampleInfo>) + 'static
     {
+        self.check_lock();
         let cb_data = box_closure_get_capi_ptr::<dyn FnMut(ListResult<&SampleInfo>)>(Box::new(callback));
         let ptr = unsafe { capi::pa_context_get_sample_info_list(self.context,
             Some(get_sample_info_list_cb_proxy), cb_data) };
@@ -2391,7 +2886,19 @@ extern "C"
 fn get_sample_info_list_cb_proxy(_: *mut ContextInternal, i: *const SampleInfoInternal, eol: i32,
     userdata: *mut c_void)
 {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         callback_for_list_instance(i, eol, userdata, SampleInfo::new_from_raw);
     });
 }
+
+#[test]
+fn device_ref_parse() {
+    assert_eq!(DeviceRef::parse("3"), DeviceRef::Index(3));
+    assert_eq!(DeviceRef::parse("alsa_output.0"), DeviceRef::from("alsa_output.0"));
+    assert_eq!(DeviceRef::parse("+3"), DeviceRef::Name(Cow::Borrowed("+3")));
+    assert_eq!(DeviceRef::parse("99999999999"), DeviceRef::from("99999999999"));
+    assert_eq!(DeviceRef::parse("@DEFAULT_SINK@"), DeviceRef::DEFAULT_SINK);
+    assert_eq!(DeviceRef::DEFAULT_MONITOR.name(), Some("@DEFAULT_MONITOR@"));
+    assert_eq!(DeviceRef::from(7).to_string(), "7");
+    assert_eq!(DeviceRef::from(String::from("x")).into_owned().index(), None);
+}
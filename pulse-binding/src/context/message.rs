@@ -0,0 +1,661 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+//
+// Portions of documentation are copied from the LGPL 2.1+ licensed PulseAudio C headers on a
+// fair-use basis, as discussed in the overall project readme (available in the git repository).
+
+//! Encoding and decoding of message parameters.
+//!
+//! # Overview
+//!
+//! Messages sent to server objects with [`Introspector::send_message_to_object()`] carry their
+//! parameters, and receive their response, as text in the format defined by the [messaging API].
+//! Each value is an element enclosed in curly braces: simple values (strings, numbers and booleans)
+//! are held as text, with any `{`, `}` and `\` characters escaped with a backslash, while lists
+//! hold further elements. Whitespace between elements is ignored. For instance, a list of two
+//! strings, followed by a number, is written as `{{first}{second}}{5}`.
+//!
+//! A parameter string is built with a [`Writer`], and decoded into a tree of [`Value`]s with
+//! [`parse()`]. Rust values are converted to and from elements through the [`Encode`] and
+//! [`Decode`] traits, implemented for strings, numbers, booleans, options (with `None` being an
+//! empty element), vectors and tuples (as lists), such that [`to_string()`] and [`from_str()`] can
+//! convert whole parameter strings:
+//!
+//! ```rust,ignore
+//! use libpulse_binding::context::message;
+//!
+//! assert_eq!(message::to_string(&("sbc", true, 3)), "{{sbc}{1}{3}}");
+//! let codecs: Vec<(String, String)> = message::from_str("{{{sbc}{SBC}}{{aac}{AAC}}}")?;
+//! ```
+//!
+//! # Sending messages
+//!
+//! [`Introspector::send_typed_message()`] sends a message with encoded parameters, decoding the
+//! response, and [`Introspector::list_message_handlers()`] discovers the objects accepting
+//! messages, by way of the `list-handlers` message of the `/core` object. Both are available
+//! regardless of the PA version feature flags enabled, being resolved at runtime, as with
+//! [`Introspector::try_send_message_to_object()`].
+//!
+//! [messaging API]: https://gitlab.freedesktop.org/pulseaudio/pulseaudio/-/blob/master/doc/messaging_api.txt
+
+use std::fmt;
+use super::introspect::Introspector;
+use crate::operation::Operation;
+
+/// Path of the core object, which handles messages about the server as a whole.
+pub const CORE_PATH: &str = "/core";
+
+/// A decoded element of a parameter string.
+///
+/// Since simple values are all held as text, their type is only determined upon conversion, with
+/// [`Decode`]. An empty element (`{}`) is decoded as an empty string, but is also accepted as an
+/// empty list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    /// A simple value, unescaped.
+    String(String),
+    /// A list of elements.
+    List(Vec<Value>),
+}
+
+impl Value {
+    /// Gets the text of a simple value, or `None` for a list.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            Value::List(_) => None,
+        }
+    }
+
+    /// Gets the elements of a list, or `None` for a simple value, other than an empty one.
+    pub fn as_list(&self) -> Option<&[Value]> {
+        match self {
+            Value::List(list) => Some(list),
+            Value::String(s) if s.is_empty() => Some(&[]),
+            Value::String(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    /// Writes the value as an element, as encoded in a parameter string.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&to_string(self))
+    }
+}
+
+/// Error in decoding a parameter string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The text is malformed. Holds the byte offset at which the problem was found.
+    Syntax(usize),
+    /// An element was not of the type expected. Holds a description of the type expected.
+    UnexpectedType(&'static str),
+    /// A list did not have the number of elements expected.
+    Length {
+        /// Number of elements expected.
+        expected: usize,
+        /// Number of elements found.
+        found: usize,
+    },
+}
+
+impl std::error::Error for DecodeError {}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::Syntax(pos) => write!(f, "Malformed parameters at offset {}", pos),
+            DecodeError::UnexpectedType(t) => write!(f, "Expected {} element", t),
+            DecodeError::Length { expected, found } => {
+                write!(f, "Expected list of {} elements, found {}", expected, found)
+            },
+        }
+    }
+}
+
+/// A builder of parameter strings.
+///
+/// Each `write_*` method appends a single element; lists are built by enclosing elements between
+/// calls to [`begin_list()`](Self::begin_list) and [`end_list()`](Self::end_list).
+#[derive(Debug, Default, Clone)]
+pub struct Writer {
+    buffer: String,
+}
+
+impl Writer {
+    /// Creates a new, empty writer.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a list.
+    pub fn begin_list(&mut self) -> &mut Self {
+        self.buffer.push('{');
+        self
+    }
+
+    /// Ends a list.
+    pub fn end_list(&mut self) -> &mut Self {
+        self.buffer.push('}');
+        self
+    }
+
+    /// Writes a string, escaping it as necessary.
+    pub fn write_string(&mut self, value: &str) -> &mut Self {
+        self.buffer.reserve(value.len() + 2);
+        self.buffer.push('{');
+        for c in value.chars() {
+            if c == '{' || c == '}' || c == '\\' {
+                self.buffer.push('\\');
+            }
+            self.buffer.push(c);
+        }
+        self.buffer.push('}');
+        self
+    }
+
+    /// Writes text as is, enclosed in braces if `add_braces`.
+    ///
+    /// This allows an already encoded list or value to be included. No checks are made.
+    pub fn write_raw(&mut self, value: &str, add_braces: bool) -> &mut Self {
+        match add_braces {
+            true => self.write_unescaped(value),
+            false => {
+                self.buffer.push_str(value);
+                self
+            },
+        }
+    }
+
+    /// Writes a boolean, as `1` or `0`.
+    pub fn write_bool(&mut self, value: bool) -> &mut Self {
+        self.write_unescaped(if value { "1" } else { "0" })
+    }
+
+    /// Writes a signed integer.
+    pub fn write_i64(&mut self, value: i64) -> &mut Self {
+        self.write_unescaped(&value.to_string())
+    }
+
+    /// Writes an unsigned integer.
+    pub fn write_u64(&mut self, value: u64) -> &mut Self {
+        self.write_unescaped(&value.to_string())
+    }
+
+    /// Writes a floating point number.
+    pub fn write_f64(&mut self, value: f64) -> &mut Self {
+        self.write_unescaped(&value.to_string())
+    }
+
+    /// Writes any value that can be encoded.
+    pub fn write<T: Encode + ?Sized>(&mut self, value: &T) -> &mut Self {
+        value.encode(self);
+        self
+    }
+
+    /// Gets the parameter string written so far.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.buffer
+    }
+
+    /// Converts into the parameter string written.
+    #[inline]
+    pub fn into_string(self) -> String {
+        self.buffer
+    }
+
+    /// Writes text needing no escaping.
+    fn write_unescaped(&mut self, value: &str) -> &mut Self {
+        self.buffer.push('{');
+        self.buffer.push_str(value);
+        self.buffer.push('}');
+        self
+    }
+}
+
+/// Conversion of a value into an element of a parameter string.
+pub trait Encode {
+    /// Writes the value as a single element.
+    fn encode(&self, writer: &mut Writer);
+}
+
+/// Conversion of an element of a parameter string into a value.
+pub trait Decode: Sized {
+    /// Converts an element into a value.
+    fn decode(value: &Value) -> Result<Self, DecodeError>;
+}
+
+/// Encodes a value as a parameter string.
+pub fn to_string<T: Encode + ?Sized>(value: &T) -> String {
+    let mut writer = Writer::new();
+    value.encode(&mut writer);
+    writer.into_string()
+}
+
+/// Decodes a parameter string holding a single element into a value.
+///
+/// An empty string is taken as a single empty element, such that it can be decoded as `()`, an
+/// empty string, `None` or an empty list.
+pub fn from_str<T: Decode>(s: &str) -> Result<T, DecodeError> {
+    let mut elements = parse(s)?;
+    match elements.len() {
+        0 => T::decode(&Value::String(String::new())),
+        1 => T::decode(&elements.remove(0)),
+        found => Err(DecodeError::Length { expected: 1, found }),
+    }
+}
+
+/// Parses a parameter string into its elements.
+pub fn parse(s: &str) -> Result<Vec<Value>, DecodeError> {
+    let mut parser = Parser { bytes: s.as_bytes(), pos: 0 };
+    let elements = parser.elements()?;
+    match parser.pos == s.len() {
+        true => Ok(elements),
+        // An unmatched closing brace
+        false => Err(DecodeError::Syntax(parser.pos)),
+    }
+}
+
+/// A parser of parameter strings.
+struct Parser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// Parses elements up to the end of the text or of the enclosing list.
+    fn elements(&mut self) -> Result<Vec<Value>, DecodeError> {
+        let mut elements = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.bytes.get(self.pos) {
+                None | Some(b'}') => return Ok(elements),
+                Some(b'{') => elements.push(self.element()?),
+                Some(_) => return Err(DecodeError::Syntax(self.pos)),
+            }
+        }
+    }
+
+    /// Parses a single element, starting at its opening brace.
+    fn element(&mut self) -> Result<Value, DecodeError> {
+        self.pos += 1;
+        let start = self.pos;
+        self.skip_whitespace();
+        if self.bytes.get(self.pos) == Some(&b'{') {
+            let elements = self.elements()?;
+            return match self.bytes.get(self.pos) {
+                Some(b'}') => {
+                    self.pos += 1;
+                    Ok(Value::List(elements))
+                },
+                _ => Err(DecodeError::Syntax(self.pos)),
+            };
+        }
+        self.pos = start;
+        let mut text = Vec::new();
+        loop {
+            match self.bytes.get(self.pos) {
+                Some(b'}') => break,
+                Some(b'\\') if self.pos + 1 < self.bytes.len() => {
+                    text.push(self.bytes[self.pos + 1]);
+                    self.pos += 2;
+                },
+                Some(b'{') | Some(b'\\') | None => return Err(DecodeError::Syntax(self.pos)),
+                Some(&c) => {
+                    text.push(c);
+                    self.pos += 1;
+                },
+            }
+        }
+        self.pos += 1;
+        // Only ASCII characters are removed in unescaping, so the text remains valid UTF-8
+        Ok(Value::String(String::from_utf8(text).map_err(|_| DecodeError::Syntax(start))?))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.bytes.get(self.pos).map_or(false, u8::is_ascii_whitespace) {
+            self.pos += 1;
+        }
+    }
+}
+
+impl Encode for Value {
+    fn encode(&self, writer: &mut Writer) {
+        match self {
+            Value::String(s) => {
+                writer.write_string(s);
+            },
+            Value::List(list) => {
+                writer.begin_list();
+                for element in list {
+                    element.encode(writer);
+                }
+                writer.end_list();
+            },
+        }
+    }
+}
+
+impl Decode for Value {
+    #[inline]
+    fn decode(value: &Value) -> Result<Self, DecodeError> {
+        Ok(value.clone())
+    }
+}
+
+impl Encode for str {
+    #[inline]
+    fn encode(&self, writer: &mut Writer) {
+        writer.write_string(self);
+    }
+}
+
+impl Encode for String {
+    #[inline]
+    fn encode(&self, writer: &mut Writer) {
+        writer.write_string(self);
+    }
+}
+
+impl Decode for String {
+    fn decode(value: &Value) -> Result<Self, DecodeError> {
+        value.as_str().map(str::to_owned).ok_or(DecodeError::UnexpectedType("string"))
+    }
+}
+
+impl Encode for bool {
+    #[inline]
+    fn encode(&self, writer: &mut Writer) {
+        writer.write_bool(*self);
+    }
+}
+
+impl Decode for bool {
+    /// Accepts the same values as the server, such as `1`, `yes` and `true`.
+    fn decode(value: &Value) -> Result<Self, DecodeError> {
+        let text = value.as_str().map(|s| s.trim().to_ascii_lowercase());
+        match text.as_deref() {
+            Some("1") | Some("y") | Some("yes") | Some("true") | Some("on") => Ok(true),
+            Some("0") | Some("n") | Some("no") | Some("false") | Some("off") => Ok(false),
+            _ => Err(DecodeError::UnexpectedType("boolean")),
+        }
+    }
+}
+
+macro_rules! numbers {
+    ($what:expr, $write:ident as $wide:ty, $($t:ty),+) => {
+        $(
+            impl Encode for $t {
+                #[inline]
+                fn encode(&self, writer: &mut Writer) {
+                    writer.$write(*self as $wide);
+                }
+            }
+
+            impl Decode for $t {
+                fn decode(value: &Value) -> Result<Self, DecodeError> {
+                    value.as_str()
+                        .and_then(|s| s.trim().parse().ok())
+                        .ok_or(DecodeError::UnexpectedType($what))
+                }
+            }
+        )+
+    };
+}
+
+numbers!("integer", write_i64 as i64, i8, i16, i32, i64, isize);
+numbers!("unsigned integer", write_u64 as u64, u8, u16, u32, u64, usize);
+numbers!("number", write_f64 as f64, f32, f64);
+
+impl<T: Encode> Encode for Option<T> {
+    /// Writes `None` as an empty element.
+    fn encode(&self, writer: &mut Writer) {
+        match self {
+            Some(value) => value.encode(writer),
+            None => {
+                writer.write_string("");
+            },
+        }
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    /// Decodes an empty element as `None`.
+    fn decode(value: &Value) -> Result<Self, DecodeError> {
+        match value {
+            Value::String(s) if s.is_empty() => Ok(None),
+            value => T::decode(value).map(Some),
+        }
+    }
+}
+
+impl<T: Encode> Encode for [T] {
+    fn encode(&self, writer: &mut Writer) {
+        writer.begin_list();
+        for element in self {
+            element.encode(writer);
+        }
+        writer.end_list();
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    #[inline]
+    fn encode(&self, writer: &mut Writer) {
+        self.as_slice().encode(writer);
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode(value: &Value) -> Result<Self, DecodeError> {
+        value.as_list()
+            .ok_or(DecodeError::UnexpectedType("list"))?
+            .iter()
+            .map(T::decode)
+            .collect()
+    }
+}
+
+impl<T: Encode + ?Sized> Encode for &T {
+    #[inline]
+    fn encode(&self, writer: &mut Writer) {
+        (**self).encode(writer);
+    }
+}
+
+impl Encode for () {
+    /// Writes nothing, as for a message without parameters.
+    #[inline]
+    fn encode(&self, _writer: &mut Writer) {}
+}
+
+impl Decode for () {
+    /// Accepts any element, ignoring it, as for a response of no interest.
+    #[inline]
+    fn decode(_value: &Value) -> Result<Self, DecodeError> {
+        Ok(())
+    }
+}
+
+macro_rules! tuples {
+    ($(($len:expr; $($t:ident $i:tt),+)),+) => {
+        $(
+            impl<$($t: Encode),+> Encode for ($($t,)+) {
+                fn encode(&self, writer: &mut Writer) {
+                    writer.begin_list();
+                    $(self.$i.encode(writer);)+
+                    writer.end_list();
+                }
+            }
+
+            impl<$($t: Decode),+> Decode for ($($t,)+) {
+                fn decode(value: &Value) -> Result<Self, DecodeError> {
+                    let list = value.as_list().ok_or(DecodeError::UnexpectedType("list"))?;
+                    if list.len() != $len {
+                        return Err(DecodeError::Length { expected: $len, found: list.len() });
+                    }
+                    Ok(($($t::decode(&list[$i])?,)+))
+                }
+            }
+        )+
+    };
+}
+
+tuples! {
+    (1; A 0),
+    (2; A 0, B 1),
+    (3; A 0, B 1, C 2),
+    (4; A 0, B 1, C 2, D 3),
+    (5; A 0, B 1, C 2, D 3, E 4),
+    (6; A 0, B 1, C 2, D 3, E 4, F 5)
+}
+
+/// An object accepting messages, as listed by [`Introspector::list_message_handlers()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageHandler {
+    /// Object path, to send messages to.
+    pub path: String,
+    /// Description of the handler.
+    pub description: String,
+}
+
+impl Decode for MessageHandler {
+    fn decode(value: &Value) -> Result<Self, DecodeError> {
+        let (path, description) = Decode::decode(value)?;
+        Ok(Self { path, description })
+    }
+}
+
+/// Error in sending a message with [`Introspector::send_typed_message()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MessageError {
+    /// The message failed, with no such object or message for instance. Holds the response, if
+    /// any.
+    Failed(Option<String>),
+    /// The response could not be decoded.
+    Decode(DecodeError),
+}
+
+impl std::error::Error for MessageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MessageError::Decode(e) => Some(e),
+            MessageError::Failed(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for MessageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MessageError::Failed(Some(response)) => write!(f, "Message failed: {}", response),
+            MessageError::Failed(None) => write!(f, "Message failed"),
+            MessageError::Decode(e) => write!(f, "Invalid response: {}", e),
+        }
+    }
+}
+
+type MessageCb = dyn FnMut(bool, Option<String>);
+
+impl Introspector {
+    /// Sends a message to an object that registered a message handler, with the given parameters
+    /// encoded, decoding the response.
+    ///
+    /// Use `&()` for a message without parameters, and `()` as the response type where the
+    /// response is of no interest.
+    ///
+    /// Returns `None` if the client library in use is too old to support messaging. See
+    /// [`try_send_message_to_object()`](Self::try_send_message_to_object).
+    pub fn send_typed_message<P, R, F>(&mut self, recipient_name: &str, message: &str,
+        parameters: &P, callback: F) -> Option<Operation<MessageCb>>
+        where P: Encode + ?Sized,
+              R: Decode,
+              F: FnOnce(Result<R, MessageError>) + 'static
+    {
+        let mut callback = Some(callback);
+        self.try_send_message_to_object(recipient_name, message, &to_string(parameters),
+            move |success, response| {
+                let result = match success {
+                    true => from_str(response.as_deref().unwrap_or(""))
+                        .map_err(MessageError::Decode),
+                    false => Err(MessageError::Failed(response)),
+                };
+                if let Some(callback) = callback.take() {
+                    callback(result);
+                }
+            })
+    }
+
+    /// Gets the objects that registered a message handler, with the `list-handlers` message of the
+    /// core object.
+    ///
+    /// Returns `None` if the client library in use is too old to support messaging.
+    pub fn list_message_handlers<F>(&mut self, callback: F) -> Option<Operation<MessageCb>>
+        where F: FnOnce(Result<Vec<MessageHandler>, MessageError>) + 'static
+    {
+        self.send_typed_message(CORE_PATH, "list-handlers", &(), callback)
+    }
+}
+
+#[test]
+fn encoding() {
+    assert_eq!(to_string("plain"), "{plain}");
+    assert_eq!(to_string("a{b}c\\d"), "{a\\{b\\}c\\\\d}");
+    assert_eq!(to_string(&("sbc", true, 3)), "{{sbc}{1}{3}}");
+    assert_eq!(to_string(&vec![-1i32, 2]), "{{-1}{2}}");
+    assert_eq!(to_string(&Some(1.5f64)), "{1.5}");
+    assert_eq!(to_string(&None::<u32>), "{}");
+    assert_eq!(to_string(&()), "");
+
+    let mut writer = Writer::new();
+    writer.begin_list().write_string("x").write_u64(7).end_list().write_raw("{{y}}", false);
+    assert_eq!(writer.as_str(), "{{x}{7}}{{y}}");
+}
+
+#[test]
+fn decoding() {
+    let response = " {{{/core} {Core message handler}}\n\
+        {{/card/bluez/bluez5} {Bluetooth \\{A2DP\\}}}} ";
+    let handlers: Vec<MessageHandler> = from_str(response).unwrap();
+    assert_eq!(handlers, [
+        MessageHandler { path: "/core".into(), description: "Core message handler".into() },
+        MessageHandler {
+            path: "/card/bluez/bluez5".into(),
+            description: "Bluetooth {A2DP}".into(),
+        },
+    ]);
+
+    assert_eq!(parse("{a}{ {b} }").unwrap(), [Value::String("a".into()),
+        Value::List(vec![Value::String("b".into())])]);
+    assert_eq!(from_str::<(String, bool, i64, f64)>("{{ x}{yes}{-3}{0.25}}").unwrap(),
+        (" x".to_owned(), true, -3, 0.25));
+    assert_eq!(from_str::<Option<u8>>("{}").unwrap(), None);
+    assert_eq!(from_str::<Vec<u8>>("{}").unwrap(), []);
+    assert_eq!(from_str::<()>("").unwrap(), ());
+
+    let value: Value = from_str("{{a\\}b}{}}").unwrap();
+    assert_eq!(value.to_string(), "{{a\\}b}{}}");
+    assert_eq!(value.as_list().map(<[Value]>::len), Some(2));
+}
+
+#[test]
+fn decoding_errors() {
+    assert_eq!(parse("{a"), Err(DecodeError::Syntax(2)));
+    assert_eq!(parse("{a}}"), Err(DecodeError::Syntax(3)));
+    assert_eq!(parse("x{a}"), Err(DecodeError::Syntax(0)));
+    assert_eq!(parse("{a{b}}"), Err(DecodeError::Syntax(2)));
+    assert_eq!(from_str::<u8>("{256}"), Err(DecodeError::UnexpectedType("unsigned integer")));
+    assert_eq!(from_str::<String>("{{a}}"), Err(DecodeError::UnexpectedType("string")));
+    assert_eq!(from_str::<(u8, u8)>("{{1}}"), Err(DecodeError::Length { expected: 2, found: 1 }));
+    assert_eq!(from_str::<u8>("{1}{2}"), Err(DecodeError::Length { expected: 1, found: 2 }));
+}
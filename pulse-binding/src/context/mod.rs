@@ -62,12 +62,19 @@
 //! * [`context::introspect`](mod@crate::context::introspect)
 //! * [`context::subscribe`](mod@crate::context::subscribe)
 
+pub mod config;
+pub mod connect;
+pub mod cookie;
+pub mod event_sound;
 pub mod ext_device_manager;
 pub mod ext_device_restore;
 pub mod ext_stream_restore;
 pub mod introspect;
+pub mod message;
 pub mod scache;
+pub mod server;
 pub mod subscribe;
+pub mod supervisor;
 
 use std::os::raw::{c_char, c_void};
 use std::ffi::{CStr, CString};
@@ -76,7 +83,8 @@ use std::rc::Rc;
 use bitflags::bitflags;
 use num_derive::{FromPrimitive, ToPrimitive};
 use crate::{def, sample};
-use crate::mainloop::api::{Mainloop, MainloopInnerType};
+use crate::mainloop::api::{Mainloop, MainloopApi, MainloopInnerType};
+use crate::mainloop::lockcheck;
 use crate::mainloop::events;
 use crate::mainloop::events::timer::{TimeEvent, TimeEventRef};
 use crate::operation::Operation;
@@ -96,10 +104,12 @@ pub struct Context {
     weak: bool,
     /// Multi-use callback closure pointers.
     cb_ptrs: CallbackPointers,
+    /// Memory-backed file holding a cookie loaded with `load_cookie()`, kept open since the library
+    /// only reads it upon connecting.
+    _cookie_file: Option<std::fs::File>,
 }
 
-unsafe impl Send for Context {}
-unsafe impl Sync for Context {}
+unsafe impl crate::mainloop::threaded::LockBound for Context {}
 
 /// Holds copies of callback closure pointers, for those that are “multi-use” (may be fired multiple
 /// times), for freeing at the appropriate time.
@@ -221,14 +231,18 @@ impl Context {
     /// reason, or if the version of the PulseAudio client system library at runtime is found to be
     /// older than the minimum version set via this crate’s feature flags (as a means to help
     /// prevent “forward” compatibility problems, as discussed in the project `COMPATIBILITY.md`
-    /// documentation).
+    /// documentation). It also fails if the client library could not be loaded, with the `dlopen`
+    /// feature.
     pub fn new(mainloop: &impl Mainloop, name: &str) -> Option<Self> {
+        if !capi::is_available() {
+            return None;
+        }
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_name = CString::new(name.clone()).unwrap();
         let ptr =
             unsafe { capi::pa_context_new(mainloop.inner().get_api().as_ref(), c_name.as_ptr()) };
-        Self::create(ptr)
+        Self::create(ptr, mainloop.inner().get_api_ptr())
     }
 
     /// Instantiates a new connection context with an abstract mainloop API and an application name,
@@ -238,20 +252,24 @@ impl Context {
     /// reason, or if the version of the PulseAudio client system library at runtime is found to be
     /// older than the minimum version set via this crate’s feature flags (as a means to help
     /// prevent “forward” compatibility problems, as discussed in the project `COMPATIBILITY.md`
-    /// documentation).
+    /// documentation). It also fails if the client library could not be loaded, with the `dlopen`
+    /// feature.
     pub fn new_with_proplist(mainloop: &impl Mainloop, name: &str, proplist: &Proplist)
         -> Option<Self>
     {
+        if !capi::is_available() {
+            return None;
+        }
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_name = CString::new(name.clone()).unwrap();
         let ptr = unsafe { capi::pa_context_new_with_proplist(mainloop.inner().get_api().as_ref(),
             c_name.as_ptr(), proplist.0.ptr) };
-        Self::create(ptr)
+        Self::create(ptr, mainloop.inner().get_api_ptr())
     }
 
     /// Internal common creation function
-    fn create(ptr: *mut ContextInternal) -> Option<Self> {
+    fn create(ptr: *mut ContextInternal, api: *const MainloopApi) -> Option<Self> {
         // Block creation if runtime client system library is too old, to block the potential
         // “forward” compatibility problems discussed in the project `COMPATIBILITY.md`
         // documentation.
@@ -260,20 +278,31 @@ impl Context {
         }
 
         match ptr.is_null() {
-            false => Some(Self::from_raw(ptr)),
+            false => {
+                lockcheck::add_context(ptr, api);
+                Some(Self::from_raw(ptr))
+            },
             true => None,
         }
     }
 
+    /// Asserts, in debug builds, that the context may be used from the current thread.
+    #[inline(always)]
+    #[track_caller]
+    pub(crate) fn check_lock(&self) {
+        lockcheck::check_context(self.ptr);
+    }
+
     /// Creates a new `Context` from an existing [`ContextInternal`] pointer.
     #[inline]
     pub(crate) fn from_raw(ptr: *mut ContextInternal) -> Self {
         assert_eq!(false, ptr.is_null());
-        Self { ptr: ptr, weak: false, cb_ptrs: Default::default() }
+        Self { ptr: ptr, weak: false, cb_ptrs: Default::default(), _cookie_file: None }
     }
 
     /// Sets a callback function that is called whenever the context status changes.
     pub fn set_state_callback(&mut self, callback: Option<Box<dyn FnMut() + 'static>>) {
+        self.check_lock();
         let saved = &mut self.cb_ptrs.set_state;
         *saved = NotifyCb::new(callback);
         let (cb_fn, cb_data) = saved.get_capi_params(notify_cb_proxy_multi);
@@ -289,6 +318,7 @@ impl Context {
     pub fn set_event_callback(&mut self,
         callback: Option<Box<dyn FnMut(String, Proplist) + 'static>>)
     {
+        self.check_lock();
         let saved = &mut self.cb_ptrs.event;
         *saved = EventCb::new(callback);
         let (cb_fn, cb_data) = saved.get_capi_params(event_cb_proxy);
@@ -298,18 +328,21 @@ impl Context {
     /// Gets the error number of the last failed operation.
     #[inline]
     pub fn errno(&self) -> PAErr {
+        self.check_lock();
         PAErr(unsafe { capi::pa_context_errno(self.ptr) })
     }
 
     /// Checks if some data is pending to be written to the connection (returns `true` if so).
     #[inline]
     pub fn is_pending(&self) -> bool {
+        self.check_lock();
         unsafe { capi::pa_context_is_pending(self.ptr) != 0 }
     }
 
     /// Gets the current context status.
     #[inline]
     pub fn get_state(&self) -> State {
+        self.check_lock();
         unsafe { capi::pa_context_get_state(self.ptr).into() }
     }
 
@@ -325,6 +358,7 @@ impl Context {
     pub fn connect(&mut self, server: Option<&str>, flags: FlagSet, api: Option<&def::SpawnApi>)
         -> Result<(), PAErr>
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_server = match server {
@@ -344,6 +378,7 @@ impl Context {
     /// Terminates the context connection immediately.
     #[inline]
     pub fn disconnect(&mut self) {
+        self.check_lock();
         unsafe { capi::pa_context_disconnect(self.ptr); }
     }
 
@@ -362,6 +397,7 @@ impl Context {
     pub fn drain<F>(&mut self, callback: F) -> Option<Operation<dyn FnMut()>>
         where F: FnMut() + 'static
     {
+        self.check_lock();
         let cb_data = box_closure_get_capi_ptr::<dyn FnMut()>(Box::new(callback));
         let ptr =
             unsafe { capi::pa_context_drain(self.ptr, Some(notify_cb_proxy_single), cb_data) };
@@ -384,6 +420,7 @@ impl Context {
     pub fn exit_daemon<F>(&mut self, callback: F) -> Operation<dyn FnMut(bool)>
         where F: FnMut(bool) + 'static
     {
+        self.check_lock();
         let cb_data = box_closure_get_capi_ptr::<dyn FnMut(bool)>(Box::new(callback));
         let ptr =
             unsafe { capi::pa_context_exit_daemon(self.ptr, Some(success_cb_proxy), cb_data) };
@@ -398,6 +435,7 @@ impl Context {
     pub fn set_default_sink<F>(&mut self, name: &str, callback: F) -> Operation<dyn FnMut(bool)>
         where F: FnMut(bool) + 'static
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_name = CString::new(name.clone()).unwrap();
@@ -416,6 +454,7 @@ impl Context {
     pub fn set_default_source<F>(&mut self, name: &str, callback: F) -> Operation<dyn FnMut(bool)>
         where F: FnMut(bool) + 'static
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_name = CString::new(name.clone()).unwrap();
@@ -431,6 +470,7 @@ impl Context {
     /// Returns `true` when the connection is to a local daemon. Returns `None` on error, for
     /// instance when no connection has been made yet.
     pub fn is_local(&self) -> Option<bool> {
+        self.check_lock();
         match unsafe { capi::pa_context_is_local(self.ptr) } {
             1 => Some(true),
             0 => Some(false),
@@ -444,6 +484,7 @@ impl Context {
     pub fn set_name<F>(&mut self, name: &str, callback: F) -> Operation<dyn FnMut(bool)>
         where F: FnMut(bool) + 'static
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_name = CString::new(name.clone()).unwrap();
@@ -456,6 +497,7 @@ impl Context {
 
     /// Gets the server name this context is connected to.
     pub fn get_server(&self) -> Option<String> {
+        self.check_lock();
         let ptr = unsafe { capi::pa_context_get_server(self.ptr) };
         match ptr.is_null() {
             false => Some(unsafe { CStr::from_ptr(ptr).to_string_lossy().into_owned() }),
@@ -466,6 +508,7 @@ impl Context {
     /// Gets the protocol version of the library.
     #[inline]
     pub fn get_protocol_version(&self) -> u32 {
+        self.check_lock();
         unsafe { capi::pa_context_get_protocol_version(self.ptr) }
     }
 
@@ -473,6 +516,7 @@ impl Context {
     ///
     /// Returns `None` on error.
     pub fn get_server_protocol_version(&self) -> Option<u32> {
+        self.check_lock();
         match unsafe { capi::pa_context_get_server_protocol_version(self.ptr) } {
             def::INVALID_INDEX => None,
             r => Some(r),
@@ -491,6 +535,7 @@ impl Context {
         -> Operation<dyn FnMut(bool)>
         where F: FnMut(bool) + 'static
     {
+        self.check_lock();
         let cb_data = box_closure_get_capi_ptr::<dyn FnMut(bool)>(Box::new(callback));
         let ptr = unsafe { capi::pa_context_proplist_update(self.ptr, mode, pl.0.ptr,
             Some(success_cb_proxy), cb_data) };
@@ -503,6 +548,7 @@ impl Context {
     pub fn proplist_remove<F>(&mut self, keys: &[&str], callback: F) -> Operation<dyn FnMut(bool)>
         where F: FnMut(bool) + 'static
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let mut c_keys: Vec<CString> = Vec::with_capacity(keys.len());
@@ -531,6 +577,7 @@ impl Context {
     ///
     /// Returns `None` on error.
     pub fn get_index(&self) -> Option<u32> {
+        self.check_lock();
         match unsafe { capi::pa_context_get_index(self.ptr) } {
             def::INVALID_INDEX => None,
             r => Some(r),
@@ -564,6 +611,7 @@ impl Context {
         where T: Mainloop + 'static,
               F: FnMut(TimeEventRef<T::MI>) + 'static
     {
+        self.check_lock();
         let inner_for_wrapper = mainloop.inner();
         let wrapper_cb = Box::new(move |ptr| {
             let ref_obj = TimeEventRef::<T::MI>::from_raw(ptr, Rc::clone(&inner_for_wrapper));
@@ -602,6 +650,7 @@ impl Context {
     /// let size = context.get_tile_size(Some(ss)).unwrap();
     /// ```
     pub fn get_tile_size(&self, ss: Option<&sample::Spec>) -> Option<usize> {
+        self.check_lock();
         let p_ss = ss.map_or(null::<capi::pa_sample_spec>(), |s| s.as_ref());
         match unsafe { capi::pa_context_get_tile_size(self.ptr, p_ss) } {
             std::usize::MAX => None,
@@ -616,6 +665,7 @@ impl Context {
     /// all, but if it happens that you know what the authentication cookie is and your application
     /// needs to load it from a non-standard location, feel free to use this function.
     pub fn load_cookie_from_file(&mut self, cookie_file_path: &str) -> Result<(), PAErr> {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_path = CString::new(cookie_file_path.clone()).unwrap();
@@ -641,7 +691,7 @@ impl Drop for Context {
 extern "C"
 fn success_cb_proxy(_: *mut ContextInternal, success: i32, userdata: *mut c_void) {
     let success_actual = match success { 0 => false, _ => true };
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         assert!(!userdata.is_null());
         // Note, destroys closure callback after use - restoring outer box means it gets dropped
         let mut callback = unsafe { Box::from_raw(userdata as *mut Box<dyn FnMut(bool)>) };
@@ -654,7 +704,7 @@ fn success_cb_proxy(_: *mut ContextInternal, success: i32, userdata: *mut c_void
 /// Warning: This is for single-use cases only! It destroys the actual closure callback.
 extern "C"
 fn notify_cb_proxy_single(_: *mut ContextInternal, userdata: *mut c_void) {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         assert!(!userdata.is_null());
         // Note, destroys closure callback after use - restoring outer box means it gets dropped
         let mut callback = unsafe { Box::from_raw(userdata as *mut Box<dyn FnMut()>) };
@@ -668,7 +718,7 @@ fn notify_cb_proxy_single(_: *mut ContextInternal, userdata: *mut c_void) {
 /// must be accomplished separately to avoid a memory leak.
 extern "C"
 fn notify_cb_proxy_multi(_: *mut ContextInternal, userdata: *mut c_void) {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         let callback = NotifyCb::get_callback(userdata);
         (callback)();
     });
@@ -682,7 +732,7 @@ extern "C"
 fn event_cb_proxy(_: *mut ContextInternal, name: *const c_char, proplist: *mut ProplistInternal,
     userdata: *mut c_void)
 {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         assert!(!name.is_null());
         let n = {
             let tmp = unsafe { CStr::from_ptr(name) };
@@ -700,7 +750,7 @@ fn event_cb_proxy(_: *mut ContextInternal, name: *const c_char, proplist: *mut P
 /// Warning: This is for single-use cases only! It destroys the actual closure callback.
 extern "C"
 fn ext_test_cb_proxy(_: *mut ContextInternal, version: u32, userdata: *mut c_void) {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         // Note, destroys closure callback after use - restoring outer box means it gets dropped
         let mut callback = get_su_callback::<dyn FnMut(u32)>(userdata);
         (callback)(version);
@@ -713,7 +763,7 @@ fn ext_test_cb_proxy(_: *mut ContextInternal, version: u32, userdata: *mut c_voi
 /// must be accomplished separately to avoid a memory leak.
 extern "C"
 fn ext_subscribe_cb_proxy(_: *mut ContextInternal, userdata: *mut c_void) {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         let callback = ExtSubscribeCb::get_callback(userdata);
         (callback)();
     });
@@ -27,6 +27,16 @@
 //! To complete the upload, [`Stream::finish_upload()`] is called and the sample will receive the
 //! same name as the stream. If the upload should be aborted, simply call [`Stream::disconnect()`].
 //!
+//! Alternatively, [`Context::upload_sample()`] takes care of all of this for data held in memory,
+//! and [`Context::upload_sample_file()`] for a WAV file. Each returns an [`Upload`], which tracks
+//! the progress of the upload, and which can be waited upon just like an [`Operation`]:
+//!
+//! ```rust,ignore
+//! let upload = my_context.upload_sample("bell", &spec, None, None, data)?;
+//! mainloop.wait_for_all(&[&upload], None)?;
+//! upload.outcome().unwrap()?;
+//! ```
+//!
 //! # Playing samples
 //!
 //! To play back a sample, simply call [`Context::play_sample()`]:
@@ -51,18 +61,43 @@
 //! When a sample is no longer needed, it should be removed on the server to save resources. The
 //! sample is deleted using [`Context::remove_sample()`].
 //!
+//! # Managing samples
+//!
+//! A [`SampleCache`], obtained with [`Context::sample_cache()`], brings together the listing,
+//! replacement and removal of samples by name.
+//!
 //! [`stream`]: mod@crate::stream
 //! [`Stream::connect_upload()`]: crate::stream::Stream::connect_upload
 //! [`Stream::finish_upload()`]: crate::stream::Stream::finish_upload
 //! [`Stream::disconnect()`]: crate::stream::Stream::disconnect
 
+use std::cell::RefCell;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::mem::ManuallyDrop;
 use std::os::raw::{c_char, c_void};
 use std::ffi::CString;
-use std::ptr::null;
+use std::path::Path;
+use std::ptr::{null, null_mut};
+use std::rc::Rc;
 use super::{ContextInternal, Context};
-use crate::def;
-use crate::callbacks::{box_closure_get_capi_ptr, get_su_capi_params, get_su_callback};
-use crate::{operation::Operation, volume::Volume, proplist::Proplist};
+use super::introspect::SampleInfo;
+use crate::{def, wav};
+use crate::callbacks::{
+    ListResult, box_closure_get_capi_ptr, get_su_capi_params, get_su_callback
+};
+use crate::channelmap::Map;
+use crate::error::{Code, Error};
+use crate::mainloop::lockcheck;
+use crate::operation::{AnyOperation, Operation, State as OperationState};
+use crate::proplist::{properties, Proplist};
+use crate::sample::Spec;
+use crate::stream::{SeekMode, State as StreamState, Stream};
+use crate::volume::Volume;
+
+/// Name of the operation recorded in errors from uploads.
+const UPLOAD: &str = "upload sample";
 
 impl Context {
     /// Removes a sample from the sample cache.
@@ -75,6 +110,7 @@ impl Context {
     pub fn remove_sample<F>(&mut self, name: &str, callback: F) -> Operation<dyn FnMut(bool)>
         where F: FnMut(bool) + 'static
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_name = CString::new(name.clone()).unwrap();
@@ -102,6 +138,7 @@ impl Context {
     pub fn play_sample(&mut self, name: &str, dev: Option<&str>, volume: Option<Volume>,
         callback: Option<Box<dyn FnMut(bool) + 'static>>) -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_name = CString::new(name.clone()).unwrap();
@@ -145,6 +182,7 @@ impl Context {
         callback: Option<Box<dyn FnMut(Result<u32, ()>) + 'static>>)
         -> Operation<dyn FnMut(Result<u32, ()>)>
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a
         // variable, leading to as_ptr() giving dangling pointers!
         let c_name = CString::new(name.clone()).unwrap();
@@ -164,6 +202,427 @@ impl Context {
         };
         Operation::from_raw(ptr, cb_data as *mut Box<dyn FnMut(Result<u32, ()>)>)
     }
+
+    /// Uploads a sample to the sample cache.
+    ///
+    /// This performs the whole upload: creating an upload stream, writing the data to it as
+    /// requested by the server, and finishing the upload. An existing sample of the same name is
+    /// replaced once the upload completes.
+    ///
+    /// # Params
+    ///
+    /// * `name`: Name of the sample.
+    /// * `spec`: Sample specification of the data.
+    /// * `map`: Channel map of the data, or `None` for the default for the number of channels.
+    /// * `proplist`: Optional property list for the sample.
+    /// * `data`: The audio data, which must be made up of whole frames, and not be empty.
+    ///
+    /// Returns an [`Upload`], which must be kept until the upload completes, since dropping it
+    /// abandons the upload.
+    pub fn upload_sample(&mut self, name: &str, spec: &Spec, map: Option<&Map>,
+        proplist: Option<&Proplist>, data: Vec<u8>) -> Result<Upload, Error>
+    {
+        self.check_lock();
+        Upload::start(self, name, spec, map, proplist, data)
+    }
+
+    /// Uploads a sample read from a WAV file to the sample cache.
+    ///
+    /// The sample specification and channel map are taken from the file, and its path is recorded
+    /// in the [`properties::MEDIA_FILENAME`] property, unless the given property list sets it. The
+    /// file is read in full before the upload starts. A trailing partial frame, as found in a
+    /// truncated file, is dropped.
+    ///
+    /// See [`upload_sample()`](Self::upload_sample) for details.
+    pub fn upload_sample_file<P: AsRef<Path>>(&mut self, name: &str, path: P,
+        proplist: Option<&Proplist>) -> Result<Upload, UploadFileError>
+    {
+        let path = path.as_ref();
+        let mut reader = wav::Reader::new(BufReader::new(File::open(path)?))?;
+        let (spec, map) = (*reader.spec(), *reader.map());
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        data.truncate(data.len() - data.len() % spec.frame_size());
+
+        let mut proplist = match proplist {
+            Some(proplist) => proplist.clone(),
+            None => Proplist::new().ok_or_else(|| Error::new(Code::Internal.into(), UPLOAD))?,
+        };
+        if proplist.contains(properties::MEDIA_FILENAME) != Some(true) {
+            let _ = proplist.set_str(properties::MEDIA_FILENAME, &path.to_string_lossy());
+        }
+        Ok(self.upload_sample(name, &spec, Some(&map), Some(&proplist), data)?)
+    }
+
+    /// Gets a sample cache manager linked to the current context.
+    #[inline]
+    pub fn sample_cache(&self) -> SampleCache {
+        self.check_lock();
+        unsafe { capi::pa_context_ref(self.ptr) };
+        SampleCache { context: self.ptr }
+    }
+}
+
+/// Error in uploading a sample from a file.
+#[derive(Debug)]
+pub enum UploadFileError {
+    /// Reading the file failed.
+    File(wav::Error),
+    /// Starting the upload failed.
+    Upload(Error),
+}
+
+impl std::error::Error for UploadFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            UploadFileError::File(e) => Some(e),
+            UploadFileError::Upload(e) => Some(e),
+        }
+    }
+}
+
+impl fmt::Display for UploadFileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UploadFileError::File(e) => write!(f, "Failed to read sample file: {}", e),
+            UploadFileError::Upload(e) => e.fmt(f),
+        }
+    }
+}
+
+impl From<wav::Error> for UploadFileError {
+    #[inline]
+    fn from(e: wav::Error) -> Self {
+        UploadFileError::File(e)
+    }
+}
+
+impl From<io::Error> for UploadFileError {
+    #[inline]
+    fn from(e: io::Error) -> Self {
+        UploadFileError::File(e.into())
+    }
+}
+
+impl From<Error> for UploadFileError {
+    #[inline]
+    fn from(e: Error) -> Self {
+        UploadFileError::Upload(e)
+    }
+}
+
+/// A sample upload in progress, as started with [`Context::upload_sample()`].
+///
+/// As an [`AnyOperation`], this is [`Running`](OperationState::Running) until the upload either
+/// completes or fails, whereupon it is [`Done`](OperationState::Done) and
+/// [`outcome()`](Self::outcome) gives the result, or until [cancelled](Self::cancel).
+///
+/// Dropping it before then abandons the upload.
+pub struct Upload {
+    /// The upload stream.
+    stream: Rc<RefCell<Stream>>,
+    /// State shared with the stream callbacks.
+    state: Rc<RefCell<UploadState>>,
+}
+
+/// State of an [`Upload`], shared with the stream callbacks.
+struct UploadState {
+    name: String,
+    data: Vec<u8>,
+    /// The number of bytes written so far.
+    written: usize,
+    /// Whether all data has been written, and the upload finished.
+    finishing: bool,
+    cancelled: bool,
+    outcome: Option<Result<(), Error>>,
+    /// Callback to execute upon completion, if any.
+    callback: Option<Box<UploadCb>>,
+}
+
+type UploadCb = dyn FnOnce(Result<(), Error>);
+
+/// A completion callback, along with the outcome to give it.
+type Completion = (Box<UploadCb>, Result<(), Error>);
+
+impl Upload {
+    fn start(context: &mut Context, name: &str, spec: &Spec, map: Option<&Map>,
+        proplist: Option<&Proplist>, data: Vec<u8>) -> Result<Self, Error>
+    {
+        if data.is_empty() || !spec.is_valid() || data.len() % spec.frame_size() != 0 {
+            return Err(Error::new(Code::Invalid.into(), UPLOAD).with_target(name));
+        }
+        let stream = match proplist {
+            Some(proplist) => Stream::new_with_proplist(context, name, spec, map,
+                &mut proplist.clone()),
+            None => Stream::new(context, name, spec, map),
+        };
+        let mut stream = stream.ok_or_else(|| Error::from_context(context, UPLOAD)
+            .with_target(name))?;
+        stream.connect_upload(data.len())
+            .map_err(|e| Error::new(e, UPLOAD).with_target(name))?;
+
+        let stream = Rc::new(RefCell::new(stream));
+        let state = Rc::new(RefCell::new(UploadState {
+            name: name.to_owned(),
+            data,
+            written: 0,
+            finishing: false,
+            cancelled: false,
+            outcome: None,
+            callback: None,
+        }));
+
+        // Note, the callbacks only hold weak references to the stream, since it holds them. They
+        // do nothing should either be in use, as with callbacks executed synchronously.
+        let weak_stream = Rc::downgrade(&stream);
+        let write_state = Rc::clone(&state);
+        stream.borrow_mut().set_write_callback(Some(Box::new(move |nbytes| {
+            if let Some(stream) = weak_stream.upgrade() {
+                let completed = match (stream.try_borrow_mut(), write_state.try_borrow_mut()) {
+                    (Ok(mut stream), Ok(mut state)) => {
+                        state.write(&mut stream, nbytes);
+                        state.completed()
+                    },
+                    _ => None,
+                };
+                // Executed with nothing borrowed, such that the callback may use the upload
+                if let Some((callback, outcome)) = completed {
+                    callback(outcome);
+                }
+            }
+        })));
+        let weak_stream = Rc::downgrade(&stream);
+        let update_state = Rc::clone(&state);
+        stream.borrow_mut().set_state_callback(Some(Box::new(move || {
+            if let Some(stream) = weak_stream.upgrade() {
+                let completed = match (stream.try_borrow_mut(), update_state.try_borrow_mut()) {
+                    (Ok(mut stream), Ok(mut state)) => {
+                        state.update(&mut stream);
+                        state.completed()
+                    },
+                    _ => None,
+                };
+                if let Some((callback, outcome)) = completed {
+                    callback(outcome);
+                }
+            }
+        })));
+        Ok(Self { stream, state })
+    }
+
+    /// Gets the name of the sample.
+    pub fn name(&self) -> String {
+        self.state.borrow().name.clone()
+    }
+
+    /// Gets the outcome of the upload, or `None` if still in progress or cancelled.
+    pub fn outcome(&self) -> Option<Result<(), Error>> {
+        let state = self.state.borrow();
+        match state.cancelled {
+            true => None,
+            false => state.outcome.clone(),
+        }
+    }
+
+    /// Sets a callback to be executed once the upload completes, successfully or not, with its
+    /// outcome.
+    ///
+    /// It is not executed should the upload be cancelled. The upload must not be dropped from
+    /// within the callback, since it is executed from within a callback of the upload stream.
+    pub fn set_completion_callback(&mut self, callback: Option<Box<UploadCb>>) {
+        self.state.borrow_mut().callback = callback;
+    }
+
+    /// Cancels the upload, if still in progress.
+    ///
+    /// An upload cancelled before its stream is ready is disconnected once it becomes so.
+    pub fn cancel(&mut self) {
+        {
+            let mut state = self.state.borrow_mut();
+            if state.cancelled || state.outcome.is_some() {
+                return;
+            }
+            state.cancelled = true;
+        }
+        let _ = self.stream.borrow_mut().disconnect();
+    }
+}
+
+impl AnyOperation for Upload {
+    fn get_state(&self) -> OperationState {
+        let state = self.state.borrow();
+        match (state.cancelled, &state.outcome) {
+            (true, _) => OperationState::Cancelled,
+            (false, None) => OperationState::Running,
+            (false, Some(_)) => OperationState::Done,
+        }
+    }
+
+    #[inline]
+    fn cancel(&mut self) {
+        Upload::cancel(self)
+    }
+}
+
+impl UploadState {
+    /// Writes up to the requested number of bytes, finishing the upload once all are written.
+    fn write(&mut self, stream: &mut Stream, nbytes: usize) {
+        if self.finishing || self.cancelled || self.outcome.is_some() {
+            return;
+        }
+        let end = self.data.len().min(self.written.saturating_add(nbytes));
+        let result = stream.write_copy(&self.data[self.written..end], 0, SeekMode::Relative)
+            .and_then(|()| {
+                self.written = end;
+                match end == self.data.len() {
+                    true => {
+                        self.finishing = true;
+                        stream.finish_upload()
+                    },
+                    false => Ok(()),
+                }
+            });
+        if result.is_err() {
+            self.outcome = Some(Err(Error::from_stream(stream, UPLOAD).with_target(&*self.name)));
+            let _ = stream.disconnect();
+        }
+    }
+
+    /// Takes the completion callback along with the outcome to give it, once complete.
+    fn completed(&mut self) -> Option<Completion> {
+        match (&self.outcome, self.cancelled) {
+            (Some(outcome), false) => self.callback.take().map(|cb| (cb, outcome.clone())),
+            _ => None,
+        }
+    }
+
+    /// Records the outcome, once the stream has terminated or failed.
+    fn update(&mut self, stream: &mut Stream) {
+        if self.outcome.is_some() {
+            return;
+        }
+        self.outcome = match stream.get_state() {
+            // Disconnecting fails until the stream is ready
+            StreamState::Ready if self.cancelled => {
+                let _ = stream.disconnect();
+                None
+            },
+            StreamState::Terminated if self.finishing => Some(Ok(())),
+            StreamState::Terminated => {
+                Some(Err(Error::new(Code::Killed.into(), UPLOAD).with_target(&*self.name)))
+            },
+            StreamState::Failed => {
+                Some(Err(Error::from_stream(stream, UPLOAD).with_target(&*self.name)))
+            },
+            _ => None,
+        };
+    }
+}
+
+type SampleInfoListCb = dyn FnMut(ListResult<&SampleInfo>);
+type PlaySampleCb = dyn FnMut(Result<u32, ()>);
+
+/// A manager of the samples in the sample cache, addressing them by name.
+///
+/// Obtained with [`Context::sample_cache()`].
+pub struct SampleCache {
+    context: *mut ContextInternal,
+}
+
+unsafe impl crate::mainloop::threaded::LockBound for SampleCache {}
+
+impl SampleCache {
+    /// Gets information about all samples in the sample cache.
+    ///
+    /// The callback is given the complete list once received, or `Err` on failure.
+    pub fn list<F>(&self, callback: F) -> Operation<SampleInfoListCb>
+        where F: FnOnce(Result<Vec<SampleInfo<'static>>, ()>) + 'static
+    {
+        let mut samples = Vec::new();
+        let mut callback = Some(callback);
+        self.context().introspect().get_sample_info_list(move |result| {
+            let result = match result {
+                ListResult::Item(info) => {
+                    samples.push(owned_sample_info(info));
+                    return;
+                },
+                ListResult::End => Ok(std::mem::take(&mut samples)),
+                ListResult::Error => Err(()),
+            };
+            if let Some(callback) = callback.take() {
+                callback(result);
+            }
+        })
+    }
+
+    /// Uploads a sample under the given name, replacing any existing sample of that name once the
+    /// upload completes.
+    ///
+    /// See [`Context::upload_sample()`].
+    pub fn replace(&self, name: &str, spec: &Spec, map: Option<&Map>,
+        proplist: Option<&Proplist>, data: Vec<u8>) -> Result<Upload, Error>
+    {
+        self.context().upload_sample(name, spec, map, proplist, data)
+    }
+
+    /// Uploads a sample read from a WAV file under the given name, replacing any existing sample
+    /// of that name once the upload completes.
+    ///
+    /// See [`Context::upload_sample_file()`].
+    pub fn replace_from_file<P: AsRef<Path>>(&self, name: &str, path: P,
+        proplist: Option<&Proplist>) -> Result<Upload, UploadFileError>
+    {
+        self.context().upload_sample_file(name, path, proplist)
+    }
+
+    /// Plays the sample of the given name, with the given property list for the playback stream.
+    ///
+    /// See [`Context::play_sample_with_proplist()`].
+    pub fn play(&self, name: &str, dev: Option<&str>, volume: Option<Volume>,
+        proplist: &Proplist, callback: Option<Box<PlaySampleCb>>) -> Operation<PlaySampleCb>
+    {
+        self.context().play_sample_with_proplist(name, dev, volume, proplist, callback)
+    }
+
+    /// Removes the sample of the given name.
+    ///
+    /// The callback must accept a `bool`, which indicates success.
+    ///
+    /// Panics if the underlying C function returns a null pointer.
+    pub fn remove<F>(&self, name: &str, callback: F) -> Operation<dyn FnMut(bool)>
+        where F: FnMut(bool) + 'static
+    {
+        self.context().remove_sample(name, callback)
+    }
+
+    /// Gets a temporary weak wrapper of the context, for access to its methods.
+    fn context(&self) -> ManuallyDrop<Context> {
+        lockcheck::check_context(self.context);
+        ManuallyDrop::new(Context::from_raw(self.context))
+    }
+}
+
+impl Drop for SampleCache {
+    fn drop(&mut self) {
+        unsafe { capi::pa_context_unref(self.context) };
+        self.context = null_mut::<ContextInternal>();
+    }
+}
+
+/// Copies sample information, such that it no longer borrows from the client library.
+fn owned_sample_info(info: &SampleInfo) -> SampleInfo<'static> {
+    SampleInfo {
+        index: info.index,
+        name: info.name.as_ref().map(|n| n.to_string().into()),
+        volume: info.volume,
+        sample_spec: info.sample_spec,
+        channel_map: info.channel_map,
+        duration: info.duration,
+        bytes: info.bytes,
+        lazy: info.lazy,
+        filename: info.filename.as_ref().map(|f| f.to_string().into()),
+        proplist: info.proplist.clone(),
+    }
 }
 
 /// Proxy for completion success callbacks.
@@ -172,9 +631,36 @@ impl Context {
 extern "C"
 fn play_sample_success_cb_proxy(_: *mut ContextInternal, index: u32, userdata: *mut c_void) {
     let index_actual = match index { def::INVALID_INDEX => Err(()), i => Ok(i) };
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         // Note, destroys closure callback after use - restoring outer box means it gets dropped
         let mut callback = get_su_callback::<dyn FnMut(Result<u32, ()>)>(userdata);
         (callback)(index_actual);
     });
 }
+
+#[test]
+fn upload_validation() {
+    use crate::sample::Format;
+
+    let mainloop = crate::mainloop::standard::Mainloop::new().unwrap();
+    let mut context = Context::new(&mainloop, "upload-test").unwrap();
+    let spec = Spec { format: Format::S16le, channels: 2, rate: 44100 };
+    let invalid = Spec { channels: 0, ..spec };
+
+    // Empty, not whole frames, and an invalid spec, all rejected before any stream is created
+    for (spec, len) in &[(spec, 0), (spec, 6), (invalid, 4)] {
+        let e = context.upload_sample("bell", spec, None, None, vec![0; *len]).err().unwrap();
+        assert_eq!(e.code(), Some(Code::Invalid));
+        assert_eq!(e.operation(), Some(UPLOAD));
+        assert_eq!(e.target(), Some("bell"));
+    }
+
+    let path = std::env::temp_dir().join("pulse-binding-no-such-sample.wav");
+    match context.upload_sample_file("bell", &path, None) {
+        Err(e @ UploadFileError::File(_)) => {
+            assert!(e.to_string().starts_with("Failed to read sample file: "));
+            assert!(std::error::Error::source(&e).is_some());
+        },
+        _ => panic!("expected a file error"),
+    }
+}
@@ -0,0 +1,476 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+//
+// Portions of documentation are copied from the LGPL 2.1+ licensed PulseAudio C headers on a
+// fair-use basis, as discussed in the overall project readme (available in the git repository).
+
+//! Server address parsing and selection.
+//!
+//! # Overview
+//!
+//! [`Context::connect()`] accepts a server specification as a plain string. This module provides a
+//! typed representation of such specifications, allowing them to be validated before use, and
+//! inspected afterwards.
+//!
+//! A specification is a whitespace separated list of candidate addresses, tried in order. This is
+//! the format used by the `PULSE_SERVER` environment variable, the `default-server` option of
+//! `client.conf`, and the `PULSE_SERVER` property set on the X11 root window by
+//! `module-x11-publish`. Each candidate takes one of the following forms:
+//!
+//! * `unix:/path/to/socket` or simply `/path/to/socket`: A local Unix domain socket.
+//! * `tcp:host[:port]`: A TCP connection, over either IPv4 or IPv6.
+//! * `tcp4:host[:port]` or `tcp6:host[:port]`: A TCP connection, restricted to IPv4 or IPv6
+//!   respectively. IPv6 addresses must be enclosed in square brackets when a port is given.
+//! * `host[:port]`: Equivalent to `tcp:host[:port]`.
+//!
+//! Any candidate may additionally be prefixed with a machine ID in curly braces, for instance
+//! `{0123456789abcdef}unix:/run/user/1000/pulse/native`, in which case it is only applicable on
+//! the machine with that ID (as used by `module-x11-publish` for its Unix socket entries).
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use libpulse_binding::context::server::ServerList;
+//!
+//! let servers: ServerList = "unix:/run/pulse/native tcp:soundserver:4713".parse()?;
+//! context.connect_to(&servers, FlagSet::NOFLAGS, None)?;
+//! ```
+
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+use super::{Context, FlagSet};
+use super::connect::ConnectError;
+use crate::def::SpawnApi;
+use crate::error::{Code, PAErr};
+use crate::mainloop::standard;
+use crate::proplist::Proplist;
+use crate::time::MicroSeconds;
+
+/// The default port used for TCP connections.
+pub const DEFAULT_PORT: u16 = 4713;
+
+/// Name of the environment variable that may hold a server specification.
+pub const ENV_VAR: &str = "PULSE_SERVER";
+
+/// Address family restriction for TCP connections.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Family {
+    /// Either IPv4 or IPv6 (`tcp:`).
+    Any,
+    /// IPv4 only (`tcp4:`).
+    V4,
+    /// IPv6 only (`tcp6:`).
+    V6,
+}
+
+/// The transport part of a server address.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Transport {
+    /// A Unix domain socket, with absolute path.
+    Unix(String),
+    /// A TCP connection.
+    Tcp {
+        /// Address family restriction.
+        family: Family,
+        /// Host name or address (IPv6 addresses without brackets).
+        host: String,
+        /// Port, if specified (otherwise [`DEFAULT_PORT`] is used).
+        port: Option<u16>,
+    },
+}
+
+/// A single candidate server address.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ServerAddress {
+    /// The machine the address is restricted to, if any.
+    pub machine_id: Option<String>,
+    /// The transport.
+    pub transport: Transport,
+}
+
+/// An ordered list of candidate server addresses.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ServerList(pub Vec<ServerAddress>);
+
+/// Error in parsing a server specification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The specification (or a candidate) was empty.
+    Empty,
+    /// A machine ID prefix was missing its closing brace, or was empty.
+    InvalidMachineId,
+    /// A Unix socket path was not absolute.
+    RelativePath(String),
+    /// A TCP host was missing, or an IPv6 address was missing its closing bracket.
+    InvalidHost(String),
+    /// A TCP port was not a valid port number.
+    InvalidPort(String),
+}
+
+impl std::error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "Empty server address"),
+            ParseError::InvalidMachineId => write!(f, "Invalid machine ID prefix"),
+            ParseError::RelativePath(p) => write!(f, "Socket path is not absolute: {}", p),
+            ParseError::InvalidHost(h) => write!(f, "Invalid host: {}", h),
+            ParseError::InvalidPort(p) => write!(f, "Invalid port: {}", p),
+        }
+    }
+}
+
+impl Transport {
+    /// Checks whether this is a Unix domain socket.
+    #[inline]
+    pub fn is_unix(&self) -> bool {
+        matches!(*self, Transport::Unix(_))
+    }
+
+    /// Checks whether this refers to the local machine, i.e. a Unix domain socket, or TCP
+    /// connection to a loopback address.
+    pub fn is_local(&self) -> bool {
+        match self {
+            Transport::Unix(_) => true,
+            Transport::Tcp { host, .. } => {
+                host == "localhost" || host.parse::<IpAddr>().map_or(false, |a| a.is_loopback())
+            },
+        }
+    }
+}
+
+impl fmt::Display for Transport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Transport::Unix(path) => write!(f, "unix:{}", path),
+            Transport::Tcp { family, host, port } => {
+                let prefix = match family {
+                    Family::Any => "tcp",
+                    Family::V4 => "tcp4",
+                    Family::V6 => "tcp6",
+                };
+                match (host.contains(':'), port) {
+                    (true, Some(port)) => write!(f, "{}:[{}]:{}", prefix, host, port),
+                    (true, None) => write!(f, "{}:[{}]", prefix, host),
+                    (false, Some(port)) => write!(f, "{}:{}:{}", prefix, host, port),
+                    (false, None) => write!(f, "{}:{}", prefix, host),
+                }
+            },
+        }
+    }
+}
+
+impl FromStr for Transport {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseError::Empty);
+        }
+        if let Some(path) = s.strip_prefix("unix:") {
+            return match path.starts_with('/') {
+                true => Ok(Transport::Unix(path.to_string())),
+                false => Err(ParseError::RelativePath(path.to_string())),
+            };
+        }
+        if s.starts_with('/') {
+            return Ok(Transport::Unix(s.to_string()));
+        }
+
+        let (family, rest) = if let Some(rest) = s.strip_prefix("tcp4:") {
+            (Family::V4, rest)
+        } else if let Some(rest) = s.strip_prefix("tcp6:") {
+            (Family::V6, rest)
+        } else if let Some(rest) = s.strip_prefix("tcp:") {
+            (Family::Any, rest)
+        } else {
+            (Family::Any, s)
+        };
+
+        let (host, port) = if let Some(bracketed) = rest.strip_prefix('[') {
+            let end = bracketed.find(']')
+                .ok_or_else(|| ParseError::InvalidHost(rest.to_string()))?;
+            let port = match &bracketed[end + 1..] {
+                "" => None,
+                p => Some(p.strip_prefix(':').ok_or_else(|| ParseError::InvalidPort(p.to_string()))?),
+            };
+            (&bracketed[..end], port)
+        } else {
+            // More than one colon means an unbracketed IPv6 address, without port
+            match rest.find(':') {
+                Some(i) if rest[i + 1..].find(':').is_none() => (&rest[..i], Some(&rest[i + 1..])),
+                _ => (rest, None),
+            }
+        };
+
+        if host.is_empty() {
+            return Err(ParseError::InvalidHost(rest.to_string()));
+        }
+        let port = match port {
+            None => None,
+            Some(p) => match p.parse::<u16>() {
+                Ok(n) if n != 0 => Some(n),
+                _ => return Err(ParseError::InvalidPort(p.to_string())),
+            },
+        };
+        Ok(Transport::Tcp { family, host: host.to_string(), port })
+    }
+}
+
+impl ServerAddress {
+    /// Creates a new address with the given transport, not restricted to any machine.
+    #[inline]
+    pub fn new(transport: Transport) -> Self {
+        Self { machine_id: None, transport }
+    }
+
+    /// Checks whether this address is applicable on the machine with the given ID, i.e. it is
+    /// either not restricted to any machine, or is restricted to that machine.
+    pub fn applies_to(&self, machine_id: Option<&str>) -> bool {
+        match (&self.machine_id, machine_id) {
+            (None, _) => true,
+            (Some(a), Some(b)) => a == b,
+            (Some(_), None) => false,
+        }
+    }
+}
+
+impl fmt::Display for ServerAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(id) = &self.machine_id {
+            write!(f, "{{{}}}", id)?;
+        }
+        write!(f, "{}", self.transport)
+    }
+}
+
+impl FromStr for ServerAddress {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (machine_id, rest) = match s.strip_prefix('{') {
+            Some(rest) => {
+                let end = rest.find('}').ok_or(ParseError::InvalidMachineId)?;
+                if end == 0 {
+                    return Err(ParseError::InvalidMachineId);
+                }
+                (Some(rest[..end].to_string()), &rest[end + 1..])
+            },
+            None => (None, s),
+        };
+        Ok(Self { machine_id, transport: rest.parse()? })
+    }
+}
+
+impl From<Transport> for ServerAddress {
+    #[inline]
+    fn from(transport: Transport) -> Self {
+        Self::new(transport)
+    }
+}
+
+impl ServerList {
+    /// Parses the server specification held in the `PULSE_SERVER` environment variable.
+    ///
+    /// Returns `Ok(None)` if the variable is not set (or is empty).
+    pub fn from_env() -> Result<Option<Self>, ParseError> {
+        match std::env::var(ENV_VAR) {
+            Ok(s) if !s.trim().is_empty() => s.parse().map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Gets an iterator over all candidates.
+    #[inline]
+    pub fn iter(&self) -> std::slice::Iter<'_, ServerAddress> {
+        self.0.iter()
+    }
+
+    /// Gets the number of candidates.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Checks whether the list is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Gets an iterator over the candidates applicable to the local machine, in priority order.
+    ///
+    /// See [`local_machine_id()`] for how the ID of the local machine is determined.
+    pub fn candidates(&self) -> impl Iterator<Item = &ServerAddress> {
+        let id = local_machine_id();
+        self.0.iter().filter(move |a| a.applies_to(id.as_deref()))
+    }
+
+    /// Probes the applicable candidates in priority order, returning a context connected to the
+    /// first that accepts a connection, along with the address used.
+    ///
+    /// A new context is created (with the given name and property list) for each candidate, since
+    /// a context cannot be reused after a failed connection attempt. Each attempt is abandoned
+    /// after `timeout`, if given. It is advisable to include [`FlagSet::NOAUTOSPAWN`] in `flags`.
+    ///
+    /// Upon failure, the reason for failure of each candidate is returned.
+    pub fn probe(&self, mainloop: &mut standard::Mainloop, name: &str, proplist: &Proplist,
+        flags: FlagSet, timeout: Option<MicroSeconds>)
+        -> Result<(Context, ServerAddress), Vec<(ServerAddress, ConnectError)>>
+    {
+        let mut failures = Vec::new();
+        for candidate in self.candidates() {
+            let server = candidate.transport.to_string();
+            let mut context = match Context::new_with_proplist(mainloop, name, proplist) {
+                Some(c) => c,
+                None => {
+                    failures.push((candidate.clone(),
+                        ConnectError::Other(PAErr::from(Code::Internal))));
+                    continue;
+                },
+            };
+            match context.connect_blocking(mainloop, Some(&server), flags, timeout) {
+                Ok(()) => return Ok((context, candidate.clone())),
+                Err(e) => failures.push((candidate.clone(), e)),
+            }
+        }
+        Err(failures)
+    }
+}
+
+impl fmt::Display for ServerList {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, address) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}", address)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ServerList {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let list = s.split_whitespace().map(str::parse).collect::<Result<Vec<_>, _>>()?;
+        match list.is_empty() {
+            true => Err(ParseError::Empty),
+            false => Ok(ServerList(list)),
+        }
+    }
+}
+
+impl From<ServerAddress> for ServerList {
+    #[inline]
+    fn from(address: ServerAddress) -> Self {
+        ServerList(vec![address])
+    }
+}
+
+impl<'a> IntoIterator for &'a ServerList {
+    type Item = &'a ServerAddress;
+    type IntoIter = std::slice::Iter<'a, ServerAddress>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// Gets the ID of the local machine, as used for machine ID prefixes.
+///
+/// As with PulseAudio itself, this is read from `/etc/machine-id` or `/var/lib/dbus/machine-id`,
+/// falling back to the host name.
+pub fn local_machine_id() -> Option<String> {
+    for path in &["/etc/machine-id", "/var/lib/dbus/machine-id"] {
+        if let Ok(id) = std::fs::read_to_string(path) {
+            let id = id.trim();
+            if !id.is_empty() {
+                return Some(id.to_string());
+            }
+        }
+    }
+    crate::util::get_host_name(256)
+}
+
+impl Context {
+    /// Connects the context to the given list of candidate servers.
+    ///
+    /// This is equivalent to [`connect()`](Self::connect), with the list formatted as a server
+    /// specification; the candidates are tried in order by the library.
+    pub fn connect_to(&mut self, servers: &ServerList, flags: FlagSet, api: Option<&SpawnApi>)
+        -> Result<(), PAErr>
+    {
+        self.connect(Some(&servers.to_string()), flags, api)
+    }
+
+    /// Gets the address of the server this context is connected to, i.e. the candidate that was
+    /// used.
+    ///
+    /// Returns `None` if not connected, or if the server string could not be parsed.
+    pub fn get_server_address(&self) -> Option<ServerAddress> {
+        self.get_server()?.parse().ok()
+    }
+}
+
+#[test]
+fn parse_addresses() {
+    assert_eq!("unix:/run/pulse/native".parse(),
+        Ok(Transport::Unix("/run/pulse/native".into())));
+    assert_eq!("/run/pulse/native".parse(), Ok(Transport::Unix("/run/pulse/native".into())));
+    assert_eq!("unix:native".parse::<Transport>(),
+        Err(ParseError::RelativePath("native".into())));
+    assert_eq!("tcp:host:4713".parse(),
+        Ok(Transport::Tcp { family: Family::Any, host: "host".into(), port: Some(4713) }));
+    assert_eq!("host".parse(),
+        Ok(Transport::Tcp { family: Family::Any, host: "host".into(), port: None }));
+    assert_eq!("tcp4:127.0.0.1".parse(),
+        Ok(Transport::Tcp { family: Family::V4, host: "127.0.0.1".into(), port: None }));
+    assert_eq!("tcp6:[::1]:4000".parse(),
+        Ok(Transport::Tcp { family: Family::V6, host: "::1".into(), port: Some(4000) }));
+    assert_eq!("tcp6:fe80::1".parse(),
+        Ok(Transport::Tcp { family: Family::V6, host: "fe80::1".into(), port: None }));
+    assert_eq!("tcp:host:x".parse::<Transport>(), Err(ParseError::InvalidPort("x".into())));
+    assert_eq!("tcp:[::1".parse::<Transport>(), Err(ParseError::InvalidHost("[::1".into())));
+    assert_eq!("tcp:".parse::<Transport>(), Err(ParseError::InvalidHost("".into())));
+    assert_eq!("{}unix:/a".parse::<ServerAddress>(), Err(ParseError::InvalidMachineId));
+}
+
+#[test]
+fn locality() {
+    let tcp = |host: &str| Transport::Tcp { family: Family::Any, host: host.into(), port: None };
+    assert!(Transport::Unix("/run/pulse/native".into()).is_local());
+    assert!(tcp("localhost").is_local());
+    assert!(tcp("127.0.0.1").is_local());
+    assert!(tcp("127.1.2.3").is_local());
+    assert!(tcp("::1").is_local());
+    assert!(!tcp("127.example.com").is_local());
+    assert!(!tcp("128.0.0.1").is_local());
+    assert!(!tcp("fe80::1").is_local());
+}
+
+#[test]
+fn list_round_trip() {
+    let spec = "{abc123}unix:/run/user/1000/pulse/native tcp6:[::1]:4713 tcp:remote";
+    let list: ServerList = spec.parse().unwrap();
+    assert_eq!(list.len(), 3);
+    assert_eq!(list.0[0].machine_id.as_deref(), Some("abc123"));
+    assert!(list.0[0].applies_to(Some("abc123")));
+    assert!(!list.0[0].applies_to(Some("other")));
+    assert!(list.0[1].transport.is_local());
+    assert!(!list.0[2].transport.is_local());
+    assert_eq!(list.to_string(), spec);
+    assert_eq!("  ".parse::<ServerList>(), Err(ParseError::Empty));
+}
@@ -222,6 +222,7 @@ impl Context {
         -> operation::Operation<dyn FnMut(bool)>
         where F: FnMut(bool) + 'static
     {
+        self.check_lock();
         let cb_data = box_closure_get_capi_ptr::<dyn FnMut(bool)>(Box::new(callback));
         let ptr = unsafe { capi::pa_context_subscribe(self.ptr, mask.bits(),
             Some(super::success_cb_proxy), cb_data) };
@@ -245,6 +246,7 @@ impl Context {
     pub fn set_subscribe_callback(&mut self,
         callback: Option<Box<dyn FnMut(Option<Facility>, Option<Operation>, u32) + 'static>>)
     {
+        self.check_lock();
         let saved = &mut self.cb_ptrs.subscribe;
         *saved = Callback::new(callback);
         let (cb_fn, cb_data) = saved.get_capi_params(cb_proxy);
@@ -258,7 +260,7 @@ impl Context {
 /// must be accomplished separately to avoid a memory leak.
 extern "C"
 fn cb_proxy(_: *mut ContextInternal, et: EventType, index: u32, userdata: *mut c_void) {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         let facility = Facility::from_event(et);
         let operation = Operation::from_event(et);
         let callback = Callback::get_callback(userdata);
@@ -0,0 +1,601 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+//
+// Portions of documentation are copied from the LGPL 2.1+ licensed PulseAudio C headers on a
+// fair-use basis, as discussed in the overall project readme (available in the git repository).
+
+//! Automatically reconnecting connection contexts.
+//!
+//! # Overview
+//!
+//! Once a [`Context`] has entered the [`State::Failed`] or [`State::Terminated`] state, it cannot
+//! be reconnected; a new context object must be created. This happens for instance whenever the
+//! daemon is restarted. Note that [`FlagSet::NOFAIL`] only covers waiting for the daemon to appear
+//! upon the *initial* connection attempt.
+//!
+//! A [`Supervisor`] owns a context on your behalf. When the connection fails or is lost, it
+//! discards the context and, after a delay determined by a [`Backoff`] policy, creates and connects
+//! a new one. Once a new connection becomes ready, it restores:
+//!
+//! * The client property list (initial, plus changes made with [`Supervisor::proplist_update()`]).
+//! * The subscription mask given to [`Supervisor::subscribe()`] along with the callback set with
+//!   [`Supervisor::set_subscribe_callback()`].
+//! * Any streams registered with [`Supervisor::manage_stream()`].
+//!
+//! Connection lifecycle changes are reported to the callback set with
+//! [`Supervisor::set_event_callback()`] in the form of [`Event`] variants.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use libpulse_binding::context::supervisor::{Supervisor, Backoff, Event};
+//! use libpulse_binding::context::FlagSet;
+//! use libpulse_binding::context::subscribe::InterestMaskSet;
+//!
+//! let mut supervisor = Supervisor::new(&mainloop, "FooApp", &proplist, Backoff::default());
+//! supervisor.set_event_callback(Some(Box::new(|event| {
+//!     match event {
+//!         Event::Ready => println!("Connected"),
+//!         Event::Lost(e) => println!("Connection lost: {}", e),
+//!         _ => {},
+//!     }
+//! })));
+//! supervisor.subscribe(InterestMaskSet::SINK | InterestMaskSet::SOURCE);
+//! supervisor.connect(None, FlagSet::NOFLAGS).unwrap();
+//! ```
+//!
+//! # Notes
+//!
+//! All callbacks are executed from the mainloop, and the supervisor must be used following the
+//! same rules as any other object associated with the mainloop (e.g. under the lock of a
+//! [threaded mainloop](mod@crate::mainloop::threaded)).
+
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::rc::{Rc, Weak};
+use super::{Context, ContextInternal, FlagSet, State};
+use super::subscribe::{Facility, InterestMaskSet, Operation as SubscriptionOperation};
+use crate::error::{Code, PAErr};
+use crate::mainloop::api::{Mainloop, MainloopRef};
+use crate::mainloop::events::timer::TimeEvent;
+use crate::proplist::{self, Proplist};
+use crate::stream::Stream;
+use crate::time::{MicroSeconds, MonotonicTs};
+
+/// Reconnection back-off policy.
+///
+/// The delay before the first reconnection attempt is `initial`, with each further consecutive
+/// attempt multiplying the previous delay by `multiplier`, up to a limit of `max`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Backoff {
+    /// Delay before the first reconnection attempt.
+    pub initial: MicroSeconds,
+    /// Upper limit on the delay between attempts.
+    pub max: MicroSeconds,
+    /// Factor by which the delay grows with each consecutive failed attempt.
+    ///
+    /// A value of zero is treated as one, such that the delay never shrinks below `initial`.
+    pub multiplier: u32,
+    /// Number of consecutive attempts to make before giving up, or `None` to never give up.
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for Backoff {
+    /// Half a second initial delay, doubling up to a maximum of thirty seconds, never giving up.
+    fn default() -> Self {
+        Self {
+            initial: MicroSeconds::MILLISECOND * 500,
+            max: MicroSeconds::SECOND * 30,
+            multiplier: 2,
+            max_attempts: None,
+        }
+    }
+}
+
+impl Backoff {
+    /// Gets the delay to wait before the given reconnection attempt (counting from one).
+    pub fn delay_for(&self, attempt: u32) -> MicroSeconds {
+        let exponent = attempt.saturating_sub(1);
+        let delay = (self.multiplier.max(1) as u64).checked_pow(exponent)
+            .and_then(|factor| self.initial.0.checked_mul(factor));
+        match delay {
+            Some(d) if d < self.max.0 => MicroSeconds(d),
+            _ => self.max,
+        }
+    }
+}
+
+/// Connection lifecycle events reported by a [`Supervisor`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// A new connection attempt has been started, with the number of the attempt (zero for the
+    /// initial connection).
+    Connecting(u32),
+    /// The connection is established and all registered state has been restored.
+    Ready,
+    /// The connection failed or was lost, with the error reported by the context.
+    Lost(PAErr),
+    /// A reconnection attempt has been scheduled.
+    Reconnecting {
+        /// Number of the attempt (counting from one).
+        attempt: u32,
+        /// Time that will be waited before making the attempt.
+        delay: MicroSeconds,
+    },
+    /// No further reconnection attempts will be made, either because the [`Backoff::max_attempts`]
+    /// limit was reached or because the failure was one that retrying cannot fix.
+    GaveUp,
+    /// The connection was closed with [`Supervisor::disconnect()`].
+    Disconnected,
+}
+
+/// A stream re-created by a [`Supervisor`] each time a connection becomes ready.
+///
+/// The stream only exists while the connection is ready; it is destroyed when the connection is
+/// lost.
+#[derive(Clone)]
+pub struct ManagedStream(Rc<RefCell<Option<Stream>>>);
+
+impl ManagedStream {
+    /// Checks if the stream currently exists (returns `true` if so).
+    pub fn is_active(&self) -> bool {
+        self.0.borrow().is_some()
+    }
+
+    /// Runs the given closure with the current stream, if it exists.
+    ///
+    /// Returns `None` if the stream does not currently exist.
+    pub fn with<R, F>(&self, f: F) -> Option<R>
+        where F: FnOnce(&mut Stream) -> R
+    {
+        self.0.borrow_mut().as_mut().map(f)
+    }
+}
+
+type SubscribeCb = dyn FnMut(Option<Facility>, Option<SubscriptionOperation>, u32);
+type StreamSetup = dyn FnMut(&mut Context) -> Option<Stream>;
+
+/// Supervisor state, shared with the callbacks of the contexts it creates.
+struct Inner<M>
+    where M: Mainloop + 'static
+{
+    mainloop: Rc<M::MI>,
+    name: String,
+    proplist: Proplist,
+    server: Option<String>,
+    flags: FlagSet,
+    backoff: Backoff,
+    /// Number of consecutive reconnection attempts made since the connection was last ready.
+    attempt: u32,
+    /// Set on explicit disconnection, to stop reconnection.
+    stopped: bool,
+    subscription: InterestMaskSet,
+    subscribe_cb: Rc<RefCell<Option<Box<SubscribeCb>>>>,
+    event_cb: Option<Box<dyn FnMut(Event)>>,
+    /// Managed streams, which must be destroyed before the context they belong to.
+    streams: Vec<(Box<StreamSetup>, ManagedStream)>,
+    retired_streams: Vec<Stream>,
+    context: Option<Context>,
+    /// The current context while lent out by [`Supervisor::with_context()`], during which
+    /// `context` is `None`. Cleared should it be lost or disconnected in the meantime.
+    lent: Option<*mut ContextInternal>,
+    /// Failed contexts awaiting destruction. These cannot be destroyed from within their own
+    /// callbacks, thus destruction is deferred to the next connection attempt.
+    retired: Vec<Context>,
+    timer: Option<TimeEvent<M::MI>>,
+}
+
+/// An owner of a connection context that automatically reconnects it upon failure.
+///
+/// See the [module level documentation](mod@self) for details.
+pub struct Supervisor<M>
+    where M: Mainloop + 'static
+{
+    inner: Rc<RefCell<Inner<M>>>,
+    phantom: PhantomData<M>,
+}
+
+impl<M> Supervisor<M>
+    where M: Mainloop + 'static
+{
+    /// Creates a new supervisor.
+    ///
+    /// Contexts will be created with the given application name and client property list. No
+    /// connection is made until [`connect()`](Self::connect) is called.
+    pub fn new(mainloop: &M, name: &str, proplist: &Proplist, backoff: Backoff) -> Self {
+        let inner = Inner::<M> {
+            mainloop: mainloop.inner(),
+            name: name.to_owned(),
+            proplist: proplist.clone(),
+            server: None,
+            flags: FlagSet::NOFLAGS,
+            backoff,
+            attempt: 0,
+            stopped: true,
+            subscription: InterestMaskSet::NULL,
+            subscribe_cb: Rc::new(RefCell::new(None)),
+            event_cb: None,
+            streams: Vec::new(),
+            retired_streams: Vec::new(),
+            context: None,
+            lent: None,
+            retired: Vec::new(),
+            timer: None,
+        };
+        Self { inner: Rc::new(RefCell::new(inner)), phantom: PhantomData }
+    }
+
+    /// Sets the callback that is called upon connection lifecycle changes.
+    pub fn set_event_callback(&mut self, callback: Option<Box<dyn FnMut(Event) + 'static>>) {
+        self.inner.borrow_mut().event_cb = callback;
+    }
+
+    /// Sets the callback that is called whenever a subscribed-to event occurs.
+    ///
+    /// This callback remains in effect across reconnections. See
+    /// [`Context::set_subscribe_callback()`] for details of the parameters.
+    pub fn set_subscribe_callback(&mut self, callback: Option<Box<SubscribeCb>>) {
+        let shared = Rc::clone(&self.inner.borrow().subscribe_cb);
+        *shared.borrow_mut() = callback;
+    }
+
+    /// Sets the subscription mask.
+    ///
+    /// The mask is applied immediately if connected, and re-applied upon each reconnection.
+    pub fn subscribe(&mut self, mask: InterestMaskSet) {
+        let mut inner = self.inner.borrow_mut();
+        inner.subscription = mask;
+        if let Some(context) = inner.context.as_mut() {
+            if context.get_state() == State::Ready {
+                let _ = context.subscribe(mask, |_| {});
+            }
+        }
+    }
+
+    /// Updates the client property list.
+    ///
+    /// The change is applied immediately if connected, and used for all future connections.
+    pub fn proplist_update(&mut self, mode: proplist::UpdateMode, pl: &Proplist) {
+        let mut inner = self.inner.borrow_mut();
+        inner.proplist.merge(pl, mode);
+        if let Some(context) = inner.context.as_mut() {
+            if context.get_state() == State::Ready {
+                let _ = context.proplist_update(mode, pl, |_| {});
+            }
+        }
+    }
+
+    /// Registers a stream to be managed by the supervisor.
+    ///
+    /// The `setup` closure is called with the context each time a connection becomes ready (and
+    /// immediately, if already connected), and must create (and typically connect) the stream,
+    /// returning `None` on failure. The stream is available through the returned handle.
+    pub fn manage_stream<F>(&mut self, setup: F) -> ManagedStream
+        where F: FnMut(&mut Context) -> Option<Stream> + 'static
+    {
+        let mut setup: Box<StreamSetup> = Box::new(setup);
+        let handle = ManagedStream(Rc::new(RefCell::new(None)));
+        if let Some(stream) = self.with_context(|context| setup(context)) {
+            *handle.0.borrow_mut() = stream;
+        }
+        self.inner.borrow_mut().streams.push((setup, handle.clone()));
+        handle
+    }
+
+    /// Starts connecting to the specified server.
+    ///
+    /// See [`Context::connect()`] for the meaning of the parameters. Failure of this and any future
+    /// connection is reported via the event callback and followed by reconnection attempts, except
+    /// that if the initial attempt fails immediately for a reason that retrying cannot fix (such as
+    /// an invalid server string), then the error is returned here instead.
+    pub fn connect(&mut self, server: Option<&str>, flags: FlagSet) -> Result<(), PAErr> {
+        {
+            let mut inner = self.inner.borrow_mut();
+            if !inner.stopped {
+                return Err(Code::BadState.into());
+            }
+            inner.server = server.map(|s| s.to_owned());
+            inner.flags = flags;
+            inner.attempt = 0;
+            inner.stopped = false;
+        }
+        let result = start_attempt(&self.inner, true);
+        if result.is_err() {
+            self.inner.borrow_mut().stopped = true;
+        }
+        result
+    }
+
+    /// Closes the connection and stops any further reconnection attempts.
+    pub fn disconnect(&mut self) {
+        let (context, lent) = {
+            let mut inner = self.inner.borrow_mut();
+            inner.stopped = true;
+            (inner.context.take(), inner.lent.take())
+        };
+        if let Some(mut context) = context {
+            // Note, this results in synchronous execution of the state callback
+            context.disconnect();
+            self.inner.borrow_mut().retired.push(context);
+        } else if lent.is_some() {
+            // Lent out by `with_context()`, which disconnects it once returned
+            emit(&self.inner, Event::Disconnected);
+        }
+    }
+
+    /// Gets the state of the current context.
+    pub fn get_state(&self) -> State {
+        self.inner.borrow().context.as_ref().map_or(State::Unconnected, |c| c.get_state())
+    }
+
+    /// Runs the given closure with the current context, if the connection is ready.
+    ///
+    /// Returns `None` if there is no ready connection.
+    pub fn with_context<R, F>(&self, f: F) -> Option<R>
+        where F: FnOnce(&mut Context) -> R
+    {
+        let mut context = {
+            let mut inner = self.inner.borrow_mut();
+            match inner.context.as_ref().map(|c| c.get_state()) {
+                Some(State::Ready) => {
+                    let context = inner.context.take().unwrap();
+                    inner.lent = Some(context.ptr);
+                    context
+                },
+                _ => return None,
+            }
+        };
+        // The context is detached from the shared state while the closure runs, such that any
+        // callbacks it triggers synchronously do not conflict with our borrow.
+        let r = f(&mut context);
+        let current = self.inner.borrow_mut().lent.take() == Some(context.ptr);
+        match current && context.get_state().is_good() {
+            true => self.inner.borrow_mut().context = Some(context),
+            // Lost or disconnected in the meantime, and possibly already replaced
+            false => {
+                if context.get_state().is_good() {
+                    // Disconnected, which has already been reported
+                    context.set_state_callback(None);
+                    context.disconnect();
+                }
+                self.inner.borrow_mut().retired.push(context);
+            },
+        }
+        Some(r)
+    }
+}
+
+/// Calls the user’s event callback.
+fn emit<M>(rc: &Rc<RefCell<Inner<M>>>, event: Event)
+    where M: Mainloop + 'static
+{
+    // Taken out for the duration of the call, allowing the callback to use the supervisor.
+    let callback = rc.borrow_mut().event_cb.take();
+    if let Some(mut callback) = callback {
+        callback(event);
+        let mut inner = rc.borrow_mut();
+        if inner.event_cb.is_none() {
+            inner.event_cb = Some(callback);
+        }
+    }
+}
+
+/// Creates a new context and starts connecting it.
+///
+/// Failures that retrying cannot fix are returned, with the initial attempt not reporting them
+/// via the event callback.
+fn start_attempt<M>(rc: &Rc<RefCell<Inner<M>>>, initial: bool) -> Result<(), PAErr>
+    where M: Mainloop + 'static
+{
+    let attempt = {
+        let mut inner = rc.borrow_mut();
+        inner.retired_streams.clear();
+        inner.retired.clear();
+        inner.attempt
+    };
+    emit(rc, Event::Connecting(attempt));
+
+    let created = {
+        let inner = rc.borrow();
+        let ml = MainloopRef(Rc::clone(&inner.mainloop));
+        Context::new_with_proplist(&ml, &inner.name, &inner.proplist)
+            .map(|c| (c, inner.server.clone(), inner.flags, Rc::clone(&inner.subscribe_cb)))
+    };
+    let (mut context, server, flags, subscribe_cb) = match created {
+        Some(c) => c,
+        None => {
+            let err = PAErr::from(Code::Internal);
+            if !initial {
+                emit(rc, Event::Lost(err));
+                schedule_retry(rc);
+            }
+            return Err(err);
+        },
+    };
+
+    let ptr = context.ptr;
+    let weak = Rc::downgrade(rc);
+    context.set_state_callback(Some(Box::new(move || state_changed(&weak, ptr))));
+    context.set_subscribe_callback(Some(Box::new(move |facility, operation, index| {
+        if let Some(callback) = subscribe_cb.borrow_mut().as_mut() {
+            callback(facility, operation, index);
+        }
+    })));
+
+    // Note, the borrow must not be held here, since this may synchronously execute the state
+    // callback.
+    let result = context.connect(server.as_deref(), flags, None);
+    let state = context.get_state();
+    if state.is_good() {
+        rc.borrow_mut().context = Some(context);
+        return Ok(());
+    }
+    rc.borrow_mut().retired.push(context);
+    match (state, result) {
+        // Rejected before anything was attempted (e.g. an invalid server string)
+        (State::Unconnected, Err(e)) => {
+            if !initial {
+                emit(rc, Event::Lost(e));
+                emit(rc, Event::GaveUp);
+            }
+            Err(e)
+        },
+        // Otherwise failure has already been handled by the state callback
+        _ => Ok(()),
+    }
+}
+
+/// Handles context state changes.
+fn state_changed<M>(weak: &Weak<RefCell<Inner<M>>>, ptr: *mut ContextInternal)
+    where M: Mainloop + 'static
+{
+    let rc = match weak.upgrade() {
+        Some(rc) => rc,
+        None => return,
+    };
+    match State::from(unsafe { capi::pa_context_get_state(ptr) }) {
+        State::Ready => connection_ready(&rc),
+        State::Failed | State::Terminated => {
+            let err = PAErr(unsafe { capi::pa_context_errno(ptr) });
+            connection_lost(&rc, ptr, err);
+        },
+        _ => {},
+    }
+}
+
+/// Restores registered state upon a connection becoming ready.
+fn connection_ready<M>(rc: &Rc<RefCell<Inner<M>>>)
+    where M: Mainloop + 'static
+{
+    let (context, mask, mut streams) = {
+        let mut inner = rc.borrow_mut();
+        inner.attempt = 0;
+        (inner.context.take(), inner.subscription, std::mem::take(&mut inner.streams))
+    };
+    let mut context = match context {
+        Some(c) => c,
+        None => {
+            rc.borrow_mut().streams = streams;
+            return;
+        },
+    };
+
+    if !mask.is_empty() {
+        let _ = context.subscribe(mask, |_| {});
+    }
+    for (setup, handle) in streams.iter_mut() {
+        let stream = setup(&mut context);
+        *handle.0.borrow_mut() = stream;
+    }
+
+    {
+        let mut inner = rc.borrow_mut();
+        // Keep any streams registered from within the setup closures
+        streams.append(&mut inner.streams);
+        inner.streams = streams;
+        inner.context = Some(context);
+    }
+    emit(rc, Event::Ready);
+}
+
+/// Discards a failed connection and schedules reconnection.
+fn connection_lost<M>(rc: &Rc<RefCell<Inner<M>>>, ptr: *mut ContextInternal, err: PAErr)
+    where M: Mainloop + 'static
+{
+    let stopped = {
+        let mut inner = rc.borrow_mut();
+        let inner = &mut *inner;
+        if inner.context.as_ref().map_or(false, |c| c.ptr == ptr) {
+            // Note, we are executing within a callback of this context, so it cannot be destroyed
+            // here.
+            inner.retired.push(inner.context.take().unwrap());
+        }
+        if inner.lent == Some(ptr) {
+            // Retired by `with_context()` once returned
+            inner.lent = None;
+        }
+        for (_, handle) in inner.streams.iter() {
+            if let Some(stream) = handle.0.borrow_mut().take() {
+                inner.retired_streams.push(stream);
+            }
+        }
+        inner.stopped
+    };
+    match stopped {
+        true => emit(rc, Event::Disconnected),
+        false => {
+            emit(rc, Event::Lost(err));
+            schedule_retry(rc);
+        },
+    }
+}
+
+/// Arms the reconnection timer, or gives up if the attempt limit has been reached.
+fn schedule_retry<M>(rc: &Rc<RefCell<Inner<M>>>)
+    where M: Mainloop + 'static
+{
+    let (attempt, delay) = {
+        let mut inner = rc.borrow_mut();
+        inner.attempt += 1;
+        (inner.attempt, inner.backoff.delay_for(inner.attempt))
+    };
+    let limit = rc.borrow().backoff.max_attempts;
+    if limit.map_or(false, |max| attempt > max) {
+        rc.borrow_mut().stopped = true;
+        emit(rc, Event::GaveUp);
+        return;
+    }
+    emit(rc, Event::Reconnecting { attempt, delay });
+
+    let mut inner = rc.borrow_mut();
+    if inner.stopped {
+        return;
+    }
+    let when = MonotonicTs::now() + delay;
+    match inner.timer.as_mut() {
+        Some(timer) => timer.restart_rt(when),
+        None => {
+            let weak = Rc::downgrade(rc);
+            let mut ml = MainloopRef(Rc::clone(&inner.mainloop));
+            inner.timer = ml.new_timer_event_rt(when, Box::new(move |_| {
+                if let Some(rc) = weak.upgrade() {
+                    if !rc.borrow().stopped {
+                        let _ = start_attempt(&rc, false);
+                    }
+                }
+            }));
+        },
+    }
+}
+
+#[test]
+fn backoff_delays() {
+    let backoff = Backoff {
+        initial: MicroSeconds(100),
+        max: MicroSeconds(1000),
+        multiplier: 3,
+        max_attempts: None,
+    };
+    assert_eq!(backoff.delay_for(0), MicroSeconds(100));
+    assert_eq!(backoff.delay_for(1), MicroSeconds(100));
+    assert_eq!(backoff.delay_for(2), MicroSeconds(300));
+    assert_eq!(backoff.delay_for(3), MicroSeconds(900));
+    assert_eq!(backoff.delay_for(4), MicroSeconds(1000));
+    assert_eq!(backoff.delay_for(u32::MAX), MicroSeconds(1000));
+
+    let capped = Backoff { initial: MicroSeconds(5000), ..backoff };
+    assert_eq!(capped.delay_for(1), MicroSeconds(1000));
+
+    let zero = Backoff { multiplier: 0, ..backoff };
+    assert_eq!(zero.delay_for(2), MicroSeconds(100));
+    assert_eq!(zero.delay_for(u32::MAX), MicroSeconds(100));
+    let one = Backoff { multiplier: 1, ..backoff };
+    assert_eq!(one.delay_for(u32::MAX), MicroSeconds(100));
+}
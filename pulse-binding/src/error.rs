@@ -166,6 +166,23 @@ impl Code {
     pub fn to_string(self) -> Option<String> {
         PAErr::from(self).to_string()
     }
+
+    /// Gets the closest [`std::io::ErrorKind`] for this error code.
+    pub fn io_error_kind(self) -> std::io::ErrorKind {
+        use std::io::ErrorKind;
+        match self {
+            Code::Access | Code::AuthKey => ErrorKind::PermissionDenied,
+            Code::NoEntity | Code::NoExtension => ErrorKind::NotFound,
+            Code::Exist => ErrorKind::AlreadyExists,
+            Code::ConnectionRefused => ErrorKind::ConnectionRefused,
+            Code::ConnectionTerminated | Code::Killed => ErrorKind::ConnectionAborted,
+            Code::Timeout => ErrorKind::TimedOut,
+            Code::Invalid | Code::InvalidServer | Code::TooLarge => ErrorKind::InvalidInput,
+            Code::Protocol | Code::Version => ErrorKind::InvalidData,
+            Code::NotSupported | Code::NotImplemented | Code::Obsolete => ErrorKind::Unsupported,
+            _ => ErrorKind::Other,
+        }
+    }
 }
 
 impl std::error::Error for Code {}
@@ -201,6 +218,196 @@ impl TryFrom<PAErr> for Code {
     }
 }
 
+impl From<PAErr> for std::io::Error {
+    fn from(e: PAErr) -> Self {
+        Error::from(e).into()
+    }
+}
+
+/// Where the error code of an [`Error`] was obtained from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Origin {
+    /// The return value of the failed call.
+    ReturnValue,
+    /// The last error of the context, as obtained with
+    /// [`Context::errno()`](crate::context::Context::errno), after the call failed.
+    ContextErrno,
+}
+
+/// State of the object an operation was attempted upon, at the time of failure.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ObjectState {
+    /// State of a context.
+    Context(crate::context::State),
+    /// State of a stream.
+    Stream(crate::stream::State),
+}
+
+impl std::fmt::Display for ObjectState {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ObjectState::Context(s) => write!(f, "context state {:?}", s),
+            ObjectState::Stream(s) => write!(f, "stream state {:?}", s),
+        }
+    }
+}
+
+/// An error with context about what was being attempted.
+///
+/// Where [`PAErr`] only holds the error code, this also records the operation that failed, its
+/// target (such as a sink name or stream index), where the error code came from, and the state of
+/// the context or stream at the time. It can be converted to a [`std::io::Error`], with the
+/// error code mapped to the closest [`std::io::ErrorKind`].
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use libpulse_binding::error::Error;
+///
+/// if context.connect(None, FlagSet::NOFLAGS, None).is_err() {
+///     return Err(Error::from_context(&context, "connect").with_target("default server"));
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    /// The raw error value.
+    err: PAErr,
+    /// Where the error value was obtained from.
+    origin: Origin,
+    /// Name of the operation that failed.
+    operation: Option<&'static str>,
+    /// What the operation was attempted upon.
+    target: Option<String>,
+    /// State of the object at the time of failure.
+    state: Option<ObjectState>,
+}
+
+impl Error {
+    /// Creates an error from the return value of the given failed operation.
+    pub fn new(err: PAErr, operation: &'static str) -> Self {
+        Self { operation: Some(operation), ..Self::from(err) }
+    }
+
+    /// Creates an error for the given failed operation from the last error of the context,
+    /// recording its state.
+    pub fn from_context(context: &crate::context::Context, operation: &'static str) -> Self {
+        Self {
+            err: context.errno(),
+            origin: Origin::ContextErrno,
+            operation: Some(operation),
+            target: None,
+            state: Some(ObjectState::Context(context.get_state())),
+        }
+    }
+
+    /// Creates an error for the given failed operation on a stream from the last error of its
+    /// context, recording the state of the stream.
+    pub fn from_stream(stream: &crate::stream::Stream, operation: &'static str) -> Self {
+        Self {
+            err: stream.context_errno(),
+            origin: Origin::ContextErrno,
+            operation: Some(operation),
+            target: stream.get_index().map(|i| format!("stream #{}", i)),
+            state: Some(ObjectState::Stream(stream.get_state())),
+        }
+    }
+
+    /// Sets the target of the operation, such as a device name.
+    pub fn with_target<T: Into<String>>(mut self, target: T) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    /// Sets the state of the object at the time of failure.
+    pub fn with_state(mut self, state: ObjectState) -> Self {
+        self.state = Some(state);
+        self
+    }
+
+    /// Gets the raw error value.
+    #[inline]
+    pub fn pa_err(&self) -> PAErr {
+        self.err
+    }
+
+    /// Gets the error code, if it is a known one.
+    #[inline]
+    pub fn code(&self) -> Option<Code> {
+        Code::try_from(self.err).ok()
+    }
+
+    /// Gets where the error value was obtained from.
+    #[inline]
+    pub fn origin(&self) -> Origin {
+        self.origin
+    }
+
+    /// Gets the name of the operation that failed, if known.
+    #[inline]
+    pub fn operation(&self) -> Option<&'static str> {
+        self.operation
+    }
+
+    /// Gets what the operation was attempted upon, if known.
+    #[inline]
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
+
+    /// Gets the state of the context or stream at the time of failure, if known.
+    #[inline]
+    pub fn state(&self) -> Option<ObjectState> {
+        self.state
+    }
+
+    /// Gets the closest [`std::io::ErrorKind`] for this error.
+    pub fn io_error_kind(&self) -> std::io::ErrorKind {
+        self.code().map_or(std::io::ErrorKind::Other, Code::io_error_kind)
+    }
+}
+
+impl From<PAErr> for Error {
+    fn from(err: PAErr) -> Self {
+        Self { err, origin: Origin::ReturnValue, operation: None, target: None, state: None }
+    }
+}
+
+impl From<Code> for Error {
+    #[inline]
+    fn from(c: Code) -> Self {
+        PAErr::from(c).into()
+    }
+}
+
+impl From<Error> for std::io::Error {
+    fn from(e: Error) -> Self {
+        std::io::Error::new(e.io_error_kind(), e)
+    }
+}
+
+// Note, no source is given, since the message of the underlying `PAErr` is already included in
+// that of the error itself; it can be obtained with `pa_err()`.
+impl std::error::Error for Error {}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match (self.operation, &self.target) {
+            (Some(op), Some(target)) => write!(f, "{} on {} failed: ", op, target)?,
+            (Some(op), None) => write!(f, "{} failed: ", op)?,
+            (None, Some(target)) => write!(f, "operation on {} failed: ", target)?,
+            (None, None) => {},
+        }
+        match self.err.to_string() {
+            Some(s) => write!(f, "{}", s)?,
+            None => write!(f, "error {}", self.err.0)?,
+        }
+        match self.state {
+            Some(state) => write!(f, " ({})", state),
+            None => Ok(()),
+        }
+    }
+}
+
 /// Check `PAErr` <=> `Code` conversions
 #[test]
 fn check_code_paerr_conversions() {
@@ -262,3 +469,27 @@ fn check_code_paerr_conversions() {
     assert_eq!(PAErr::from(Code::IO),                   PAErr(-25));
     assert_eq!(PAErr::from(Code::Busy),                 PAErr(-26));
 }
+
+#[test]
+fn check_error_context() {
+    let e = Error::new(PAErr(-5), "get_sink_info_by_name").with_target("alsa_output.0");
+    assert_eq!(e.code(), Some(Code::NoEntity));
+    assert_eq!(e.origin(), Origin::ReturnValue);
+    assert_eq!(e.operation(), Some("get_sink_info_by_name"));
+    assert_eq!(e.target(), Some("alsa_output.0"));
+    assert_eq!(e.io_error_kind(), std::io::ErrorKind::NotFound);
+    assert!(e.to_string().starts_with("get_sink_info_by_name on alsa_output.0 failed: "));
+    assert!(std::error::Error::source(&e).is_none());
+
+    let e = Error::from(Code::ConnectionRefused)
+        .with_state(ObjectState::Context(crate::context::State::Failed));
+    assert_eq!(e.operation(), None);
+    assert_eq!(e.state(), Some(ObjectState::Context(crate::context::State::Failed)));
+    let io = std::io::Error::from(e);
+    assert_eq!(io.kind(), std::io::ErrorKind::ConnectionRefused);
+
+    assert_eq!(Error::from(PAErr(-100)).code(), None);
+    assert_eq!(Error::from(PAErr(-100)).io_error_kind(), std::io::ErrorKind::Other);
+    assert_eq!(Code::Timeout.io_error_kind(), std::io::ErrorKind::TimedOut);
+    assert_eq!(Code::Access.io_error_kind(), std::io::ErrorKind::PermissionDenied);
+}
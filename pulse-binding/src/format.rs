@@ -164,6 +164,23 @@ impl Encoding {
         let c_enc = CString::new(encoding.clone()).unwrap();
         unsafe { capi::pa_encoding_from_string(c_enc.as_ptr()).into() }
     }
+
+    /// Converts a string of the form returned by [`to_string()`](Self::to_string) back to an
+    /// `Encoding`, if supported.
+    ///
+    /// This is the same as [`from_string()`](Self::from_string), but is available regardless of the
+    /// PA version feature flags enabled, with the function being resolved at runtime. Returns
+    /// `None` if the client library in use is too old to provide it.
+    pub fn try_from_string(encoding: &str) -> Option<Self> {
+        type FromStringFn = unsafe extern "C" fn(*const c_char) -> capi::pa_encoding_t;
+
+        let symbol = capi::find_symbol("pa_encoding_from_string")?;
+        let from_string: FromStringFn = unsafe { std::mem::transmute(symbol) };
+        // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
+        // as_ptr() giving dangling pointers!
+        let c_enc = CString::new(encoding).unwrap();
+        Some(unsafe { from_string(c_enc.as_ptr()).into() })
+    }
 }
 
 impl Info {
@@ -482,6 +499,56 @@ impl Info {
         }
     }
 
+    /// Gets the sample format stored in the format info, if supported.
+    ///
+    /// This is the same as [`get_sample_format()`](Self::get_sample_format), but is available
+    /// regardless of the PA version feature flags enabled, with the function being resolved at
+    /// runtime. Returns `None` if the client library in use is too old to provide it.
+    pub fn try_get_sample_format(&self) -> Option<Result<crate::sample::Format, PAErr>> {
+        self.try_get("pa_format_info_get_sample_format", capi::PA_SAMPLE_INVALID)
+            .map(|r| r.map(crate::sample::Format::from))
+    }
+
+    /// Gets the sample rate stored in the format info, if supported.
+    ///
+    /// This is the same as [`get_rate()`](Self::get_rate), but is available regardless of the PA
+    /// version feature flags enabled, with the function being resolved at runtime. Returns `None`
+    /// if the client library in use is too old to provide it.
+    pub fn try_get_rate(&self) -> Option<Result<u32, PAErr>> {
+        self.try_get("pa_format_info_get_rate", 0u32)
+    }
+
+    /// Gets the channel count stored in the format info, if supported.
+    ///
+    /// This is the same as [`get_channel_count()`](Self::get_channel_count), but is available
+    /// regardless of the PA version feature flags enabled, with the function being resolved at
+    /// runtime. Returns `None` if the client library in use is too old to provide it.
+    pub fn try_get_channel_count(&self) -> Option<Result<u8, PAErr>> {
+        self.try_get("pa_format_info_get_channels", 0u8)
+    }
+
+    /// Gets the channel map stored in the format info, if supported.
+    ///
+    /// This is the same as [`get_channel_map()`](Self::get_channel_map), but is available
+    /// regardless of the PA version feature flags enabled, with the function being resolved at
+    /// runtime. Returns `None` if the client library in use is too old to provide it.
+    pub fn try_get_channel_map(&self) -> Option<Result<crate::channelmap::Map, PAErr>> {
+        self.try_get("pa_format_info_get_channel_map", capi::pa_channel_map::default())
+            .map(|r| r.map(crate::channelmap::Map::from))
+    }
+
+    /// Calls one of the PA v13 property getter functions, resolved at runtime by name.
+    fn try_get<T>(&self, name: &str, mut value: T) -> Option<Result<T, PAErr>> {
+        type GetFn<T> = unsafe extern "C" fn(*const capi::pa_format_info, *mut T) -> i32;
+
+        let symbol = capi::find_symbol(name)?;
+        let get: GetFn<T> = unsafe { std::mem::transmute(symbol) };
+        match unsafe { get(self.ptr as *const capi::pa_format_info, &mut value) } {
+            0 => Some(Ok(value)),
+            e => Some(Err(PAErr(e))),
+        }
+    }
+
     /// Sets an integer property.
     pub fn set_prop_int(&mut self, key: &str, value: i32) {
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
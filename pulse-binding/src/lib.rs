@@ -76,14 +76,12 @@
 //! The included main loop implementation is also not thread safe. Take care to make sure event
 //! objects are not manipulated when any other code is using the main loop.
 //!
-//! Note that some objects implement the `Sync` trait, despite not truly being thread-safe. The
-//! reason for this is that when the threaded mainloop is used (the most common one), its lock
-//! provides the thread safety, and when such types are used behind that lock, they are then `Sync`
-//! safe. If you use the standard mainloop though, then you would have to add an `Arc` wrapper to
-//! make them safe. Not having `Sync` would force the threaded mainloop case to require unnecessary
-//! and undesirable `Arc` wrappers. This is an unfortunate compromise resulting from the
-//! complication of needing to support multiple mainloop designs, and the threaded mainloop design
-//! being built upon a non-wrapper lock that is not typical of Rust code.
+//! Objects attached to a main loop, such as contexts, streams, introspectors and operations, are
+//! thus neither `Send` nor `Sync`, so the compiler catches attempts to use them from other threads.
+//! With the threaded mainloop, whose lock provides the necessary thread safety, such objects can be
+//! shared with other threads by wrapping them in a
+//! [`threaded::Guarded`](crate::mainloop::threaded::Guarded), which is `Send` and `Sync`, and
+//! which only gives access to the object with the lock held or from within the event loop thread.
 //!
 //! ## Logging
 //!
@@ -130,6 +128,13 @@
 //!
 //! See sub-modules for further information.
 //!
+//! # Runtime loading
+//!
+//! With the `dlopen` feature, the PulseAudio client library is loaded at runtime upon first use
+//! rather than linked at build time. Use [`is_available()`] (or [`load()`], for the reason for
+//! failure) to check that it could be loaded. Should it not be available, the main loop, context
+//! and property list constructors return `None`, but most other use of the library panics.
+//!
 //! <div style="border-left:#bda000aa 5px solid; padding:0.5em 1em; margin:1em 0 0.6em 0; background-color:#aaa2">
 //! <h3 style="color:#bfa800; margin-top:0.2em">
 //! <span aria-hidden="true">
@@ -162,6 +167,7 @@
 extern crate libpulse_sys as capi;
 
 pub mod callbacks;
+pub mod capability;
 pub mod channelmap;
 pub mod context;
 pub mod def;
@@ -170,7 +176,9 @@ pub mod error;
 pub mod format;
 pub mod mainloop;
 pub mod operation;
+pub mod panic;
 pub mod proplist;
+pub mod protocol;
 pub mod sample;
 pub mod stream;
 pub mod time;
@@ -178,3 +186,6 @@ pub mod utf8;
 pub mod util;
 pub mod version;
 pub mod volume;
+pub mod wav;
+
+pub use capi::{is_available, load, LoadError};
@@ -324,6 +324,25 @@ pub trait Mainloop {
     }
 }
 
+/// A minimal mainloop wrapper around a copy of a ref-counted inner mainloop object.
+///
+/// The outer mainloop wrappers are not available from within callbacks, but a copy of the inner
+/// object can be captured. This allows such code to make use of the [`Mainloop`] trait methods (for
+/// instance to create event sources) just the same as with the outer wrapper.
+pub(crate) struct MainloopRef<MI>(pub Rc<MI>)
+    where MI: MainloopInnerType + 'static;
+
+impl<MI> Mainloop for MainloopRef<MI>
+    where MI: MainloopInnerType + 'static
+{
+    type MI = MI;
+
+    #[inline(always)]
+    fn inner(&self) -> Rc<MI> {
+        Rc::clone(&self.0)
+    }
+}
+
 /// An IO event callback prototype.
 pub type IoEventCb = extern "C" fn(a: *const MainloopApi, e: *mut IoEventInternal, fd: i32,
     events: IoEventFlagSet, userdata: *mut c_void);
@@ -424,7 +443,7 @@ impl<'a> From<&'a MainloopApi> for *const ApiInternal {
 /// Warning: This is for single-use cases only! It destroys the actual closure callback.
 extern "C"
 fn once_cb_proxy(_: *const ApiInternal, userdata: *mut c_void) {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         // Note, destroys closure callback after use - restoring outer box means it gets dropped
         let mut callback = get_su_callback::<dyn FnMut()>(userdata);
         (callback)();
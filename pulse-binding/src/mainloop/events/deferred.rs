@@ -112,7 +112,7 @@ impl<T> Drop for DeferEvent<T>
 pub(crate)
 extern "C"
 fn event_cb_proxy(_: *const MainloopApi, e: *mut DeferEventInternal, userdata: *mut c_void) {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         let callback = EventCb::get_callback(userdata);
         (callback)(e);
     });
@@ -136,7 +136,7 @@ extern "C"
 fn event_cb_proxy(_: *const MainloopApi, e: *mut IoEventInternal, fd: i32, events: FlagSet,
     userdata: *mut c_void)
 {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         let callback = EventCb::get_callback(userdata);
         (callback)(e, fd, events);
     });
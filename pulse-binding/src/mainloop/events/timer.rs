@@ -135,7 +135,7 @@ extern "C"
 fn event_cb_proxy(_: *const MainloopApi, e: *mut TimeEventInternal, _: *const timeval,
     userdata: *mut c_void)
 {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         let callback = EventCb::get_callback(userdata);
         (callback)(e);
     });
@@ -0,0 +1,245 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! Running closures on the mainloop from other threads.
+//!
+//! # Overview
+//!
+//! Objects attached to a main loop, such as a context, may only be used from the thread running it
+//! (or with the threaded mainloop lock held). A [`Dispatcher`] is created on that thread, owning
+//! some state (typically the context), and hands out [`MainloopHandle`]s, which are `Send` and
+//! `Sync`, and through which any thread can queue work to run on the mainloop with access to that
+//! state: [`MainloopHandle::spawn()`] queues a closure without waiting for it, and
+//! [`MainloopHandle::invoke()`] waits for its result.
+//!
+//! Work is run from a defer event, which is enabled from the mainloop upon a wakeup through a pipe
+//! watched by an IO event, such that this works with any mainloop implementation, whether standard,
+//! threaded or glib. Note that this is only available on Unix platforms.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use libpulse_binding::mainloop::handle::Dispatcher;
+//!
+//! let dispatcher = Dispatcher::new(&mut mainloop, context).unwrap();
+//! let handle = dispatcher.handle();
+//!
+//! std::thread::spawn(move || {
+//!     handle.spawn(|context| {
+//!         context.introspect().get_sink_info_list(|_| {});
+//!     }).unwrap();
+//! });
+//! ```
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt;
+use std::os::raw::c_void;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::{self, ThreadId};
+use crate::mainloop::api::{Mainloop, MainloopInnerType};
+use crate::mainloop::events::deferred::DeferEvent;
+use crate::mainloop::events::io::{FlagSet as IoEventFlagSet, IoEvent};
+
+/// A queued closure.
+type Task<S> = Box<dyn FnOnce(&mut S) + Send + 'static>;
+
+/// Error returned when the [`Dispatcher`] of a handle no longer exists.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Closed;
+
+impl std::error::Error for Closed {}
+
+impl fmt::Display for Closed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the mainloop dispatcher has been dropped")
+    }
+}
+
+/// State shared between a dispatcher and its handles.
+struct Shared<S> {
+    /// Queued closures.
+    queue: Mutex<VecDeque<Task<S>>>,
+    /// Whether a wakeup has been sent and not yet received.
+    signalled: AtomicBool,
+    /// Whether the dispatcher has been dropped.
+    closed: AtomicBool,
+    /// The thread the mainloop was last seen running work on.
+    thread: Mutex<Option<ThreadId>>,
+    /// Read and write ends of the wakeup pipe.
+    pipe: [i32; 2],
+}
+
+impl<S> Drop for Shared<S> {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.pipe[0]);
+            libc::close(self.pipe[1]);
+        }
+    }
+}
+
+/// Runs closures queued through [`MainloopHandle`]s on the mainloop.
+///
+/// This must be created, used and dropped on the thread running the mainloop, or with the lock
+/// held in the case of the threaded mainloop, as with any event source. Queued work stops being run
+/// once it is dropped, and handles then report [`Closed`].
+pub struct Dispatcher<MI: MainloopInnerType, S: 'static> {
+    /// The state given to closures.
+    state: Rc<RefCell<S>>,
+    /// State shared with handles.
+    shared: Arc<Shared<S>>,
+    /// Event watching the wakeup pipe.
+    _io: IoEvent<MI>,
+    /// Event running queued closures.
+    _defer: Rc<RefCell<DeferEvent<MI>>>,
+}
+
+impl<MI: MainloopInnerType + 'static, S: 'static> Dispatcher<MI, S> {
+    /// Creates a dispatcher on the given mainloop, owning the given state, which is given to each
+    /// closure run.
+    ///
+    /// Returns `None` if the wakeup pipe or the event sources could not be created.
+    pub fn new<M>(mainloop: &mut M, state: S) -> Option<Self>
+        where M: Mainloop<MI = MI>
+    {
+        let mut pipe = [-1; 2];
+        if unsafe { libc::pipe(pipe.as_mut_ptr()) } != 0 {
+            return None;
+        }
+        for fd in &pipe {
+            unsafe {
+                let flags = libc::fcntl(*fd, libc::F_GETFL);
+                libc::fcntl(*fd, libc::F_SETFL, flags | libc::O_NONBLOCK);
+                libc::fcntl(*fd, libc::F_SETFD, libc::FD_CLOEXEC);
+            }
+        }
+        let shared = Arc::new(Shared {
+            queue: Mutex::new(VecDeque::new()),
+            signalled: AtomicBool::new(false),
+            closed: AtomicBool::new(false),
+            thread: Mutex::new(None),
+            pipe,
+        });
+        let state = Rc::new(RefCell::new(state));
+
+        let shared_for_defer = Arc::clone(&shared);
+        let state_for_defer = Rc::clone(&state);
+        let mut defer = mainloop.new_deferred_event(Box::new(move |mut event| {
+            *shared_for_defer.thread.lock().unwrap() = Some(thread::current().id());
+            // Note, one at a time, such that upon a panic the rest remain queued, with the event
+            // still enabled
+            loop {
+                let task = shared_for_defer.queue.lock().unwrap().pop_front();
+                match task {
+                    Some(task) => task(&mut *state_for_defer.borrow_mut()),
+                    None => {
+                        event.disable();
+                        break;
+                    },
+                }
+            }
+        }))?;
+        // Defer events are created enabled
+        defer.disable();
+        let defer = Rc::new(RefCell::new(defer));
+
+        let shared_for_io = Arc::clone(&shared);
+        let defer_for_io = Rc::clone(&defer);
+        let io = mainloop.new_io_event(pipe[0], IoEventFlagSet::INPUT, Box::new(move |_, fd, _| {
+            shared_for_io.signalled.store(false, Ordering::Release);
+            let mut buf = [0u8; 64];
+            while unsafe { libc::read(fd, buf.as_mut_ptr() as *mut c_void, buf.len()) } > 0 {}
+            defer_for_io.borrow_mut().enable();
+        }))?;
+
+        Some(Self { state, shared, _io: io, _defer: defer })
+    }
+
+    /// Creates a new handle for queueing closures from any thread.
+    pub fn handle(&self) -> MainloopHandle<S> {
+        MainloopHandle { shared: Arc::clone(&self.shared) }
+    }
+
+    /// Gets the state given to closures.
+    pub fn state(&self) -> &Rc<RefCell<S>> {
+        &self.state
+    }
+}
+
+impl<MI: MainloopInnerType, S: 'static> Drop for Dispatcher<MI, S> {
+    fn drop(&mut self) {
+        self.shared.closed.store(true, Ordering::Release);
+        // Drop anything left queued, such that waiting `invoke()` calls return
+        self.shared.queue.lock().unwrap().clear();
+    }
+}
+
+/// A handle for running closures on the mainloop from any thread.
+///
+/// Obtained from [`Dispatcher::handle()`].
+pub struct MainloopHandle<S: 'static> {
+    shared: Arc<Shared<S>>,
+}
+
+impl<S: 'static> Clone for MainloopHandle<S> {
+    fn clone(&self) -> Self {
+        Self { shared: Arc::clone(&self.shared) }
+    }
+}
+
+impl<S: 'static> MainloopHandle<S> {
+    /// Queues the given closure to be run on the mainloop, without waiting for it.
+    pub fn spawn<F>(&self, f: F) -> Result<(), Closed>
+        where F: FnOnce(&mut S) + Send + 'static
+    {
+        if self.shared.closed.load(Ordering::Acquire) {
+            return Err(Closed);
+        }
+        self.shared.queue.lock().unwrap().push_back(Box::new(f));
+        // The dispatcher may have been dropped meanwhile, in which case nothing would run it
+        if self.shared.closed.load(Ordering::Acquire) {
+            self.shared.queue.lock().unwrap().clear();
+            return Err(Closed);
+        }
+        if !self.shared.signalled.swap(true, Ordering::AcqRel) {
+            let byte = 1u8;
+            unsafe { libc::write(self.shared.pipe[1], &byte as *const u8 as *const c_void, 1) };
+        }
+        Ok(())
+    }
+
+    /// Runs the given closure on the mainloop, waiting for and returning its result.
+    ///
+    /// This must not be called from the mainloop thread, nor with the threaded mainloop lock held,
+    /// since the closure could then never run. Panics if called from the thread that the mainloop
+    /// was last seen running work on, rather than deadlocking.
+    ///
+    /// Returns `Err(Closed)` if the dispatcher is dropped before the closure is run.
+    pub fn invoke<F, T>(&self, f: F) -> Result<T, Closed>
+        where F: FnOnce(&mut S) -> T + Send + 'static,
+              T: Send + 'static
+    {
+        assert!(*self.shared.thread.lock().unwrap() != Some(thread::current().id()),
+            "invoke() can not be called from the mainloop thread!");
+        let (sender, receiver) = mpsc::sync_channel(1);
+        self.spawn(move |state| {
+            let _ = sender.send(f(state));
+        })?;
+        receiver.recv().map_err(|_| Closed)
+    }
+
+    /// Checks whether the dispatcher has been dropped.
+    pub fn is_closed(&self) -> bool {
+        self.shared.closed.load(Ordering::Acquire)
+    }
+}
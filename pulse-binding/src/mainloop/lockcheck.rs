@@ -0,0 +1,225 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! Debug checking of threaded mainloop lock discipline.
+//!
+//! In debug builds, threaded mainloops and the contexts created with them are recorded here, along
+//! with which threaded mainloop locks each thread holds, such that methods of context, stream,
+//! introspector and operation objects can assert that they are called either with the lock held or
+//! from within the event loop thread, once the event loop thread is running. In release builds
+//! everything here compiles to nothing.
+
+#[cfg(debug_assertions)]
+use std::cell::RefCell;
+#[cfg(debug_assertions)]
+use std::collections::HashMap;
+#[cfg(debug_assertions)]
+use std::sync::{Mutex, MutexGuard, Once};
+use capi::pa_context as ContextInternal;
+use capi::pa_threaded_mainloop as ThreadedInternal;
+use crate::mainloop::api::MainloopApi;
+
+#[cfg(debug_assertions)]
+#[derive(Default)]
+struct Registry {
+    /// Threaded mainloops by API pointer, with whether or not the event loop thread is running.
+    mainloops: HashMap<usize, (usize, bool)>,
+    /// Threaded mainloops of contexts, by context pointer.
+    contexts: HashMap<usize, usize>,
+}
+
+#[cfg(debug_assertions)]
+impl Registry {
+    fn add_mainloop(&mut self, ml: usize, api: usize) {
+        self.mainloops.insert(api, (ml, false));
+    }
+
+    fn remove_mainloop(&mut self, ml: usize) {
+        self.mainloops.retain(|_, (m, _)| *m != ml);
+        self.contexts.retain(|_, m| *m != ml);
+    }
+
+    fn set_running(&mut self, ml: usize, running: bool) {
+        for (m, r) in self.mainloops.values_mut() {
+            if *m == ml {
+                *r = running;
+            }
+        }
+    }
+
+    fn add_context(&mut self, context: usize, api: usize) {
+        // Note, an entry may exist for a previous context at the same address
+        match self.mainloops.get(&api).map(|(ml, _)| *ml) {
+            Some(ml) => self.contexts.insert(context, ml),
+            None => self.contexts.remove(&context),
+        };
+    }
+
+    /// Gets the threaded mainloop of a context, if any, with whether or not it is running.
+    fn context_mainloop(&self, context: usize) -> Option<(usize, bool)> {
+        self.contexts.get(&context)
+            .and_then(|ml| self.mainloops.values().find(|(m, _)| m == ml).copied())
+    }
+
+    /// Gets the threaded mainloops whose event loop thread is running.
+    fn running(&self) -> Vec<usize> {
+        self.mainloops.values().filter(|(_, running)| *running).map(|(ml, _)| *ml).collect()
+    }
+}
+
+#[cfg(debug_assertions)]
+static REGISTRY_INIT: Once = Once::new();
+#[cfg(debug_assertions)]
+static mut REGISTRY: Option<Mutex<Registry>> = None;
+
+#[cfg(debug_assertions)]
+thread_local! {
+    /// Lock depth of the threaded mainloops locked by this thread.
+    static HELD: RefCell<HashMap<usize, usize>> = RefCell::new(HashMap::new());
+}
+
+#[cfg(debug_assertions)]
+fn registry() -> MutexGuard<'static, Registry> {
+    // Safe, only written once, within the `Once`, before any read
+    REGISTRY_INIT.call_once(|| unsafe { REGISTRY = Some(Mutex::new(Registry::default())) });
+    match unsafe { &*std::ptr::addr_of!(REGISTRY) } {
+        Some(m) => m.lock().unwrap_or_else(|e| e.into_inner()),
+        None => unreachable!(),
+    }
+}
+
+/// Records a new threaded mainloop.
+#[inline]
+pub(crate) fn add_mainloop(_ml: *mut ThreadedInternal, _api: *const MainloopApi) {
+    #[cfg(debug_assertions)]
+    registry().add_mainloop(_ml as usize, _api as usize);
+}
+
+/// Forgets a threaded mainloop being freed.
+#[inline]
+pub(crate) fn remove_mainloop(_ml: *mut ThreadedInternal) {
+    #[cfg(debug_assertions)]
+    registry().remove_mainloop(_ml as usize);
+}
+
+/// Records whether or not the event loop thread of a threaded mainloop is running.
+#[inline]
+pub(crate) fn set_running(_ml: *mut ThreadedInternal, _running: bool) {
+    #[cfg(debug_assertions)]
+    registry().set_running(_ml as usize, _running);
+}
+
+/// Records the mainloop of a new context, if a threaded one.
+#[inline]
+pub(crate) fn add_context(_context: *mut ContextInternal, _api: *const MainloopApi) {
+    #[cfg(debug_assertions)]
+    registry().add_context(_context as usize, _api as usize);
+}
+
+/// Records that the current thread has taken the lock of a threaded mainloop.
+#[inline]
+pub(crate) fn locked(_ml: *mut ThreadedInternal) {
+    #[cfg(debug_assertions)]
+    HELD.with(|held| *held.borrow_mut().entry(_ml as usize).or_insert(0) += 1);
+}
+
+/// Records that the current thread has released the lock of a threaded mainloop.
+#[inline]
+pub(crate) fn unlocked(_ml: *mut ThreadedInternal) {
+    #[cfg(debug_assertions)]
+    HELD.with(|held| {
+        let mut held = held.borrow_mut();
+        if let Some(depth) = held.get_mut(&(_ml as usize)) {
+            *depth -= 1;
+            if *depth == 0 {
+                held.remove(&(_ml as usize));
+            }
+        }
+    });
+}
+
+/// Checks whether the current thread holds the lock of the given threaded mainloop.
+#[cfg(all(test, debug_assertions))]
+pub(crate) fn is_locked(ml: *mut ThreadedInternal) -> bool {
+    HELD.with(|held| held.borrow().contains_key(&(ml as usize)))
+}
+
+/// Checks whether the lock discipline is satisfied for the given threaded mainloop.
+#[cfg(debug_assertions)]
+fn satisfied(ml: usize, running: bool) -> bool {
+    !running
+        || HELD.with(|held| held.borrow().contains_key(&ml))
+        || unsafe { capi::pa_threaded_mainloop_in_thread(ml as *mut ThreadedInternal) != 0 }
+}
+
+/// Asserts that an object of the given context may be used from the current thread.
+#[inline]
+#[track_caller]
+pub(crate) fn check_context(_context: *mut ContextInternal) {
+    #[cfg(debug_assertions)]
+    {
+        let found = registry().context_mainloop(_context as usize);
+        if let Some((ml, running)) = found {
+            assert!(satisfied(ml, running),
+                "used without the threaded mainloop lock held, outside of the event loop thread");
+        }
+    }
+}
+
+/// Asserts that an object of unknown context may be used from the current thread.
+///
+/// This is satisfied if there are no running threaded mainloops, or if the lock of any is held or
+/// the current thread is the event loop thread of any.
+#[inline]
+#[track_caller]
+pub(crate) fn check_any() {
+    #[cfg(debug_assertions)]
+    {
+        let running = registry().running();
+        if !running.is_empty() {
+            assert!(running.iter().any(|ml| satisfied(*ml, true)),
+                "used without the threaded mainloop lock held, outside of the event loop thread");
+        }
+    }
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn lock_tracking() {
+    // Note, a registry of its own is used, such that checks made by other tests never see the fake
+    // mainloop, and the pointers are thus never used; nor are they here, since the lock is held
+    // whenever checked running
+    let mut reg = Registry::default();
+    let (ml, api, context) = (0x1000, 0x2000, 0x3000);
+    reg.add_mainloop(ml, api);
+    reg.add_context(context, api);
+    reg.add_context(0x4000, 0x5000);
+    assert_eq!(reg.context_mainloop(context), Some((ml, false)));
+    assert_eq!(reg.context_mainloop(0x4000), None);
+    assert!(reg.running().is_empty());
+    assert!(satisfied(ml, false));
+
+    reg.set_running(ml, true);
+    assert_eq!(reg.context_mainloop(context), Some((ml, true)));
+    assert_eq!(reg.running(), vec![ml]);
+    locked(ml as *mut ThreadedInternal);
+    locked(ml as *mut ThreadedInternal);
+    unlocked(ml as *mut ThreadedInternal);
+    assert!(satisfied(ml, true));
+    assert!(HELD.with(|held| held.borrow().get(&ml) == Some(&1)));
+    unlocked(ml as *mut ThreadedInternal);
+    assert!(HELD.with(|held| held.borrow().is_empty()));
+
+    reg.set_running(ml, false);
+    assert!(reg.running().is_empty());
+    reg.remove_mainloop(ml);
+    assert!(reg.mainloops.is_empty());
+    assert!(reg.contexts.is_empty());
+}
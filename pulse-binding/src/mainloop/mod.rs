@@ -45,6 +45,13 @@
 //! [`signal`](mod@signal) mod. This relies only on the main loop abstraction and can therefore be
 //! used with any of the implementations.
 //!
+//! Work can be queued onto a main loop from other threads with a
+//! [`MainloopHandle`](self::handle::MainloopHandle), from the [`handle`](mod@handle) mod, which
+//! likewise works with any of the implementations.
+//!
+//! Repeating timers, sleep futures and operation timeouts, built upon timer events, are provided
+//! by the [`timers`](mod@timers) mod, again for any of the implementations.
+//!
 //! # Callback Notes
 //!
 //! ## Execution
@@ -94,6 +101,10 @@
 
 pub mod api;
 pub mod events;
+#[cfg(unix)]
+pub mod handle;
+pub(crate) mod lockcheck;
 pub mod signal;
 pub mod standard;
 pub mod threaded;
+pub mod timers;
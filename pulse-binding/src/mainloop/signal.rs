@@ -89,7 +89,7 @@ extern "C"
 fn signal_cb_proxy(_api: *const ApiInternal, _e: *mut EventInternal, sig: i32,
     userdata: *mut c_void)
 {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         let callback = SignalCb::get_callback(userdata);
         (callback)(sig);
     });
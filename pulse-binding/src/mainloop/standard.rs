@@ -44,6 +44,13 @@
 //! using [`Mainloop::iterate()`], or let the library iterate automatically using
 //! [`Mainloop::run()`].
 //!
+//! Helpers are also provided for the common case of iterating until something happens:
+//! [`Mainloop::run_until()`] iterates until a condition is met or a timeout passes, and
+//! [`Mainloop::wait_for_operation()`], [`Mainloop::wait_for_context_state()`] and
+//! [`Mainloop::wait_for_stream_state()`] build upon it. To stop [`Mainloop::run()`] from within a
+//! callback, which cannot borrow the main loop itself, a [`QuitHandle`] may be obtained with
+//! [`Mainloop::quit_handle()`].
+//!
 //! # Threads
 //!
 //! The main loop functions are designed to be thread safe, but the objects are not. What this means
@@ -197,17 +204,22 @@
 //! }
 //! ```
 
+use std::fmt;
 use std::os::raw::{c_ulong, c_void};
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
+use std::time::{Duration, Instant};
 #[cfg(not(windows))]
 use libc::pollfd;
 #[cfg(windows)]
 use winapi::um::winsock2::WSAPOLLFD as pollfd;
-use crate::def;
-use crate::error::{Code as ErrCode, PAErr};
+use crate::{def, panic};
+use crate::context::{self, Context};
+use crate::error::{Code as ErrCode, Error, PAErr};
 use crate::mainloop::api::{MainloopInternalType, MainloopInner, MainloopInnerType, MainloopApi,
                            Mainloop as MainloopTrait};
 use crate::mainloop::signal::MainloopSignals;
+use crate::operation::{AnyOperation, Batch, BatchError, Operation, State as OperationState};
+use crate::stream::{self, Stream};
 use crate::time::MicroSeconds;
 
 pub use capi::pa_mainloop as MainloopInternal;
@@ -258,6 +270,73 @@ impl IterateResult {
     }
 }
 
+/// Error type for [`Mainloop::run_until()`] and the other waiting helpers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WaitError {
+    /// The timeout passed before the condition was met.
+    Timeout,
+    /// Quit was called, with quit’s retval.
+    Quit(def::Retval),
+    /// Iterating the main loop failed, with error value.
+    Mainloop(PAErr),
+    /// The operation waited upon was cancelled.
+    Cancelled,
+    /// The context or stream waited upon failed or was terminated before reaching the wanted
+    /// state, with the last error of its context.
+    Failed(Error),
+    /// Operations of the batch waited upon failed or were cancelled.
+    Batch(BatchError),
+}
+
+impl std::error::Error for WaitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WaitError::Mainloop(e) => Some(e),
+            WaitError::Failed(e) => Some(e),
+            WaitError::Batch(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for WaitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WaitError::Timeout => write!(f, "timed out"),
+            WaitError::Quit(retval) => write!(f, "main loop quit, with retval {}", retval.0),
+            WaitError::Mainloop(e) => write!(f, "main loop iteration failed: {}", e),
+            WaitError::Cancelled => write!(f, "operation cancelled"),
+            WaitError::Failed(e) => write!(f, "{}", e),
+            WaitError::Batch(e) => write!(f, "batch of operations unsuccessful: {}", e),
+        }
+    }
+}
+
+/// A handle for stopping a [`Mainloop`] from within callbacks.
+///
+/// Obtained from [`Mainloop::quit_handle()`], it does not keep the main loop alive, doing nothing
+/// once it has been destroyed. Like the main loop, it may only be used from the thread the main
+/// loop belongs to.
+#[derive(Clone)]
+pub struct QuitHandle {
+    inner: Weak<MainloopInner<MainloopInternal>>,
+}
+
+impl QuitHandle {
+    /// Shuts down the main loop with the specified return value, as with [`Mainloop::quit()`].
+    ///
+    /// Returns `false` if the main loop no longer exists.
+    pub fn quit(&self, retval: def::Retval) -> bool {
+        match self.inner.upgrade() {
+            Some(inner) => {
+                unsafe { capi::pa_mainloop_quit(inner.get_ptr(), retval.0); }
+                true
+            },
+            None => false,
+        }
+    }
+}
+
 /// This acts as a safe interface to the internal PA Mainloop.
 ///
 /// The mainloop object pointers are further enclosed here in a ref counted wrapper, allowing this
@@ -290,7 +369,13 @@ impl MainloopInner<MainloopInternal> {
 
 impl Mainloop {
     /// Allocates a new main loop object.
+    ///
+    /// Returns `None` on failure, including if the client library could not be loaded, with the
+    /// `dlopen` feature.
     pub fn new() -> Option<Self> {
+        if !capi::is_available() {
+            return None;
+        }
         let ptr = unsafe { capi::pa_mainloop_new() };
         if ptr.is_null() {
             return None;
@@ -345,8 +430,12 @@ impl Mainloop {
     /// Dispatchs timeout, IO and deferred events from the previously executed poll.
     ///
     /// On success returns the number of source dispatched.
+    ///
+    /// Any panic within a callback is resumed here, per [`panic::Policy::Propagate`].
     pub fn dispatch(&mut self) -> Result<u32, PAErr> {
-        match unsafe { capi::pa_mainloop_dispatch(self._inner.get_ptr()) } {
+        let r = unsafe { capi::pa_mainloop_dispatch(self._inner.get_ptr()) };
+        panic::resume();
+        match r {
             e if e >= 0 => Ok(e as u32),
             e => Err(PAErr(e)),
         }
@@ -371,14 +460,18 @@ impl Mainloop {
     /// * If exit was requested, returns `IterateResult::Quit` containing quit’s retval.
     /// * On error, returns `IterateResult::Err` containing error value.
     ///
+    /// Any panic within a callback is resumed here, per [`panic::Policy::Propagate`].
+    ///
     /// [`prepare()`]: Self::prepare
     /// [`poll()`]: Self::poll
     /// [`dispatch()`]: Self::dispatch
     pub fn iterate(&mut self, block: bool) -> IterateResult {
         let mut retval: i32 = 0;
-        match unsafe {
+        let r = unsafe {
             capi::pa_mainloop_iterate(self._inner.get_ptr(), block as i32, &mut retval)
-        } {
+        };
+        panic::resume();
+        match r {
             r if r >= 0 => IterateResult::Success(r as u32),
             -2 => IterateResult::Quit(def::Retval(retval)),
             e => IterateResult::Err(PAErr(e)),
@@ -390,11 +483,220 @@ impl Mainloop {
     ///
     /// On success, returns `Ok` containing quit’s return value. On error returns `Err` containing a
     /// tuple of the error value and quit’s return value.
+    ///
+    /// Any panic within a callback is resumed here, per [`panic::Policy::Propagate`].
     pub fn run(&mut self) -> Result<def::Retval, (PAErr, def::Retval)> {
-        let mut retval: i32 = 0;
-        match unsafe { capi::pa_mainloop_run(self._inner.get_ptr(), &mut retval) } {
-            r if r >= 0 => Ok(def::Retval(retval)),
-            r => Err((PAErr(r), def::Retval(retval))),
+        // Note, implemented with `iterate()` rather than `pa_mainloop_run()`, which it is
+        // equivalent to, such that panics can be resumed without waiting for the loop to quit.
+        loop {
+            match self.iterate(true) {
+                IterateResult::Success(_) => {},
+                IterateResult::Quit(retval) => return Ok(retval),
+                IterateResult::Err(e) => return Err((e, self.get_retval())),
+            }
+        }
+    }
+
+    /// Runs a single iteration of the main loop, blocking for events for no longer than the given
+    /// timeout.
+    ///
+    /// With a `timeout` of `None`, this blocks for events as with [`iterate()`](Self::iterate)
+    /// given `true`. Timeouts too large for the underlying C API are clamped to the largest
+    /// supported (around 35 minutes). Returns an [`IterateResult`] variant as with `iterate()`.
+    ///
+    /// Any panic within a callback is resumed here, per [`panic::Policy::Propagate`].
+    pub fn iterate_timeout(&mut self, timeout: Option<MicroSeconds>) -> IterateResult {
+        let t: i32 = match timeout {
+            None => -1,
+            Some(MicroSeconds(i)) => std::cmp::min(i, i32::MAX as u64) as i32,
+        };
+        let ptr = self._inner.get_ptr();
+        let mut r = unsafe { capi::pa_mainloop_prepare(ptr, t) };
+        if r >= 0 {
+            r = unsafe { capi::pa_mainloop_poll(ptr) };
+        }
+        if r >= 0 {
+            r = unsafe { capi::pa_mainloop_dispatch(ptr) };
+            panic::resume();
+        }
+        match r {
+            r if r >= 0 => IterateResult::Success(r as u32),
+            -2 => IterateResult::Quit(self.get_retval()),
+            e => IterateResult::Err(PAErr(e)),
+        }
+    }
+
+    /// Runs iterations of the main loop until the given condition is met.
+    ///
+    /// The condition is checked before the first iteration and after each one. With a `timeout`,
+    /// [`WaitError::Timeout`] is returned if the condition is not met within that time; otherwise
+    /// this waits indefinitely. Should the main loop be quit, or iteration fail, the corresponding
+    /// error is returned.
+    ///
+    /// Any panic within a callback is resumed here, per [`panic::Policy::Propagate`].
+    pub fn run_until<F>(&mut self, mut condition: F, timeout: Option<MicroSeconds>)
+        -> Result<(), WaitError>
+        where F: FnMut() -> bool
+    {
+        self.run_while(|| match condition() {
+            true => Some(Ok(())),
+            false => None,
+        }, timeout)
+    }
+
+    /// Runs iterations of the main loop until the given operation completes.
+    ///
+    /// Returns [`WaitError::Cancelled`] if the operation was cancelled.
+    pub fn wait_for_operation<ClosureProto: ?Sized>(&mut self, operation: &Operation<ClosureProto>)
+        -> Result<(), WaitError>
+    {
+        self.run_while(|| match operation.get_state() {
+            OperationState::Running => None,
+            OperationState::Done => Some(Ok(())),
+            OperationState::Cancelled => Some(Err(WaitError::Cancelled)),
+        }, None)
+    }
+
+    /// Runs iterations of the main loop until all of the given operations have completed or been
+    /// cancelled.
+    ///
+    /// Returns [`WaitError::Cancelled`] if any were cancelled, and [`WaitError::Timeout`] if any
+    /// are still running once the given timeout, if any, passes, in which case they are left
+    /// running.
+    pub fn wait_for_all(&mut self, operations: &[&dyn AnyOperation],
+        timeout: Option<MicroSeconds>) -> Result<(), WaitError>
+    {
+        self.run_while(|| {
+            let mut cancelled = false;
+            for operation in operations {
+                match operation.get_state() {
+                    OperationState::Running => return None,
+                    OperationState::Cancelled => cancelled = true,
+                    OperationState::Done => {},
+                }
+            }
+            match cancelled {
+                true => Some(Err(WaitError::Cancelled)),
+                false => Some(Ok(())),
+            }
+        }, timeout)
+    }
+
+    /// Runs iterations of the main loop until any of the given operations completes, returning the
+    /// index of the first found to have done so.
+    ///
+    /// Returns [`WaitError::Cancelled`] should all instead be cancelled (or none be given), and
+    /// [`WaitError::Timeout`] if none complete within the given timeout, if any.
+    pub fn wait_for_any(&mut self, operations: &[&dyn AnyOperation],
+        timeout: Option<MicroSeconds>) -> Result<usize, WaitError>
+    {
+        let mut found = 0;
+        self.run_while(|| {
+            let mut running = false;
+            for (i, operation) in operations.iter().enumerate() {
+                match operation.get_state() {
+                    OperationState::Done => {
+                        found = i;
+                        return Some(Ok(()));
+                    },
+                    OperationState::Running => running = true,
+                    OperationState::Cancelled => {},
+                }
+            }
+            match running {
+                true => None,
+                false => Some(Err(WaitError::Cancelled)),
+            }
+        }, timeout)?;
+        Ok(found)
+    }
+
+    /// Runs iterations of the main loop until all operations of the given batch have completed or
+    /// been cancelled, returning their aggregated outcome.
+    ///
+    /// Should the given timeout, if any, pass first, the operations still running are cancelled,
+    /// and reported as such in the [`WaitError::Batch`] error, marked as timed out.
+    pub fn wait_for_batch(&mut self, batch: &mut Batch, timeout: Option<MicroSeconds>)
+        -> Result<(), WaitError>
+    {
+        match self.run_while(|| batch.outcome().map(|r| r.map_err(WaitError::Batch)), timeout) {
+            Err(WaitError::Timeout) => {
+                batch.cancel();
+                let mut err = match batch.outcome() {
+                    Some(Err(e)) => e,
+                    _ => BatchError::default(),
+                };
+                err.timed_out = true;
+                Err(WaitError::Batch(err))
+            },
+            r => r,
+        }
+    }
+
+    /// Runs iterations of the main loop until the given context reaches the given state, typically
+    /// [`context::State::Ready`].
+    ///
+    /// Returns [`WaitError::Failed`] should the context instead fail or be terminated, and
+    /// [`WaitError::Timeout`] if the state is not reached within the given timeout, if any.
+    pub fn wait_for_context_state(&mut self, context: &Context, state: context::State,
+        timeout: Option<MicroSeconds>) -> Result<(), WaitError>
+    {
+        self.run_while(|| match context.get_state() {
+            s if s == state => Some(Ok(())),
+            context::State::Failed | context::State::Terminated => {
+                Some(Err(WaitError::Failed(Error::from_context(context, "wait_for_context_state"))))
+            },
+            _ => None,
+        }, timeout)
+    }
+
+    /// Runs iterations of the main loop until the given stream reaches the given state, typically
+    /// [`stream::State::Ready`].
+    ///
+    /// Returns [`WaitError::Failed`] should the stream instead fail or be terminated, and
+    /// [`WaitError::Timeout`] if the state is not reached within the given timeout, if any.
+    pub fn wait_for_stream_state(&mut self, stream: &Stream, state: stream::State,
+        timeout: Option<MicroSeconds>) -> Result<(), WaitError>
+    {
+        self.run_while(|| match stream.get_state() {
+            s if s == state => Some(Ok(())),
+            stream::State::Failed | stream::State::Terminated => {
+                Some(Err(WaitError::Failed(Error::from_stream(stream, "wait_for_stream_state"))))
+            },
+            _ => None,
+        }, timeout)
+    }
+
+    /// Gets a handle for stopping the main loop from within callbacks.
+    pub fn quit_handle(&self) -> QuitHandle {
+        QuitHandle { inner: Rc::downgrade(&self._inner) }
+    }
+
+    /// Runs iterations of the main loop until the given check returns a result.
+    fn run_while<F>(&mut self, mut check: F, timeout: Option<MicroSeconds>)
+        -> Result<(), WaitError>
+        where F: FnMut() -> Option<Result<(), WaitError>>
+    {
+        let deadline = timeout.map(|t| Instant::now() + Duration::from_micros(t.inner()));
+        loop {
+            if let Some(r) = check() {
+                return r;
+            }
+            let remaining = match deadline {
+                None => None,
+                Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                    Some(d) if d > Duration::from_secs(0) => {
+                        // Note, rounded up, such that we do not spin upon sub-microsecond waits
+                        Some(MicroSeconds(((d.as_nanos() + 999) / 1000) as u64))
+                    },
+                    _ => return Err(WaitError::Timeout),
+                },
+            };
+            match self.iterate_timeout(remaining) {
+                IterateResult::Success(_) => {},
+                IterateResult::Quit(retval) => return Err(WaitError::Quit(retval)),
+                IterateResult::Err(e) => return Err(WaitError::Mainloop(e)),
+            }
         }
     }
 
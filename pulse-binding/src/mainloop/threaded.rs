@@ -78,6 +78,14 @@
 //! }
 //! ```
 //!
+//! Alternatively, [`Mainloop::lock_guard()`] returns a guard which releases the lock when dropped,
+//! and [`Mainloop::with_lock()`] runs a closure with the lock held.
+//!
+//! In debug builds, methods of context, stream, introspector and operation objects assert that
+//! they are called with the lock held, or from within the event loop thread, once the event loop
+//! thread is running. Note that operations do not record which mainloop they belong to, so for
+//! them the lock of any running threaded mainloop is accepted.
+//!
 //! # Callbacks
 //!
 //! Callbacks in PulseAudio are asynchronous, so they require extra care when using them together
@@ -388,12 +396,17 @@
 //! }
 //! ```
 
+use std::cell::UnsafeCell;
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::ffi::CString;
-use crate::def;
+use crate::{def, panic};
 use crate::error::PAErr;
 use crate::mainloop::api::{MainloopInternalType, MainloopInner, MainloopInnerType, MainloopApi,
                            Mainloop as MainloopTrait};
+use crate::mainloop::lockcheck;
 use crate::mainloop::signal::MainloopSignals;
 
 pub use capi::pa_threaded_mainloop as MainloopInternal;
@@ -426,15 +439,177 @@ impl MainloopSignals for Mainloop {}
 impl MainloopInner<MainloopInternal> {
     #[inline(always)]
     fn drop_actual(&mut self) {
+        lockcheck::remove_mainloop(self.get_ptr());
+        panic::remove_mainloop(self.get_api_ptr());
         unsafe { capi::pa_threaded_mainloop_free(self.get_ptr()) };
     }
 }
 
+/// Guard holding the lock of a threaded mainloop, releasing it when dropped.
+///
+/// Obtained with [`Mainloop::lock_guard()`].
+#[must_use = "the lock is released immediately if the guard is not held"]
+pub struct LockGuard {
+    /// The ref-counted inner data of the locked mainloop.
+    inner: Rc<MainloopInner<MainloopInternal>>,
+}
+
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        lockcheck::unlocked(self.inner.get_ptr());
+        unsafe { capi::pa_threaded_mainloop_unlock(self.inner.get_ptr()); }
+    }
+}
+
+/// Marker for objects attached to a main loop which may be used from any thread with the threaded
+/// mainloop lock held, allowing them to be wrapped in a [`Guarded`].
+///
+/// # Safety
+///
+/// Implementors must not hold state that is tied to the thread they are used from, beyond that
+/// protected by the threaded mainloop lock.
+pub unsafe trait LockBound {}
+
+/// An object attached to a threaded mainloop, which can be shared with other threads.
+///
+/// Objects such as [`Context`](crate::context::Context) and [`Stream`](crate::stream::Stream)
+/// are not `Send` or `Sync`, since they must only be used with the threaded mainloop lock held, or
+/// from within the event loop thread. This wrapper is `Send` and `Sync`, and only gives access to
+/// the object under those conditions: with [`with()`](Self::with), which takes the lock as needed,
+/// or with [`get_mut()`](Self::get_mut), given a [`LockGuard`] for the mainloop. The object is
+/// likewise dropped with the lock held.
+///
+/// The wrapper borrows the mainloop, such that it cannot outlive it; share it with scoped threads.
+/// The mainloop methods needed alongside it, such as [`Mainloop::lock()`] and [`Mainloop::wait()`],
+/// only take a shared reference.
+///
+/// The objects themselves cannot be sent to other threads:
+///
+/// ```rust,compile_fail
+/// fn assert_send<T: Send>() {}
+/// assert_send::<libpulse_binding::context::Context>();
+/// ```
+///
+/// # Example
+///
+/// ```rust,ignore
+/// // Safe, the context was created with this mainloop
+/// let context = unsafe { Guarded::new(&mainloop, context) };
+/// crossbeam::scope(|s| {
+///     s.spawn(|_| {
+///         let state = context.with(|c| c.get_state());
+///     });
+/// });
+/// ```
+pub struct Guarded<'a, T: LockBound> {
+    /// The wrapped object.
+    value: ManuallyDrop<UnsafeCell<T>>,
+    /// The mainloop the object is attached to.
+    ptr: *mut MainloopInternal,
+    /// Whether the object is currently borrowed, to catch re-entrant use.
+    borrowed: AtomicBool,
+    _mainloop: PhantomData<&'a ()>,
+}
+
+unsafe impl<T: LockBound> Send for Guarded<'_, T> {}
+unsafe impl<T: LockBound> Sync for Guarded<'_, T> {}
+
+impl<'a, T: LockBound> Guarded<'a, T> {
+    /// Wraps an object attached to the given mainloop.
+    ///
+    /// # Safety
+    ///
+    /// The object must be attached to the given mainloop, such as a context created with it, or a
+    /// stream, introspector or operation of such a context, since only the lock of this mainloop is
+    /// taken for access to it.
+    pub unsafe fn new(mainloop: &'a Mainloop, value: T) -> Self {
+        Self {
+            value: ManuallyDrop::new(UnsafeCell::new(value)),
+            ptr: mainloop._inner.get_ptr(),
+            borrowed: AtomicBool::new(false),
+            _mainloop: PhantomData,
+        }
+    }
+
+    /// Runs the given closure with access to the object, returning its result.
+    ///
+    /// The mainloop lock is taken for the duration, unless called from within the event loop
+    /// thread, where it is already held. This may be called with the lock already held, since it is
+    /// recursive.
+    ///
+    /// Panics if called re-entrantly from within the closure.
+    pub fn with<R, F>(&self, f: F) -> R
+        where F: FnOnce(&mut T) -> R
+    {
+        let _lock = RawLock::new(self.ptr);
+        assert!(!self.borrowed.swap(true, Ordering::Acquire), "object already in use");
+        // Reset the flag even upon panic
+        let _borrow = ResetOnDrop(&self.borrowed);
+        // Safe, with the lock held, and re-entrant use prevented
+        f(unsafe { &mut *self.value.get() })
+    }
+
+    /// Gets access to the object, given a guard holding the lock of the mainloop.
+    ///
+    /// Panics if the guard is for a different mainloop.
+    pub fn get_mut<'b>(&'b mut self, guard: &'b LockGuard) -> &'b mut T {
+        assert_eq!(guard.inner.get_ptr(), self.ptr, "lock guard is for a different mainloop");
+        self.value.get_mut()
+    }
+}
+
+impl<T: LockBound> Drop for Guarded<'_, T> {
+    fn drop(&mut self) {
+        let _lock = RawLock::new(self.ptr);
+        unsafe { ManuallyDrop::drop(&mut self.value) };
+    }
+}
+
+/// Holds the lock of a threaded mainloop, unless in its event loop thread, for internal use.
+struct RawLock(Option<*mut MainloopInternal>);
+
+impl RawLock {
+    fn new(ptr: *mut MainloopInternal) -> Self {
+        match unsafe { capi::pa_threaded_mainloop_in_thread(ptr) } {
+            0 => {
+                unsafe { capi::pa_threaded_mainloop_lock(ptr) };
+                lockcheck::locked(ptr);
+                RawLock(Some(ptr))
+            },
+            _ => RawLock(None),
+        }
+    }
+}
+
+impl Drop for RawLock {
+    fn drop(&mut self) {
+        if let Some(ptr) = self.0 {
+            lockcheck::unlocked(ptr);
+            unsafe { capi::pa_threaded_mainloop_unlock(ptr) };
+        }
+    }
+}
+
+/// Clears a flag when dropped.
+struct ResetOnDrop<'a>(&'a AtomicBool);
+
+impl Drop for ResetOnDrop<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
 impl Mainloop {
     /// Allocates a new threaded main loop object.
     ///
     /// You have to call [`start()`](Self::start) before the event loop thread starts running.
+    ///
+    /// Returns `None` on failure, including if the client library could not be loaded, with the
+    /// `dlopen` feature.
     pub fn new() -> Option<Self> {
+        if !capi::is_available() {
+            return None;
+        }
         let ptr = unsafe { capi::pa_threaded_mainloop_new() };
         if ptr.is_null() {
             return None;
@@ -445,13 +620,18 @@ impl Mainloop {
             MainloopInner::<MainloopInternal>::new(ptr, std::mem::transmute(api_ptr),
                 MainloopInner::<MainloopInternal>::drop_actual, true)
         };
+        lockcheck::add_mainloop(ptr, ml_inner.get_api_ptr());
+        panic::add_mainloop(ptr, ml_inner.get_api_ptr());
         Some(Self { _inner: Rc::new(ml_inner) })
     }
 
     /// Starts the event loop thread.
     pub fn start(&mut self) -> Result<(), PAErr> {
         match unsafe { capi::pa_threaded_mainloop_start(self._inner.get_ptr()) } {
-            0 => Ok(()),
+            0 => {
+                lockcheck::set_running(self._inner.get_ptr(), true);
+                Ok(())
+            },
             e => Err(PAErr(e)),
         }
     }
@@ -460,8 +640,9 @@ impl Mainloop {
     ///
     /// Make sure to unlock the mainloop object before calling this function.
     #[inline]
-    pub fn stop(&mut self) {
+    pub fn stop(&self) {
         unsafe { capi::pa_threaded_mainloop_stop(self._inner.get_ptr()); }
+        lockcheck::set_running(self._inner.get_ptr(), false);
     }
 
     /// Locks the event loop object, effectively blocking the event loop thread from processing
@@ -470,18 +651,45 @@ impl Mainloop {
     /// You can use this to enforce exclusive access to all objects attached to the event loop. This
     /// lock is recursive. This function may not be called inside the event loop thread. Events that
     /// are dispatched from the event loop thread are executed with this lock held.
+    ///
+    /// See also [`lock_guard()`](Self::lock_guard) and [`with_lock()`](Self::with_lock), which
+    /// release the lock automatically.
     #[inline]
-    pub fn lock(&mut self) {
+    pub fn lock(&self) {
         assert!(!self.in_thread(), "lock() can not be called from within the event loop thread!");
         unsafe { capi::pa_threaded_mainloop_lock(self._inner.get_ptr()); }
+        lockcheck::locked(self._inner.get_ptr());
     }
 
     /// Unlocks the event loop object, inverse of [`lock()`](Self::lock).
     #[inline]
-    pub fn unlock(&mut self) {
+    pub fn unlock(&self) {
+        lockcheck::unlocked(self._inner.get_ptr());
         unsafe { capi::pa_threaded_mainloop_unlock(self._inner.get_ptr()); }
     }
 
+    /// Locks the event loop object, as with [`lock()`](Self::lock), returning a guard that unlocks
+    /// it again when dropped.
+    ///
+    /// Note that the guard does not borrow the mainloop, such that methods like
+    /// [`wait()`](Self::wait) remain available while it is held.
+    pub fn lock_guard(&self) -> LockGuard {
+        assert!(!self.in_thread(), "lock() can not be called from within the event loop thread!");
+        unsafe { capi::pa_threaded_mainloop_lock(self._inner.get_ptr()); }
+        lockcheck::locked(self._inner.get_ptr());
+        LockGuard { inner: Rc::clone(&self._inner) }
+    }
+
+    /// Runs the given closure with the event loop object locked, returning its result.
+    ///
+    /// The lock is released upon return, including if the closure panics.
+    pub fn with_lock<R, F>(&mut self, f: F) -> R
+        where F: FnOnce(&mut Self) -> R
+    {
+        let _guard = self.lock_guard();
+        f(self)
+    }
+
     /// Waits for an event to be signalled by the event loop thread.
     ///
     /// You can use this to pass data from the event loop thread to the main thread in a
@@ -494,7 +702,7 @@ impl Mainloop {
     /// [`lock()`]: Self::lock
     /// [`signal()`]: Self::signal
     #[inline]
-    pub fn wait(&mut self) {
+    pub fn wait(&self) {
         unsafe { capi::pa_threaded_mainloop_wait(self._inner.get_ptr()); }
     }
 
@@ -506,7 +714,7 @@ impl Mainloop {
     /// [`wait()`]: Self::wait
     /// [`accept()`]: Self::accept
     #[inline]
-    pub fn signal(&mut self, wait_for_accept: bool) {
+    pub fn signal(&self, wait_for_accept: bool) {
         unsafe {
             capi::pa_threaded_mainloop_signal(self._inner.get_ptr(), wait_for_accept as i32);
         }
@@ -519,7 +727,7 @@ impl Mainloop {
     ///
     /// [`signal()`]: Self::signal
     #[inline]
-    pub fn accept(&mut self) {
+    pub fn accept(&self) {
         unsafe { capi::pa_threaded_mainloop_accept(self._inner.get_ptr()); }
     }
 
@@ -552,6 +760,33 @@ impl Mainloop {
         unsafe { capi::pa_threaded_mainloop_in_thread(self._inner.get_ptr()) != 0 }
     }
 
+    /// Takes the payload of a panic caught within a callback of this mainloop, if any.
+    ///
+    /// Callbacks run in the event loop thread, so panics within them cannot be resumed in the
+    /// thread using the mainloop automatically. With [`panic::Policy::Propagate`] they are instead
+    /// stored, to be collected with this, such as after returning from [`wait()`]. Only panics of
+    /// callbacks run within this mainloop’s event loop thread are returned.
+    ///
+    /// [`wait()`]: Self::wait
+    #[inline]
+    pub fn take_panic(&self) -> Option<panic::Payload> {
+        panic::take_mainloop(self._inner.get_api_ptr())
+    }
+
+    /// Resumes a panic caught within a callback, if any.
+    ///
+    /// This is a convenience for [`take_panic()`] followed by [`std::panic::resume_unwind()`].
+    /// Take care not to call it with the lock held, unless unwinding will not be caught, since the
+    /// lock would not be released.
+    ///
+    /// [`take_panic()`]: Self::take_panic
+    #[inline]
+    pub fn resume_panic(&self) {
+        if let Some(payload) = self.take_panic() {
+            std::panic::resume_unwind(payload);
+        }
+    }
+
     /// Sets the name of the thread.
     pub fn set_name(&mut self, name: &str) {
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
@@ -560,3 +795,48 @@ impl Mainloop {
         unsafe { capi::pa_threaded_mainloop_set_name(self._inner.get_ptr(), c_name.as_ptr()); }
     }
 }
+
+#[cfg(debug_assertions)]
+#[test]
+fn guarded() {
+    use std::sync::Arc;
+
+    /// Records whether or not the lock was held when dropped.
+    struct Probe(*mut MainloopInternal, Arc<AtomicBool>);
+    unsafe impl LockBound for Probe {}
+    impl Drop for Probe {
+        fn drop(&mut self) {
+            self.1.store(lockcheck::is_locked(self.0), Ordering::SeqCst);
+        }
+    }
+
+    let mut mainloop = Mainloop::new().unwrap();
+    mainloop.start().unwrap();
+    let ptr = mainloop._inner.get_ptr();
+    let dropped_locked = Arc::new(AtomicBool::new(false));
+    let mut guarded = unsafe { Guarded::new(&mainloop, Probe(ptr, Arc::clone(&dropped_locked))) };
+
+    assert!(guarded.with(|probe| lockcheck::is_locked(probe.0)));
+    assert!(!lockcheck::is_locked(ptr));
+    let reentrant = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        guarded.with(|_| guarded.with(|_| ()))
+    }));
+    assert!(reentrant.is_err());
+    assert!(!lockcheck::is_locked(ptr));
+
+    // The mainloop remains usable while wrapped objects borrow it
+    mainloop.lock();
+    mainloop.signal(false);
+    mainloop.unlock();
+
+    {
+        let guard = mainloop.lock_guard();
+        assert_eq!(guarded.get_mut(&guard).0, ptr);
+    }
+    assert!(!lockcheck::is_locked(ptr));
+
+    drop(guarded);
+    assert!(dropped_locked.load(Ordering::SeqCst));
+    assert!(!lockcheck::is_locked(ptr));
+    mainloop.stop();
+}
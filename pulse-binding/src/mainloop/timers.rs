@@ -0,0 +1,253 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! Higher level timers, built upon main loop timer events.
+//!
+//! # Overview
+//!
+//! Timer events, as created with [`Mainloop::new_timer_event_rt()`] or
+//! [`Context::rttime_new()`], fire once, and must be restarted manually to fire again. This module
+//! provides, for any main loop implementation:
+//!
+//!  * [`Interval`]: A repeating timer, firing at a fixed period. Each expiry is scheduled relative
+//!    to the previous one rather than to when the callback happened to run, such that dispatch
+//!    latency does not accumulate as drift; should the main loop fall behind by more than a period,
+//!    the missed expiries are skipped and reported to the callback.
+//!  * [`Sleep`]: A [`Future`] completing after a given time, as obtained with [`sleep()`], for use
+//!    by executors integrated with the main loop.
+//!  * [`TimedOperation`]: An [`Operation`] with a deadline, cancelled should it not have completed
+//!    in time, as obtained with [`Operation::with_timeout()`].
+//!
+//! Like the timer events they are built upon, these must be created, used and dropped on the
+//! thread running the main loop, or with the lock held in the case of the threaded main loop, and
+//! stop firing once dropped.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use libpulse_binding::mainloop::timers::Interval;
+//! use libpulse_binding::time::MicroSeconds;
+//!
+//! let _interval = Interval::new(&mut mainloop, MicroSeconds::from_millis(100).unwrap(),
+//!     |ticks| { println!("{} period(s) elapsed", ticks); }).unwrap();
+//! ```
+//!
+//! [`Mainloop::new_timer_event_rt()`]: crate::mainloop::api::Mainloop::new_timer_event_rt
+//! [`Context::rttime_new()`]: crate::context::Context::rttime_new
+
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context as TaskContext, Poll, Waker};
+use crate::mainloop::api::{Mainloop, MainloopInnerType};
+use crate::mainloop::events::timer::TimeEvent;
+use crate::operation::{self, Operation};
+use crate::time::{MicroSeconds, MonotonicTs};
+
+/// A repeating timer.
+///
+/// The callback is given the number of periods elapsed since it was last called, which is `1`
+/// unless expiries were skipped due to the main loop falling behind.
+pub struct Interval<MI: MainloopInnerType> {
+    /// The period.
+    period: MicroSeconds,
+    /// The underlying timer event.
+    _event: TimeEvent<MI>,
+}
+
+impl<MI: MainloopInnerType + 'static> Interval<MI> {
+    /// Creates a new interval timer on the given main loop, first firing one period from now.
+    ///
+    /// Panics if `period` is zero or invalid. Returns `None` if the timer event could not be
+    /// created.
+    pub fn new<M, F>(mainloop: &mut M, period: MicroSeconds, mut callback: F) -> Option<Self>
+        where M: Mainloop<MI = MI> + ?Sized,
+              F: FnMut(u64) + 'static
+    {
+        assert!(period.is_valid() && !period.is_zero(), "invalid interval period");
+        let mut next = MonotonicTs::now() + period;
+        let event = mainloop.new_timer_event_rt(next, Box::new(move |mut event| {
+            let ticks = ticks_due(next, MonotonicTs::now(), period);
+            next += MicroSeconds(period.0 * ticks);
+            event.restart_rt(next);
+            callback(ticks);
+        }))?;
+        Some(Self { period, _event: event })
+    }
+
+    /// Gets the period.
+    #[inline]
+    pub fn period(&self) -> MicroSeconds {
+        self.period
+    }
+}
+
+/// Gets the number of periods elapsed upon an expiry scheduled for `due` running at `now`.
+fn ticks_due(due: MonotonicTs, now: MonotonicTs, period: MicroSeconds) -> u64 {
+    1 + (now.0).0.saturating_sub((due.0).0) / period.0
+}
+
+/// State shared between a [`Sleep`] and its timer event.
+#[derive(Default)]
+struct SleepState {
+    /// Whether the timer has fired.
+    fired: bool,
+    /// The waker of the task last polling the future.
+    waker: Option<Waker>,
+}
+
+/// A future completing once a given time has passed.
+///
+/// Obtained from [`sleep()`]. The task polling it is woken from the main loop when the timer fires,
+/// so it must be polled by an executor running along with the main loop, on its thread.
+pub struct Sleep<MI: MainloopInnerType> {
+    /// State shared with the timer event.
+    state: Rc<RefCell<SleepState>>,
+    /// The underlying timer event.
+    _event: TimeEvent<MI>,
+}
+
+/// Creates a future completing once the given time has passed, timed by the given main loop.
+///
+/// Returns `None` if the timer event could not be created.
+pub fn sleep<M>(mainloop: &mut M, duration: MicroSeconds) -> Option<Sleep<M::MI>>
+    where M: Mainloop + ?Sized,
+          M::MI: 'static
+{
+    let state = Rc::new(RefCell::new(SleepState::default()));
+    let state_for_event = Rc::clone(&state);
+    let event = mainloop.new_timer_event_rt(MonotonicTs::now() + duration, Box::new(move |_| {
+        let waker = {
+            let mut state = state_for_event.borrow_mut();
+            state.fired = true;
+            state.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }))?;
+    Some(Sleep { state, _event: event })
+}
+
+impl<MI: MainloopInnerType> Sleep<MI> {
+    /// Checks whether the time has passed.
+    #[inline]
+    pub fn is_elapsed(&self) -> bool {
+        self.state.borrow().fired
+    }
+}
+
+impl<MI: MainloopInnerType> Future for Sleep<MI> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskContext) -> Poll<()> {
+        let mut state = self.state.borrow_mut();
+        match state.fired {
+            true => Poll::Ready(()),
+            false => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            },
+        }
+    }
+}
+
+/// An operation with a deadline.
+///
+/// Obtained from [`Operation::with_timeout()`]. Should the operation still be running once the
+/// timeout passes, it is cancelled, with [`timed_out()`](Self::timed_out) then returning `true`.
+/// The operation itself remains accessible by dereferencing, such as for waiting upon it with
+/// [`standard::Mainloop::wait_for_operation()`], which reports the cancellation.
+///
+/// [`standard::Mainloop::wait_for_operation()`]:
+///     crate::mainloop::standard::Mainloop::wait_for_operation
+pub struct TimedOperation<MI: MainloopInnerType, ClosureProto: ?Sized> {
+    /// The timer event, declared first such that it is dropped before the operation.
+    _event: TimeEvent<MI>,
+    /// The operation.
+    operation: Operation<ClosureProto>,
+    /// Whether the operation was cancelled due to the timeout.
+    timed_out: Rc<Cell<bool>>,
+}
+
+impl<MI: MainloopInnerType + 'static, ClosureProto: ?Sized> TimedOperation<MI, ClosureProto> {
+    /// Attaches a timeout to the given operation.
+    pub(crate) fn new<M>(mainloop: &mut M, operation: Operation<ClosureProto>,
+        timeout: MicroSeconds) -> Option<Self>
+        where M: Mainloop<MI = MI> + ?Sized
+    {
+        let timed_out = Rc::new(Cell::new(false));
+        let timed_out_for_event = Rc::clone(&timed_out);
+        let ptr = operation.as_ptr();
+        // Note, the event is dropped before the operation, so the pointer remains valid
+        let event = mainloop.new_timer_event_rt(MonotonicTs::now() + timeout, Box::new(move |_| {
+            if unsafe { operation::cancel_raw_if_running(ptr) } {
+                timed_out_for_event.set(true);
+            }
+        }))?;
+        Some(Self { _event: event, operation, timed_out })
+    }
+
+    /// Checks whether the operation was cancelled due to the timeout.
+    #[inline]
+    pub fn timed_out(&self) -> bool {
+        self.timed_out.get()
+    }
+}
+
+impl<MI: MainloopInnerType, ClosureProto: ?Sized> operation::AnyOperation
+    for TimedOperation<MI, ClosureProto>
+{
+    #[inline]
+    fn get_state(&self) -> operation::State {
+        self.operation.get_state()
+    }
+
+    #[inline]
+    fn cancel(&mut self) {
+        self.operation.cancel()
+    }
+}
+
+impl<MI: MainloopInnerType, ClosureProto: ?Sized> Deref for TimedOperation<MI, ClosureProto> {
+    type Target = Operation<ClosureProto>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.operation
+    }
+}
+
+impl<MI: MainloopInnerType, ClosureProto: ?Sized> DerefMut for TimedOperation<MI, ClosureProto> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.operation
+    }
+}
+
+impl<MI: MainloopInnerType, ClosureProto: ?Sized> Drop for TimedOperation<MI, ClosureProto> {
+    fn drop(&mut self) {
+        // Complete the cancellation made from the timer event, releasing the saved callback
+        if self.timed_out.get() {
+            self.operation.cancel();
+        }
+    }
+}
+
+#[test]
+fn interval_ticks() {
+    let period = MicroSeconds(1000);
+    let due = MonotonicTs(MicroSeconds(10_000));
+    assert_eq!(ticks_due(due, MonotonicTs(MicroSeconds(9_990)), period), 1);
+    assert_eq!(ticks_due(due, due, period), 1);
+    assert_eq!(ticks_due(due, MonotonicTs(MicroSeconds(10_999)), period), 1);
+    assert_eq!(ticks_due(due, MonotonicTs(MicroSeconds(11_000)), period), 2);
+    assert_eq!(ticks_due(due, MonotonicTs(MicroSeconds(13_500)), period), 4);
+}
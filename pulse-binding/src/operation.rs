@@ -12,10 +12,40 @@
 // fair-use basis, as discussed in the overall project readme (available in the git repository).
 
 //! Asynchronous operations.
+//!
+//! # Combining operations
+//!
+//! Sets of operations, of any callback type, can be waited upon together through the
+//! [`AnyOperation`] trait, with [`standard::Mainloop::wait_for_all()`] and
+//! [`standard::Mainloop::wait_for_any()`]. Where a batch of changes is made, each reporting success
+//! through a callback, such as setting the volumes of several sink inputs, a [`Batch`] collects the
+//! operations and aggregates their outcomes into a single [`Result`], waited upon, optionally with
+//! a deadline, with [`standard::Mainloop::wait_for_batch()`].
+//!
+//! ```rust,ignore
+//! use libpulse_binding::operation::Batch;
+//!
+//! let mut batch = Batch::new();
+//! for index in sink_inputs {
+//!     batch.add(|cb| introspector.set_sink_input_volume(index, &volume, Some(cb)));
+//! }
+//! mainloop.wait_for_batch(&mut batch, Some(MicroSeconds::from_secs(5).unwrap()))?;
+//! ```
+//!
+//! [`standard::Mainloop::wait_for_all()`]: crate::mainloop::standard::Mainloop::wait_for_all
+//! [`standard::Mainloop::wait_for_any()`]: crate::mainloop::standard::Mainloop::wait_for_any
+//! [`standard::Mainloop::wait_for_batch()`]: crate::mainloop::standard::Mainloop::wait_for_batch
 
+use std::cell::RefCell;
+use std::fmt;
 use std::os::raw::c_void;
 use std::ptr::null_mut;
+use std::rc::Rc;
 use crate::callbacks;
+use crate::mainloop::api::{Mainloop, MainloopInnerType};
+use crate::mainloop::lockcheck;
+use crate::mainloop::timers::TimedOperation;
+use crate::time::MicroSeconds;
 
 use capi::pa_operation as OperationInternal;
 pub use capi::pa_operation_state_t as State;
@@ -36,8 +66,8 @@ pub struct Operation<ClosureProto: ?Sized> {
     state_cb: NotifyCb,
 }
 
-unsafe impl<ClosureProto: ?Sized> Send for Operation<ClosureProto> {}
-unsafe impl<ClosureProto: ?Sized> Sync for Operation<ClosureProto> {}
+unsafe impl<ClosureProto: ?Sized> crate::mainloop::threaded::LockBound
+    for Operation<ClosureProto> {}
 
 type NotifyCb = callbacks::MultiUseCallback<dyn FnMut(),
     extern "C" fn(*mut OperationInternal, *mut c_void)>;
@@ -67,6 +97,7 @@ impl<ClosureProto: ?Sized> Operation<ClosureProto> {
     /// no sense to try and do this, but be aware that this is not supported by the C API and
     /// **will** break things.
     pub fn cancel(&mut self) {
+        self.check_lock();
         unsafe { capi::pa_operation_cancel(self.ptr); }
         // Release the memory allocated for the closure.
         // Note, we `take()` here to help avoid issues if this function is mistakenly called more
@@ -82,6 +113,7 @@ impl<ClosureProto: ?Sized> Operation<ClosureProto> {
     /// Gets the current status of the operation.
     #[inline]
     pub fn get_state(&self) -> State {
+        self.check_lock();
         unsafe { capi::pa_operation_get_state(self.ptr) }
     }
 
@@ -92,11 +124,60 @@ impl<ClosureProto: ?Sized> Operation<ClosureProto> {
     /// callback is mainly useful, if you want to get called back also if the operation gets
     /// cancelled.
     pub fn set_state_callback(&mut self, callback: Option<Box<dyn FnMut() + 'static>>) {
+        self.check_lock();
         let saved = &mut self.state_cb;
         *saved = NotifyCb::new(callback);
         let (cb_fn, cb_data) = saved.get_capi_params(notify_cb_proxy);
         unsafe { capi::pa_operation_set_state_callback(self.ptr, cb_fn, cb_data); }
     }
+
+    /// Attaches a timeout to the operation, such that it is cancelled should it not have completed
+    /// within the given time, timed by the given main loop.
+    ///
+    /// Returns `None` if the timer event could not be created. See [`TimedOperation`] for details.
+    pub fn with_timeout<M>(self, mainloop: &mut M, timeout: MicroSeconds)
+        -> Option<TimedOperation<M::MI, ClosureProto>>
+        where M: Mainloop + ?Sized,
+              M::MI: MainloopInnerType + 'static
+    {
+        TimedOperation::new(mainloop, self, timeout)
+    }
+
+    /// Gets the underlying C object pointer.
+    #[inline(always)]
+    pub(crate) fn as_ptr(&self) -> *mut OperationInternal {
+        self.ptr
+    }
+}
+
+/// Cancels the operation at the given pointer if it is still running, returning whether it was.
+///
+/// Unlike [`Operation::cancel()`], this does not release the saved callback closure, which must be
+/// done separately by cancelling the owning [`Operation`].
+///
+/// # Safety
+///
+/// The pointer must be that of a live operation.
+pub(crate) unsafe fn cancel_raw_if_running(ptr: *mut OperationInternal) -> bool {
+    match capi::pa_operation_get_state(ptr) {
+        State::Running => {
+            capi::pa_operation_cancel(ptr);
+            true
+        },
+        _ => false,
+    }
+}
+
+impl<ClosureProto: ?Sized> Operation<ClosureProto> {
+    /// Asserts, in debug builds, that the operation may be used from the current thread.
+    ///
+    /// Operations do not record their context, so the lock of any running threaded mainloop is
+    /// accepted.
+    #[inline(always)]
+    #[track_caller]
+    fn check_lock(&self) {
+        lockcheck::check_any();
+    }
 }
 
 impl<ClosureProto: ?Sized> Drop for Operation<ClosureProto> {
@@ -108,14 +189,161 @@ impl<ClosureProto: ?Sized> Drop for Operation<ClosureProto> {
     }
 }
 
+/// An operation of any callback type.
+///
+/// This allows operations with differing callback types to be handled together, such as for
+/// waiting upon a set of them.
+pub trait AnyOperation {
+    /// Gets the current status of the operation.
+    fn get_state(&self) -> State;
+
+    /// Cancels the operation. See [`Operation::cancel()`].
+    fn cancel(&mut self);
+}
+
+impl<ClosureProto: ?Sized> AnyOperation for Operation<ClosureProto> {
+    #[inline]
+    fn get_state(&self) -> State {
+        Operation::get_state(self)
+    }
+
+    #[inline]
+    fn cancel(&mut self) {
+        Operation::cancel(self)
+    }
+}
+
+/// A batch of operations reporting success through callbacks, with a single aggregated outcome.
+///
+/// Operations are added with [`add()`](Self::add), which provides the success callback to be given
+/// to the function issuing each. Operations are identified in a [`BatchError`] by the order in
+/// which they were added, starting from zero.
+#[derive(Default)]
+pub struct Batch {
+    /// The operations, in order added.
+    operations: Vec<Operation<dyn FnMut(bool)>>,
+    /// The success of each operation, once its callback has been called.
+    results: Rc<RefCell<Vec<Option<bool>>>>,
+}
+
+unsafe impl crate::mainloop::threaded::LockBound for Batch {}
+
+impl Batch {
+    /// Creates a new empty batch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Issues an operation and adds it to the batch.
+    ///
+    /// The given closure is given the success callback to pass to the function issuing the
+    /// operation, and must return the resulting operation.
+    pub fn add<F>(&mut self, issue: F)
+        where F: FnOnce(Box<dyn FnMut(bool) + 'static>) -> Operation<dyn FnMut(bool)>
+    {
+        let index = self.operations.len();
+        self.results.borrow_mut().push(None);
+        let results = Rc::clone(&self.results);
+        let operation = issue(Box::new(move |success| {
+            results.borrow_mut()[index] = Some(success);
+        }));
+        self.operations.push(operation);
+    }
+
+    /// Gets the number of operations in the batch.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    /// Checks whether the batch is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Gets the operations, in order added.
+    #[inline]
+    pub fn operations(&self) -> &[Operation<dyn FnMut(bool)>] {
+        &self.operations
+    }
+
+    /// Checks whether all of the operations have completed or been cancelled.
+    pub fn is_done(&self) -> bool {
+        self.operations.iter().all(|o| o.get_state() != State::Running)
+    }
+
+    /// Cancels all operations still running.
+    pub fn cancel(&mut self) {
+        for operation in self.operations.iter_mut() {
+            if operation.get_state() == State::Running {
+                operation.cancel();
+            }
+        }
+    }
+
+    /// Gets the aggregated outcome of the operations, or `None` if any are still running.
+    ///
+    /// This is `Ok` only if every operation completed and reported success.
+    pub fn outcome(&self) -> Option<Result<(), BatchError>> {
+        let results = self.results.borrow();
+        let mut err = BatchError::default();
+        for (i, operation) in self.operations.iter().enumerate() {
+            match operation.get_state() {
+                State::Running => return None,
+                State::Cancelled => err.cancelled.push(i),
+                State::Done if results[i] != Some(true) => err.failed.push(i),
+                State::Done => {},
+            }
+        }
+        match err.failed.is_empty() && err.cancelled.is_empty() {
+            true => Some(Ok(())),
+            false => Some(Err(err)),
+        }
+    }
+}
+
+/// Error type for the aggregated outcome of a [`Batch`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BatchError {
+    /// Indices of the operations that completed but reported failure.
+    pub failed: Vec<usize>,
+    /// Indices of the operations that were cancelled, including any cancelled upon a deadline.
+    pub cancelled: Vec<usize>,
+    /// Whether a deadline passed, with the operations still running then cancelled.
+    pub timed_out: bool,
+}
+
+impl std::error::Error for BatchError {}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} operation(s) failed, {} cancelled", self.failed.len(), self.cancelled.len())?;
+        match self.timed_out {
+            true => write!(f, " (timed out)"),
+            false => Ok(()),
+        }
+    }
+}
+
 /// Proxy for notification callbacks.
 ///
 /// Warning: This is for multi-use cases! It does **not** destroy the actual closure callback, which
 /// must be accomplished separately to avoid a memory leak.
 extern "C"
 fn notify_cb_proxy(_: *mut OperationInternal, userdata: *mut c_void) {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         let callback = NotifyCb::get_callback(userdata);
         (callback)();
     });
 }
+
+#[test]
+fn batch_outcome() {
+    let batch = Batch::new();
+    assert!(batch.is_empty() && batch.is_done());
+    assert_eq!(batch.outcome(), Some(Ok(())));
+
+    let err = BatchError { failed: vec![1, 4], cancelled: vec![2], timed_out: true };
+    assert_eq!(err.to_string(), "2 operation(s) failed, 1 cancelled (timed out)");
+}
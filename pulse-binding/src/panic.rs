@@ -0,0 +1,207 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! Handling of panics within callbacks.
+//!
+//! # Overview
+//!
+//! Callbacks given to this binding are called from C code, through which a panic must not unwind.
+//! Panics within callbacks are therefore caught, and then handled according to the [`Policy`] set
+//! with [`set_policy()`]:
+//!
+//!  - With [`Policy::Propagate`] (the default), the panic payload is stored, and the panic is
+//!    resumed from the next call to [`standard::Mainloop::iterate()`], [`run()`] or
+//!    [`dispatch()`] once control has returned from C code. With the threaded mainloop, where
+//!    callbacks run on the mainloop’s own thread, the payload must instead be collected with
+//!    [`threaded::Mainloop::take_panic()`].
+//!  - With [`Policy::Log`], a message is printed to standard error and execution continues.
+//!  - With [`Policy::Abort`], a message is printed to standard error and the process is aborted.
+//!
+//! Stored payloads are held per threaded mainloop for callbacks run within its event loop thread,
+//! and otherwise per thread, such that each mainloop only resumes panics of its own callbacks.
+//!
+//! [`standard::Mainloop::iterate()`]: crate::mainloop::standard::Mainloop::iterate
+//! [`run()`]: crate::mainloop::standard::Mainloop::run
+//! [`dispatch()`]: crate::mainloop::standard::Mainloop::dispatch
+//! [`threaded::Mainloop::take_panic()`]: crate::mainloop::threaded::Mainloop::take_panic
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::panic::UnwindSafe;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Mutex, MutexGuard, Once};
+use capi::pa_threaded_mainloop as ThreadedInternal;
+use crate::mainloop::api::MainloopApi;
+
+/// A panic payload, as returned by [`std::panic::catch_unwind()`].
+pub type Payload = Box<dyn Any + Send + 'static>;
+
+/// What to do with a panic caught within a callback.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Policy {
+    /// Store the payload, to be resumed from the mainloop once control returns from C code.
+    Propagate,
+    /// Print a message to standard error and carry on.
+    Log,
+    /// Print a message to standard error and abort the process.
+    Abort,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Policy::Propagate
+    }
+}
+
+impl Policy {
+    fn from_u8(p: u8) -> Self {
+        match p {
+            p if p == Policy::Log as u8 => Policy::Log,
+            p if p == Policy::Abort as u8 => Policy::Abort,
+            _ => Policy::Propagate,
+        }
+    }
+}
+
+static POLICY: AtomicU8 = AtomicU8::new(Policy::Propagate as u8);
+
+/// Payloads stored for the callbacks of each threaded mainloop, by API pointer.
+#[derive(Default)]
+struct Threaded {
+    /// Mainloop pointer and stored payloads, by API pointer.
+    mainloops: HashMap<usize, (usize, VecDeque<Payload>)>,
+}
+
+static THREADED_INIT: Once = Once::new();
+static mut THREADED: Option<Mutex<Threaded>> = None;
+
+thread_local! {
+    /// Payloads stored for callbacks run on this thread, other than within the event loop thread
+    /// of a threaded mainloop.
+    static PENDING: RefCell<VecDeque<Payload>> = RefCell::new(VecDeque::new());
+}
+
+/// Sets the policy for handling panics within callbacks, returning the previous one.
+pub fn set_policy(policy: Policy) -> Policy {
+    Policy::from_u8(POLICY.swap(policy as u8, Ordering::AcqRel))
+}
+
+/// Gets the policy for handling panics within callbacks.
+pub fn policy() -> Policy {
+    Policy::from_u8(POLICY.load(Ordering::Acquire))
+}
+
+/// Takes the oldest stored panic payload of a callback run on the current thread, if any.
+///
+/// Payloads of callbacks run within the event loop thread of a threaded mainloop are not returned
+/// here, use [`threaded::Mainloop::take_panic()`] for those.
+///
+/// [`threaded::Mainloop::take_panic()`]: crate::mainloop::threaded::Mainloop::take_panic
+pub fn take() -> Option<Payload> {
+    PENDING.with(|pending| pending.borrow_mut().pop_front())
+}
+
+/// Resumes the oldest stored panic of a callback run on the current thread, if any.
+pub fn resume() {
+    if let Some(payload) = take() {
+        std::panic::resume_unwind(payload);
+    }
+}
+
+/// Gets the message of a panic payload, if it has one.
+pub fn message(payload: &Payload) -> Option<&str> {
+    match payload.downcast_ref::<&'static str>() {
+        Some(s) => Some(*s),
+        None => payload.downcast_ref::<String>().map(|s| s.as_str()),
+    }
+}
+
+/// Runs a callback, catching and handling any panic per the current policy.
+pub(crate) fn catch<F: FnOnce() -> R + UnwindSafe, R>(f: F) -> Option<R> {
+    match std::panic::catch_unwind(f) {
+        Ok(r) => Some(r),
+        Err(payload) => {
+            handle(payload);
+            None
+        },
+    }
+}
+
+/// Handles a caught panic per the current policy.
+fn handle(payload: Payload) {
+    match policy() {
+        Policy::Propagate => {
+            let mut threaded = threaded();
+            let own = threaded.mainloops.values_mut().find(|(ml, _)| unsafe {
+                capi::pa_threaded_mainloop_in_thread(*ml as *mut ThreadedInternal) != 0
+            });
+            match own {
+                Some((_, pending)) => pending.push_back(payload),
+                None => PENDING.with(|pending| pending.borrow_mut().push_back(payload)),
+            }
+        },
+        Policy::Log => {
+            eprintln!("libpulse-binding: panic in callback: {}",
+                message(&payload).unwrap_or("Box<dyn Any>"));
+        },
+        Policy::Abort => {
+            eprintln!("libpulse-binding: panic in callback, aborting: {}",
+                message(&payload).unwrap_or("Box<dyn Any>"));
+            std::process::abort();
+        },
+    }
+}
+
+/// Records a new threaded mainloop, for storing the panics of its callbacks.
+pub(crate) fn add_mainloop(ml: *mut ThreadedInternal, api: *const MainloopApi) {
+    threaded().mainloops.insert(api as usize, (ml as usize, VecDeque::new()));
+}
+
+/// Forgets a threaded mainloop being freed, along with any panics stored for it.
+pub(crate) fn remove_mainloop(api: *const MainloopApi) {
+    threaded().mainloops.remove(&(api as usize));
+}
+
+/// Takes the oldest stored panic payload of a callback of the given threaded mainloop, if any.
+pub(crate) fn take_mainloop(api: *const MainloopApi) -> Option<Payload> {
+    threaded().mainloops.get_mut(&(api as usize)).and_then(|(_, pending)| pending.pop_front())
+}
+
+fn threaded() -> MutexGuard<'static, Threaded> {
+    // Safe, only written once, within the `Once`, before any read
+    THREADED_INIT.call_once(|| unsafe { THREADED = Some(Mutex::new(Threaded::default())) });
+    match unsafe { &*std::ptr::addr_of!(THREADED) } {
+        Some(m) => m.lock().unwrap_or_else(|e| e.into_inner()),
+        None => unreachable!(),
+    }
+}
+
+#[test]
+fn propagate() {
+    let r = catch(|| -> u32 { panic!("in callback") });
+    assert!(r.is_none());
+    let payload = take().unwrap();
+    assert_eq!(message(&payload), Some("in callback"));
+    assert_eq!(catch(|| 5), Some(5));
+    assert!(std::panic::catch_unwind(|| {
+        let _ = catch(|| -> () { panic!("{}", 5) });
+        resume();
+    }).is_err());
+    assert!(take().is_none());
+}
+
+#[test]
+fn per_thread() {
+    std::thread::spawn(|| {
+        let _ = catch(|| -> () { panic!("elsewhere") });
+    }).join().unwrap();
+    assert!(take().is_none());
+}
@@ -215,7 +215,13 @@ impl PartialEq for Proplist {
 
 impl Proplist {
     /// Allocates a property list.
+    ///
+    /// Returns `None` on failure, including if the client library could not be loaded, with the
+    /// `dlopen` feature.
     pub fn new() -> Option<Self> {
+        if !capi::is_available() {
+            return None;
+        }
         let ptr = unsafe { capi::pa_proplist_new() };
         match ptr.is_null() {
             false => Some(Self::from_raw(ptr)),
@@ -0,0 +1,395 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+//
+// Portions of documentation are copied from the LGPL 2.1+ licensed PulseAudio C headers on a
+// fair-use basis, as discussed in the overall project readme (available in the git repository).
+
+//! Native protocol wire format.
+//!
+//! # Overview
+//!
+//! Clients communicate with the PulseAudio daemon over a socket using the “native” protocol. This
+//! is normally entirely hidden within the client library, but being able to encode and decode it
+//! directly is useful for building protocol dumpers, debugging wire-level issues, and for
+//! implementing stand-in servers for testing.
+//!
+//! The byte stream consists of a sequence of packets, each made up of a fixed size [`Descriptor`]
+//! followed by a payload. Packets on the [`CONTROL_CHANNEL`] carry commands, replies and
+//! notifications, encoded as a “tagstruct”, a sequence of type tagged values; packets on other
+//! channels carry audio data for the stream with the corresponding channel number. Reading and
+//! writing of whole packets is offered by [`Packet`].
+//!
+//! Tagstructs are built with [`tagstruct::Writer`] and taken apart with [`tagstruct::Reader`].
+//! Sample specs, channel maps, volumes, property lists and format info objects are encoded from
+//! and decoded into the same types used throughout the rest of this binding. A command tagstruct
+//! starts with the [`Command`] and a tag number, which is echoed in the reply to match it to the
+//! request.
+//!
+//! Note that the layout of the content of each command varies with the negotiated protocol version.
+//! This module deals with the encoding of the individual values only; which values are present is
+//! up to the user.
+//!
+//! # Example
+//!
+//! Dumping the commands in a captured stream of packets:
+//!
+//! ```rust,ignore
+//! use libpulse_binding::protocol::{Command, Packet, tagstruct::Reader};
+//!
+//! while let Ok(packet) = Packet::read(&mut capture) {
+//!     if let Packet::Control(data) = packet {
+//!         let mut reader = Reader::new(&data);
+//!         let command = reader.get_u32().unwrap();
+//!         let tag = reader.get_u32().unwrap();
+//!         print!("{:?} (tag {}):", Command::from_u32(command), tag);
+//!         while !reader.is_empty() {
+//!             print!(" {:?}", reader.get_value().unwrap());
+//!         }
+//!         println!();
+//!     }
+//! }
+//! ```
+
+pub mod tagstruct;
+
+use std::io::{self, Read, Write};
+use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::FromPrimitive as FromPrimitiveTrait;
+use crate::stream::SeekMode;
+
+/// Highest protocol version known to this module.
+pub const VERSION: u32 = 35;
+
+/// Mask for the version number within the version field of the `Auth` command and its reply. The
+/// remaining bits are flags.
+pub const VERSION_MASK: u32 = 0x0000_ffff;
+/// Flag in the version field of the `Auth` command and its reply, indicating shared memory support.
+pub const FLAG_SHM: u32 = 0x8000_0000;
+/// Flag in the version field of the `Auth` command and its reply, indicating memfd support.
+pub const FLAG_MEMFD: u32 = 0x4000_0000;
+
+/// Size of a packet descriptor, in bytes.
+pub const DESCRIPTOR_SIZE: usize = 20;
+/// Channel used for control packets (tagstructs).
+pub const CONTROL_CHANNEL: u32 = u32::MAX;
+/// Largest packet payload accepted, in bytes.
+pub const FRAME_SIZE_MAX: usize = 16 * 1024 * 1024;
+/// Tag used in notifications sent without a preceding request.
+pub const NO_TAG: u32 = u32::MAX;
+
+/// Descriptor flag indicating that the payload is a shared memory block reference.
+pub const FLAG_SHMDATA: u32 = 0x8000_0000;
+/// Descriptor flag indicating that the shared memory block is in a memfd pool.
+pub const FLAG_SHMDATA_MEMFD_BLOCK: u32 = 0x2000_0000;
+/// Descriptor flag indicating that the payload releases a shared memory block.
+pub const FLAG_SHMRELEASE: u32 = 0x4000_0000;
+/// Descriptor flag indicating that the payload revokes a shared memory block.
+pub const FLAG_SHMREVOKE: u32 = 0xC000_0000;
+/// Mask of the shared memory descriptor flags.
+pub const FLAG_SHMMASK: u32 = 0xFF00_0000;
+/// Mask of the seek mode within the descriptor flags of a memory block packet.
+pub const FLAG_SEEKMASK: u32 = 0x0000_00FF;
+
+/// Native protocol commands.
+///
+/// The values up to [`Reply`](Self::Reply) are general; the others are requests sent by the client,
+/// or notifications sent by the server.
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(FromPrimitive, ToPrimitive)]
+#[allow(missing_docs)]
+pub enum Command {
+    /// Error reply to a request.
+    Error = 0,
+    /// Timeout (internal to the client library).
+    Timeout,
+    /// Successful reply to a request.
+    Reply,
+
+    CreatePlaybackStream,
+    DeletePlaybackStream,
+    CreateRecordStream,
+    DeleteRecordStream,
+    Exit,
+    Auth,
+    SetClientName,
+    LookupSink,
+    LookupSource,
+    DrainPlaybackStream,
+    Stat,
+    GetPlaybackLatency,
+    CreateUploadStream,
+    DeleteUploadStream,
+    FinishUploadStream,
+    PlaySample,
+    RemoveSample,
+
+    GetServerInfo,
+    GetSinkInfo,
+    GetSinkInfoList,
+    GetSourceInfo,
+    GetSourceInfoList,
+    GetModuleInfo,
+    GetModuleInfoList,
+    GetClientInfo,
+    GetClientInfoList,
+    GetSinkInputInfo,
+    GetSinkInputInfoList,
+    GetSourceOutputInfo,
+    GetSourceOutputInfoList,
+    GetSampleInfo,
+    GetSampleInfoList,
+    Subscribe,
+
+    SetSinkVolume,
+    SetSinkInputVolume,
+    SetSourceVolume,
+    SetSinkMute,
+    SetSourceMute,
+    CorkPlaybackStream,
+    FlushPlaybackStream,
+    TriggerPlaybackStream,
+    SetDefaultSink,
+    SetDefaultSource,
+    SetPlaybackStreamName,
+    SetRecordStreamName,
+    KillClient,
+    KillSinkInput,
+    KillSourceOutput,
+    LoadModule,
+    UnloadModule,
+
+    /// Obsolete.
+    AddAutoloadObsolete,
+    /// Obsolete.
+    RemoveAutoloadObsolete,
+    /// Obsolete.
+    GetAutoloadInfoObsolete,
+    /// Obsolete.
+    GetAutoloadInfoListObsolete,
+
+    GetRecordLatency,
+    CorkRecordStream,
+    FlushRecordStream,
+    PrebufPlaybackStream,
+
+    /// Server notification: a playback stream wants more data.
+    Request,
+    /// Server notification: playback stream buffer overflow.
+    Overflow,
+    /// Server notification: playback stream buffer underflow.
+    Underflow,
+    /// Server notification: a playback stream was killed.
+    PlaybackStreamKilled,
+    /// Server notification: a record stream was killed.
+    RecordStreamKilled,
+    /// Server notification: a subscription event.
+    SubscribeEvent,
+
+    MoveSinkInput,
+    MoveSourceOutput,
+    SetSinkInputMute,
+    SuspendSink,
+    SuspendSource,
+    SetPlaybackStreamBufferAttr,
+    SetRecordStreamBufferAttr,
+    UpdatePlaybackStreamSampleRate,
+    UpdateRecordStreamSampleRate,
+
+    /// Server notification: a playback stream was suspended or resumed.
+    PlaybackStreamSuspended,
+    /// Server notification: a record stream was suspended or resumed.
+    RecordStreamSuspended,
+    /// Server notification: a playback stream was moved.
+    PlaybackStreamMoved,
+    /// Server notification: a record stream was moved.
+    RecordStreamMoved,
+
+    UpdateRecordStreamProplist,
+    UpdatePlaybackStreamProplist,
+    UpdateClientProplist,
+    RemoveRecordStreamProplist,
+    RemovePlaybackStreamProplist,
+    RemoveClientProplist,
+
+    /// Server notification: a playback stream started playing.
+    Started,
+
+    Extension,
+    GetCardInfo,
+    GetCardInfoList,
+    SetCardProfile,
+
+    /// Server notification: a client event.
+    ClientEvent,
+    /// Server notification: a playback stream event.
+    PlaybackStreamEvent,
+    /// Server notification: a record stream event.
+    RecordStreamEvent,
+    /// Server notification: playback stream buffer attributes changed.
+    PlaybackBufferAttrChanged,
+    /// Server notification: record stream buffer attributes changed.
+    RecordBufferAttrChanged,
+
+    SetSinkPort,
+    SetSourcePort,
+    SetSourceOutputVolume,
+    SetSourceOutputMute,
+    SetPortLatencyOffset,
+    EnableSrbchannel,
+    DisableSrbchannel,
+    RegisterMemfdShmid,
+    SendObjectMessage,
+}
+
+impl Command {
+    /// Gets the command with the given numeric value, if known.
+    #[inline]
+    pub fn from_u32(value: u32) -> Option<Self> {
+        FromPrimitiveTrait::from_u32(value)
+    }
+}
+
+/// A packet descriptor.
+///
+/// Every packet is preceded by a descriptor, five big-endian 32-bit values giving the length of the
+/// payload, the channel, a 64-bit offset and flags.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Descriptor {
+    /// Length of the payload, in bytes.
+    pub length: u32,
+    /// Channel, either [`CONTROL_CHANNEL`] or the channel of a stream.
+    pub channel: u32,
+    /// Seek offset, for memory blocks.
+    pub offset: i64,
+    /// Flags (the `FLAG_SHM*` constants, or the seek mode for memory blocks).
+    pub flags: u32,
+}
+
+impl Descriptor {
+    /// Decodes a descriptor.
+    pub fn from_bytes(bytes: &[u8; DESCRIPTOR_SIZE]) -> Self {
+        let word = |i: usize| u32::from_be_bytes([bytes[i * 4], bytes[i * 4 + 1], bytes[i * 4 + 2],
+            bytes[i * 4 + 3]]);
+        Self {
+            length: word(0),
+            channel: word(1),
+            offset: (((word(2) as u64) << 32) | word(3) as u64) as i64,
+            flags: word(4),
+        }
+    }
+
+    /// Encodes the descriptor.
+    pub fn to_bytes(&self) -> [u8; DESCRIPTOR_SIZE] {
+        let mut bytes = [0; DESCRIPTOR_SIZE];
+        let offset = self.offset as u64;
+        let words = [self.length, self.channel, (offset >> 32) as u32, offset as u32, self.flags];
+        for (chunk, word) in bytes.chunks_mut(4).zip(words.iter()) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        bytes
+    }
+}
+
+/// A packet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Packet {
+    /// A control packet, holding a tagstruct.
+    Control(Vec<u8>),
+    /// A memory block, holding audio data for the stream with the given channel.
+    Memblock {
+        /// Channel.
+        channel: u32,
+        /// Seek offset.
+        offset: i64,
+        /// Seek mode.
+        seek: SeekMode,
+        /// Audio data.
+        data: Vec<u8>,
+    },
+    /// A shared memory block reference, release or revocation (as indicated by the descriptor
+    /// flags), with its undecoded payload.
+    Shm(Descriptor, Vec<u8>),
+}
+
+impl Packet {
+    /// Reads a single packet.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidData`] if the payload is larger than [`FRAME_SIZE_MAX`]
+    /// or the descriptor holds an unknown seek mode.
+    pub fn read<R: Read>(r: &mut R) -> io::Result<Self> {
+        let mut bytes = [0; DESCRIPTOR_SIZE];
+        r.read_exact(&mut bytes)?;
+        let desc = Descriptor::from_bytes(&bytes);
+        if desc.length as usize > FRAME_SIZE_MAX {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "packet too large"));
+        }
+        let mut data = vec![0; desc.length as usize];
+        r.read_exact(&mut data)?;
+
+        if desc.flags & FLAG_SHMMASK != 0 {
+            return Ok(Packet::Shm(desc, data));
+        }
+        match desc.channel {
+            CONTROL_CHANNEL => Ok(Packet::Control(data)),
+            channel => {
+                let seek = FromPrimitiveTrait::from_u32(desc.flags & FLAG_SEEKMASK)
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData,
+                        "invalid seek mode"))?;
+                Ok(Packet::Memblock { channel, offset: desc.offset, seek, data })
+            },
+        }
+    }
+
+    /// Writes the packet.
+    pub fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let (desc, data) = match self {
+            Packet::Control(data) => {
+                (Descriptor { length: 0, channel: CONTROL_CHANNEL, offset: 0, flags: 0 }, data)
+            },
+            Packet::Memblock { channel, offset, seek, data } => {
+                (Descriptor { length: 0, channel: *channel, offset: *offset, flags: *seek as u32 },
+                    data)
+            },
+            Packet::Shm(desc, data) => (*desc, data),
+        };
+        let desc = Descriptor { length: data.len() as u32, ..desc };
+        let mut frame = Vec::with_capacity(DESCRIPTOR_SIZE + data.len());
+        frame.extend_from_slice(&desc.to_bytes());
+        frame.extend_from_slice(data);
+        w.write_all(&frame)
+    }
+}
+
+#[test]
+fn packet_round_trip() {
+    let packets = vec![
+        Packet::Control(vec![1, 2, 3]),
+        Packet::Memblock { channel: 4, offset: -8, seek: SeekMode::Absolute, data: vec![9; 10] },
+    ];
+    let mut buf = Vec::new();
+    for p in &packets {
+        p.write(&mut buf).unwrap();
+    }
+    assert_eq!(buf.len(), 2 * DESCRIPTOR_SIZE + 13);
+    assert_eq!(&buf[..8], &[0, 0, 0, 3, 0xff, 0xff, 0xff, 0xff]);
+
+    let mut r = &buf[..];
+    for p in &packets {
+        assert_eq!(&Packet::read(&mut r).unwrap(), p);
+    }
+    assert!(Packet::read(&mut r).is_err());
+
+    let desc = Descriptor { length: 1, channel: 2, offset: -3, flags: FLAG_SHMRELEASE };
+    assert_eq!(Descriptor::from_bytes(&desc.to_bytes()), desc);
+    assert_eq!(Command::from_u32(61), Some(Command::Request));
+    assert_eq!(Command::from_u32(104), Some(Command::SendObjectMessage));
+    assert_eq!(Command::from_u32(105), None);
+}
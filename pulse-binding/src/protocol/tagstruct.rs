@@ -0,0 +1,685 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+//
+// Portions of documentation are copied from the LGPL 2.1+ licensed PulseAudio C headers on a
+// fair-use basis, as discussed in the overall project readme (available in the git repository).
+
+//! Tagstruct encoding and decoding.
+//!
+//! # Overview
+//!
+//! A tagstruct is a sequence of values, each preceded by a single byte [`Tag`] identifying its
+//! type. All multi-byte integers are big-endian. Values are appended with the `put_*` methods of a
+//! [`Writer`], and consumed in the same order with the matching `get_*` methods of a [`Reader`],
+//! which fail with a [`DecodeError`] if the next value is not of the type requested.
+//!
+//! Where the type of the next value is not known in advance, as in a protocol dumper,
+//! [`Reader::get_value()`] decodes any value into a [`Value`].
+//!
+//! Property lists and format info objects are encoded from and decoded into [`Proplist`] and
+//! [`Info`] objects, which are allocated by the client library. Property lists can also be handled
+//! as plain key/value pairs with [`Writer::put_proplist_entries()`] and
+//! [`Reader::get_proplist_entries()`].
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use libpulse_binding::protocol::{Command, tagstruct::{Reader, Writer}};
+//!
+//! let mut writer = Writer::command(Command::SetSinkMute, 12);
+//! writer.put_u32(0).put_string(None).put_bool(true);
+//! let bytes = writer.into_bytes();
+//!
+//! let mut reader = Reader::new(&bytes);
+//! assert_eq!(reader.get_u32().unwrap(), Command::SetSinkMute as u32);
+//! ```
+
+use std::fmt;
+use std::io;
+use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::FromPrimitive as FromPrimitiveTrait;
+use super::Command;
+use crate::channelmap::{Map, Position};
+use crate::format::{Encoding, Info};
+use crate::proplist::Proplist;
+use crate::sample::{Format, Spec};
+use crate::time::{MicroSeconds, Timeval};
+use crate::volume::{ChannelVolumes, Volume};
+
+/// Type tags.
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(FromPrimitive, ToPrimitive)]
+pub enum Tag {
+    /// A NUL terminated string.
+    String = b't',
+    /// A null string.
+    StringNull = b'N',
+    /// A 32-bit unsigned integer.
+    U32 = b'L',
+    /// An 8-bit unsigned integer.
+    U8 = b'B',
+    /// A 64-bit unsigned integer.
+    U64 = b'R',
+    /// A 64-bit signed integer.
+    S64 = b'r',
+    /// A sample spec.
+    SampleSpec = b'a',
+    /// Arbitrary data, preceded by its length.
+    Arbitrary = b'x',
+    /// Boolean true.
+    BooleanTrue = b'1',
+    /// Boolean false.
+    BooleanFalse = b'0',
+    /// A time value.
+    Timeval = b'T',
+    /// A duration in microseconds.
+    Usec = b'U',
+    /// A channel map.
+    ChannelMap = b'm',
+    /// A per-channel volume.
+    CVolume = b'v',
+    /// A property list.
+    Proplist = b'P',
+    /// A single volume.
+    Volume = b'V',
+    /// A format info object.
+    FormatInfo = b'f',
+}
+
+/// Error in decoding a tagstruct.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The data ended before the value was complete.
+    Truncated,
+    /// The next value was not of the type requested. Holds the tag expected (the first, where
+    /// several are acceptable) and that found.
+    UnexpectedTag {
+        /// Tag expected.
+        expected: Tag,
+        /// Tag byte found.
+        found: u8,
+    },
+    /// An unknown tag byte was found.
+    UnknownTag(u8),
+    /// A string was not NUL terminated or not valid UTF-8.
+    InvalidString,
+    /// A value of the given type was malformed, for instance having too many channels.
+    InvalidValue(Tag),
+}
+
+impl std::error::Error for DecodeError {}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::Truncated => write!(f, "Truncated tagstruct"),
+            DecodeError::UnexpectedTag { expected, found } => {
+                write!(f, "Expected {:?} value, found tag {:#04x}", expected, found)
+            },
+            DecodeError::UnknownTag(t) => write!(f, "Unknown tag {:#04x}", t),
+            DecodeError::InvalidString => write!(f, "Invalid string"),
+            DecodeError::InvalidValue(t) => write!(f, "Invalid {:?} value", t),
+        }
+    }
+}
+
+impl From<DecodeError> for io::Error {
+    #[inline]
+    fn from(e: DecodeError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, e)
+    }
+}
+
+/// A decoded value of any type, as returned by [`Reader::get_value()`].
+#[derive(Debug, Clone)]
+#[allow(missing_docs)]
+pub enum Value {
+    String(Option<String>),
+    U32(u32),
+    U8(u8),
+    U64(u64),
+    S64(i64),
+    SampleSpec(Spec),
+    Arbitrary(Vec<u8>),
+    Boolean(bool),
+    Timeval(Timeval),
+    Usec(MicroSeconds),
+    ChannelMap(Map),
+    CVolume(ChannelVolumes),
+    Proplist(Proplist),
+    Volume(Volume),
+    FormatInfo(Info),
+}
+
+/// Tagstruct encoder.
+#[derive(Debug, Clone, Default)]
+pub struct Writer(Vec<u8>);
+
+impl Writer {
+    /// Creates a new, empty tagstruct.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new tagstruct starting with the given command and tag.
+    pub fn command(command: Command, tag: u32) -> Self {
+        let mut w = Self::new();
+        w.put_u32(command as u32).put_u32(tag);
+        w
+    }
+
+    /// Gets the encoded data.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Consumes the tagstruct, returning the encoded data.
+    #[inline]
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0
+    }
+
+    fn put_tagged(&mut self, tag: Tag, data: &[u8]) -> &mut Self {
+        self.0.push(tag as u8);
+        self.0.extend_from_slice(data);
+        self
+    }
+
+    /// Appends a string, or a null string.
+    ///
+    /// **Panics** if the string contains a NUL byte.
+    pub fn put_string(&mut self, v: Option<&str>) -> &mut Self {
+        match v {
+            Some(s) => {
+                assert!(!s.as_bytes().contains(&0), "string contains NUL");
+                self.put_tagged(Tag::String, s.as_bytes());
+                self.0.push(0);
+                self
+            },
+            None => self.put_tagged(Tag::StringNull, &[]),
+        }
+    }
+
+    /// Appends a 32-bit unsigned integer.
+    #[inline]
+    pub fn put_u32(&mut self, v: u32) -> &mut Self {
+        self.put_tagged(Tag::U32, &v.to_be_bytes())
+    }
+
+    /// Appends an 8-bit unsigned integer.
+    #[inline]
+    pub fn put_u8(&mut self, v: u8) -> &mut Self {
+        self.put_tagged(Tag::U8, &[v])
+    }
+
+    /// Appends a 64-bit unsigned integer.
+    #[inline]
+    pub fn put_u64(&mut self, v: u64) -> &mut Self {
+        self.put_tagged(Tag::U64, &v.to_be_bytes())
+    }
+
+    /// Appends a 64-bit signed integer.
+    #[inline]
+    pub fn put_s64(&mut self, v: i64) -> &mut Self {
+        self.put_tagged(Tag::S64, &v.to_be_bytes())
+    }
+
+    /// Appends a boolean.
+    #[inline]
+    pub fn put_bool(&mut self, v: bool) -> &mut Self {
+        self.put_tagged(if v { Tag::BooleanTrue } else { Tag::BooleanFalse }, &[])
+    }
+
+    /// Appends arbitrary data.
+    pub fn put_arbitrary(&mut self, v: &[u8]) -> &mut Self {
+        self.put_tagged(Tag::Arbitrary, &(v.len() as u32).to_be_bytes());
+        self.0.extend_from_slice(v);
+        self
+    }
+
+    /// Appends a time value.
+    pub fn put_timeval(&mut self, v: &Timeval) -> &mut Self {
+        self.put_tagged(Tag::Timeval, &(v.0.tv_sec as u32).to_be_bytes());
+        self.0.extend_from_slice(&(v.0.tv_usec as u32).to_be_bytes());
+        self
+    }
+
+    /// Appends a duration in microseconds.
+    #[inline]
+    pub fn put_usec(&mut self, v: MicroSeconds) -> &mut Self {
+        self.put_tagged(Tag::Usec, &v.0.to_be_bytes())
+    }
+
+    /// Appends a sample spec.
+    pub fn put_sample_spec(&mut self, v: &Spec) -> &mut Self {
+        self.put_tagged(Tag::SampleSpec, &[v.format as i8 as u8, v.channels]);
+        self.0.extend_from_slice(&v.rate.to_be_bytes());
+        self
+    }
+
+    /// Appends a channel map.
+    pub fn put_channel_map(&mut self, v: &Map) -> &mut Self {
+        self.put_tagged(Tag::ChannelMap, &[v.len()]);
+        self.0.extend(v.get().iter().map(|p| *p as i8 as u8));
+        self
+    }
+
+    /// Appends a per-channel volume.
+    pub fn put_cvolume(&mut self, v: &ChannelVolumes) -> &mut Self {
+        self.put_tagged(Tag::CVolume, &[v.len()]);
+        for vol in v.get() {
+            self.0.extend_from_slice(&vol.0.to_be_bytes());
+        }
+        self
+    }
+
+    /// Appends a single volume.
+    #[inline]
+    pub fn put_volume(&mut self, v: Volume) -> &mut Self {
+        self.put_tagged(Tag::Volume, &v.0.to_be_bytes())
+    }
+
+    /// Appends a property list.
+    pub fn put_proplist(&mut self, v: &Proplist) -> &mut Self {
+        let entries: Vec<(String, Vec<u8>)> = v.iter()
+            .filter_map(|key| v.get(&key).map(|data| data.to_vec()).map(|data| (key, data)))
+            .collect();
+        self.put_proplist_entries(entries.iter().map(|(k, d)| (k.as_str(), d.as_slice())))
+    }
+
+    /// Appends a property list given as key/value pairs.
+    ///
+    /// Values are raw data; note that string values are NUL terminated within property lists.
+    pub fn put_proplist_entries<'a, I>(&mut self, entries: I) -> &mut Self
+        where I: IntoIterator<Item = (&'a str, &'a [u8])>
+    {
+        self.put_tagged(Tag::Proplist, &[]);
+        for (key, data) in entries {
+            self.put_string(Some(key)).put_u32(data.len() as u32).put_arbitrary(data);
+        }
+        self.put_string(None)
+    }
+
+    /// Appends a format info object.
+    pub fn put_format_info(&mut self, v: &Info) -> &mut Self {
+        self.put_tagged(Tag::FormatInfo, &[]);
+        self.put_u8(v.get_encoding() as u8).put_proplist(v.get_properties())
+    }
+
+    /// Appends a value of any type.
+    pub fn put_value(&mut self, v: &Value) -> &mut Self {
+        match v {
+            Value::String(s) => self.put_string(s.as_deref()),
+            Value::U32(x) => self.put_u32(*x),
+            Value::U8(x) => self.put_u8(*x),
+            Value::U64(x) => self.put_u64(*x),
+            Value::S64(x) => self.put_s64(*x),
+            Value::SampleSpec(x) => self.put_sample_spec(x),
+            Value::Arbitrary(x) => self.put_arbitrary(x),
+            Value::Boolean(x) => self.put_bool(*x),
+            Value::Timeval(x) => self.put_timeval(x),
+            Value::Usec(x) => self.put_usec(*x),
+            Value::ChannelMap(x) => self.put_channel_map(x),
+            Value::CVolume(x) => self.put_cvolume(x),
+            Value::Proplist(x) => self.put_proplist(x),
+            Value::Volume(x) => self.put_volume(*x),
+            Value::FormatInfo(x) => self.put_format_info(x),
+        }
+    }
+}
+
+/// Tagstruct decoder.
+///
+/// Upon failure to get a value, the read position is left unchanged.
+#[derive(Debug, Clone)]
+pub struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Creates a decoder for the given data.
+    #[inline]
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    /// Checks whether all data has been consumed.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    /// Gets the data not yet consumed.
+    #[inline]
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.data[self.pos..]
+    }
+
+    /// Gets the tag of the next value, without consuming it. Returns `None` if all data has been
+    /// consumed.
+    pub fn peek_tag(&self) -> Option<Result<Tag, DecodeError>> {
+        self.data.get(self.pos)
+            .map(|&b| FromPrimitiveTrait::from_u8(b).ok_or(DecodeError::UnknownTag(b)))
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], DecodeError> {
+        if self.data.len() - self.pos < n {
+            return Err(DecodeError::Truncated);
+        }
+        let s = &self.data[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(s)
+    }
+
+    /// Runs the given decoding, restoring the read position should it fail.
+    fn restoring<T, F>(&mut self, f: F) -> Result<T, DecodeError>
+        where F: FnOnce(&mut Self) -> Result<T, DecodeError>
+    {
+        let pos = self.pos;
+        let result = f(self);
+        if result.is_err() {
+            self.pos = pos;
+        }
+        result
+    }
+
+    fn expect(&mut self, tag: Tag) -> Result<(), DecodeError> {
+        match self.take(1)?[0] {
+            found if found == tag as u8 => Ok(()),
+            found => {
+                self.pos -= 1;
+                Err(DecodeError::UnexpectedTag { expected: tag, found })
+            },
+        }
+    }
+
+    fn raw_u32(&mut self) -> Result<u32, DecodeError> {
+        let b = self.take(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn raw_u64(&mut self) -> Result<u64, DecodeError> {
+        let mut a = [0; 8];
+        a.copy_from_slice(self.take(8)?);
+        Ok(u64::from_be_bytes(a))
+    }
+
+    /// Gets a string, or a null string.
+    pub fn get_string(&mut self) -> Result<Option<String>, DecodeError> {
+        self.restoring(|r| {
+            if r.expect(Tag::StringNull).is_ok() {
+                return Ok(None);
+            }
+            r.expect(Tag::String)?;
+            let rest = r.remaining();
+            let len = rest.iter().position(|&b| b == 0).ok_or(DecodeError::InvalidString)?;
+            let s = std::str::from_utf8(&rest[..len]).map_err(|_| DecodeError::InvalidString)?;
+            r.pos += len + 1;
+            Ok(Some(s.to_string()))
+        })
+    }
+
+    /// Gets a 32-bit unsigned integer.
+    pub fn get_u32(&mut self) -> Result<u32, DecodeError> {
+        self.restoring(|r| {
+            r.expect(Tag::U32)?;
+            r.raw_u32()
+        })
+    }
+
+    /// Gets an 8-bit unsigned integer.
+    pub fn get_u8(&mut self) -> Result<u8, DecodeError> {
+        self.restoring(|r| {
+            r.expect(Tag::U8)?;
+            Ok(r.take(1)?[0])
+        })
+    }
+
+    /// Gets a 64-bit unsigned integer.
+    pub fn get_u64(&mut self) -> Result<u64, DecodeError> {
+        self.restoring(|r| {
+            r.expect(Tag::U64)?;
+            r.raw_u64()
+        })
+    }
+
+    /// Gets a 64-bit signed integer.
+    pub fn get_s64(&mut self) -> Result<i64, DecodeError> {
+        self.restoring(|r| {
+            r.expect(Tag::S64)?;
+            Ok(r.raw_u64()? as i64)
+        })
+    }
+
+    /// Gets a boolean.
+    pub fn get_bool(&mut self) -> Result<bool, DecodeError> {
+        self.restoring(|r| {
+            if r.expect(Tag::BooleanTrue).is_ok() {
+                return Ok(true);
+            }
+            r.expect(Tag::BooleanFalse)?;
+            Ok(false)
+        })
+    }
+
+    /// Gets arbitrary data.
+    pub fn get_arbitrary(&mut self) -> Result<&'a [u8], DecodeError> {
+        self.restoring(|r| {
+            r.expect(Tag::Arbitrary)?;
+            let len = r.raw_u32()? as usize;
+            r.take(len)
+        })
+    }
+
+    /// Gets a time value.
+    pub fn get_timeval(&mut self) -> Result<Timeval, DecodeError> {
+        self.restoring(|r| {
+            r.expect(Tag::Timeval)?;
+            let secs = r.raw_u32()?;
+            let usecs = r.raw_u32()?;
+            Ok(Timeval::new(secs as _, usecs as _))
+        })
+    }
+
+    /// Gets a duration in microseconds.
+    pub fn get_usec(&mut self) -> Result<MicroSeconds, DecodeError> {
+        self.restoring(|r| {
+            r.expect(Tag::Usec)?;
+            Ok(MicroSeconds(r.raw_u64()?))
+        })
+    }
+
+    /// Gets a sample spec.
+    ///
+    /// An unknown sample format is decoded as [`Format::Invalid`].
+    pub fn get_sample_spec(&mut self) -> Result<Spec, DecodeError> {
+        self.restoring(|r| {
+            r.expect(Tag::SampleSpec)?;
+            let b = r.take(2)?;
+            let format = Format::from_i8(b[0] as i8).unwrap_or(Format::Invalid);
+            let channels = b[1];
+            Ok(Spec { format, channels, rate: r.raw_u32()? })
+        })
+    }
+
+    /// Gets a channel map.
+    ///
+    /// Unknown positions are decoded as [`Position::Invalid`].
+    pub fn get_channel_map(&mut self) -> Result<Map, DecodeError> {
+        self.restoring(|r| {
+            r.expect(Tag::ChannelMap)?;
+            let channels = r.take(1)?[0];
+            if channels > Map::CHANNELS_MAX {
+                return Err(DecodeError::InvalidValue(Tag::ChannelMap));
+            }
+            let mut map = Map::default();
+            map.set_len(channels);
+            for (p, b) in map.get_mut().iter_mut().zip(r.take(channels as usize)?) {
+                *p = Position::from_i8(*b as i8).unwrap_or(Position::Invalid);
+            }
+            Ok(map)
+        })
+    }
+
+    /// Gets a per-channel volume.
+    pub fn get_cvolume(&mut self) -> Result<ChannelVolumes, DecodeError> {
+        self.restoring(|r| {
+            r.expect(Tag::CVolume)?;
+            let channels = r.take(1)?[0];
+            if channels > ChannelVolumes::CHANNELS_MAX {
+                return Err(DecodeError::InvalidValue(Tag::CVolume));
+            }
+            let mut cv = ChannelVolumes::default();
+            cv.set_len(channels);
+            for i in 0..channels as usize {
+                cv.get_mut()[i] = Volume(r.raw_u32()?);
+            }
+            Ok(cv)
+        })
+    }
+
+    /// Gets a single volume.
+    pub fn get_volume(&mut self) -> Result<Volume, DecodeError> {
+        self.restoring(|r| {
+            r.expect(Tag::Volume)?;
+            Ok(Volume(r.raw_u32()?))
+        })
+    }
+
+    /// Gets a property list.
+    pub fn get_proplist(&mut self) -> Result<Proplist, DecodeError> {
+        self.restoring(|r| {
+            let entries = r.get_proplist_entries()?;
+            let mut list = Proplist::new().ok_or(DecodeError::InvalidValue(Tag::Proplist))?;
+            for (key, data) in entries {
+                list.set(&key, &data).map_err(|_| DecodeError::InvalidValue(Tag::Proplist))?;
+            }
+            Ok(list)
+        })
+    }
+
+    /// Gets a property list as key/value pairs, in the order encoded.
+    ///
+    /// Values are raw data; note that string values are NUL terminated within property lists.
+    pub fn get_proplist_entries(&mut self) -> Result<Vec<(String, Vec<u8>)>, DecodeError> {
+        self.restoring(|r| {
+            r.expect(Tag::Proplist)?;
+            let mut entries = Vec::new();
+            while let Some(key) = r.get_string()? {
+                let len = r.get_u32()? as usize;
+                let data = r.get_arbitrary()?;
+                if data.len() != len {
+                    return Err(DecodeError::InvalidValue(Tag::Proplist));
+                }
+                entries.push((key, data.to_vec()));
+            }
+            Ok(entries)
+        })
+    }
+
+    /// Gets a format info object.
+    pub fn get_format_info(&mut self) -> Result<Info, DecodeError> {
+        self.restoring(|r| {
+            r.expect(Tag::FormatInfo)?;
+            let encoding = Encoding::from_u8(r.get_u8()?)
+                .ok_or(DecodeError::InvalidValue(Tag::FormatInfo))?;
+            let entries = r.get_proplist_entries()?;
+            let mut info = Info::new().ok_or(DecodeError::InvalidValue(Tag::FormatInfo))?;
+            info.set_encoding(encoding);
+            for (key, data) in entries {
+                info.get_properties_mut().set(&key, &data)
+                    .map_err(|_| DecodeError::InvalidValue(Tag::FormatInfo))?;
+            }
+            Ok(info)
+        })
+    }
+
+    /// Gets a value of any type.
+    pub fn get_value(&mut self) -> Result<Value, DecodeError> {
+        self.restoring(|r| {
+            let tag = match r.peek_tag() {
+                Some(t) => t?,
+                None => return Err(DecodeError::Truncated),
+            };
+            Ok(match tag {
+                Tag::String | Tag::StringNull => Value::String(r.get_string()?),
+                Tag::U32 => Value::U32(r.get_u32()?),
+                Tag::U8 => Value::U8(r.get_u8()?),
+                Tag::U64 => Value::U64(r.get_u64()?),
+                Tag::S64 => Value::S64(r.get_s64()?),
+                Tag::SampleSpec => Value::SampleSpec(r.get_sample_spec()?),
+                Tag::Arbitrary => Value::Arbitrary(r.get_arbitrary()?.to_vec()),
+                Tag::BooleanTrue | Tag::BooleanFalse => Value::Boolean(r.get_bool()?),
+                Tag::Timeval => Value::Timeval(r.get_timeval()?),
+                Tag::Usec => Value::Usec(r.get_usec()?),
+                Tag::ChannelMap => Value::ChannelMap(r.get_channel_map()?),
+                Tag::CVolume => Value::CVolume(r.get_cvolume()?),
+                Tag::Proplist => Value::Proplist(r.get_proplist()?),
+                Tag::Volume => Value::Volume(r.get_volume()?),
+                Tag::FormatInfo => Value::FormatInfo(r.get_format_info()?),
+            })
+        })
+    }
+}
+
+#[test]
+fn tagstruct_round_trip() {
+    let mut map = Map::default();
+    map.set_len(2);
+    map.get_mut().copy_from_slice(&[Position::FrontLeft, Position::FrontRight]);
+    let mut cv = ChannelVolumes::default();
+    cv.set_len(2);
+    cv.get_mut().copy_from_slice(&[Volume::NORMAL, Volume::MUTED]);
+    let spec = Spec { format: Format::S16le, channels: 2, rate: 44100 };
+
+    let mut w = Writer::command(Command::Auth, 3);
+    w.put_string(Some("abc")).put_string(None).put_bool(true).put_u8(9).put_sample_spec(&spec)
+        .put_channel_map(&map).put_cvolume(&cv).put_usec(MicroSeconds(5)).put_s64(-3)
+        .put_timeval(&Timeval::new(1, 2)).put_arbitrary(&[1, 2])
+        .put_proplist_entries(vec![("media.name", &b"x\0"[..])]);
+    let bytes = w.into_bytes();
+    assert_eq!(&bytes[..5], &[b'L', 0, 0, 0, 8]);
+
+    let mut r = Reader::new(&bytes);
+    assert_eq!(Command::from_u32(r.get_u32().unwrap()), Some(Command::Auth));
+    assert_eq!(r.get_u32().unwrap(), 3);
+    assert_eq!(r.get_string().unwrap().as_deref(), Some("abc"));
+    assert_eq!(r.get_string().unwrap(), None);
+    assert_eq!(r.get_u32(), Err(DecodeError::UnexpectedTag { expected: Tag::U32, found: b'1' }));
+    assert!(r.get_bool().unwrap());
+    assert_eq!(r.get_u8().unwrap(), 9);
+    let got = r.get_sample_spec().unwrap();
+    assert_eq!((got.format, got.channels, got.rate), (spec.format, spec.channels, spec.rate));
+    assert_eq!(r.get_channel_map().unwrap().get(), map.get());
+    assert_eq!(r.get_cvolume().unwrap().get(), cv.get());
+    assert_eq!(r.get_usec().unwrap(), MicroSeconds(5));
+    assert!(matches!(r.get_value().unwrap(), Value::S64(-3)));
+    assert_eq!(r.get_timeval().unwrap(), Timeval::new(1, 2));
+    assert_eq!(r.get_arbitrary().unwrap(), &[1, 2]);
+    assert_eq!(r.peek_tag(), Some(Ok(Tag::Proplist)));
+    assert_eq!(r.get_proplist_entries().unwrap(),
+        vec![("media.name".to_string(), b"x\0".to_vec())]);
+    assert!(r.is_empty());
+    assert_eq!(r.get_u32(), Err(DecodeError::Truncated));
+
+    let mut r = Reader::new(b"ta");
+    assert_eq!(r.get_string(), Err(DecodeError::InvalidString));
+    assert_eq!(r.remaining(), b"ta");
+    let mut r = Reader::new(&[b'L', 0, 0]);
+    assert_eq!(r.get_u32(), Err(DecodeError::Truncated));
+    assert_eq!(r.remaining().len(), 3);
+    assert_eq!(Reader::new(&[b'm', 200]).get_channel_map().err(),
+        Some(DecodeError::InvalidValue(Tag::ChannelMap)));
+    assert_eq!(Reader::new(b"z").peek_tag(), Some(Err(DecodeError::UnknownTag(b'z'))));
+}
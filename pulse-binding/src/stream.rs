@@ -246,8 +246,7 @@ pub struct Stream {
     cb_ptrs: CallbackPointers,
 }
 
-unsafe impl Send for Stream {}
-unsafe impl Sync for Stream {}
+unsafe impl crate::mainloop::threaded::LockBound for Stream {}
 
 /// Holds copies of callback closure pointers, for those that are “multi-use” (may be fired multiple
 /// times), for freeing at the appropriate time.
@@ -806,9 +805,15 @@ impl Stream {
     /// Gets the current state of the stream.
     #[inline]
     pub fn get_state(&self) -> State {
+        self.check_lock();
         unsafe { capi::pa_stream_get_state(self.ptr).into() }
     }
 
+    /// Gets the last error of the context the stream belongs to.
+    pub(crate) fn context_errno(&self) -> PAErr {
+        PAErr(unsafe { capi::pa_context_errno(capi::pa_stream_get_context(self.ptr)) })
+    }
+
     /// Gets the sink input resp. source output index this stream is identified in the server with.
     ///
     /// This is useful with the introspection functions such as
@@ -817,6 +822,7 @@ impl Stream {
     /// [`Introspector::get_sink_input_info()`]: crate::context::introspect::Introspector::get_sink_input_info
     /// [`Introspector::get_source_output_info()`]: crate::context::introspect::Introspector::get_source_output_info
     pub fn get_index(&self) -> Option<u32> {
+        self.check_lock();
         match unsafe { capi::pa_stream_get_index(self.ptr) } {
             def::INVALID_INDEX => None,
             r => Some(r),
@@ -835,6 +841,7 @@ impl Stream {
     /// [`Introspector::get_sink_info_by_index()`]: crate::context::introspect::Introspector::get_sink_info_by_index
     /// [`Introspector::get_source_info_by_index()`]: crate::context::introspect::Introspector::get_source_info_by_index
     pub fn get_device_index(&self) -> Option<u32> {
+        self.check_lock();
         match unsafe { capi::pa_stream_get_device_index(self.ptr) } {
             def::INVALID_INDEX => None,
             r => Some(r),
@@ -853,6 +860,7 @@ impl Stream {
     /// [`Introspector::get_sink_info_by_name()`]: crate::context::introspect::Introspector::get_sink_info_by_name
     /// [`Introspector::get_source_info_by_name()`]: crate::context::introspect::Introspector::get_source_info_by_name
     pub fn get_device_name(&self) -> Option<Cow<'static, str>> {
+        self.check_lock();
         let ptr: *const c_char = unsafe { capi::pa_stream_get_device_name(self.ptr) };
         match ptr.is_null() {
             false => Some(unsafe { CStr::from_ptr(ptr).to_string_lossy() }),
@@ -862,6 +870,7 @@ impl Stream {
 
     /// Checks whether or not the sink or source this stream is connected to has been suspended.
     pub fn is_suspended(&self) -> Result<bool, PAErr> {
+        self.check_lock();
         match unsafe { capi::pa_stream_is_suspended(self.ptr) } {
             0 => Ok(false),
             1 => Ok(true),
@@ -871,6 +880,7 @@ impl Stream {
 
     /// Checks whether or not this stream has been corked.
     pub fn is_corked(&self) -> Result<bool, PAErr> {
+        self.check_lock();
         match unsafe { capi::pa_stream_is_corked(self.ptr) } {
             0 => Ok(false),
             1 => Ok(true),
@@ -915,6 +925,7 @@ impl Stream {
         flags: FlagSet, volume: Option<&ChannelVolumes>, sync_stream: Option<&mut Self>)
         -> Result<(), PAErr>
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_dev = match dev {
@@ -946,6 +957,7 @@ impl Stream {
     pub fn connect_record(&mut self, dev: Option<&str>, attr: Option<&def::BufferAttr>,
         flags: FlagSet) -> Result<(), PAErr>
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let c_dev = match dev {
@@ -966,6 +978,7 @@ impl Stream {
     ///
     /// (See [`scache`](mod@crate::context::scache)).
     pub fn connect_upload(&mut self, length: usize) -> Result<(), PAErr> {
+        self.check_lock();
         match unsafe { capi::pa_stream_connect_upload(self.ptr, length) } {
             0 => Ok(()),
             e => Err(PAErr(e)),
@@ -976,6 +989,7 @@ impl Stream {
     ///
     /// You cancel a sample upload by issuing [`disconnect()`](Self::disconnect).
     pub fn finish_upload(&mut self) -> Result<(), PAErr> {
+        self.check_lock();
         match unsafe { capi::pa_stream_finish_upload(self.ptr) } {
             0 => Ok(()),
             e => Err(PAErr(e)),
@@ -984,6 +998,7 @@ impl Stream {
 
     /// Disconnects a stream from a source/sink.
     pub fn disconnect(&mut self) -> Result<(), PAErr> {
+        self.check_lock();
         match unsafe { capi::pa_stream_disconnect(self.ptr) } {
             0 => Ok(()),
             e => Err(PAErr(e)),
@@ -1027,6 +1042,7 @@ impl Stream {
     pub fn begin_write<'a>(&mut self, nbytes: Option<usize>)
         -> Result<Option<&'a mut [u8]>, PAErr>
     {
+        self.check_lock();
         let mut data_ptr = null_mut::<c_void>();
         // If user asks for size to be automatically chosen by PA, we pass in std::usize::MAX
         // (-1 as size_t) to signal this.
@@ -1054,6 +1070,7 @@ impl Stream {
     /// [`write()`]: Self::write
     /// [`begin_write()`]: Self::begin_write
     pub fn cancel_write(&mut self) -> Result<(), PAErr> {
+        self.check_lock();
         match unsafe { capi::pa_stream_cancel_write(self.ptr) } {
             0 => Ok(()),
             e => Err(PAErr(e)),
@@ -1093,6 +1110,7 @@ impl Stream {
     pub fn write(&mut self, data: &[u8], free_cb: Option<def::FreeCb>, offset: i64,
         seek: SeekMode) -> Result<(), PAErr>
     {
+        self.check_lock();
         debug_assert_eq!(0, data.len().checked_rem(self.get_sample_spec().unwrap().frame_size())
             .unwrap());
         let r = unsafe {
@@ -1121,6 +1139,7 @@ impl Stream {
     /// [`write()`]: Self::write
     #[inline(always)]
     pub fn write_copy(&mut self, data: &[u8], offset: i64, seek: SeekMode) -> Result<(), PAErr> {
+        self.check_lock();
         self.write(data, None, offset, seek)
     }
 
@@ -1145,6 +1164,7 @@ impl Stream {
     pub fn write_ext_free(&mut self, data: &[u8], free_cb: Option<(def::FreeCb, *mut c_void)>,
         offset: i64, seek: SeekMode) -> Result<(), PAErr>
     {
+        self.check_lock();
         let (cb_f, cb_d) = match free_cb {
             Some((f, d)) => (Some(f), d),
             None => (None, null_mut::<c_void>()),
@@ -1185,6 +1205,7 @@ impl Stream {
     /// [`Data`]: PeekResult::Data
     /// [`discard()`]: Self::discard
     pub fn peek<'a>(&mut self) -> Result<PeekResult<'a>, PAErr> {
+        self.check_lock();
         let mut data_ptr = null::<c_void>();
         let mut nbytes: usize = 0;
         // Note, C function returns an i32, but documentation does not mention any use of it, so we
@@ -1214,6 +1235,7 @@ impl Stream {
     /// Note: The original C function name used the term `drop`; We instead use `discard` here to
     /// avoid conflict with the Rust `Drop` trait!
     pub fn discard(&mut self) -> Result<(), PAErr> {
+        self.check_lock();
         match unsafe { capi::pa_stream_drop(self.ptr) } {
             0 => Ok(()),
             e => Err(PAErr(e)),
@@ -1229,6 +1251,7 @@ impl Stream {
     /// [`buffer_attr.maxlength`]: crate::def::BufferAttr.maxlength
     /// [`buffer_attr.tlength`]: crate::def::BufferAttr.tlength
     pub fn writable_size(&self) -> Option<usize> {
+        self.check_lock();
         match unsafe { capi::pa_stream_writable_size(self.ptr) } {
             std::usize::MAX => None,
             r => Some(r),
@@ -1239,6 +1262,7 @@ impl Stream {
     ///
     /// Returns `None` on error.
     pub fn readable_size(&self) -> Option<usize> {
+        self.check_lock();
         match unsafe { capi::pa_stream_readable_size(self.ptr) } {
             std::usize::MAX => None,
             r => Some(r),
@@ -1256,6 +1280,7 @@ impl Stream {
     pub fn drain(&mut self, callback: Option<Box<dyn FnMut(bool) + 'static>>)
         -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         let (cb_fn, cb_data): (Option<extern "C" fn(_, _, _)>, _) =
             get_su_capi_params::<_, _>(callback, success_cb_proxy);
         let ptr = unsafe { capi::pa_stream_drain(self.ptr, cb_fn, cb_data) };
@@ -1277,6 +1302,7 @@ impl Stream {
     pub fn update_timing_info(&mut self, callback: Option<Box<dyn FnMut(bool) + 'static>>)
         -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         let (cb_fn, cb_data): (Option<extern "C" fn(_, _, _)>, _) =
             get_su_capi_params::<_, _>(callback, success_cb_proxy);
         let ptr = unsafe { capi::pa_stream_update_timing_info(self.ptr, cb_fn, cb_data) };
@@ -1285,6 +1311,7 @@ impl Stream {
 
     /// Sets the callback function that is called whenever the state of the stream changes.
     pub fn set_state_callback(&mut self, callback: Option<Box<dyn FnMut() + 'static>>) {
+        self.check_lock();
         let saved = &mut self.cb_ptrs.set_state;
         *saved = NotifyCb::new(callback);
         let (cb_fn, cb_data) = saved.get_capi_params(notify_cb_proxy);
@@ -1295,6 +1322,7 @@ impl Stream {
     ///
     /// The callback accepts an argument giving the number of bytes.
     pub fn set_write_callback(&mut self, callback: Option<Box<dyn FnMut(usize) + 'static>>) {
+        self.check_lock();
         let saved = &mut self.cb_ptrs.write;
         *saved = RequestCb::new(callback);
         let (cb_fn, cb_data) = saved.get_capi_params(request_cb_proxy);
@@ -1305,6 +1333,7 @@ impl Stream {
     ///
     /// The callback accepts an argument giving the number of bytes.
     pub fn set_read_callback(&mut self, callback: Option<Box<dyn FnMut(usize) + 'static>>) {
+        self.check_lock();
         let saved = &mut self.cb_ptrs.read;
         *saved = RequestCb::new(callback);
         let (cb_fn, cb_data) = saved.get_capi_params(request_cb_proxy);
@@ -1314,6 +1343,7 @@ impl Stream {
     /// Sets the callback function that is called when a buffer overflow happens. (Only for playback
     /// streams).
     pub fn set_overflow_callback(&mut self, callback: Option<Box<dyn FnMut() + 'static>>) {
+        self.check_lock();
         let saved = &mut self.cb_ptrs.overflow;
         *saved = NotifyCb::new(callback);
         let (cb_fn, cb_data) = saved.get_capi_params(notify_cb_proxy);
@@ -1327,6 +1357,7 @@ impl Stream {
     /// This can be used inside the underflow callback to get information about the current
     /// underflow. (Only for playback streams).
     pub fn get_underflow_index(&self) -> Option<u64> {
+        self.check_lock();
         match unsafe { capi::pa_stream_get_underflow_index(self.ptr) } {
             r if r < 0 => None,
             r => Some(r as u64),
@@ -1337,6 +1368,7 @@ impl Stream {
     ///
     /// (Only for playback streams).
     pub fn set_underflow_callback(&mut self, callback: Option<Box<dyn FnMut() + 'static>>) {
+        self.check_lock();
         let saved = &mut self.cb_ptrs.underflow;
         *saved = NotifyCb::new(callback);
         let (cb_fn, cb_data) = saved.get_capi_params(notify_cb_proxy);
@@ -1349,6 +1381,7 @@ impl Stream {
     /// This only informs that audio is flowing again, it is no indication that audio started to
     /// reach the speakers already. (Only for playback streams).
     pub fn set_started_callback(&mut self, callback: Option<Box<dyn FnMut() + 'static>>) {
+        self.check_lock();
         let saved = &mut self.cb_ptrs.started;
         *saved = NotifyCb::new(callback);
         let (cb_fn, cb_data) = saved.get_capi_params(notify_cb_proxy);
@@ -1359,6 +1392,7 @@ impl Stream {
     ///
     /// Useful on [`FlagSet::AUTO_TIMING_UPDATE`] streams only.
     pub fn set_latency_update_callback(&mut self, callback: Option<Box<dyn FnMut() + 'static>>) {
+        self.check_lock();
         let saved = &mut self.cb_ptrs.latency_update;
         *saved = NotifyCb::new(callback);
         let (cb_fn, cb_data) = saved.get_capi_params(notify_cb_proxy);
@@ -1373,6 +1407,7 @@ impl Stream {
     /// [`get_device_name()`]: Self::get_device_name
     /// [`get_device_index()`]: Self::get_device_index
     pub fn set_moved_callback(&mut self, callback: Option<Box<dyn FnMut() + 'static>>) {
+        self.check_lock();
         let saved = &mut self.cb_ptrs.moved;
         *saved = NotifyCb::new(callback);
         let (cb_fn, cb_data) = saved.get_capi_params(notify_cb_proxy);
@@ -1389,6 +1424,7 @@ impl Stream {
     /// [`is_suspended()`]: Self::is_suspended
     /// [`set_moved_callback()`]: Self::set_moved_callback
     pub fn set_suspended_callback(&mut self, callback: Option<Box<dyn FnMut() + 'static>>) {
+        self.check_lock();
         let saved = &mut self.cb_ptrs.suspended;
         *saved = NotifyCb::new(callback);
         let (cb_fn, cb_data) = saved.get_capi_params(notify_cb_proxy);
@@ -1405,6 +1441,7 @@ impl Stream {
     pub fn set_event_callback(&mut self,
         callback: Option<Box<dyn FnMut(String, Proplist) + 'static>>)
     {
+        self.check_lock();
         let saved = &mut self.cb_ptrs.event;
         *saved = EventCb::new(callback);
         let (cb_fn, cb_data) = saved.get_capi_params(event_cb_proxy);
@@ -1420,6 +1457,7 @@ impl Stream {
     ///
     /// [`set_moved_callback()`]: Self::set_moved_callback
     pub fn set_buffer_attr_callback(&mut self, callback: Option<Box<dyn FnMut() + 'static>>) {
+        self.check_lock();
         let saved = &mut self.cb_ptrs.buffer_attr;
         *saved = NotifyCb::new(callback);
         let (cb_fn, cb_data) = saved.get_capi_params(notify_cb_proxy);
@@ -1444,6 +1482,7 @@ impl Stream {
     pub fn cork(&mut self, callback: Option<Box<dyn FnMut(bool) + 'static>>)
         -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         let (cb_fn, cb_data): (Option<extern "C" fn(_, _, _)>, _) =
             get_su_capi_params::<_, _>(callback, success_cb_proxy);
         let ptr = unsafe { capi::pa_stream_cork(self.ptr, true as i32, cb_fn, cb_data) };
@@ -1468,6 +1507,7 @@ impl Stream {
     pub fn uncork(&mut self, callback: Option<Box<dyn FnMut(bool) + 'static>>)
         -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         let (cb_fn, cb_data): (Option<extern "C" fn(_, _, _)>, _) =
             get_su_capi_params::<_, _>(callback, success_cb_proxy);
         let ptr = unsafe { capi::pa_stream_cork(self.ptr, false as i32, cb_fn, cb_data) };
@@ -1485,6 +1525,7 @@ impl Stream {
     pub fn flush(&mut self, callback: Option<Box<dyn FnMut(bool) + 'static>>)
         -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         let (cb_fn, cb_data): (Option<extern "C" fn(_, _, _)>, _) =
             get_su_capi_params::<_, _>(callback, success_cb_proxy);
         let ptr = unsafe { capi::pa_stream_flush(self.ptr, cb_fn, cb_data) };
@@ -1503,6 +1544,7 @@ impl Stream {
     pub fn prebuf(&mut self, callback: Option<Box<dyn FnMut(bool) + 'static>>)
         -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         let (cb_fn, cb_data): (Option<extern "C" fn(_, _, _)>, _) =
             get_su_capi_params::<_, _>(callback, success_cb_proxy);
         let ptr = unsafe { capi::pa_stream_prebuf(self.ptr, cb_fn, cb_data) };
@@ -1522,6 +1564,7 @@ impl Stream {
     pub fn trigger(&mut self, callback: Option<Box<dyn FnMut(bool) + 'static>>)
         -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         let (cb_fn, cb_data): (Option<extern "C" fn(_, _, _)>, _) =
             get_su_capi_params::<_, _>(callback, success_cb_proxy);
         let ptr = unsafe { capi::pa_stream_trigger(self.ptr, cb_fn, cb_data) };
@@ -1536,6 +1579,7 @@ impl Stream {
     pub fn set_name(&mut self, name: &str, callback: Option<Box<dyn FnMut(bool) + 'static>>)
         -> Operation<dyn FnMut(bool)>
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a
         // variable, leading to as_ptr() giving dangling pointers!
         let c_name = CString::new(name.clone()).unwrap();
@@ -1576,6 +1620,7 @@ impl Stream {
     /// [`get_timing_info()`]: Self::get_timing_info
     /// [`update_timing_info()`]: Self::update_timing_info
     pub fn get_time(&self) -> Result<Option<MicroSeconds>, PAErr> {
+        self.check_lock();
         let mut r_usecs = MicroSeconds(0);
         match unsafe { capi::pa_stream_get_time(self.ptr, &mut r_usecs.0) } {
             0 => Ok(Some(r_usecs)),
@@ -1600,6 +1645,7 @@ impl Stream {
     /// [`get_time()`]: Self::get_time
     /// [`get_timing_info()`]: Self::get_timing_info
     pub fn get_latency(&self) -> Result<Latency, PAErr> {
+        self.check_lock();
         let mut r_usecs = MicroSeconds(0);
         let mut negative: i32 = 0;
         match unsafe { capi::pa_stream_get_latency(self.ptr, &mut r_usecs.0, &mut negative) } {
@@ -1628,6 +1674,7 @@ impl Stream {
     /// [`update_timing_info()`]: Self::update_timing_info
     /// [`write()`]: Self::write
     pub fn get_timing_info<'a>(&mut self) -> Option<&'a def::TimingInfo> {
+        self.check_lock();
         unsafe {
             let ptr = capi::pa_stream_get_timing_info(self.ptr);
             ptr.as_ref().map(|r| r.as_ref())
@@ -1636,6 +1683,7 @@ impl Stream {
 
     /// Gets a pointer to the stream’s sample specification.
     pub fn get_sample_spec<'a>(&mut self) -> Option<&'a sample::Spec> {
+        self.check_lock();
         unsafe {
             let ptr = capi::pa_stream_get_sample_spec(self.ptr);
             ptr.as_ref().map(|r| r.as_ref())
@@ -1644,6 +1692,7 @@ impl Stream {
 
     /// Gets a pointer to the stream’s channel map.
     pub fn get_channel_map<'a>(&mut self) -> Option<&'a channelmap::Map> {
+        self.check_lock();
         unsafe {
             let ptr = capi::pa_stream_get_channel_map(self.ptr);
             ptr.as_ref().map(|r| r.as_ref())
@@ -1652,6 +1701,7 @@ impl Stream {
 
     /// Gets a pointer to the stream’s format.
     pub fn get_format_info(&self) -> Option<format::Info> {
+        self.check_lock();
         let ptr = unsafe { capi::pa_stream_get_format_info(self.ptr) };
         match ptr.is_null() {
             false => Some(format::Info::from_raw_weak(ptr as *mut InfoInternal)),
@@ -1670,6 +1720,7 @@ impl Stream {
     /// [`connect_record()`]: Self::connect_record
     /// [`connect_playback()`]: Self::connect_playback
     pub fn get_buffer_attr<'a>(&mut self) -> Option<&'a def::BufferAttr> {
+        self.check_lock();
         unsafe {
             let ptr = capi::pa_stream_get_buffer_attr(self.ptr);
             ptr.as_ref().map(|r| r.as_ref())
@@ -1692,6 +1743,7 @@ impl Stream {
         -> Operation<dyn FnMut(bool)>
         where F: FnMut(bool) + 'static
     {
+        self.check_lock();
         let cb_data = box_closure_get_capi_ptr::<dyn FnMut(bool)>(Box::new(callback));
         let ptr = unsafe { capi::pa_stream_set_buffer_attr(self.ptr, attr.as_ref(),
             Some(success_cb_proxy), cb_data) };
@@ -1712,6 +1764,7 @@ impl Stream {
     pub fn update_sample_rate<F>(&mut self, rate: u32, callback: F) -> Operation<dyn FnMut(bool)>
         where F: FnMut(bool) + 'static
     {
+        self.check_lock();
         let cb_data = box_closure_get_capi_ptr::<dyn FnMut(bool)>(Box::new(callback));
         let ptr = unsafe { capi::pa_stream_update_sample_rate(self.ptr, rate,
             Some(success_cb_proxy), cb_data) };
@@ -1734,6 +1787,7 @@ impl Stream {
         callback: F) -> Operation<dyn FnMut(bool)>
         where F: FnMut(bool) + 'static
     {
+        self.check_lock();
         let cb_data = box_closure_get_capi_ptr::<dyn FnMut(bool)>(Box::new(callback));
         let ptr = unsafe { capi::pa_stream_proplist_update(self.ptr, mode, proplist.0.ptr,
             Some(success_cb_proxy), cb_data) };
@@ -1748,6 +1802,7 @@ impl Stream {
     pub fn remove_proplist<F>(&mut self, keys: &[&str], callback: F) -> Operation<dyn FnMut(bool)>
         where F: FnMut(bool) + 'static
     {
+        self.check_lock();
         // Warning: New CStrings will be immediately freed if not bound to a variable, leading to
         // as_ptr() giving dangling pointers!
         let mut c_keys: Vec<CString> = Vec::with_capacity(keys.len());
@@ -1777,6 +1832,7 @@ impl Stream {
     /// This function needs to be called before [`connect_record()`](Self::connect_record) is
     /// called.
     pub fn set_monitor_stream(&mut self, sink_input_index: u32) -> Result<(), PAErr> {
+        self.check_lock();
         match unsafe { capi::pa_stream_set_monitor_stream(self.ptr, sink_input_index) } {
             0 => Ok(()),
             e => Err(PAErr(e)),
@@ -1787,6 +1843,7 @@ impl Stream {
     ///
     /// [`set_monitor_stream()`]: Self::set_monitor_stream
     pub fn get_monitor_stream(&self) -> Option<u32> {
+        self.check_lock();
         match unsafe { capi::pa_stream_get_monitor_stream(self.ptr) } {
             def::INVALID_INDEX => None,
             r => Some(r),
@@ -1794,6 +1851,19 @@ impl Stream {
     }
 }
 
+impl Stream {
+    /// Asserts, in debug builds, that the stream may be used from the current thread.
+    #[inline(always)]
+    #[track_caller]
+    fn check_lock(&self) {
+        #[cfg(debug_assertions)]
+        {
+            let context = unsafe { capi::pa_stream_get_context(self.ptr) };
+            crate::mainloop::lockcheck::check_context(context);
+        }
+    }
+}
+
 impl Drop for Stream {
     fn drop(&mut self) {
         // Throw away the `Result` from disconnecting, it may legitimately be bad if stream failed.
@@ -1810,7 +1880,7 @@ impl Drop for Stream {
 extern "C"
 fn success_cb_proxy(_: *mut StreamInternal, success: i32, userdata: *mut c_void) {
     let success_actual = match success { 0 => false, _ => true };
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         // Note, destroys closure callback after use - restoring outer box means it gets dropped
         let mut callback = get_su_callback::<dyn FnMut(bool)>(userdata);
         (callback)(success_actual);
@@ -1823,7 +1893,7 @@ fn success_cb_proxy(_: *mut StreamInternal, success: i32, userdata: *mut c_void)
 /// must be accomplished separately to avoid a memory leak.
 extern "C"
 fn request_cb_proxy(_: *mut StreamInternal, nbytes: usize, userdata: *mut c_void) {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         let callback = RequestCb::get_callback(userdata);
         (callback)(nbytes);
     });
@@ -1835,7 +1905,7 @@ fn request_cb_proxy(_: *mut StreamInternal, nbytes: usize, userdata: *mut c_void
 /// must be accomplished separately to avoid a memory leak.
 extern "C"
 fn notify_cb_proxy(_: *mut StreamInternal, userdata: *mut c_void) {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         let callback = NotifyCb::get_callback(userdata);
         (callback)();
     });
@@ -1849,7 +1919,7 @@ extern "C"
 fn event_cb_proxy(_: *mut StreamInternal, name: *const c_char, proplist: *mut ProplistInternal,
     userdata: *mut c_void)
 {
-    let _ = std::panic::catch_unwind(|| {
+    let _ = crate::panic::catch(|| {
         assert!(!name.is_null());
         let n = {
             let tmp = unsafe { CStr::from_ptr(name) };
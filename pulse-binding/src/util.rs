@@ -79,3 +79,19 @@ pub fn make_thread_realtime(rtprio: i32) -> Result<(), ()> {
         _ => Err(()),
     }
 }
+
+/// Makes the calling thread realtime if we can, if supported.
+///
+/// This is the same as [`make_thread_realtime()`], but is available regardless of the PA version
+/// feature flags enabled, with the function being resolved at runtime. Returns `None` if the client
+/// library in use is too old to provide it.
+pub fn try_make_thread_realtime(rtprio: i32) -> Option<Result<(), ()>> {
+    type MakeRealtimeFn = unsafe extern "C" fn(i32) -> i32;
+
+    let symbol = capi::find_symbol("pa_thread_make_realtime")?;
+    let make_realtime: MakeRealtimeFn = unsafe { std::mem::transmute(symbol) };
+    match unsafe { make_realtime(rtprio) } {
+        0 => Some(Ok(())),
+        _ => Some(Err(())),
+    }
+}
@@ -41,6 +41,9 @@
 //!    a system with too old of a version of PA, helping combat the “forward” compatibility problem
 //!    discussed in the project `COMPATIBILITY.md` documentation.
 //!
+//! To check for particular functionality rather than versions, see the
+//! [`capability`](mod@crate::capability) module.
+//!
 //! # Dynamic constants
 //!
 //! The version constants defined here mostly relate to those provided in the PA C headers, and are
@@ -0,0 +1,511 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! Reading and writing of WAV files.
+//!
+//! # Overview
+//!
+//! Sounds to play with a stream, or to upload to the sample cache, are commonly kept in RIFF/WAVE
+//! files. A [`Reader`] parses the header of such a file into a [`Spec`] and [`Map`], and then
+//! yields the raw audio data, ready to be written to a stream; a [`Writer`] does the reverse, for
+//! recorded data.
+//!
+//! Integer PCM, IEEE float, A-law and µ-law data is supported, in both the plain and the
+//! `WAVEFORMATEXTENSIBLE` layouts. The channel mask of the latter is mapped to channel positions
+//! with [`map_from_mask()`], and back with [`mask_from_map()`]; without a usable mask, the default
+//! channel map for the number of channels is assumed.
+//!
+//! Data is streamed rather than held in memory, such that files of unknown length, such as those
+//! written to a pipe, can be handled.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use libpulse_binding::wav;
+//!
+//! let mut reader = wav::Reader::new(BufReader::new(File::open("sound.wav")?))?;
+//! let stream = Stream::new(&mut context, "Sound", reader.spec(), Some(reader.map()));
+//! // Then read from `reader` into the stream upon each write request
+//! ```
+
+use std::fmt;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use crate::channelmap::{Map, MapDef, Position};
+use crate::sample::{Format, Spec};
+
+const FORMAT_PCM: u16 = 0x0001;
+const FORMAT_IEEE_FLOAT: u16 = 0x0003;
+const FORMAT_ALAW: u16 = 0x0006;
+const FORMAT_MULAW: u16 = 0x0007;
+const FORMAT_EXTENSIBLE: u16 = 0xfffe;
+
+/// The tail of the sub-format GUIDs of `WAVEFORMATEXTENSIBLE`, following the format tag.
+const GUID_TAIL: [u8; 14] =
+    [0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xaa, 0x00, 0x38, 0x9b, 0x71];
+
+/// Length value used for chunks of unknown length.
+const UNKNOWN_LENGTH: u32 = u32::MAX;
+
+/// Channel positions of the bits of a `WAVEFORMATEXTENSIBLE` channel mask, in order.
+const MASK_POSITIONS: [Position; 18] = [
+    Position::FrontLeft,
+    Position::FrontRight,
+    Position::FrontCenter,
+    Position::Lfe,
+    Position::RearLeft,
+    Position::RearRight,
+    Position::FrontLeftOfCenter,
+    Position::FrontRightOfCenter,
+    Position::RearCenter,
+    Position::SideLeft,
+    Position::SideRight,
+    Position::TopCenter,
+    Position::TopFrontLeft,
+    Position::TopFrontCenter,
+    Position::TopFrontRight,
+    Position::TopRearLeft,
+    Position::TopRearCenter,
+    Position::TopRearRight,
+];
+
+/// Error in reading or writing a WAV file.
+#[derive(Debug)]
+pub enum Error {
+    /// An I/O error.
+    Io(io::Error),
+    /// The data is not a WAV file, or is malformed.
+    Malformed(&'static str),
+    /// The WAV file, or the sample format to write, is not supported.
+    Unsupported(String),
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(e) => e.fmt(f),
+            Error::Malformed(what) => write!(f, "Malformed WAV file: {}", what),
+            Error::Unsupported(what) => write!(f, "Unsupported WAV file: {}", what),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    #[inline]
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::Io(e) => e,
+            e => io::Error::new(io::ErrorKind::InvalidData, e),
+        }
+    }
+}
+
+/// Gets the position of each bit set in a `WAVEFORMATEXTENSIBLE` channel mask, in order.
+///
+/// Returns `None` if the mask does not describe exactly the given number of channels.
+pub fn map_from_mask(mask: u32, channels: u8) -> Option<Map> {
+    if mask.count_ones() != channels as u32 || mask >> MASK_POSITIONS.len() != 0 {
+        return None;
+    }
+    let mut map = Map::default();
+    map.set_len(channels);
+    let positions = MASK_POSITIONS.iter().enumerate().filter(|(bit, _)| mask & (1 << bit) != 0);
+    for (slot, (_, position)) in map.get_mut().iter_mut().zip(positions) {
+        *slot = *position;
+    }
+    Some(map)
+}
+
+/// Gets the `WAVEFORMATEXTENSIBLE` channel mask of a channel map.
+///
+/// Returns `None` if the map has positions not representable in a mask, or not in mask order.
+pub fn mask_from_map(map: &Map) -> Option<u32> {
+    let mut mask = 0u32;
+    for position in map.get() {
+        let bit = MASK_POSITIONS.iter().position(|p| p == position)?;
+        if mask >> bit != 0 {
+            return None;
+        }
+        mask |= 1 << bit;
+    }
+    Some(mask)
+}
+
+/// Gets the default channel map for the given number of channels.
+fn default_map(channels: u8) -> Map {
+    let mut map = Map::default();
+    map.init_extend(channels, MapDef::WAVEEx);
+    map
+}
+
+/// A reader of the audio data of a WAV file.
+///
+/// Reading yields the data of the `data` chunk only.
+pub struct Reader<R: Read> {
+    inner: R,
+    spec: Spec,
+    map: Map,
+    /// The number of bytes of data remaining, if known.
+    remaining: Option<u64>,
+}
+
+impl<R: Read> Reader<R> {
+    /// Reads the header of a WAV file, up to the start of its audio data.
+    pub fn new(mut inner: R) -> Result<Self, Error> {
+        let mut riff = [0u8; 12];
+        inner.read_exact(&mut riff)?;
+        if &riff[0..4] != b"RIFF" || &riff[8..12] != b"WAVE" {
+            return Err(Error::Malformed("not a RIFF WAVE file"));
+        }
+        let mut format = None;
+        loop {
+            let mut header = [0u8; 8];
+            inner.read_exact(&mut header)?;
+            let len = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+            match &header[0..4] {
+                b"fmt " => {
+                    if !(16..=1024).contains(&len) {
+                        return Err(Error::Malformed("bad format chunk length"));
+                    }
+                    let mut fmt = vec![0u8; len as usize + (len & 1) as usize];
+                    inner.read_exact(&mut fmt)?;
+                    format = Some(parse_format(&fmt)?);
+                },
+                b"data" => {
+                    let (spec, map) = format.ok_or(Error::Malformed("data precedes format"))?;
+                    let remaining = match len {
+                        UNKNOWN_LENGTH => None,
+                        len => Some(len as u64),
+                    };
+                    return Ok(Self { inner, spec, map, remaining });
+                },
+                _ => {
+                    let skip = len as u64 + (len & 1) as u64;
+                    if io::copy(&mut (&mut inner).take(skip), &mut io::sink())? != skip {
+                        return Err(Error::Malformed("truncated chunk"));
+                    }
+                },
+            }
+        }
+    }
+
+    /// Gets the sample specification of the data.
+    #[inline]
+    pub fn spec(&self) -> &Spec {
+        &self.spec
+    }
+
+    /// Gets the channel map of the data.
+    #[inline]
+    pub fn map(&self) -> &Map {
+        &self.map
+    }
+
+    /// Gets the number of bytes of data remaining, if known.
+    #[inline]
+    pub fn remaining(&self) -> Option<u64> {
+        self.remaining
+    }
+
+    /// Returns the underlying reader, positioned within the data.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for Reader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = match self.remaining {
+            Some(remaining) => buf.len().min(remaining.min(usize::MAX as u64) as usize),
+            None => buf.len(),
+        };
+        let n = self.inner.read(&mut buf[..len])?;
+        if let Some(remaining) = self.remaining.as_mut() {
+            *remaining -= n as u64;
+        }
+        Ok(n)
+    }
+}
+
+/// Parses the content of a `fmt ` chunk.
+fn parse_format(fmt: &[u8]) -> Result<(Spec, Map), Error> {
+    let u16_at = |i: usize| u16::from_le_bytes([fmt[i], fmt[i + 1]]);
+    let u32_at = |i: usize| u32::from_le_bytes([fmt[i], fmt[i + 1], fmt[i + 2], fmt[i + 3]]);
+    let mut tag = u16_at(0);
+    let channels = u16_at(2);
+    let rate = u32_at(4);
+    let block_align = u16_at(12);
+    let container_bits = u16_at(14);
+    let mut valid_bits = container_bits;
+    let mut mask = 0;
+    if tag == FORMAT_EXTENSIBLE {
+        if fmt.len() < 40 || u16_at(16) < 22 {
+            return Err(Error::Malformed("short extensible format"));
+        }
+        valid_bits = u16_at(18);
+        mask = u32_at(20);
+        if fmt[26..40] != GUID_TAIL {
+            return Err(Error::Unsupported("unknown sub-format".to_owned()));
+        }
+        tag = u16_at(24);
+    }
+    if channels == 0 || channels > Map::CHANNELS_MAX as u16 {
+        return Err(Error::Unsupported(format!("{} channels", channels)));
+    }
+    let format = match (tag, container_bits, valid_bits) {
+        (FORMAT_PCM, 8, 8) => Format::U8,
+        (FORMAT_PCM, 16, 16) => Format::S16le,
+        (FORMAT_PCM, 24, 24) => Format::S24le,
+        (FORMAT_PCM, 32, 32) => Format::S32le,
+        (FORMAT_PCM, 32, 24) => Format::S24_32le,
+        (FORMAT_IEEE_FLOAT, 32, 32) => Format::F32le,
+        (FORMAT_ALAW, 8, 8) => Format::ALaw,
+        (FORMAT_MULAW, 8, 8) => Format::ULaw,
+        _ => {
+            return Err(Error::Unsupported(format!(
+                "format tag {:#06x} with {} bits in {}", tag, valid_bits, container_bits)));
+        },
+    };
+    let spec = Spec { format, rate, channels: channels as u8 };
+    if !spec.is_valid() || block_align as usize != spec.frame_size() {
+        return Err(Error::Malformed("inconsistent format"));
+    }
+    let map = map_from_mask(mask, spec.channels).unwrap_or_else(|| default_map(spec.channels));
+    Ok((spec, map))
+}
+
+/// A writer of a WAV file.
+///
+/// The header is written upon creation, with the lengths left unknown. Once all data has been
+/// written, [`finish()`](Self::finish) fills them in for seekable outputs, while
+/// [`into_inner()`](Self::into_inner) leaves them unknown, as suits pipes.
+pub struct Writer<W: Write> {
+    inner: W,
+    /// The number of bytes of data written.
+    written: u64,
+    /// The length of the header, up to the start of the data.
+    header_len: u64,
+}
+
+impl<W: Write> Writer<W> {
+    /// Writes the header of a WAV file for the given sample specification and channel map.
+    ///
+    /// The extensible layout is used where needed, that is, for more than two channels, a
+    /// non-default channel map, or samples of more than 16 bits.
+    ///
+    /// A non-default channel map must be expressible as a channel mask (see [`mask_from_map()`]),
+    /// otherwise this fails with [`Error::Unsupported`]. The default map is written with an empty
+    /// mask where it cannot be expressed, which readers take to mean the default map.
+    pub fn new(mut inner: W, spec: &Spec, map: &Map) -> Result<Self, Error> {
+        let (tag, container_bits, valid_bits) = match spec.format {
+            Format::U8 => (FORMAT_PCM, 8, 8),
+            Format::S16le => (FORMAT_PCM, 16, 16),
+            Format::S24le => (FORMAT_PCM, 24, 24),
+            Format::S32le => (FORMAT_PCM, 32, 32),
+            Format::S24_32le => (FORMAT_PCM, 32, 24),
+            Format::F32le => (FORMAT_IEEE_FLOAT, 32, 32),
+            Format::ALaw => (FORMAT_ALAW, 8, 8),
+            Format::ULaw => (FORMAT_MULAW, 8, 8),
+            f => return Err(Error::Unsupported(format!("sample format {:?}", f))),
+        };
+        if !spec.is_valid() || map.get().len() != spec.channels as usize {
+            return Err(Error::Unsupported("invalid specification".to_owned()));
+        }
+        let is_default = *map == default_map(spec.channels);
+        let mask = match (mask_from_map(map), is_default) {
+            (Some(mask), _) => mask,
+            (None, true) => 0,
+            (None, false) => {
+                return Err(Error::Unsupported("channel map not expressible as a mask".to_owned()));
+            },
+        };
+        let extensible = tag != FORMAT_ALAW && tag != FORMAT_MULAW
+            && (spec.channels > 2 || container_bits > 16 || !is_default);
+
+        let mut fmt = Vec::with_capacity(40);
+        fmt.extend_from_slice(&(if extensible { FORMAT_EXTENSIBLE } else { tag }).to_le_bytes());
+        fmt.extend_from_slice(&(spec.channels as u16).to_le_bytes());
+        fmt.extend_from_slice(&spec.rate.to_le_bytes());
+        fmt.extend_from_slice(&((spec.rate as usize * spec.frame_size()) as u32).to_le_bytes());
+        fmt.extend_from_slice(&(spec.frame_size() as u16).to_le_bytes());
+        fmt.extend_from_slice(&(container_bits as u16).to_le_bytes());
+        if extensible {
+            fmt.extend_from_slice(&22u16.to_le_bytes());
+            fmt.extend_from_slice(&(valid_bits as u16).to_le_bytes());
+            fmt.extend_from_slice(&mask.to_le_bytes());
+            fmt.extend_from_slice(&tag.to_le_bytes());
+            fmt.extend_from_slice(&GUID_TAIL);
+        }
+        else if tag != FORMAT_PCM {
+            fmt.extend_from_slice(&0u16.to_le_bytes());
+        }
+
+        let mut header = Vec::with_capacity(fmt.len() + 28);
+        header.extend_from_slice(b"RIFF");
+        header.extend_from_slice(&UNKNOWN_LENGTH.to_le_bytes());
+        header.extend_from_slice(b"WAVEfmt ");
+        header.extend_from_slice(&(fmt.len() as u32).to_le_bytes());
+        header.extend_from_slice(&fmt);
+        header.extend_from_slice(b"data");
+        header.extend_from_slice(&UNKNOWN_LENGTH.to_le_bytes());
+        inner.write_all(&header)?;
+        Ok(Self { inner, written: 0, header_len: header.len() as u64 })
+    }
+
+    /// Gets the number of bytes of data written.
+    #[inline]
+    pub fn written(&self) -> u64 {
+        self.written
+    }
+
+    /// Flushes and returns the output, leaving the lengths in the header unknown.
+    pub fn into_inner(mut self) -> Result<W, Error> {
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write + Seek> Writer<W> {
+    /// Fills in the lengths in the header, and returns the output.
+    ///
+    /// Lengths too large for the format are left unknown.
+    pub fn finish(self) -> Result<W, Error> {
+        let (written, header_len) = (self.written, self.header_len);
+        let mut inner = self.inner;
+        // Chunks are padded to an even length
+        if written & 1 != 0 {
+            inner.write_all(&[0])?;
+        }
+        let data_len = u32::try_from(written).unwrap_or(UNKNOWN_LENGTH);
+        let riff_len = u32::try_from(header_len - 8 + written + (written & 1))
+            .unwrap_or(UNKNOWN_LENGTH);
+        inner.seek(SeekFrom::Start(4))?;
+        inner.write_all(&riff_len.to_le_bytes())?;
+        inner.seek(SeekFrom::Start(header_len - 4))?;
+        inner.write_all(&data_len.to_le_bytes())?;
+        inner.seek(SeekFrom::End(0))?;
+        inner.flush()?;
+        Ok(inner)
+    }
+}
+
+impl<W: Write> Write for Writer<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[test]
+fn masks() {
+    let map = map_from_mask(0x3f, 6).unwrap();
+    assert_eq!(map.get(), &[Position::FrontLeft, Position::FrontRight, Position::FrontCenter,
+        Position::Lfe, Position::RearLeft, Position::RearRight]);
+    assert_eq!(mask_from_map(&map), Some(0x3f));
+    assert_eq!(map_from_mask(0x3, 1), None);
+    assert_eq!(map_from_mask(1 << 20, 1), None);
+
+    let mut reversed = Map::default();
+    reversed.set_len(2);
+    reversed.get_mut().copy_from_slice(&[Position::FrontRight, Position::FrontLeft]);
+    assert_eq!(mask_from_map(&reversed), None);
+}
+
+#[test]
+fn round_trip() {
+    use std::io::Cursor;
+    let spec = Spec { format: Format::S24_32le, rate: 48000, channels: 4 };
+    let map = map_from_mask(0x33, 4).unwrap();
+    let data: Vec<u8> = (0..64).collect();
+
+    let mut writer = Writer::new(Cursor::new(Vec::new()), &spec, &map).unwrap();
+    writer.write_all(&data).unwrap();
+    let file = writer.finish().unwrap().into_inner();
+
+    let mut reader = Reader::new(Cursor::new(&file)).unwrap();
+    assert_eq!(reader.spec(), &spec);
+    assert_eq!(reader.map(), &map);
+    assert_eq!(reader.remaining(), Some(64));
+    let mut read = Vec::new();
+    reader.read_to_end(&mut read).unwrap();
+    assert_eq!(read, data);
+
+    // Unknown lengths, as written to a pipe, are read up to the end
+    let spec = Spec { format: Format::ULaw, rate: 8000, channels: 1 };
+    let mut writer = Writer::new(Vec::new(), &spec, &default_map(1)).unwrap();
+    writer.write_all(&data[..3]).unwrap();
+    let file = writer.into_inner().unwrap();
+    let mut reader = Reader::new(&file[..]).unwrap();
+    assert_eq!(reader.spec(), &spec);
+    assert_eq!(reader.remaining(), None);
+    let mut read = Vec::new();
+    reader.read_to_end(&mut read).unwrap();
+    assert_eq!(read, [0, 1, 2]);
+
+    assert!(Reader::new(&b"RIFF\0\0\0\0AVI "[..]).is_err());
+}
+
+#[test]
+fn channel_maps() {
+    use std::io::Cursor;
+    let spec = Spec { format: Format::S16le, rate: 44100, channels: 2 };
+    let mut reversed = Map::default();
+    reversed.set_len(2);
+    reversed.get_mut().copy_from_slice(&[Position::FrontRight, Position::FrontLeft]);
+    assert!(matches!(Writer::new(Vec::new(), &spec, &reversed), Err(Error::Unsupported(_))));
+    let mut aux = Map::default();
+    aux.set_len(2);
+    aux.get_mut().copy_from_slice(&[Position::Aux0, Position::Aux1]);
+    assert!(matches!(Writer::new(Vec::new(), &spec, &aux), Err(Error::Unsupported(_))));
+
+    // The default mono map has no mask, but is the default assumed without one
+    let spec = Spec { format: Format::S24le, rate: 44100, channels: 1 };
+    assert_eq!(mask_from_map(&default_map(1)), None);
+    let writer = Writer::new(Cursor::new(Vec::new()), &spec, &default_map(1)).unwrap();
+    let file = writer.finish().unwrap().into_inner();
+    let reader = Reader::new(Cursor::new(&file)).unwrap();
+    assert_eq!(reader.spec(), &spec);
+    assert_eq!(reader.map(), &default_map(1));
+}
+
+#[test]
+fn empty_data() {
+    use std::io::Cursor;
+    let spec = Spec { format: Format::U8, rate: 8000, channels: 1 };
+    let writer = Writer::new(Cursor::new(Vec::new()), &spec, &default_map(1)).unwrap();
+    let mut file = writer.finish().unwrap().into_inner();
+    // Anything following an empty data chunk is not audio data
+    file.extend_from_slice(b"LIST\0\0\0\0");
+    let mut reader = Reader::new(&file[..]).unwrap();
+    assert_eq!(reader.remaining(), Some(0));
+    let mut read = Vec::new();
+    reader.read_to_end(&mut read).unwrap();
+    assert!(read.is_empty());
+}
@@ -0,0 +1,53 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! Testing running closures on the mainloop from other threads.
+
+#![cfg(unix)]
+
+extern crate libpulse_binding as pulse;
+
+use pulse::mainloop::handle::{Closed, Dispatcher, MainloopHandle};
+use pulse::mainloop::standard::{IterateResult, Mainloop};
+
+#[test]
+fn handle_is_send() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<MainloopHandle<std::rc::Rc<u32>>>();
+}
+
+#[test]
+fn spawn_and_invoke() {
+    let mut mainloop = Mainloop::new().unwrap();
+    let dispatcher = Dispatcher::new(&mut mainloop, 0u32).unwrap();
+    let handle = dispatcher.handle();
+
+    let worker = std::thread::spawn(move || {
+        for _ in 0..10 {
+            handle.spawn(|count| *count += 1).unwrap();
+        }
+        let count = handle.invoke(|count| *count).unwrap();
+        handle.spawn(|count| *count += 100).unwrap();
+        count
+    });
+
+    while *dispatcher.state().borrow() < 110 {
+        match mainloop.iterate(true) {
+            IterateResult::Success(_) => {},
+            r => panic!("iteration failed: {:?}", r),
+        }
+    }
+    assert_eq!(worker.join().unwrap(), 10);
+
+    let handle = dispatcher.handle();
+    drop(dispatcher);
+    assert!(handle.is_closed());
+    assert_eq!(handle.spawn(|_| {}), Err(Closed));
+}
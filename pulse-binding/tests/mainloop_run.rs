@@ -0,0 +1,54 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! Testing the standard mainloop run helpers.
+
+extern crate libpulse_binding as pulse;
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Instant;
+use pulse::def::Retval;
+use pulse::mainloop::api::Mainloop as MainloopTrait;
+use pulse::mainloop::standard::{Mainloop, WaitError};
+use pulse::time::MicroSeconds;
+
+#[test]
+fn run_until_timeout() {
+    let mut mainloop = Mainloop::new().unwrap();
+    let start = Instant::now();
+    let r = mainloop.run_until(|| false, Some(MicroSeconds::from_millis(50).unwrap()));
+    assert_eq!(r, Err(WaitError::Timeout));
+    assert!(start.elapsed().as_millis() >= 50);
+
+    let mut checks = 0;
+    let r = mainloop.run_until(|| { checks += 1; checks == 1 }, Some(MicroSeconds::ZERO));
+    assert_eq!(r, Ok(()));
+}
+
+#[test]
+fn quit_from_callback() {
+    let mut mainloop = Mainloop::new().unwrap();
+    let quit = mainloop.quit_handle();
+    let fired = Rc::new(Cell::new(false));
+    let fired_ref = Rc::clone(&fired);
+    let _event = mainloop.new_deferred_event(Box::new(move |mut event| {
+        event.disable();
+        fired_ref.set(true);
+        assert!(quit.quit(Retval(3)));
+    })).unwrap();
+    assert_eq!(mainloop.run(), Ok(Retval(3)));
+    assert!(fired.get());
+
+    let quit = mainloop.quit_handle();
+    drop(_event);
+    drop(mainloop);
+    assert!(!quit.quit(Retval(0)));
+}
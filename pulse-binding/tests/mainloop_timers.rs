@@ -0,0 +1,58 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! Testing the higher level timers.
+
+extern crate libpulse_binding as pulse;
+
+use std::cell::Cell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use pulse::mainloop::standard::Mainloop;
+use pulse::mainloop::timers::{sleep, Interval};
+use pulse::time::MicroSeconds;
+
+struct Flag(AtomicBool);
+
+impl Wake for Flag {
+    fn wake(self: Arc<Self>) {
+        self.0.store(true, Ordering::Release);
+    }
+}
+
+#[test]
+fn interval() {
+    let mut mainloop = Mainloop::new().unwrap();
+    let ticks = Rc::new(Cell::new(0));
+    let ticks_ref = Rc::clone(&ticks);
+    let interval = Interval::new(&mut mainloop, MicroSeconds::from_millis(10).unwrap(),
+        move |n| ticks_ref.set(ticks_ref.get() + n)).unwrap();
+    assert_eq!(interval.period(), MicroSeconds::from_millis(10).unwrap());
+    mainloop.run_until(|| ticks.get() >= 3, Some(MicroSeconds::from_secs(5).unwrap())).unwrap();
+}
+
+#[test]
+fn sleep_future() {
+    let mut mainloop = Mainloop::new().unwrap();
+    let mut future = sleep(&mut mainloop, MicroSeconds::from_millis(10).unwrap()).unwrap();
+    let flag = Arc::new(Flag(AtomicBool::new(false)));
+    let waker = Waker::from(Arc::clone(&flag));
+    let mut cx = Context::from_waker(&waker);
+
+    assert_eq!(Pin::new(&mut future).poll(&mut cx), Poll::Pending);
+    mainloop.run_until(|| flag.0.load(Ordering::Acquire),
+        Some(MicroSeconds::from_secs(5).unwrap())).unwrap();
+    assert!(future.is_elapsed());
+    assert_eq!(Pin::new(&mut future).poll(&mut cx), Poll::Ready(()));
+}
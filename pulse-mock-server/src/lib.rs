@@ -0,0 +1,827 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! An in-process mock PulseAudio server, for testing PulseAudio client code.
+//!
+//! # About
+//!
+//! This crate provides a [`MockServer`], which listens on a Unix domain socket and speaks enough of
+//! the PulseAudio native protocol for the PulseAudio client library to connect to it and make use
+//! of its most common functionality, allowing code built upon the `libpulse-binding` crate to be
+//! exercised without a real PulseAudio daemon. It is written entirely in Rust and runs on
+//! background threads within the test process.
+//!
+//! The devices (sinks, sources and cards) offered are scripted through the API, and the state of
+//! the server can be inspected at any time, including any audio played by clients.
+//!
+//! Supported functionality includes:
+//!
+//! * Authentication (optionally with a specific cookie) and setting of the client name/properties.
+//! * Server, sink, source, card, client, sink-input and source-output introspection.
+//! * Setting of device volume, mute and port, sink-input volume and mute, default devices and card
+//!   profiles.
+//! * Subscriptions, with events generated for all changes made, whether by clients or through the
+//!   API.
+//! * Playback and record streams, with timing driven by a virtual clock, advanced explicitly with
+//!   [`MockServer::advance()`].
+//! * Sample uploads, and listing and removal of the samples held in the sample cache (though not
+//!   their playback).
+//!
+//! The server negotiates protocol version [`PROTOCOL_VERSION`], and never offers shared memory, so
+//! all audio is transferred over the socket. Extensions (as used by the `ext_*` modules of the
+//! binding) and module management are not supported, and fail with an error reply.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! extern crate libpulse_binding as pulse;
+//! extern crate libpulse_mock_server as mock;
+//!
+//! use mock::{Device, MockServer};
+//!
+//! let server = MockServer::start().unwrap();
+//! let index = server.add_sink(Device::new("speakers").with_description("Speakers"));
+//!
+//! // Connect a context as usual...
+//! context.connect(Some(&server.address()), pulse::context::FlagSet::NOFLAGS, None).unwrap();
+//!
+//! // ...and later check what it did
+//! let sink = server.sink(index).unwrap();
+//! assert!(sink.mute);
+//! ```
+
+#![cfg(unix)]
+
+extern crate libpulse_binding as pulse;
+
+mod server;
+mod wire;
+
+use std::collections::BTreeMap;
+use std::io;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use pulse::channelmap::{Map, Position};
+use pulse::sample::{Format, Spec};
+use pulse::volume::{ChannelVolumes, Volume};
+use server::{Shared, State};
+#[cfg(test)]
+use wire::PutProperties;
+
+/// The native protocol version offered by the server.
+pub const PROTOCOL_VERSION: u32 = 16;
+
+/// A device port.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Port {
+    /// Name.
+    pub name: String,
+    /// Description.
+    pub description: String,
+    /// Priority.
+    pub priority: u32,
+}
+
+impl Port {
+    /// Creates a new port.
+    pub fn new(name: &str, description: &str, priority: u32) -> Self {
+        Self { name: name.to_string(), description: description.to_string(), priority }
+    }
+}
+
+/// A sink or source.
+#[derive(Debug, Clone)]
+pub struct Device {
+    /// Index, assigned by the server.
+    pub index: u32,
+    /// Name.
+    pub name: String,
+    /// Description.
+    pub description: String,
+    /// Sample spec.
+    pub spec: Spec,
+    /// Channel map.
+    pub map: Map,
+    /// Volume.
+    pub volume: ChannelVolumes,
+    /// Mute state.
+    pub mute: bool,
+    /// Ports.
+    pub ports: Vec<Port>,
+    /// Name of the active port.
+    pub active_port: Option<String>,
+    /// Index of the card the device belongs to.
+    pub card: Option<u32>,
+    /// Properties (in addition to `device.description`, which is provided automatically).
+    pub properties: BTreeMap<String, String>,
+}
+
+impl Device {
+    /// Creates a new stereo, 16-bit, 44.1 kHz device, with normal volume.
+    pub fn new(name: &str) -> Self {
+        Self {
+            index: 0,
+            name: name.to_string(),
+            description: name.to_string(),
+            spec: Spec { format: Format::S16le, channels: 2, rate: 44100 },
+            map: stereo_map(),
+            volume: normal_volume(2),
+            mute: false,
+            ports: Vec::new(),
+            active_port: None,
+            card: None,
+            properties: BTreeMap::new(),
+        }
+    }
+
+    /// Sets the description.
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self
+    }
+
+    /// Sets the sample spec and channel map, resetting the volume to normal.
+    ///
+    /// **Panics** if the number of channels of the spec and map differ.
+    pub fn with_spec(mut self, spec: Spec, map: Map) -> Self {
+        assert_eq!(spec.channels, map.len());
+        self.spec = spec;
+        self.map = map;
+        self.volume = normal_volume(spec.channels);
+        self
+    }
+
+    /// Adds a port. The first port added becomes the active port.
+    pub fn with_port(mut self, port: Port) -> Self {
+        if self.active_port.is_none() {
+            self.active_port = Some(port.name.clone());
+        }
+        self.ports.push(port);
+        self
+    }
+
+    /// Sets the card the device belongs to.
+    pub fn with_card(mut self, card: u32) -> Self {
+        self.card = Some(card);
+        self
+    }
+
+    /// Sets a property.
+    pub fn with_property(mut self, key: &str, value: &str) -> Self {
+        self.properties.insert(key.to_string(), value.to_string());
+        self
+    }
+}
+
+/// A card profile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    /// Name.
+    pub name: String,
+    /// Description.
+    pub description: String,
+    /// Number of sinks the profile would create.
+    pub n_sinks: u32,
+    /// Number of sources the profile would create.
+    pub n_sources: u32,
+    /// Priority.
+    pub priority: u32,
+}
+
+impl Profile {
+    /// Creates a new profile.
+    pub fn new(name: &str, description: &str, n_sinks: u32, n_sources: u32, priority: u32)
+        -> Self
+    {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            n_sinks,
+            n_sources,
+            priority,
+        }
+    }
+}
+
+/// A card.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Card {
+    /// Index, assigned by the server.
+    pub index: u32,
+    /// Name.
+    pub name: String,
+    /// Profiles.
+    pub profiles: Vec<Profile>,
+    /// Name of the active profile.
+    pub active_profile: Option<String>,
+    /// Properties.
+    pub properties: BTreeMap<String, String>,
+}
+
+impl Card {
+    /// Creates a new card, without profiles.
+    pub fn new(name: &str) -> Self {
+        Self {
+            index: 0,
+            name: name.to_string(),
+            profiles: Vec::new(),
+            active_profile: None,
+            properties: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a profile. The first profile added becomes the active profile.
+    pub fn with_profile(mut self, profile: Profile) -> Self {
+        if self.active_profile.is_none() {
+            self.active_profile = Some(profile.name.clone());
+        }
+        self.profiles.push(profile);
+        self
+    }
+
+    /// Sets a property.
+    pub fn with_property(mut self, key: &str, value: &str) -> Self {
+        self.properties.insert(key.to_string(), value.to_string());
+        self
+    }
+}
+
+/// A connected (and authenticated) client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Client {
+    /// Index.
+    pub index: u32,
+    /// Negotiated protocol version.
+    pub protocol_version: u32,
+    /// Properties, as given by the client.
+    pub properties: BTreeMap<String, String>,
+}
+
+/// A playback stream (sink input).
+#[derive(Debug, Clone)]
+pub struct PlaybackStream {
+    /// Sink input index.
+    pub index: u32,
+    /// Index of the owning client.
+    pub client: u32,
+    /// Index of the sink.
+    pub sink: u32,
+    /// Name (`media.name` property).
+    pub name: Option<String>,
+    /// Sample spec.
+    pub spec: Spec,
+    /// Channel map.
+    pub map: Map,
+    /// Volume.
+    pub volume: ChannelVolumes,
+    /// Mute state.
+    pub mute: bool,
+    /// Whether the stream is corked (paused).
+    pub corked: bool,
+    /// Properties, as given by the client.
+    pub properties: BTreeMap<String, String>,
+    /// All audio data received from the client.
+    pub received: Vec<u8>,
+    /// Number of bytes played so far, per the virtual clock.
+    pub played: u64,
+}
+
+/// A record stream (source output).
+#[derive(Debug, Clone)]
+pub struct RecordStream {
+    /// Source output index.
+    pub index: u32,
+    /// Index of the owning client.
+    pub client: u32,
+    /// Index of the source.
+    pub source: u32,
+    /// Name (`media.name` property).
+    pub name: Option<String>,
+    /// Sample spec.
+    pub spec: Spec,
+    /// Channel map.
+    pub map: Map,
+    /// Whether the stream is corked (paused).
+    pub corked: bool,
+    /// Properties, as given by the client.
+    pub properties: BTreeMap<String, String>,
+    /// Number of bytes sent to the client so far.
+    pub sent: u64,
+}
+
+/// A sample upload stream, not yet finished.
+#[derive(Debug, Clone)]
+pub struct UploadStream {
+    /// Index of the owning client.
+    pub client: u32,
+    /// Name of the sample (`event.id` property, or else `media.name`).
+    pub name: String,
+    /// Sample spec.
+    pub spec: Spec,
+    /// Channel map.
+    pub map: Map,
+    /// Length of the sample, in bytes, as announced by the client.
+    pub length: usize,
+    /// Properties, as given by the client.
+    pub properties: BTreeMap<String, String>,
+    /// All audio data received from the client so far.
+    pub received: Vec<u8>,
+}
+
+/// A sample in the sample cache.
+#[derive(Debug, Clone)]
+pub struct Sample {
+    /// Index.
+    pub index: u32,
+    /// Name.
+    pub name: String,
+    /// Sample spec.
+    pub spec: Spec,
+    /// Channel map.
+    pub map: Map,
+    /// Properties, as given by the client.
+    pub properties: BTreeMap<String, String>,
+    /// The audio data.
+    pub data: Vec<u8>,
+}
+
+/// A copy of the complete server state at some point in time.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    /// Sinks.
+    pub sinks: Vec<Device>,
+    /// Sources.
+    pub sources: Vec<Device>,
+    /// Cards.
+    pub cards: Vec<Card>,
+    /// Clients.
+    pub clients: Vec<Client>,
+    /// Playback streams.
+    pub playback_streams: Vec<PlaybackStream>,
+    /// Record streams.
+    pub record_streams: Vec<RecordStream>,
+    /// Sample upload streams.
+    pub upload_streams: Vec<UploadStream>,
+    /// Samples in the sample cache.
+    pub samples: Vec<Sample>,
+    /// Name of the default sink.
+    pub default_sink: Option<String>,
+    /// Name of the default source.
+    pub default_source: Option<String>,
+    /// Virtual clock.
+    pub clock: Duration,
+}
+
+/// Builder for a [`MockServer`].
+#[derive(Debug, Default)]
+pub struct Builder {
+    socket_path: Option<PathBuf>,
+    cookie: Option<Vec<u8>>,
+}
+
+impl Builder {
+    /// Sets the socket path to listen on. By default a path within a new directory in the system
+    /// temporary directory is used.
+    pub fn socket_path<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.socket_path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Requires clients to authenticate with the given cookie. By default any cookie is accepted.
+    pub fn cookie(mut self, cookie: &[u8]) -> Self {
+        self.cookie = Some(cookie.to_vec());
+        self
+    }
+
+    /// Starts the server.
+    pub fn start(self) -> io::Result<MockServer> {
+        let (path, dir) = match self.socket_path {
+            Some(p) => (p, None),
+            None => {
+                static COUNTER: AtomicUsize = AtomicUsize::new(0);
+                let dir = std::env::temp_dir().join(format!("pulse-mock-{}-{}",
+                    std::process::id(), COUNTER.fetch_add(1, Ordering::Relaxed)));
+                std::fs::create_dir_all(&dir)?;
+                (dir.join("native"), Some(dir))
+            },
+        };
+        let listener = UnixListener::bind(&path)?;
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State::new(self.cookie)),
+            changed: Condvar::new(),
+        });
+        let threads = Arc::new(Mutex::new(Vec::new()));
+
+        let accept_shared = Arc::clone(&shared);
+        let accept_threads = Arc::clone(&threads);
+        let acceptor = std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(s) => s,
+                    Err(_) => continue,
+                };
+                let mut state = accept_shared.state.lock().unwrap();
+                if state.shutdown {
+                    break;
+                }
+                let (index, writer) = match state.add_client(&stream) {
+                    Ok(c) => c,
+                    Err(_) => continue,
+                };
+                drop(state);
+                let shared = Arc::clone(&accept_shared);
+                let handle = std::thread::spawn(move || server::serve(shared, stream, index));
+                accept_threads.lock().unwrap().extend(vec![writer, handle]);
+            }
+        });
+
+        Ok(MockServer { shared, path, dir, acceptor: Some(acceptor), threads })
+    }
+}
+
+/// An in-process mock PulseAudio server.
+///
+/// The server shuts down when dropped, disconnecting all clients.
+pub struct MockServer {
+    shared: Arc<Shared>,
+    path: PathBuf,
+    dir: Option<PathBuf>,
+    acceptor: Option<JoinHandle<()>>,
+    threads: Arc<Mutex<Vec<JoinHandle<()>>>>,
+}
+
+impl MockServer {
+    /// Starts a new server with default settings.
+    pub fn start() -> io::Result<Self> {
+        Self::builder().start()
+    }
+
+    /// Creates a builder, for starting a server with custom settings.
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Gets the path of the socket the server listens on.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Gets the server address, for use with `Context::connect()` (or the `PULSE_SERVER`
+    /// environment variable).
+    pub fn address(&self) -> String {
+        format!("unix:{}", self.path.display())
+    }
+
+    fn with_state<R, F: FnOnce(&mut State) -> R>(&self, f: F) -> R {
+        let r = f(&mut self.shared.state.lock().unwrap());
+        self.shared.changed.notify_all();
+        r
+    }
+
+    /// Adds a sink, returning its index.
+    pub fn add_sink(&self, device: Device) -> u32 {
+        self.with_state(|s| s.add_sink(device))
+    }
+
+    /// Adds a source, returning its index.
+    pub fn add_source(&self, device: Device) -> u32 {
+        self.with_state(|s| s.add_source(device))
+    }
+
+    /// Adds a card, returning its index.
+    pub fn add_card(&self, card: Card) -> u32 {
+        self.with_state(|s| s.add_card(card))
+    }
+
+    /// Removes a sink, killing any streams connected to it. Returns `false` if not found.
+    pub fn remove_sink(&self, index: u32) -> bool {
+        self.with_state(|s| s.remove_sink(index))
+    }
+
+    /// Removes a source, killing any streams connected to it. Returns `false` if not found.
+    pub fn remove_source(&self, index: u32) -> bool {
+        self.with_state(|s| s.remove_source(index))
+    }
+
+    /// Removes a card. Returns `false` if not found.
+    pub fn remove_card(&self, index: u32) -> bool {
+        self.with_state(|s| s.remove_card(index))
+    }
+
+    /// Modifies a sink, notifying subscribed clients of the change. Returns `false` if not found.
+    pub fn update_sink<F: FnOnce(&mut Device)>(&self, index: u32, f: F) -> bool {
+        self.with_state(|s| match s.sinks.iter_mut().find(|d| d.index == index) {
+            Some(d) => {
+                f(d);
+                d.index = index;
+                s.event(server::event::SINK, server::event::CHANGE, index);
+                true
+            },
+            None => false,
+        })
+    }
+
+    /// Modifies a source, notifying subscribed clients of the change. Returns `false` if not
+    /// found.
+    pub fn update_source<F: FnOnce(&mut Device)>(&self, index: u32, f: F) -> bool {
+        self.with_state(|s| match s.sources.iter_mut().find(|d| d.index == index) {
+            Some(d) => {
+                f(d);
+                d.index = index;
+                s.event(server::event::SOURCE, server::event::CHANGE, index);
+                true
+            },
+            None => false,
+        })
+    }
+
+    /// Sets the default sink, by name, notifying subscribed clients of the change.
+    pub fn set_default_sink(&self, name: Option<&str>) {
+        self.with_state(|s| {
+            s.default_sink = name.map(String::from);
+            s.event(server::event::SERVER, server::event::CHANGE, pulse::def::INVALID_INDEX);
+        })
+    }
+
+    /// Sets the default source, by name, notifying subscribed clients of the change.
+    pub fn set_default_source(&self, name: Option<&str>) {
+        self.with_state(|s| {
+            s.default_source = name.map(String::from);
+            s.event(server::event::SERVER, server::event::CHANGE, pulse::def::INVALID_INDEX);
+        })
+    }
+
+    /// Gets a copy of the complete server state.
+    pub fn snapshot(&self) -> Snapshot {
+        self.shared.state.lock().unwrap().snapshot()
+    }
+
+    /// Gets a copy of the sink with the given index.
+    pub fn sink(&self, index: u32) -> Option<Device> {
+        self.shared.state.lock().unwrap().sinks.iter().find(|d| d.index == index).cloned()
+    }
+
+    /// Gets a copy of the source with the given index.
+    pub fn source(&self, index: u32) -> Option<Device> {
+        self.shared.state.lock().unwrap().sources.iter().find(|d| d.index == index).cloned()
+    }
+
+    /// Gets a copy of the card with the given index.
+    pub fn card(&self, index: u32) -> Option<Card> {
+        self.shared.state.lock().unwrap().cards.iter().find(|c| c.index == index).cloned()
+    }
+
+    /// Gets all audio data received on the playback stream (sink input) with the given index.
+    pub fn received_audio(&self, index: u32) -> Option<Vec<u8>> {
+        self.shared.state.lock().unwrap().received_audio(index)
+    }
+
+    /// Gets a copy of the sample with the given name.
+    pub fn sample(&self, name: &str) -> Option<Sample> {
+        self.shared.state.lock().unwrap().samples.iter().find(|s| s.name == name).cloned()
+    }
+
+    /// Kills all sample uploads in progress, failing them on the client side. Returns the number
+    /// killed.
+    pub fn kill_uploads(&self) -> usize {
+        self.with_state(|s| s.kill_uploads())
+    }
+
+    /// Queues audio data to be captured from the source with the given index, as the virtual
+    /// clock advances. Silence is captured once the data queued is exhausted.
+    pub fn push_record_audio(&self, source: u32, data: &[u8]) {
+        self.with_state(|s| s.record_data.entry(source).or_default().extend(data.iter().copied()))
+    }
+
+    /// Advances the virtual clock.
+    ///
+    /// Playback streams that are not corked consume the corresponding amount of audio (with more
+    /// then requested from the client), and record streams that are not corked are sent the
+    /// corresponding amount of audio.
+    pub fn advance(&self, dt: Duration) {
+        self.with_state(|s| s.advance(dt))
+    }
+
+    /// Gets the current time of the virtual clock.
+    pub fn clock(&self) -> Duration {
+        self.shared.state.lock().unwrap().clock
+    }
+
+    /// Waits until the given condition holds for the server state, or the timeout expires.
+    ///
+    /// The condition is checked initially, and then whenever the state changes. Returns the state
+    /// for which the condition held, or `None` upon timeout.
+    pub fn wait_until<F>(&self, timeout: Duration, mut condition: F) -> Option<Snapshot>
+        where F: FnMut(&Snapshot) -> bool
+    {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.shared.state.lock().unwrap();
+        loop {
+            let snapshot = state.snapshot();
+            if condition(&snapshot) {
+                return Some(snapshot);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return None;
+            }
+            state = self.shared.changed.wait_timeout(state, deadline - now).unwrap().0;
+        }
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.with_state(|s| {
+            s.shutdown = true;
+            s.close_all();
+        });
+        // Wakes the acceptor, which then sees the shutdown flag
+        let _ = UnixStream::connect(&self.path);
+        if let Some(acceptor) = self.acceptor.take() {
+            let _ = acceptor.join();
+        }
+        for handle in self.threads.lock().unwrap().drain(..) {
+            let _ = handle.join();
+        }
+        let _ = std::fs::remove_file(&self.path);
+        if let Some(dir) = &self.dir {
+            let _ = std::fs::remove_dir(dir);
+        }
+    }
+}
+
+/// Creates a stereo channel map.
+pub(crate) fn stereo_map() -> Map {
+    let mut map = Map::default();
+    map.set_len(2);
+    map.get_mut().copy_from_slice(&[Position::FrontLeft, Position::FrontRight]);
+    map
+}
+
+pub(crate) fn normal_volume(channels: u8) -> ChannelVolumes {
+    let mut v = ChannelVolumes::default();
+    v.set_len(channels);
+    v.get_mut().iter_mut().for_each(|x| *x = Volume::NORMAL);
+    v
+}
+
+/// Sends a request, returning the reply payload following the command and tag.
+#[cfg(test)]
+fn request(stream: &mut UnixStream, tag: u32, body: wire::Writer) -> Vec<u8> {
+    wire::write_control(stream, &body.into_bytes()).unwrap();
+    match pulse::protocol::Packet::read(stream).unwrap() {
+        pulse::protocol::Packet::Control(data) => {
+            let mut r = wire::Reader::new(&data);
+            assert_eq!(r.get_u32().unwrap(), 2, "expected reply");
+            assert_eq!(r.get_u32().unwrap(), tag);
+            data[10..].to_vec()
+        },
+        _ => panic!("expected control packet"),
+    }
+}
+
+#[test]
+fn raw_protocol() {
+    let server = MockServer::builder().cookie(&[7; 256]).start().unwrap();
+    let sink = server.add_sink(Device::new("speakers").with_port(Port::new("out", "Out", 1)));
+    let mut stream = UnixStream::connect(server.path()).unwrap();
+
+    let mut w = wire::Writer::new();
+    w.put_u32(8).put_u32(0).put_u32(32).put_arbitrary(&[7; 256]);
+    let data = request(&mut stream, 0, w);
+    assert_eq!(wire::Reader::new(&data).get_u32().unwrap(), PROTOCOL_VERSION);
+
+    let mut props = BTreeMap::new();
+    props.insert("application.name".to_string(), "raw".to_string());
+    let mut w = wire::Writer::new();
+    w.put_u32(9).put_u32(1).put_properties(&props);
+    let data = request(&mut stream, 1, w);
+    let client = wire::Reader::new(&data).get_u32().unwrap();
+    assert_eq!(server.snapshot().clients[0].properties, props);
+
+    let mut w = wire::Writer::new();
+    w.put_u32(22).put_u32(2);
+    let data = request(&mut stream, 2, w);
+    let mut r = wire::Reader::new(&data);
+    assert_eq!(r.get_u32().unwrap(), sink);
+    assert_eq!(r.get_string().unwrap().as_deref(), Some("speakers"));
+
+    let mut volume = ChannelVolumes::default();
+    volume.set_len(1);
+    volume.get_mut()[0] = Volume(0x8000);
+    let mut w = wire::Writer::new();
+    w.put_u32(36).put_u32(3).put_u32(pulse::def::INVALID_INDEX).put_string(Some("speakers"))
+        .put_cvolume(&volume);
+    request(&mut stream, 3, w);
+    assert_eq!(server.sink(sink).unwrap().volume.get(), &[Volume(0x8000); 2]);
+
+    let spec = Spec { format: Format::S16le, channels: 2, rate: 44100 };
+    let mut w = wire::Writer::new();
+    w.put_u32(3).put_u32(4).put_sample_spec(&spec).put_channel_map(&stereo_map())
+        .put_u32(sink).put_string(None).put_u32(u32::MAX).put_bool(false).put_u32(u32::MAX)
+        .put_u32(u32::MAX).put_u32(u32::MAX).put_u32(0).put_cvolume(&normal_volume(2));
+    for _ in 0..7 {
+        w.put_bool(false);
+    }
+    w.put_bool(false).put_bool(false).put_properties(&BTreeMap::new()).put_bool(false);
+    let data = request(&mut stream, 4, w);
+    let mut r = wire::Reader::new(&data);
+    let channel = r.get_u32().unwrap();
+    let index = r.get_u32().unwrap();
+
+    wire::write_memblock(&mut stream, channel, &[1; 4410]).unwrap();
+    let snapshot = server.wait_until(Duration::from_secs(5),
+        |s| s.playback_streams.iter().any(|p| p.received.len() == 4410)).unwrap();
+    assert_eq!(snapshot.playback_streams[0].client, client);
+
+    server.advance(Duration::from_millis(10));
+    match pulse::protocol::Packet::read(&mut stream).unwrap() {
+        pulse::protocol::Packet::Control(data) => {
+            let mut r = wire::Reader::new(&data);
+            assert_eq!(r.get_u32().unwrap(), 61);
+            assert_eq!(r.get_u32().unwrap(), u32::MAX);
+            assert_eq!(r.get_u32().unwrap(), channel);
+            assert_eq!(r.get_u32().unwrap(), 1764);
+        },
+        _ => panic!("expected control packet"),
+    }
+    assert_eq!(server.received_audio(index).unwrap(), vec![1; 4410]);
+    assert_eq!(server.clock(), Duration::from_millis(10));
+}
+
+#[test]
+fn unread_client() {
+    let server = MockServer::start().unwrap();
+    let mut stream = UnixStream::connect(server.path()).unwrap();
+    let mut w = wire::Writer::new();
+    w.put_u32(8).put_u32(0).put_u32(32).put_arbitrary(&[0; 256]);
+    request(&mut stream, 0, w);
+    let mut w = wire::Writer::new();
+    w.put_u32(35).put_u32(1).put_u32(0x02ff);
+    request(&mut stream, 1, w);
+
+    // Far more events than the socket can buffer, none of which are read
+    for _ in 0..20000 {
+        let sink = server.add_sink(Device::new("speakers"));
+        server.remove_sink(sink);
+    }
+    assert!(server.snapshot().sinks.is_empty());
+}
+
+#[test]
+fn raw_upload() {
+    let server = MockServer::start().unwrap();
+    let mut stream = UnixStream::connect(server.path()).unwrap();
+    let mut w = wire::Writer::new();
+    w.put_u32(8).put_u32(0).put_u32(32).put_arbitrary(&[0; 256]);
+    request(&mut stream, 0, w);
+
+    let spec = Spec { format: Format::S16le, channels: 2, rate: 44100 };
+    let mut props = BTreeMap::new();
+    props.insert("media.name".to_string(), "bell".to_string());
+    let mut w = wire::Writer::new();
+    w.put_u32(15).put_u32(1).put_sample_spec(&spec).put_channel_map(&stereo_map()).put_u32(8)
+        .put_properties(&props);
+    let data = request(&mut stream, 1, w);
+    let mut r = wire::Reader::new(&data);
+    let channel = r.get_u32().unwrap();
+    assert_eq!(r.get_u32().unwrap(), 8);
+
+    // Data beyond the length announced is dropped
+    wire::write_memblock(&mut stream, channel, &[1; 6]).unwrap();
+    wire::write_memblock(&mut stream, channel, &[2; 6]).unwrap();
+    let snapshot = server.wait_until(Duration::from_secs(5),
+        |s| s.upload_streams.iter().any(|u| u.received.len() == 8)).unwrap();
+    assert_eq!(snapshot.upload_streams[0].name, "bell");
+
+    let mut w = wire::Writer::new();
+    w.put_u32(17).put_u32(2).put_u32(channel);
+    request(&mut stream, 2, w);
+    let sample = server.sample("bell").unwrap();
+    assert_eq!(sample.data, [1, 1, 1, 1, 1, 1, 2, 2]);
+    assert!(server.snapshot().upload_streams.is_empty());
+
+    let mut w = wire::Writer::new();
+    w.put_u32(34).put_u32(3);
+    let data = request(&mut stream, 3, w);
+    let mut r = wire::Reader::new(&data);
+    assert_eq!(r.get_u32().unwrap(), sample.index);
+    assert_eq!(r.get_string().unwrap().as_deref(), Some("bell"));
+
+    let mut w = wire::Writer::new();
+    w.put_u32(19).put_u32(4).put_string(Some("bell"));
+    request(&mut stream, 4, w);
+    assert!(server.snapshot().samples.is_empty());
+}
@@ -0,0 +1,1446 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! Server state and native protocol command handling.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::io::{self, Write};
+use std::os::unix::net::UnixStream;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use pulse::def::INVALID_INDEX;
+use pulse::sample::{Format, Spec};
+use pulse::volume::{ChannelVolumes, Volume};
+use pulse::protocol::Packet;
+use pulse::time::{MicroSeconds, Timeval};
+use crate::wire::{self, GetProperties, PutProperties, Reader, Writer};
+use crate::{
+    Card, Client, Device, PlaybackStream, RecordStream, Sample, Snapshot, UploadStream,
+    PROTOCOL_VERSION,
+};
+
+/// Native protocol command codes.
+#[allow(dead_code)]
+mod command {
+    pub const ERROR: u32 = 0;
+    pub const REPLY: u32 = 2;
+    pub const CREATE_PLAYBACK_STREAM: u32 = 3;
+    pub const DELETE_PLAYBACK_STREAM: u32 = 4;
+    pub const CREATE_RECORD_STREAM: u32 = 5;
+    pub const DELETE_RECORD_STREAM: u32 = 6;
+    pub const AUTH: u32 = 8;
+    pub const SET_CLIENT_NAME: u32 = 9;
+    pub const DRAIN_PLAYBACK_STREAM: u32 = 12;
+    pub const GET_PLAYBACK_LATENCY: u32 = 14;
+    pub const CREATE_UPLOAD_STREAM: u32 = 15;
+    pub const DELETE_UPLOAD_STREAM: u32 = 16;
+    pub const FINISH_UPLOAD_STREAM: u32 = 17;
+    pub const REMOVE_SAMPLE: u32 = 19;
+    pub const GET_SERVER_INFO: u32 = 20;
+    pub const GET_SINK_INFO: u32 = 21;
+    pub const GET_SINK_INFO_LIST: u32 = 22;
+    pub const GET_SOURCE_INFO: u32 = 23;
+    pub const GET_SOURCE_INFO_LIST: u32 = 24;
+    pub const GET_CLIENT_INFO: u32 = 27;
+    pub const GET_CLIENT_INFO_LIST: u32 = 28;
+    pub const GET_SINK_INPUT_INFO: u32 = 29;
+    pub const GET_SINK_INPUT_INFO_LIST: u32 = 30;
+    pub const GET_SOURCE_OUTPUT_INFO: u32 = 31;
+    pub const GET_SOURCE_OUTPUT_INFO_LIST: u32 = 32;
+    pub const GET_SAMPLE_INFO: u32 = 33;
+    pub const GET_SAMPLE_INFO_LIST: u32 = 34;
+    pub const SUBSCRIBE: u32 = 35;
+    pub const SET_SINK_VOLUME: u32 = 36;
+    pub const SET_SINK_INPUT_VOLUME: u32 = 37;
+    pub const SET_SOURCE_VOLUME: u32 = 38;
+    pub const SET_SINK_MUTE: u32 = 39;
+    pub const SET_SOURCE_MUTE: u32 = 40;
+    pub const CORK_PLAYBACK_STREAM: u32 = 41;
+    pub const FLUSH_PLAYBACK_STREAM: u32 = 42;
+    pub const TRIGGER_PLAYBACK_STREAM: u32 = 43;
+    pub const SET_DEFAULT_SINK: u32 = 44;
+    pub const SET_DEFAULT_SOURCE: u32 = 45;
+    pub const SET_PLAYBACK_STREAM_NAME: u32 = 46;
+    pub const SET_RECORD_STREAM_NAME: u32 = 47;
+    pub const GET_RECORD_LATENCY: u32 = 57;
+    pub const CORK_RECORD_STREAM: u32 = 58;
+    pub const FLUSH_RECORD_STREAM: u32 = 59;
+    pub const PREBUF_PLAYBACK_STREAM: u32 = 60;
+    pub const REQUEST: u32 = 61;
+    pub const PLAYBACK_STREAM_KILLED: u32 = 64;
+    pub const RECORD_STREAM_KILLED: u32 = 65;
+    pub const SUBSCRIBE_EVENT: u32 = 66;
+    pub const SET_SINK_INPUT_MUTE: u32 = 69;
+    pub const EXTENSION: u32 = 87;
+    pub const GET_CARD_INFO: u32 = 88;
+    pub const GET_CARD_INFO_LIST: u32 = 89;
+    pub const SET_CARD_PROFILE: u32 = 90;
+    pub const SET_SINK_PORT: u32 = 96;
+    pub const SET_SOURCE_PORT: u32 = 97;
+}
+
+/// Protocol error codes (as `pulse::error::Code`).
+mod code {
+    pub const ACCESS: u32 = 1;
+    pub const INVALID: u32 = 3;
+    pub const NO_ENTITY: u32 = 5;
+    pub const NOT_SUPPORTED: u32 = 19;
+    pub const NO_EXTENSION: u32 = 21;
+    pub const NOT_IMPLEMENTED: u32 = 23;
+}
+
+/// Subscription facilities and event types (as `pulse::context::subscribe`).
+pub(crate) mod event {
+    pub const SINK: u32 = 0;
+    pub const SOURCE: u32 = 1;
+    pub const SINK_INPUT: u32 = 2;
+    pub const SOURCE_OUTPUT: u32 = 3;
+    pub const SAMPLE_CACHE: u32 = 4;
+    pub const CLIENT: u32 = 5;
+    pub const SERVER: u32 = 7;
+    pub const CARD: u32 = 9;
+
+    pub const NEW: u32 = 0x00;
+    pub const CHANGE: u32 = 0x10;
+    pub const REMOVE: u32 = 0x20;
+}
+
+const PROTOCOL_VERSION_MASK: u32 = 0x0000_ffff;
+const DEFAULT_MAXLENGTH: u32 = 4 * 1024 * 1024;
+const DEFAULT_TLENGTH: Duration = Duration::from_millis(250);
+const DEFAULT_FRAGSIZE: Duration = Duration::from_millis(25);
+const MEMBLOCK_MAX: usize = 64 * 1024;
+/// Largest sample accepted for upload, as with PulseAudio.
+const SAMPLE_MAX: usize = 16 * 1024 * 1024;
+/// Device flags reported: `LATENCY` and `DECIBEL_VOLUME`.
+const DEVICE_FLAGS: u32 = 0x0002 | 0x0020;
+const DEVICE_STATE_RUNNING: u32 = 0;
+const DEVICE_STATE_IDLE: u32 = 1;
+
+/// State shared between the server threads and the API.
+pub(crate) struct Shared {
+    pub state: Mutex<State>,
+    pub changed: Condvar,
+}
+
+/// Outcome of handling a command.
+enum Response {
+    /// Send the given reply (including header).
+    Reply(Writer),
+    /// Send an error reply with the given code.
+    Error(u32),
+    /// The reply will be sent later.
+    Deferred,
+}
+
+/// A connected client.
+pub(crate) struct Conn {
+    index: u32,
+    /// The socket, kept for shutting down the connection.
+    socket: UnixStream,
+    /// Packets queued for the writer thread of the connection.
+    ///
+    /// Packets are never written with the state locked, since a client not reading would then
+    /// block the server and the API alike.
+    outgoing: Sender<Vec<u8>>,
+    version: u32,
+    authorized: bool,
+    subscription: u32,
+    properties: BTreeMap<String, String>,
+    next_channel: u32,
+}
+
+struct Playback {
+    info: PlaybackStream,
+    channel: u32,
+    maxlength: u32,
+    tlength: u32,
+    prebuf: u32,
+    minreq: u32,
+    queued: usize,
+    write_index: i64,
+    read_index: i64,
+    drain_tags: Vec<u32>,
+}
+
+struct Record {
+    info: RecordStream,
+    channel: u32,
+}
+
+struct Upload {
+    info: UploadStream,
+    channel: u32,
+}
+
+#[derive(Default)]
+struct Counters {
+    sink: u32,
+    source: u32,
+    card: u32,
+    client: u32,
+    sink_input: u32,
+    source_output: u32,
+    sample: u32,
+}
+
+fn next(counter: &mut u32) -> u32 {
+    let i = *counter;
+    *counter += 1;
+    i
+}
+
+/// Complete server state.
+#[derive(Default)]
+pub(crate) struct State {
+    pub sinks: Vec<Device>,
+    pub sources: Vec<Device>,
+    pub cards: Vec<Card>,
+    pub default_sink: Option<String>,
+    pub default_source: Option<String>,
+    pub cookie: Option<Vec<u8>>,
+    pub clock: Duration,
+    pub shutdown: bool,
+    pub record_data: HashMap<u32, VecDeque<u8>>,
+    pub samples: Vec<Sample>,
+    clients: Vec<Conn>,
+    playback: Vec<Playback>,
+    record: Vec<Record>,
+    uploads: Vec<Upload>,
+    counters: Counters,
+}
+
+impl State {
+    /// Creates an empty state, requiring the given cookie (if any) for authentication.
+    pub fn new(cookie: Option<Vec<u8>>) -> Self {
+        Self { cookie, ..Default::default() }
+    }
+}
+
+impl Conn {
+    /// Queues a control packet.
+    fn send_control(&self, payload: &[u8]) {
+        let mut frame = Vec::new();
+        let _ = wire::write_control(&mut frame, payload);
+        let _ = self.outgoing.send(frame);
+    }
+
+    /// Queues a memory block packet.
+    fn send_memblock(&self, channel: u32, data: &[u8]) {
+        let mut frame = Vec::new();
+        let _ = wire::write_memblock(&mut frame, channel, data);
+        let _ = self.outgoing.send(frame);
+    }
+}
+
+/// Writes the packets queued for a connection, until it is removed or closed.
+fn write_queued(mut socket: UnixStream, queue: Receiver<Vec<u8>>) {
+    for frame in queue {
+        if socket.write_all(&frame).is_err() {
+            break;
+        }
+    }
+}
+
+/// Serves a connection until it is closed.
+pub(crate) fn serve(shared: Arc<Shared>, mut stream: UnixStream, client: u32) {
+    while let Ok(packet) = Packet::read(&mut stream) {
+        let mut state = shared.state.lock().unwrap();
+        if state.shutdown {
+            break;
+        }
+        let keep = match packet {
+            Packet::Control(data) => state.handle_control(client, &data),
+            Packet::Memblock { channel, data, .. } => {
+                state.handle_memblock(client, channel, data);
+                true
+            },
+            // Shared memory is never offered
+            Packet::Shm(..) => false,
+        };
+        drop(state);
+        shared.changed.notify_all();
+        if !keep {
+            break;
+        }
+    }
+    let _ = stream.shutdown(std::net::Shutdown::Both);
+    shared.state.lock().unwrap().remove_client(client);
+    shared.changed.notify_all();
+}
+
+/// Gets the size of a frame, or zero if the spec is invalid.
+pub(crate) fn frame_size(spec: &Spec) -> usize {
+    let sample = match spec.format {
+        Format::U8 | Format::ALaw | Format::ULaw => 1,
+        Format::S16le | Format::S16be => 2,
+        Format::S24le | Format::S24be => 3,
+        Format::F32le | Format::F32be | Format::S32le | Format::S32be | Format::S24_32le
+            | Format::S24_32be => 4,
+        _ => 0,
+    };
+    sample * spec.channels as usize
+}
+
+/// Gets the number of bytes (whole frames) played in the given time.
+fn duration_to_bytes(d: Duration, spec: &Spec) -> usize {
+    let frames = d.as_micros() * spec.rate as u128 / 1_000_000;
+    frames as usize * frame_size(spec)
+}
+
+fn bytes_to_usec(bytes: usize, spec: &Spec) -> MicroSeconds {
+    match frame_size(spec) as u64 * spec.rate as u64 {
+        0 => MicroSeconds(0),
+        bps => MicroSeconds(bytes as u64 * 1_000_000 / bps),
+    }
+}
+
+/// Uses the requested buffer metric, or the default, rounded to whole frames.
+fn buffer_metric(requested: u32, default: usize, frame: usize) -> u32 {
+    let value = match requested {
+        u32::MAX | 0 => default,
+        v => v as usize,
+    };
+    (std::cmp::max(value / frame, 1) * frame) as u32
+}
+
+fn now_timeval() -> Timeval {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    Timeval::new(now.as_secs() as _, now.subsec_micros() as _)
+}
+
+fn reply(tag: u32) -> Writer {
+    let mut t = Writer::new();
+    t.put_u32(command::REPLY).put_u32(tag);
+    t
+}
+
+fn find_device(list: &[Device], index: u32, name: Option<&str>, default: Option<&str>,
+    default_alias: &str) -> Option<usize>
+{
+    if index != INVALID_INDEX {
+        return list.iter().position(|d| d.index == index);
+    }
+    match name {
+        None => None,
+        Some(n) if n == default_alias => match default {
+            Some(d) => list.iter().position(|x| x.name == d),
+            None if !list.is_empty() => Some(0),
+            None => None,
+        },
+        Some(n) => list.iter().position(|d| d.name == n),
+    }
+}
+
+/// Applies a new volume to an existing one, allowing a single channel volume to apply to all.
+fn apply_volume(target: &mut ChannelVolumes, new: &ChannelVolumes) -> bool {
+    if new.len() == target.len() {
+        *target = *new;
+        true
+    } else if new.len() == 1 {
+        let v = new.get()[0];
+        target.get_mut().iter_mut().for_each(|x| *x = v);
+        true
+    } else {
+        false
+    }
+}
+
+impl State {
+    /// Registers a new connection, returning its index along with the thread writing to it.
+    pub fn add_client(&mut self, socket: &UnixStream) -> io::Result<(u32, JoinHandle<()>)> {
+        let writer = socket.try_clone()?;
+        let socket = socket.try_clone()?;
+        let (outgoing, queue) = mpsc::channel();
+        let thread = std::thread::spawn(move || write_queued(writer, queue));
+        let index = next(&mut self.counters.client);
+        self.clients.push(Conn {
+            index,
+            socket,
+            outgoing,
+            version: 0,
+            authorized: false,
+            subscription: 0,
+            properties: BTreeMap::new(),
+            next_channel: 0,
+        });
+        Ok((index, thread))
+    }
+
+    /// Shuts down all connections.
+    pub fn close_all(&mut self) {
+        for c in &self.clients {
+            let _ = c.socket.shutdown(std::net::Shutdown::Both);
+        }
+    }
+
+    /// Removes a connection, along with its streams.
+    pub fn remove_client(&mut self, client: u32) {
+        let pos = match self.clients.iter().position(|c| c.index == client) {
+            Some(p) => p,
+            None => return,
+        };
+        let was_authorized = self.clients[pos].authorized;
+        self.clients.remove(pos);
+        let (gone, kept): (Vec<_>, Vec<_>) =
+            self.playback.drain(..).partition(|p| p.info.client == client);
+        self.playback = kept;
+        for p in gone {
+            self.event(event::SINK_INPUT, event::REMOVE, p.info.index);
+        }
+        let (gone, kept): (Vec<_>, Vec<_>) =
+            self.record.drain(..).partition(|r| r.info.client == client);
+        self.record = kept;
+        for r in gone {
+            self.event(event::SOURCE_OUTPUT, event::REMOVE, r.info.index);
+        }
+        self.uploads.retain(|u| u.info.client != client);
+        if was_authorized {
+            self.event(event::CLIENT, event::REMOVE, client);
+        }
+    }
+
+    /// Creates a read-only copy of the state.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            sinks: self.sinks.clone(),
+            sources: self.sources.clone(),
+            cards: self.cards.clone(),
+            clients: self.clients.iter().filter(|c| c.authorized).map(|c| Client {
+                index: c.index,
+                protocol_version: c.version,
+                properties: c.properties.clone(),
+            }).collect(),
+            playback_streams: self.playback.iter().map(|p| p.info.clone()).collect(),
+            record_streams: self.record.iter().map(|r| r.info.clone()).collect(),
+            upload_streams: self.uploads.iter().map(|u| u.info.clone()).collect(),
+            samples: self.samples.clone(),
+            default_sink: self.default_sink_name().map(String::from),
+            default_source: self.default_source_name().map(String::from),
+            clock: self.clock,
+        }
+    }
+
+    fn default_sink_name(&self) -> Option<&str> {
+        self.default_sink.as_deref().or_else(|| self.sinks.first().map(|d| d.name.as_str()))
+    }
+
+    fn default_source_name(&self) -> Option<&str> {
+        self.default_source.as_deref().or_else(|| self.sources.first().map(|d| d.name.as_str()))
+    }
+
+    pub fn add_sink(&mut self, mut device: Device) -> u32 {
+        device.index = next(&mut self.counters.sink);
+        let index = device.index;
+        self.sinks.push(device);
+        self.event(event::SINK, event::NEW, index);
+        index
+    }
+
+    pub fn add_source(&mut self, mut device: Device) -> u32 {
+        device.index = next(&mut self.counters.source);
+        let index = device.index;
+        self.sources.push(device);
+        self.event(event::SOURCE, event::NEW, index);
+        index
+    }
+
+    pub fn add_card(&mut self, mut card: Card) -> u32 {
+        card.index = next(&mut self.counters.card);
+        let index = card.index;
+        self.cards.push(card);
+        self.event(event::CARD, event::NEW, index);
+        index
+    }
+
+    /// Removes a sink, killing any streams connected to it.
+    pub fn remove_sink(&mut self, index: u32) -> bool {
+        let pos = match self.sinks.iter().position(|d| d.index == index) {
+            Some(p) => p,
+            None => return false,
+        };
+        let device = self.sinks.remove(pos);
+        let (gone, kept): (Vec<_>, Vec<_>) =
+            self.playback.drain(..).partition(|p| p.info.sink == index);
+        self.playback = kept;
+        for p in gone {
+            self.notify(p.info.client, command::PLAYBACK_STREAM_KILLED, p.channel, None);
+            self.event(event::SINK_INPUT, event::REMOVE, p.info.index);
+        }
+        self.event(event::SINK, event::REMOVE, index);
+        if self.default_sink.as_deref() == Some(&device.name) {
+            self.default_sink = None;
+            self.event(event::SERVER, event::CHANGE, INVALID_INDEX);
+        }
+        true
+    }
+
+    /// Removes a source, killing any streams connected to it.
+    pub fn remove_source(&mut self, index: u32) -> bool {
+        let pos = match self.sources.iter().position(|d| d.index == index) {
+            Some(p) => p,
+            None => return false,
+        };
+        let device = self.sources.remove(pos);
+        let (gone, kept): (Vec<_>, Vec<_>) =
+            self.record.drain(..).partition(|r| r.info.source == index);
+        self.record = kept;
+        for r in gone {
+            self.notify(r.info.client, command::RECORD_STREAM_KILLED, r.channel, None);
+            self.event(event::SOURCE_OUTPUT, event::REMOVE, r.info.index);
+        }
+        self.record_data.remove(&index);
+        self.event(event::SOURCE, event::REMOVE, index);
+        if self.default_source.as_deref() == Some(&device.name) {
+            self.default_source = None;
+            self.event(event::SERVER, event::CHANGE, INVALID_INDEX);
+        }
+        true
+    }
+
+    pub fn remove_card(&mut self, index: u32) -> bool {
+        let before = self.cards.len();
+        self.cards.retain(|c| c.index != index);
+        let removed = self.cards.len() != before;
+        if removed {
+            self.event(event::CARD, event::REMOVE, index);
+        }
+        removed
+    }
+
+    /// Kills all sample uploads in progress, returning the number killed.
+    pub fn kill_uploads(&mut self) -> usize {
+        let gone: Vec<_> = self.uploads.drain(..).collect();
+        for u in &gone {
+            // Note, the client library keeps upload streams along with playback streams
+            self.notify(u.info.client, command::PLAYBACK_STREAM_KILLED, u.channel, None);
+        }
+        gone.len()
+    }
+
+    /// Adds a sample to the cache, replacing (and taking the index of) any of the same name.
+    fn add_sample(&mut self, mut sample: Sample) {
+        match self.samples.iter_mut().find(|s| s.name == sample.name) {
+            Some(existing) => {
+                sample.index = existing.index;
+                *existing = sample;
+                let index = existing.index;
+                self.event(event::SAMPLE_CACHE, event::CHANGE, index);
+            },
+            None => {
+                sample.index = next(&mut self.counters.sample);
+                let index = sample.index;
+                self.samples.push(sample);
+                self.event(event::SAMPLE_CACHE, event::NEW, index);
+            },
+        }
+    }
+
+    pub fn received_audio(&self, index: u32) -> Option<Vec<u8>> {
+        self.playback.iter().find(|p| p.info.index == index).map(|p| p.info.received.clone())
+    }
+
+    /// Sends a subscription event to all interested clients.
+    pub fn event(&mut self, facility: u32, op: u32, index: u32) {
+        let mut t = Writer::new();
+        t.put_u32(command::SUBSCRIBE_EVENT).put_u32(u32::MAX).put_u32(facility | op)
+            .put_u32(index);
+        let payload = t.into_bytes();
+        for c in self.clients.iter().filter(|c| c.subscription & (1 << facility) != 0) {
+            c.send_control(&payload);
+        }
+    }
+
+    /// Sends an unsolicited stream notification to a client.
+    fn notify(&mut self, client: u32, cmd: u32, channel: u32, value: Option<u32>) {
+        let mut t = Writer::new();
+        t.put_u32(cmd).put_u32(u32::MAX).put_u32(channel);
+        if let Some(v) = value {
+            t.put_u32(v);
+        }
+        self.send(client, &t.into_bytes());
+    }
+
+    fn send(&mut self, client: u32, payload: &[u8]) {
+        if let Some(c) = self.clients.iter().find(|c| c.index == client) {
+            c.send_control(payload);
+        }
+    }
+
+    /// Advances the virtual clock, consuming playback data and producing record data.
+    pub fn advance(&mut self, dt: Duration) {
+        self.clock += dt;
+        let mut requests = Vec::new();
+        let mut drained = Vec::new();
+        for p in self.playback.iter_mut().filter(|p| !p.info.corked) {
+            let n = std::cmp::min(duration_to_bytes(dt, &p.info.spec), p.queued);
+            p.queued -= n;
+            p.read_index += n as i64;
+            p.info.played += n as u64;
+            if n > 0 {
+                requests.push((p.info.client, p.channel, n as u32));
+            }
+            if p.queued == 0 {
+                drained.extend(p.drain_tags.drain(..).map(|tag| (p.info.client, tag)));
+            }
+        }
+        for (client, channel, n) in requests {
+            self.notify(client, command::REQUEST, channel, Some(n));
+        }
+        for (client, tag) in drained {
+            self.send(client, &reply(tag).into_bytes());
+        }
+
+        let mut blocks = Vec::new();
+        for r in self.record.iter_mut().filter(|r| !r.info.corked) {
+            let n = duration_to_bytes(dt, &r.info.spec);
+            let queue = self.record_data.entry(r.info.source).or_default();
+            let mut data: Vec<u8> = queue.drain(..std::cmp::min(n, queue.len())).collect();
+            data.resize(n, 0);
+            r.info.sent += n as u64;
+            blocks.push((r.info.client, r.channel, data));
+        }
+        for (client, channel, data) in blocks {
+            if let Some(c) = self.clients.iter().find(|c| c.index == client) {
+                for chunk in data.chunks(MEMBLOCK_MAX) {
+                    c.send_memblock(channel, chunk);
+                }
+            }
+        }
+    }
+
+    fn handle_memblock(&mut self, client: u32, channel: u32, data: Vec<u8>) {
+        if let Some(p) = self.playback.iter_mut()
+            .find(|p| p.info.client == client && p.channel == channel)
+        {
+            p.queued += data.len();
+            p.write_index += data.len() as i64;
+            p.info.received.extend_from_slice(&data);
+        } else if let Some(u) = self.uploads.iter_mut()
+            .find(|u| u.info.client == client && u.channel == channel)
+        {
+            // Anything beyond the length announced is dropped
+            let n = std::cmp::min(data.len(), u.info.length - u.info.received.len());
+            u.info.received.extend_from_slice(&data[..n]);
+        }
+    }
+
+    /// Handles a control packet, returning `false` if the connection should be closed.
+    fn handle_control(&mut self, client: u32, data: &[u8]) -> bool {
+        let mut t = Reader::new(data);
+        let (cmd, tag) = match (t.get_u32(), t.get_u32()) {
+            (Ok(cmd), Ok(tag)) => (cmd, tag),
+            _ => return false,
+        };
+        let authorized = self.clients.iter().any(|c| c.index == client && c.authorized);
+        if !authorized && cmd != command::AUTH {
+            return false;
+        }
+        let response = match self.dispatch(client, cmd, tag, &mut t) {
+            Ok(r) => r,
+            Err(_) => return false,
+        };
+        let payload = match response {
+            Response::Reply(t) => t.into_bytes(),
+            Response::Error(code) => {
+                let mut t = Writer::new();
+                t.put_u32(command::ERROR).put_u32(tag).put_u32(code);
+                t.into_bytes()
+            },
+            Response::Deferred => return true,
+        };
+        self.send(client, &payload);
+        true
+    }
+
+    fn version(&self, client: u32) -> u32 {
+        self.clients.iter().find(|c| c.index == client).map_or(0, |c| c.version)
+    }
+
+    fn dispatch(&mut self, client: u32, cmd: u32, tag: u32, t: &mut Reader)
+        -> io::Result<Response>
+    {
+        use command::*;
+
+        let version = self.version(client);
+        let response = match cmd {
+            AUTH => {
+                let requested = t.get_u32()? & PROTOCOL_VERSION_MASK;
+                let cookie = t.get_arbitrary()?;
+                if let Some(expected) = &self.cookie {
+                    if expected.as_slice() != cookie {
+                        return Ok(Response::Error(code::ACCESS));
+                    }
+                }
+                let conn = self.clients.iter_mut().find(|c| c.index == client).unwrap();
+                conn.version = std::cmp::min(requested, PROTOCOL_VERSION);
+                conn.authorized = true;
+                // Note, no shared memory support flags are set
+                let mut r = reply(tag);
+                r.put_u32(PROTOCOL_VERSION);
+                self.event(event::CLIENT, event::NEW, client);
+                Response::Reply(r)
+            },
+            SET_CLIENT_NAME => {
+                let properties = match version >= 13 {
+                    true => t.get_properties()?,
+                    false => {
+                        let mut p = BTreeMap::new();
+                        if let Some(name) = t.get_string()? {
+                            p.insert("application.name".to_string(), name);
+                        }
+                        p
+                    },
+                };
+                if let Some(c) = self.clients.iter_mut().find(|c| c.index == client) {
+                    c.properties = properties;
+                }
+                self.event(event::CLIENT, event::CHANGE, client);
+                let mut r = reply(tag);
+                if version >= 13 {
+                    r.put_u32(client);
+                }
+                Response::Reply(r)
+            },
+            SUBSCRIBE => {
+                let mask = t.get_u32()?;
+                if let Some(c) = self.clients.iter_mut().find(|c| c.index == client) {
+                    c.subscription = mask;
+                }
+                Response::Reply(reply(tag))
+            },
+            GET_SERVER_INFO => {
+                let spec = self.sinks.first().map(|d| d.spec)
+                    .unwrap_or(Spec { format: Format::S16le, channels: 2, rate: 44100 });
+                let map = self.sinks.first().map(|d| d.map).unwrap_or_else(crate::stereo_map);
+                let user = std::env::var("USER").unwrap_or_else(|_| "mock".to_string());
+                let mut r = reply(tag);
+                r.put_string(Some("pulseaudio"))
+                    .put_string(Some(concat!("mock-", env!("CARGO_PKG_VERSION"))))
+                    .put_string(Some(&user))
+                    .put_string(Some("localhost"))
+                    .put_sample_spec(&spec)
+                    .put_string(self.default_sink_name())
+                    .put_string(self.default_source_name())
+                    .put_u32(0x4d4f_434b);
+                if version >= 15 {
+                    r.put_channel_map(&map);
+                }
+                Response::Reply(r)
+            },
+            GET_SINK_INFO | GET_SOURCE_INFO => {
+                let index = t.get_u32()?;
+                let name = t.get_string()?;
+                let sink = cmd == GET_SINK_INFO;
+                let (list, default, alias) = match sink {
+                    true => (&self.sinks, self.default_sink_name(), "@DEFAULT_SINK@"),
+                    false => (&self.sources, self.default_source_name(), "@DEFAULT_SOURCE@"),
+                };
+                match find_device(list, index, name.as_deref(), default, alias) {
+                    Some(i) => {
+                        let mut r = reply(tag);
+                        self.fill_device(&mut r, &list[i], sink, version);
+                        Response::Reply(r)
+                    },
+                    None => Response::Error(code::NO_ENTITY),
+                }
+            },
+            GET_SINK_INFO_LIST | GET_SOURCE_INFO_LIST => {
+                let sink = cmd == GET_SINK_INFO_LIST;
+                let mut r = reply(tag);
+                let list = if sink { &self.sinks } else { &self.sources };
+                for d in list {
+                    self.fill_device(&mut r, d, sink, version);
+                }
+                Response::Reply(r)
+            },
+            GET_CARD_INFO | GET_CARD_INFO_LIST if version < 15 => Response::Error(code::NOT_SUPPORTED),
+            GET_CARD_INFO => {
+                let index = t.get_u32()?;
+                let name = t.get_string()?;
+                let found = self.cards.iter().find(|c| match index {
+                    INVALID_INDEX => Some(&c.name) == name.as_ref(),
+                    i => c.index == i,
+                });
+                match found {
+                    Some(c) => {
+                        let mut r = reply(tag);
+                        fill_card(&mut r, c);
+                        Response::Reply(r)
+                    },
+                    None => Response::Error(code::NO_ENTITY),
+                }
+            },
+            GET_CARD_INFO_LIST => {
+                let mut r = reply(tag);
+                for c in &self.cards {
+                    fill_card(&mut r, c);
+                }
+                Response::Reply(r)
+            },
+            GET_CLIENT_INFO | GET_CLIENT_INFO_LIST => {
+                let index = match cmd {
+                    GET_CLIENT_INFO => Some(t.get_u32()?),
+                    _ => None,
+                };
+                let mut r = reply(tag);
+                let mut found = false;
+                for c in self.clients.iter().filter(|c| c.authorized) {
+                    if index.map_or(true, |i| i == c.index) {
+                        found = true;
+                        r.put_u32(c.index)
+                            .put_string(c.properties.get("application.name").map(|s| s.as_str()))
+                            .put_u32(INVALID_INDEX)
+                            .put_string(Some("mock"));
+                        if version >= 13 {
+                            r.put_properties(&c.properties);
+                        }
+                    }
+                }
+                match index.is_some() && !found {
+                    true => Response::Error(code::NO_ENTITY),
+                    false => Response::Reply(r),
+                }
+            },
+            GET_SINK_INPUT_INFO | GET_SINK_INPUT_INFO_LIST => {
+                let index = match cmd {
+                    GET_SINK_INPUT_INFO => Some(t.get_u32()?),
+                    _ => None,
+                };
+                let mut r = reply(tag);
+                let mut found = false;
+                for p in self.playback.iter().filter(|p| index.map_or(true, |i| i == p.info.index)) {
+                    found = true;
+                    r.put_u32(p.info.index)
+                        .put_string(p.info.name.as_deref())
+                        .put_u32(INVALID_INDEX)
+                        .put_u32(p.info.client)
+                        .put_u32(p.info.sink)
+                        .put_sample_spec(&p.info.spec)
+                        .put_channel_map(&p.info.map)
+                        .put_cvolume(&p.info.volume)
+                        .put_usec(bytes_to_usec(p.queued, &p.info.spec))
+                        .put_usec(MicroSeconds(0))
+                        .put_string(None)
+                        .put_string(Some("mock"));
+                    if version >= 11 {
+                        r.put_bool(p.info.mute);
+                    }
+                    if version >= 13 {
+                        r.put_properties(&p.info.properties);
+                    }
+                }
+                match index.is_some() && !found {
+                    true => Response::Error(code::NO_ENTITY),
+                    false => Response::Reply(r),
+                }
+            },
+            GET_SOURCE_OUTPUT_INFO | GET_SOURCE_OUTPUT_INFO_LIST => {
+                let index = match cmd {
+                    GET_SOURCE_OUTPUT_INFO => Some(t.get_u32()?),
+                    _ => None,
+                };
+                let mut r = reply(tag);
+                let mut found = false;
+                for s in self.record.iter().filter(|s| index.map_or(true, |i| i == s.info.index)) {
+                    found = true;
+                    r.put_u32(s.info.index)
+                        .put_string(s.info.name.as_deref())
+                        .put_u32(INVALID_INDEX)
+                        .put_u32(s.info.client)
+                        .put_u32(s.info.source)
+                        .put_sample_spec(&s.info.spec)
+                        .put_channel_map(&s.info.map)
+                        .put_usec(MicroSeconds(0))
+                        .put_usec(MicroSeconds(0))
+                        .put_string(None)
+                        .put_string(Some("mock"));
+                    if version >= 13 {
+                        r.put_properties(&s.info.properties);
+                    }
+                }
+                match index.is_some() && !found {
+                    true => Response::Error(code::NO_ENTITY),
+                    false => Response::Reply(r),
+                }
+            },
+            SET_SINK_VOLUME | SET_SOURCE_VOLUME | SET_SINK_MUTE | SET_SOURCE_MUTE
+                | SET_SINK_PORT | SET_SOURCE_PORT =>
+            {
+                let index = t.get_u32()?;
+                let name = t.get_string()?;
+                let sink = matches!(cmd, SET_SINK_VOLUME | SET_SINK_MUTE | SET_SINK_PORT);
+                let (default, alias) = match sink {
+                    true => (self.default_sink_name().map(String::from), "@DEFAULT_SINK@"),
+                    false => (self.default_source_name().map(String::from), "@DEFAULT_SOURCE@"),
+                };
+                let list = if sink { &mut self.sinks } else { &mut self.sources };
+                let pos = match find_device(list, index, name.as_deref(), default.as_deref(), alias) {
+                    Some(p) => p,
+                    None => return Ok(Response::Error(code::NO_ENTITY)),
+                };
+                let device = &mut list[pos];
+                let ok = match cmd {
+                    SET_SINK_VOLUME | SET_SOURCE_VOLUME => {
+                        apply_volume(&mut device.volume, &t.get_cvolume()?)
+                    },
+                    SET_SINK_MUTE | SET_SOURCE_MUTE => {
+                        device.mute = t.get_bool()?;
+                        true
+                    },
+                    _ => match t.get_string()? {
+                        Some(port) if device.ports.iter().any(|p| p.name == port) => {
+                            device.active_port = Some(port);
+                            true
+                        },
+                        _ => false,
+                    },
+                };
+                let index = device.index;
+                match ok {
+                    true => {
+                        self.event(if sink { event::SINK } else { event::SOURCE }, event::CHANGE,
+                            index);
+                        Response::Reply(reply(tag))
+                    },
+                    false => Response::Error(code::INVALID),
+                }
+            },
+            SET_SINK_INPUT_VOLUME | SET_SINK_INPUT_MUTE => {
+                let index = t.get_u32()?;
+                let p = match self.playback.iter_mut().find(|p| p.info.index == index) {
+                    Some(p) => p,
+                    None => return Ok(Response::Error(code::NO_ENTITY)),
+                };
+                let ok = match cmd {
+                    SET_SINK_INPUT_VOLUME => apply_volume(&mut p.info.volume, &t.get_cvolume()?),
+                    _ => {
+                        p.info.mute = t.get_bool()?;
+                        true
+                    },
+                };
+                match ok {
+                    true => {
+                        self.event(event::SINK_INPUT, event::CHANGE, index);
+                        Response::Reply(reply(tag))
+                    },
+                    false => Response::Error(code::INVALID),
+                }
+            },
+            SET_DEFAULT_SINK | SET_DEFAULT_SOURCE => {
+                let name = t.get_string()?;
+                let sink = cmd == SET_DEFAULT_SINK;
+                let list = if sink { &self.sinks } else { &self.sources };
+                match name {
+                    Some(n) if list.iter().any(|d| d.name == n) => {
+                        match sink {
+                            true => self.default_sink = Some(n),
+                            false => self.default_source = Some(n),
+                        }
+                        self.event(event::SERVER, event::CHANGE, INVALID_INDEX);
+                        Response::Reply(reply(tag))
+                    },
+                    _ => Response::Error(code::NO_ENTITY),
+                }
+            },
+            SET_CARD_PROFILE => {
+                let index = t.get_u32()?;
+                let name = t.get_string()?;
+                let profile = t.get_string()?;
+                let card = self.cards.iter_mut().find(|c| match index {
+                    INVALID_INDEX => Some(&c.name) == name.as_ref(),
+                    i => c.index == i,
+                });
+                match (card, profile) {
+                    (Some(c), Some(p)) if c.profiles.iter().any(|x| x.name == p) => {
+                        c.active_profile = Some(p);
+                        let index = c.index;
+                        self.event(event::CARD, event::CHANGE, index);
+                        Response::Reply(reply(tag))
+                    },
+                    (Some(_), _) => Response::Error(code::INVALID),
+                    (None, _) => Response::Error(code::NO_ENTITY),
+                }
+            },
+            CREATE_PLAYBACK_STREAM => self.create_playback(client, tag, version, t)?,
+            CREATE_RECORD_STREAM => self.create_record(client, tag, version, t)?,
+            DELETE_PLAYBACK_STREAM => {
+                let channel = t.get_u32()?;
+                match self.playback.iter().position(|p| p.info.client == client && p.channel == channel) {
+                    Some(i) => {
+                        let p = self.playback.remove(i);
+                        self.event(event::SINK_INPUT, event::REMOVE, p.info.index);
+                        Response::Reply(reply(tag))
+                    },
+                    None => Response::Error(code::NO_ENTITY),
+                }
+            },
+            DELETE_RECORD_STREAM => {
+                let channel = t.get_u32()?;
+                match self.record.iter().position(|r| r.info.client == client && r.channel == channel) {
+                    Some(i) => {
+                        let r = self.record.remove(i);
+                        self.event(event::SOURCE_OUTPUT, event::REMOVE, r.info.index);
+                        Response::Reply(reply(tag))
+                    },
+                    None => Response::Error(code::NO_ENTITY),
+                }
+            },
+            CREATE_UPLOAD_STREAM => self.create_upload(client, tag, version, t)?,
+            DELETE_UPLOAD_STREAM | FINISH_UPLOAD_STREAM => {
+                let channel = t.get_u32()?;
+                let pos = self.uploads.iter()
+                    .position(|u| u.info.client == client && u.channel == channel);
+                match pos {
+                    Some(i) => {
+                        let u = self.uploads.remove(i);
+                        if cmd == FINISH_UPLOAD_STREAM {
+                            self.add_sample(Sample {
+                                index: INVALID_INDEX,
+                                name: u.info.name,
+                                spec: u.info.spec,
+                                map: u.info.map,
+                                properties: u.info.properties,
+                                data: u.info.received,
+                            });
+                        }
+                        Response::Reply(reply(tag))
+                    },
+                    None => Response::Error(code::NO_ENTITY),
+                }
+            },
+            REMOVE_SAMPLE => {
+                let name = t.get_string()?;
+                match self.samples.iter().position(|s| Some(&s.name) == name.as_ref()) {
+                    Some(i) => {
+                        let s = self.samples.remove(i);
+                        self.event(event::SAMPLE_CACHE, event::REMOVE, s.index);
+                        Response::Reply(reply(tag))
+                    },
+                    None => Response::Error(code::NO_ENTITY),
+                }
+            },
+            GET_SAMPLE_INFO => {
+                let index = t.get_u32()?;
+                let name = t.get_string()?;
+                let found = self.samples.iter().find(|s| match index {
+                    INVALID_INDEX => Some(&s.name) == name.as_ref(),
+                    i => s.index == i,
+                });
+                match found {
+                    Some(s) => {
+                        let mut r = reply(tag);
+                        fill_sample(&mut r, s, version);
+                        Response::Reply(r)
+                    },
+                    None => Response::Error(code::NO_ENTITY),
+                }
+            },
+            GET_SAMPLE_INFO_LIST => {
+                let mut r = reply(tag);
+                for s in &self.samples {
+                    fill_sample(&mut r, s, version);
+                }
+                Response::Reply(r)
+            },
+            CORK_PLAYBACK_STREAM | FLUSH_PLAYBACK_STREAM | TRIGGER_PLAYBACK_STREAM
+                | PREBUF_PLAYBACK_STREAM | DRAIN_PLAYBACK_STREAM | SET_PLAYBACK_STREAM_NAME =>
+            {
+                let channel = t.get_u32()?;
+                let p = match self.playback.iter_mut()
+                    .find(|p| p.info.client == client && p.channel == channel)
+                {
+                    Some(p) => p,
+                    None => return Ok(Response::Error(code::NO_ENTITY)),
+                };
+                match cmd {
+                    CORK_PLAYBACK_STREAM => p.info.corked = t.get_bool()?,
+                    FLUSH_PLAYBACK_STREAM => p.queued = 0,
+                    SET_PLAYBACK_STREAM_NAME => p.info.name = t.get_string()?,
+                    DRAIN_PLAYBACK_STREAM if p.queued > 0 => {
+                        p.drain_tags.push(tag);
+                        return Ok(Response::Deferred);
+                    },
+                    _ => {},
+                }
+                let index = p.info.index;
+                if matches!(cmd, CORK_PLAYBACK_STREAM | SET_PLAYBACK_STREAM_NAME) {
+                    self.event(event::SINK_INPUT, event::CHANGE, index);
+                }
+                Response::Reply(reply(tag))
+            },
+            CORK_RECORD_STREAM | FLUSH_RECORD_STREAM | SET_RECORD_STREAM_NAME => {
+                let channel = t.get_u32()?;
+                let r = match self.record.iter_mut()
+                    .find(|r| r.info.client == client && r.channel == channel)
+                {
+                    Some(r) => r,
+                    None => return Ok(Response::Error(code::NO_ENTITY)),
+                };
+                match cmd {
+                    CORK_RECORD_STREAM => r.info.corked = t.get_bool()?,
+                    SET_RECORD_STREAM_NAME => r.info.name = t.get_string()?,
+                    _ => {},
+                }
+                let index = r.info.index;
+                if cmd != FLUSH_RECORD_STREAM {
+                    self.event(event::SOURCE_OUTPUT, event::CHANGE, index);
+                }
+                Response::Reply(reply(tag))
+            },
+            GET_PLAYBACK_LATENCY => {
+                let channel = t.get_u32()?;
+                let requested = t.get_timeval()?;
+                let p = match self.playback.iter()
+                    .find(|p| p.info.client == client && p.channel == channel)
+                {
+                    Some(p) => p,
+                    None => return Ok(Response::Error(code::NO_ENTITY)),
+                };
+                let now = now_timeval();
+                let mut r = reply(tag);
+                r.put_usec(bytes_to_usec(p.queued, &p.info.spec))
+                    .put_usec(MicroSeconds(0))
+                    .put_bool(!p.info.corked)
+                    .put_timeval(&requested)
+                    .put_timeval(&now)
+                    .put_s64(p.write_index)
+                    .put_s64(p.read_index);
+                if version >= 13 {
+                    r.put_u64(0).put_u64(0);
+                }
+                Response::Reply(r)
+            },
+            GET_RECORD_LATENCY => {
+                let channel = t.get_u32()?;
+                let requested = t.get_timeval()?;
+                let s = match self.record.iter()
+                    .find(|r| r.info.client == client && r.channel == channel)
+                {
+                    Some(s) => s,
+                    None => return Ok(Response::Error(code::NO_ENTITY)),
+                };
+                let now = now_timeval();
+                let mut r = reply(tag);
+                r.put_usec(MicroSeconds(0))
+                    .put_usec(MicroSeconds(0))
+                    .put_bool(!s.info.corked)
+                    .put_timeval(&requested)
+                    .put_timeval(&now)
+                    .put_s64(s.info.sent as i64)
+                    .put_s64(s.info.sent as i64);
+                Response::Reply(r)
+            },
+            EXTENSION => Response::Error(code::NO_EXTENSION),
+            _ => Response::Error(code::NOT_IMPLEMENTED),
+        };
+        Ok(response)
+    }
+
+    fn fill_device(&self, t: &mut Writer, d: &Device, sink: bool, version: u32) {
+        let running = match sink {
+            true => self.playback.iter().any(|p| p.info.sink == d.index && !p.info.corked),
+            false => self.record.iter().any(|r| r.info.source == d.index && !r.info.corked),
+        };
+        let mut properties = d.properties.clone();
+        properties.entry("device.description".to_string()).or_insert_with(|| d.description.clone());
+
+        t.put_u32(d.index)
+            .put_string(Some(&d.name))
+            .put_string(Some(&d.description))
+            .put_sample_spec(&d.spec)
+            .put_channel_map(&d.map)
+            .put_u32(INVALID_INDEX)
+            .put_cvolume(&d.volume)
+            .put_bool(d.mute)
+            .put_u32(INVALID_INDEX)
+            .put_string(None)
+            .put_usec(MicroSeconds(0))
+            .put_string(Some("mock"))
+            .put_u32(DEVICE_FLAGS);
+        if version >= 13 {
+            t.put_properties(&properties).put_usec(MicroSeconds(0));
+        }
+        if version >= 15 {
+            t.put_volume(Volume::NORMAL)
+                .put_u32(if running { DEVICE_STATE_RUNNING } else { DEVICE_STATE_IDLE })
+                .put_u32(Volume::NORMAL.0 + 1)
+                .put_u32(d.card.unwrap_or(INVALID_INDEX));
+        }
+        if version >= 16 {
+            t.put_u32(d.ports.len() as u32);
+            for p in &d.ports {
+                t.put_string(Some(&p.name)).put_string(Some(&p.description)).put_u32(p.priority);
+            }
+            t.put_string(d.active_port.as_deref());
+        }
+    }
+
+    fn create_playback(&mut self, client: u32, tag: u32, version: u32, t: &mut Reader)
+        -> io::Result<Response>
+    {
+        let spec = t.get_sample_spec()?;
+        let map = t.get_channel_map()?;
+        let sink_index = t.get_u32()?;
+        let sink_name = t.get_string()?;
+        let maxlength = t.get_u32()?;
+        let corked = t.get_bool()?;
+        let tlength = t.get_u32()?;
+        let prebuf = t.get_u32()?;
+        let minreq = t.get_u32()?;
+        let _sync_id = t.get_u32()?;
+        let volume = t.get_cvolume()?;
+        let mut muted = false;
+        let mut properties = BTreeMap::new();
+        let mut volume_set = true;
+        if version >= 12 {
+            for _ in 0..7 {
+                t.get_bool()?;
+            }
+        }
+        if version >= 13 {
+            muted = t.get_bool()?;
+            t.get_bool()?;
+            properties = t.get_properties()?;
+        }
+        if version >= 14 {
+            volume_set = t.get_bool()?;
+        }
+
+        let frame = frame_size(&spec);
+        if frame == 0 || spec.rate == 0 || map.len() != spec.channels {
+            return Ok(Response::Error(code::INVALID));
+        }
+        let default = self.default_sink_name().map(String::from);
+        let pos = match find_device(&self.sinks, sink_index, sink_name.as_deref(),
+            default.as_deref(), "@DEFAULT_SINK@")
+        {
+            Some(p) => p,
+            None => return Ok(Response::Error(code::NO_ENTITY)),
+        };
+        let (sink_index, sink_name) = (self.sinks[pos].index, self.sinks[pos].name.clone());
+
+        let tlength = buffer_metric(tlength, duration_to_bytes(DEFAULT_TLENGTH, &spec), frame);
+        let maxlength = std::cmp::max(buffer_metric(maxlength, DEFAULT_MAXLENGTH as usize, frame),
+            tlength);
+        let prebuf = match prebuf {
+            u32::MAX => tlength,
+            v => std::cmp::min(v, tlength),
+        };
+        let minreq = buffer_metric(minreq, tlength as usize / 4, frame);
+        let volume = match volume_set && volume.len() == spec.channels {
+            true => volume,
+            false => {
+                let mut v = ChannelVolumes::default();
+                v.set_len(spec.channels);
+                v.get_mut().iter_mut().for_each(|x| *x = Volume::NORMAL);
+                v
+            },
+        };
+
+        let index = next(&mut self.counters.sink_input);
+        let conn = self.clients.iter_mut().find(|c| c.index == client).unwrap();
+        let channel = next(&mut conn.next_channel);
+        self.playback.push(Playback {
+            info: PlaybackStream {
+                index,
+                client,
+                sink: sink_index,
+                name: properties.get("media.name").cloned(),
+                spec,
+                map,
+                volume,
+                mute: muted,
+                corked,
+                properties,
+                received: Vec::new(),
+                played: 0,
+            },
+            channel,
+            maxlength,
+            tlength,
+            prebuf,
+            minreq,
+            queued: 0,
+            write_index: 0,
+            read_index: 0,
+            drain_tags: Vec::new(),
+        });
+        self.event(event::SINK_INPUT, event::NEW, index);
+
+        let p = self.playback.last().unwrap();
+        let mut r = reply(tag);
+        r.put_u32(channel).put_u32(index).put_u32(p.tlength);
+        if version >= 9 {
+            r.put_u32(p.maxlength).put_u32(p.tlength).put_u32(p.prebuf).put_u32(p.minreq);
+        }
+        if version >= 12 {
+            r.put_sample_spec(&spec).put_channel_map(&map).put_u32(sink_index)
+                .put_string(Some(&sink_name)).put_bool(false);
+        }
+        if version >= 13 {
+            r.put_usec(bytes_to_usec(p.tlength as usize, &spec));
+        }
+        Ok(Response::Reply(r))
+    }
+
+    fn create_record(&mut self, client: u32, tag: u32, version: u32, t: &mut Reader)
+        -> io::Result<Response>
+    {
+        let spec = t.get_sample_spec()?;
+        let map = t.get_channel_map()?;
+        let source_index = t.get_u32()?;
+        let source_name = t.get_string()?;
+        let maxlength = t.get_u32()?;
+        let corked = t.get_bool()?;
+        let fragsize = t.get_u32()?;
+        let mut properties = BTreeMap::new();
+        if version >= 12 {
+            for _ in 0..7 {
+                t.get_bool()?;
+            }
+        }
+        if version >= 13 {
+            t.get_bool()?;
+            t.get_bool()?;
+            properties = t.get_properties()?;
+        }
+
+        let frame = frame_size(&spec);
+        if frame == 0 || spec.rate == 0 || map.len() != spec.channels {
+            return Ok(Response::Error(code::INVALID));
+        }
+        let default = self.default_source_name().map(String::from);
+        let pos = match find_device(&self.sources, source_index, source_name.as_deref(),
+            default.as_deref(), "@DEFAULT_SOURCE@")
+        {
+            Some(p) => p,
+            None => return Ok(Response::Error(code::NO_ENTITY)),
+        };
+        let (source_index, source_name) = (self.sources[pos].index, self.sources[pos].name.clone());
+
+        let fragsize = buffer_metric(fragsize, duration_to_bytes(DEFAULT_FRAGSIZE, &spec), frame);
+        let maxlength = std::cmp::max(buffer_metric(maxlength, DEFAULT_MAXLENGTH as usize, frame),
+            fragsize);
+
+        let index = next(&mut self.counters.source_output);
+        let conn = self.clients.iter_mut().find(|c| c.index == client).unwrap();
+        let channel = next(&mut conn.next_channel);
+        self.record.push(Record {
+            info: RecordStream {
+                index,
+                client,
+                source: source_index,
+                name: properties.get("media.name").cloned(),
+                spec,
+                map,
+                corked,
+                properties,
+                sent: 0,
+            },
+            channel,
+        });
+        self.event(event::SOURCE_OUTPUT, event::NEW, index);
+
+        let mut r = reply(tag);
+        r.put_u32(channel).put_u32(index);
+        if version >= 9 {
+            r.put_u32(maxlength).put_u32(fragsize);
+        }
+        if version >= 12 {
+            r.put_sample_spec(&spec).put_channel_map(&map).put_u32(source_index)
+                .put_string(Some(&source_name)).put_bool(false);
+        }
+        if version >= 13 {
+            r.put_usec(bytes_to_usec(fragsize as usize, &spec));
+        }
+        Ok(Response::Reply(r))
+    }
+
+    fn create_upload(&mut self, client: u32, tag: u32, version: u32, t: &mut Reader)
+        -> io::Result<Response>
+    {
+        let mut name = match version < 13 {
+            true => t.get_string()?,
+            false => None,
+        };
+        let spec = t.get_sample_spec()?;
+        let map = t.get_channel_map()?;
+        let length = t.get_u32()? as usize;
+        let mut properties = BTreeMap::new();
+        if version >= 13 {
+            properties = t.get_properties()?;
+            name = properties.get("event.id").or_else(|| properties.get("media.name")).cloned();
+        }
+
+        let frame = frame_size(&spec);
+        if frame == 0 || spec.rate == 0 || map.len() != spec.channels || length == 0
+            || length % frame != 0 || length > SAMPLE_MAX
+        {
+            return Ok(Response::Error(code::INVALID));
+        }
+        let name = match name {
+            Some(n) => n,
+            None => return Ok(Response::Error(code::INVALID)),
+        };
+
+        let conn = self.clients.iter_mut().find(|c| c.index == client).unwrap();
+        let channel = next(&mut conn.next_channel);
+        self.uploads.push(Upload {
+            info: UploadStream {
+                client,
+                name,
+                spec,
+                map,
+                length,
+                properties,
+                received: Vec::new(),
+            },
+            channel,
+        });
+
+        let mut r = reply(tag);
+        r.put_u32(channel).put_u32(length as u32);
+        Ok(Response::Reply(r))
+    }
+}
+
+fn fill_card(t: &mut Writer, c: &Card) {
+    t.put_u32(c.index)
+        .put_string(Some(&c.name))
+        .put_u32(INVALID_INDEX)
+        .put_string(Some("mock"))
+        .put_u32(c.profiles.len() as u32);
+    for p in &c.profiles {
+        t.put_string(Some(&p.name))
+            .put_string(Some(&p.description))
+            .put_u32(p.n_sinks)
+            .put_u32(p.n_sources)
+            .put_u32(p.priority);
+    }
+    t.put_string(c.active_profile.as_deref()).put_properties(&c.properties);
+}
+
+fn fill_sample(t: &mut Writer, s: &Sample, version: u32) {
+    t.put_u32(s.index)
+        .put_string(Some(&s.name))
+        .put_cvolume(&crate::normal_volume(s.spec.channels))
+        .put_usec(bytes_to_usec(s.data.len(), &s.spec))
+        .put_sample_spec(&s.spec)
+        .put_channel_map(&s.map)
+        .put_u32(s.data.len() as u32)
+        .put_bool(false)
+        .put_string(None);
+    if version >= 13 {
+        t.put_properties(&s.properties);
+    }
+}
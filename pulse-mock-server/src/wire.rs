@@ -0,0 +1,87 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! Native protocol wire format.
+//!
+//! Packet framing and tagstruct encoding are those of the binding’s `protocol` module; this just
+//! adds the few conveniences the server needs on top.
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use pulse::stream::SeekMode;
+use pulse::protocol::Packet;
+
+pub(crate) use pulse::protocol::tagstruct::{Reader, Writer};
+
+/// Writes a control packet.
+pub(crate) fn write_control<W: Write>(w: &mut W, payload: &[u8]) -> io::Result<()> {
+    Packet::Control(payload.to_vec()).write(w)
+}
+
+/// Writes a memory block packet for the given channel (with relative seek mode).
+pub(crate) fn write_memblock<W: Write>(w: &mut W, channel: u32, data: &[u8]) -> io::Result<()> {
+    Packet::Memblock { channel, offset: 0, seek: SeekMode::Relative, data: data.to_vec() }.write(w)
+}
+
+// The binding’s own property list methods work with `Proplist`, which is backed by the C library,
+// whereas the server keeps property lists as plain maps of strings.
+
+/// Encoding of property lists held as maps.
+pub(crate) trait PutProperties {
+    /// Puts a property list, with string values (stored NUL terminated, as the C API does).
+    fn put_properties(&mut self, v: &BTreeMap<String, String>) -> &mut Self;
+}
+
+/// Decoding of property lists held as maps.
+pub(crate) trait GetProperties {
+    /// Gets a property list, with values converted to strings (dropping any NUL terminator).
+    fn get_properties(&mut self) -> io::Result<BTreeMap<String, String>>;
+}
+
+impl PutProperties for Writer {
+    fn put_properties(&mut self, v: &BTreeMap<String, String>) -> &mut Self {
+        let entries: Vec<(&str, Vec<u8>)> = v.iter()
+            .map(|(key, value)| {
+                let mut data = value.as_bytes().to_vec();
+                data.push(0);
+                (key.as_str(), data)
+            })
+            .collect();
+        self.put_proplist_entries(entries.iter().map(|(k, d)| (*k, d.as_slice())))
+    }
+}
+
+impl GetProperties for Reader<'_> {
+    fn get_properties(&mut self) -> io::Result<BTreeMap<String, String>> {
+        Ok(self.get_proplist_entries()?
+            .into_iter()
+            .map(|(key, data)| {
+                let value = data.strip_suffix(&[0]).unwrap_or(&data);
+                (key, String::from_utf8_lossy(value).into_owned())
+            })
+            .collect())
+    }
+}
+
+#[test]
+fn properties_round_trip() {
+    let mut props = BTreeMap::new();
+    props.insert("application.name".to_string(), "test".to_string());
+    props.insert("media.role".to_string(), String::new());
+
+    let mut w = Writer::new();
+    w.put_properties(&props).put_u32(7);
+    let bytes = w.into_bytes();
+
+    let mut r = Reader::new(&bytes);
+    assert_eq!(r.get_properties().unwrap(), props);
+    assert_eq!(r.get_u32().unwrap(), 7);
+    assert!(r.is_empty());
+}
@@ -0,0 +1,398 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! Testing the mock server with the PulseAudio client library
+
+extern crate libpulse_binding as pulse;
+extern crate libpulse_mock_server as mock;
+
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::Write;
+use std::rc::Rc;
+use std::time::Duration;
+use mock::{Card, Device, MockServer, Port, Profile};
+use pulse::channelmap::Map;
+use pulse::context::{Context, FlagSet};
+use pulse::context::connect::ConnectError;
+use pulse::context::cookie::Cookie;
+use pulse::context::subscribe::{Facility, InterestMaskSet, Operation as Event};
+use pulse::callbacks::ListResult;
+use pulse::error::Code;
+use pulse::mainloop::standard::{IterateResult, Mainloop, WaitError};
+use pulse::operation::{AnyOperation, Batch, BatchError, Operation, State};
+use pulse::sample::{Format, Spec};
+use pulse::stream::{self, PeekResult, SeekMode, Stream};
+use pulse::time::MicroSeconds;
+use pulse::wav;
+
+const SPEC: Spec = Spec { format: Format::S16le, channels: 2, rate: 44100 };
+
+fn connect(server: &MockServer) -> (Mainloop, Context) {
+    let mut mainloop = Mainloop::new().unwrap();
+    let mut context = Context::new(&mainloop, "mock-test").unwrap();
+    context.connect_blocking(&mut mainloop, Some(&server.address()), FlagSet::NOAUTOSPAWN,
+        Some(MicroSeconds::from_secs(5).unwrap())).unwrap();
+    (mainloop, context)
+}
+
+fn iterate_until<F: FnMut() -> bool>(mainloop: &mut Mainloop, mut done: F) {
+    while !done() {
+        match mainloop.iterate(true) {
+            IterateResult::Success(_) => {},
+            _ => panic!("mainloop iteration failed"),
+        }
+    }
+}
+
+fn wait<T: ?Sized>(mainloop: &mut Mainloop, op: Operation<T>) {
+    iterate_until(mainloop, || op.get_state() != State::Running);
+}
+
+fn wait_ready(mainloop: &mut Mainloop, stream: &Stream) {
+    iterate_until(mainloop, || match stream.get_state() {
+        stream::State::Ready => true,
+        stream::State::Failed | stream::State::Terminated => panic!("stream failed"),
+        _ => false,
+    });
+}
+
+// Check that sinks scripted through the API are listed, and that changes made are seen
+#[test]
+fn introspect() {
+    let server = MockServer::start().unwrap();
+    let index = server.add_sink(Device::new("speakers").with_description("Speakers")
+        .with_port(Port::new("analog-output", "Analog Output", 100)));
+    let (mut mainloop, mut context) = connect(&server);
+
+    let found = Rc::new(RefCell::new(Vec::new()));
+    let found_ref = Rc::clone(&found);
+    let op = context.introspect().get_sink_info_list(move |result| {
+        if let ListResult::Item(info) = result {
+            found_ref.borrow_mut().push((info.index, info.name.as_ref().map(|n| n.to_string()),
+                info.description.as_ref().map(|d| d.to_string())));
+        }
+    });
+    wait(&mut mainloop, op);
+    assert_eq!(*found.borrow(),
+        vec![(index, Some("speakers".to_string()), Some("Speakers".to_string()))]);
+
+    let op = context.introspect().set_sink_mute_by_index(index, true, None);
+    wait(&mut mainloop, op);
+    assert!(server.sink(index).unwrap().mute);
+
+    let snapshot = server.wait_until(Duration::from_secs(5), |s| !s.clients.is_empty()).unwrap();
+    assert_eq!(snapshot.clients[0].properties.get("application.name").map(String::as_str),
+        Some("mock-test"));
+    context.disconnect();
+}
+
+// Check that a cookie loaded from memory reaches the server upon connecting, with other files
+// opened in between, and that a wrong one is rejected
+#[cfg(target_os = "linux")]
+#[test]
+fn in_memory_cookie() {
+    let server = MockServer::builder().cookie(&[7; 256]).start().unwrap();
+    for &(byte, accepted) in &[(7u8, true), (8u8, false)] {
+        let mut mainloop = Mainloop::new().unwrap();
+        let mut context = Context::new(&mainloop, "mock-test").unwrap();
+        context.load_cookie(&Cookie::from_bytes(&[byte; 256]).unwrap()).unwrap();
+        let _others: Vec<_> = (0..4).map(|_| std::fs::File::open("/dev/null").unwrap()).collect();
+        let result = context.connect_blocking(&mut mainloop, Some(&server.address()),
+            FlagSet::NOAUTOSPAWN, Some(MicroSeconds::from_secs(5).unwrap()));
+        assert_eq!(result.is_ok(), accepted);
+        context.disconnect();
+    }
+}
+
+// Check that a cookie read from a file is checked, with a wrong one denied access
+#[test]
+fn auth_rejection() {
+    let server = MockServer::builder().cookie(&[7; 256]).start().unwrap();
+    let path = std::env::temp_dir().join(format!("mock-cookie-{}", std::process::id()));
+    for &(byte, expected) in &[(8u8, Err(ConnectError::AccessDenied)), (7u8, Ok(()))] {
+        std::fs::write(&path, [byte; 256]).unwrap();
+        let mut mainloop = Mainloop::new().unwrap();
+        let mut context = Context::new(&mainloop, "mock-test").unwrap();
+        context.load_cookie_from_file(path.to_str().unwrap()).unwrap();
+        let result = context.connect_blocking(&mut mainloop, Some(&server.address()),
+            FlagSet::NOAUTOSPAWN, Some(MicroSeconds::from_secs(5).unwrap()));
+        assert_eq!(result, expected);
+        assert_eq!(server.snapshot().clients.len(), expected.map_or(0, |_| 1));
+        context.disconnect();
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+
+// Check that audio played reaches the server, and is consumed (with more requested) only as the
+// virtual clock advances while the stream is not corked
+#[test]
+fn playback() {
+    let server = MockServer::start().unwrap();
+    let sink = server.add_sink(Device::new("speakers"));
+    let (mut mainloop, mut context) = connect(&server);
+
+    let mut stream = Stream::new(&mut context, "playback", &SPEC, None).unwrap();
+    stream.connect_playback(Some("speakers"), None, stream::FlagSet::NOFLAGS, None, None)
+        .unwrap();
+    wait_ready(&mut mainloop, &stream);
+
+    // 100 ms of audio
+    stream.write(&[1; 17640], None, 0, SeekMode::Relative).unwrap();
+    let snapshot = server.wait_until(Duration::from_secs(5),
+        |s| s.playback_streams.iter().any(|p| p.received.len() == 17640)).unwrap();
+    let index = stream.get_index().unwrap();
+    assert_eq!(snapshot.playback_streams[0].index, index);
+    assert_eq!(snapshot.playback_streams[0].sink, sink);
+    assert_eq!(snapshot.playback_streams[0].played, 0);
+
+    let writable = stream.writable_size().unwrap();
+    server.advance(Duration::from_millis(10));
+    iterate_until(&mut mainloop, || stream.writable_size().unwrap() > writable);
+    assert_eq!(stream.writable_size().unwrap(), writable + 1764);
+    assert_eq!(server.snapshot().playback_streams[0].played, 1764);
+    assert_eq!(server.clock(), Duration::from_millis(10));
+
+    let op = stream.cork(None);
+    wait(&mut mainloop, op);
+    assert!(server.snapshot().playback_streams[0].corked);
+    server.advance(Duration::from_millis(10));
+    assert_eq!(server.snapshot().playback_streams[0].played, 1764);
+    assert_eq!(server.clock(), Duration::from_millis(20));
+
+    assert_eq!(server.received_audio(index).unwrap(), vec![1; 17640]);
+    stream.disconnect().unwrap();
+    server.wait_until(Duration::from_secs(5), |s| s.playback_streams.is_empty()).unwrap();
+    context.disconnect();
+}
+
+// Check that audio queued for a source is captured as the virtual clock advances, followed by
+// silence
+#[test]
+fn record() {
+    let server = MockServer::start().unwrap();
+    let source = server.add_source(Device::new("microphone"));
+    server.push_record_audio(source, &[5; 1764]);
+    let (mut mainloop, mut context) = connect(&server);
+
+    let mut stream = Stream::new(&mut context, "record", &SPEC, None).unwrap();
+    stream.connect_record(Some("microphone"), None, stream::FlagSet::NOFLAGS).unwrap();
+    wait_ready(&mut mainloop, &stream);
+    assert_eq!(server.snapshot().record_streams[0].source, source);
+
+    server.advance(Duration::from_millis(20));
+    let mut captured = Vec::new();
+    iterate_until(&mut mainloop, || {
+        loop {
+            match stream.peek().unwrap() {
+                PeekResult::Empty => break,
+                PeekResult::Data(data) => captured.extend_from_slice(data),
+                PeekResult::Hole(_) => {},
+            }
+            stream.discard().unwrap();
+        }
+        captured.len() >= 3528
+    });
+    assert_eq!(captured.len(), 3528);
+    assert_eq!(&captured[..1764], &[5; 1764][..]);
+    assert_eq!(&captured[1764..], &[0; 1764][..]);
+    assert_eq!(server.snapshot().record_streams[0].sent, 3528);
+    stream.disconnect().unwrap();
+    context.disconnect();
+}
+
+// Check that changes made through the API generate events for subscribed facilities only
+#[test]
+fn subscriptions() {
+    let server = MockServer::start().unwrap();
+    let (mut mainloop, mut context) = connect(&server);
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let events_ref = Rc::clone(&events);
+    context.set_subscribe_callback(Some(Box::new(move |facility, op, index| {
+        events_ref.borrow_mut().push((facility, op, index));
+    })));
+    let op = context.subscribe(InterestMaskSet::SINK, |success| assert!(success));
+    wait(&mut mainloop, op);
+
+    let source = server.add_source(Device::new("microphone"));
+    let sink = server.add_sink(Device::new("speakers"));
+    server.update_sink(sink, |d| d.mute = true);
+    server.update_source(source, |d| d.mute = true);
+    server.remove_sink(sink);
+    iterate_until(&mut mainloop, || events.borrow().len() >= 3);
+    assert_eq!(*events.borrow(), vec![
+        (Some(Facility::Sink), Some(Event::New), sink),
+        (Some(Facility::Sink), Some(Event::Changed), sink),
+        (Some(Facility::Sink), Some(Event::Removed), sink),
+    ]);
+    context.disconnect();
+}
+
+// Check that cards and their profiles are listed, and that the active profile can be changed
+#[test]
+fn cards() {
+    let server = MockServer::start().unwrap();
+    let index = server.add_card(Card::new("card0")
+        .with_profile(Profile::new("off", "Off", 0, 0, 0))
+        .with_profile(Profile::new("stereo", "Stereo", 1, 1, 10)));
+    let (mut mainloop, mut context) = connect(&server);
+
+    let found = Rc::new(RefCell::new(Vec::new()));
+    let found_ref = Rc::clone(&found);
+    let op = context.introspect().get_card_info_list(move |result| {
+        if let ListResult::Item(info) = result {
+            let profiles: Vec<String> = info.profiles.iter()
+                .filter_map(|p| p.name.as_ref().map(|n| n.to_string()))
+                .collect();
+            found_ref.borrow_mut().push((info.index, info.name.as_ref().map(|n| n.to_string()),
+                profiles));
+        }
+    });
+    wait(&mut mainloop, op);
+    assert_eq!(*found.borrow(), vec![(index, Some("card0".to_string()),
+        vec!["off".to_string(), "stereo".to_string()])]);
+
+    let results = Rc::new(RefCell::new(Vec::new()));
+    for profile in &["stereo", "surround"] {
+        let results_ref = Rc::clone(&results);
+        let op = context.introspect().set_card_profile_by_index(index, profile,
+            Some(Box::new(move |success| results_ref.borrow_mut().push(success))));
+        wait(&mut mainloop, op);
+    }
+    assert_eq!(*results.borrow(), vec![true, false]);
+    assert_eq!(server.card(index).unwrap().active_profile.as_deref(), Some("stereo"));
+    context.disconnect();
+}
+
+// Check the aggregated outcome of batches, with operations failing, cancelled, and still running
+// upon the deadline passing
+#[test]
+fn batch() {
+    let server = MockServer::start().unwrap();
+    let sink = server.add_sink(Device::new("speakers"));
+    let (mut mainloop, mut context) = connect(&server);
+    let mut introspector = context.introspect();
+    let timeout = Some(MicroSeconds::from_secs(5).unwrap());
+
+    let mut batch = Batch::new();
+    batch.add(|cb| introspector.set_sink_mute_by_index(sink, true, Some(cb)));
+    batch.add(|cb| introspector.set_sink_mute_by_index(sink + 100, true, Some(cb)));
+    batch.add(|cb| introspector.set_sink_mute_by_index(sink, true, Some(cb)));
+    let expected = BatchError { failed: vec![1], cancelled: vec![], timed_out: false };
+    assert_eq!(mainloop.wait_for_batch(&mut batch, timeout), Err(WaitError::Batch(expected)));
+    assert!(server.sink(sink).unwrap().mute);
+
+    let mut batch = Batch::new();
+    batch.add(|cb| introspector.set_sink_mute_by_index(sink, false, Some(cb)));
+    batch.add(|cb| introspector.set_sink_mute_by_index(sink, false, Some(cb)));
+    assert_eq!(batch.outcome(), None);
+    batch.cancel();
+    assert!(batch.is_done());
+    let expected = BatchError { failed: vec![], cancelled: vec![0, 1], timed_out: false };
+    assert_eq!(batch.outcome(), Some(Err(expected)));
+
+    // A drain only completes once the audio written is played, which never happens here, since
+    // the virtual clock is not advanced
+    let mut stream = Stream::new(&mut context, "playback", &SPEC, None).unwrap();
+    stream.connect_playback(None, None, stream::FlagSet::NOFLAGS, None, None).unwrap();
+    wait_ready(&mut mainloop, &stream);
+    stream.write(&[0; 1764], None, 0, SeekMode::Relative).unwrap();
+    let mut batch = Batch::new();
+    batch.add(|cb| introspector.set_sink_mute_by_index(sink, false, Some(cb)));
+    batch.add(|cb| stream.drain(Some(cb)));
+    let expected = BatchError { failed: vec![], cancelled: vec![1], timed_out: true };
+    assert_eq!(mainloop.wait_for_batch(&mut batch, Some(MicroSeconds::from_millis(200).unwrap())),
+        Err(WaitError::Batch(expected)));
+    assert_eq!(batch.operations()[0].get_state(), State::Done);
+    assert_eq!(batch.operations()[1].get_state(), State::Cancelled);
+    assert!(!server.sink(sink).unwrap().mute);
+    context.disconnect();
+}
+
+// Check that samples are uploaded in full, replacing any of the same name, with a trailing partial
+// frame of a file dropped, and that uploads cancelled or killed store nothing
+#[test]
+fn upload() {
+    let server = MockServer::start().unwrap();
+    let (mut mainloop, mut context) = connect(&server);
+    let timeout = Some(MicroSeconds::from_secs(5).unwrap());
+
+    // More than fits in a single memory block
+    let data: Vec<u8> = (0..70000u32).map(|i| i as u8).collect();
+    let upload = context.upload_sample("bell", &SPEC, None, None, data.clone()).unwrap();
+    assert_eq!(upload.get_state(), State::Running);
+    assert_eq!(upload.outcome(), None);
+    mainloop.wait_for_all(&[&upload], timeout).unwrap();
+    assert_eq!(upload.outcome(), Some(Ok(())));
+    let index = server.sample("bell").unwrap().index;
+    assert_eq!(server.sample("bell").unwrap().data, data);
+
+    let cache = context.sample_cache();
+    let upload = cache.replace("bell", &SPEC, None, None, vec![1; 1764]).unwrap();
+    mainloop.wait_for_all(&[&upload], timeout).unwrap();
+    let listed = Rc::new(RefCell::new(None));
+    let listed_ref = Rc::clone(&listed);
+    let op = cache.list(move |result| *listed_ref.borrow_mut() = Some(result));
+    wait(&mut mainloop, op);
+    let samples = listed.borrow_mut().take().unwrap().unwrap();
+    assert_eq!(samples.len(), 1);
+    assert_eq!((samples[0].index, samples[0].bytes), (index, 1764));
+    assert_eq!(samples[0].name.as_deref(), Some("bell"));
+
+    // A file of unknown length, cut short part way through a frame
+    let path = std::env::temp_dir().join(format!("mock-sample-{}.wav", std::process::id()));
+    let mut writer = wav::Writer::new(File::create(&path).unwrap(), &SPEC,
+        Map::default().init_stereo()).unwrap();
+    writer.write_all(&[2; 1767]).unwrap();
+    writer.into_inner().unwrap();
+    let upload = context.upload_sample_file("knock", &path, None).unwrap();
+    mainloop.wait_for_all(&[&upload], timeout).unwrap();
+    assert_eq!(upload.outcome(), Some(Ok(())));
+    let sample = server.sample("knock").unwrap();
+    assert_eq!(sample.data, vec![2; 1764]);
+    assert_eq!(sample.properties.get("media.filename"), Some(&path.display().to_string()));
+    std::fs::remove_file(&path).unwrap();
+
+    // Cancelled before the stream is ready, and so only disconnected once it is, as seen by the
+    // server once two round trips are made
+    let mut upload = context.upload_sample("chime", &SPEC, None, None, vec![3; 1764]).unwrap();
+    upload.cancel();
+    assert_eq!(upload.get_state(), State::Cancelled);
+    for _ in 0..2 {
+        let op = cache.list(|result| assert!(result.is_ok()));
+        wait(&mut mainloop, op);
+    }
+    assert!(server.snapshot().upload_streams.is_empty());
+    assert!(server.sample("chime").is_none());
+    assert_eq!(upload.outcome(), None);
+
+    // Killed by the server before the client even sees the stream ready. A single iteration sends
+    // the request, but cannot also receive the reply.
+    let upload = context.upload_sample("chime", &SPEC, None, None, vec![3; 1764]).unwrap();
+    assert!(matches!(mainloop.iterate(false), IterateResult::Success(_)));
+    server.wait_until(Duration::from_secs(5), |s| !s.upload_streams.is_empty()).unwrap();
+    assert_eq!(server.kill_uploads(), 1);
+    mainloop.wait_for_all(&[&upload], timeout).unwrap();
+    let e = upload.outcome().unwrap().unwrap_err();
+    assert_eq!((e.code(), e.target()), (Some(Code::Killed), Some("chime")));
+    assert!(server.sample("chime").is_none());
+
+    let removed = Rc::new(RefCell::new(Vec::new()));
+    for name in &["bell", "bell"] {
+        let removed_ref = Rc::clone(&removed);
+        let op = cache.remove(name, move |success| removed_ref.borrow_mut().push(success));
+        wait(&mut mainloop, op);
+    }
+    assert_eq!(*removed.borrow(), vec![true, false]);
+    assert_eq!(server.snapshot().samples.len(), 1);
+    drop(cache);
+    context.disconnect();
+}
@@ -4,6 +4,11 @@ fn main() {
         return;
     }
 
+    // Nothing to link if the library is to be loaded at runtime.
+    if std::env::var_os("CARGO_FEATURE_DLOPEN").is_some() {
+        return;
+    }
+
     let lib_name = "libpulse";
     let fallback_name = {
         #[cfg(target_os = "linux")]
@@ -204,8 +204,7 @@ pub struct pa_channel_map {
 pub const PA_CHANNEL_MAP_SNPRINT_MAX: usize = 336;
 
 #[rustfmt::skip]
-#[link(name = "pulse")]
-extern "C" {
+pa_extern! {
     pub fn pa_channel_map_init(m: *mut pa_channel_map) -> *mut pa_channel_map;
     pub fn pa_channel_map_init_mono(m: *mut pa_channel_map) -> *mut pa_channel_map;
     pub fn pa_channel_map_init_stereo(m: *mut pa_channel_map) -> *mut pa_channel_map;
@@ -43,8 +43,7 @@ pub type pa_ext_device_manager_read_cb_t = Option<extern "C" fn(c: *mut pa_conte
 pub type pa_ext_device_manager_subscribe_cb_t = Option<extern "C" fn(c: *mut pa_context, userdata: *mut c_void)>;
 
 #[rustfmt::skip]
-#[link(name = "pulse")]
-extern "C" {
+pa_extern! {
     pub fn pa_ext_device_manager_test(c: *mut pa_context, cb: pa_ext_device_manager_test_cb_t, userdata: *mut c_void) -> *mut pa_operation;
     pub fn pa_ext_device_manager_read(c: *mut pa_context, cb: pa_ext_device_manager_read_cb_t, userdata: *mut c_void) -> *mut pa_operation;
     pub fn pa_ext_device_manager_set_device_description(c: *mut pa_context, device: *const c_char, description: *const c_char, cb: pa_context_success_cb_t, userdata: *mut c_void) -> *mut pa_operation;
@@ -35,8 +35,7 @@ pub type pa_ext_device_restore_subscribe_cb_t = Option<extern "C" fn(c: *mut pa_
 pub type pa_ext_device_restore_read_device_formats_cb_t = Option<extern "C" fn(c: *mut pa_context, info: *const pa_ext_device_restore_info, eol: i32, userdata: *mut c_void)>;
 
 #[rustfmt::skip]
-#[link(name = "pulse")]
-extern "C" {
+pa_extern! {
     pub fn pa_ext_device_restore_test(c: *mut pa_context, cb: pa_ext_device_restore_test_cb_t, userdata: *mut c_void) -> *mut pa_operation;
     pub fn pa_ext_device_restore_subscribe(c: *mut pa_context, enable: i32, cb: pa_context_success_cb_t, userdata: *mut c_void) -> *mut pa_operation;
     pub fn pa_ext_device_restore_set_subscribe_cb(c: *mut pa_context, cb: pa_ext_device_restore_subscribe_cb_t, userdata: *mut c_void);
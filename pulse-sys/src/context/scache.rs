@@ -20,8 +20,7 @@ use crate::{operation::pa_operation, proplist::pa_proplist, volume::pa_volume_t}
 pub type pa_context_play_sample_cb_t = Option<extern "C" fn(c: *mut super::pa_context, idx: u32, userdata: *mut c_void)>;
 
 #[rustfmt::skip]
-#[link(name = "pulse")]
-extern "C" {
+pa_extern! {
     pub fn pa_context_remove_sample(c: *mut super::pa_context, name: *const c_char, cb: super::pa_context_success_cb_t, userdata: *mut c_void) -> *mut pa_operation;
 
     pub fn pa_context_play_sample(c: *mut super::pa_context, name: *const c_char, dev: *const c_char, volume: pa_volume_t, cb: super::pa_context_success_cb_t, userdata: *mut c_void) -> *mut pa_operation;
@@ -25,8 +25,7 @@ pub type pa_direction_t = i32;
 pub const PA_DIRECTION_OUTPUT: pa_direction_t = 0x1;
 pub const PA_DIRECTION_INPUT:  pa_direction_t = 0x2;
 
-#[link(name = "pulse")]
-extern "C" {
+pa_extern! {
     #[cfg(any(doc, feature = "pa_v6"))]
     #[cfg_attr(docsrs, doc(cfg(feature = "pa_v6")))]
     pub fn pa_direction_valid(direction: pa_direction_t) -> i32;
@@ -0,0 +1,198 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! Library linking, either at build time, or at runtime with the `dlopen` feature.
+//!
+//! Normally the library is linked at build time, and a program using it fails to start on a system
+//! without it. With the `dlopen` feature, the library is instead loaded upon first use, with each
+//! function resolved upon first call, which allows for PulseAudio support to be optional. Use
+//! [`is_available()`] or [`load()`] to check that the library could be loaded before making any
+//! other calls; calling a function when it could not be loaded panics.
+
+use std::fmt;
+
+/// Declares library functions, either as an `extern` block linked at build time, or as wrappers
+/// that resolve the symbol at runtime with the `dlopen` feature.
+macro_rules! pa_extern {
+    ($(
+        $(#[$attr:meta])*
+        pub fn $name:ident($($arg:ident: $ty:ty),* $(,)?) $(-> $ret:ty)?;
+    )*) => {
+        #[cfg(not(feature = "dlopen"))]
+        #[link(name = "pulse")]
+        extern "C" {
+            $(
+                $(#[$attr])*
+                pub fn $name($($arg: $ty),*) $(-> $ret)?;
+            )*
+        }
+
+        $(
+            #[cfg(feature = "dlopen")]
+            $(#[$attr])*
+            #[inline]
+            #[allow(clippy::missing_safety_doc)]
+            pub unsafe fn $name($($arg: $ty),*) $(-> $ret)? {
+                static SYMBOL: std::sync::atomic::AtomicPtr<std::os::raw::c_void> =
+                    std::sync::atomic::AtomicPtr::new(std::ptr::null_mut());
+                let ptr = crate::dynamic::resolve(&SYMBOL,
+                    concat!(stringify!($name), "\0").as_bytes());
+                let f: unsafe extern "C" fn($($ty),*) $(-> $ret)? = std::mem::transmute(ptr);
+                f($($arg),*)
+            }
+        )*
+    };
+}
+
+/// Name of the library loaded with the `dlopen` feature.
+#[cfg(target_os = "macos")]
+pub const LIBRARY_NAME: &str = "libpulse.0.dylib";
+/// Name of the library loaded with the `dlopen` feature.
+#[cfg(not(target_os = "macos"))]
+pub const LIBRARY_NAME: &str = "libpulse.so.0";
+
+/// Error in loading the library at runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadError(String);
+
+impl LoadError {
+    /// Gets the error message, as given by the dynamic loader.
+    #[inline]
+    pub fn message(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Failed to load {}: {}", LIBRARY_NAME, self.0)
+    }
+}
+
+/// Loads the library, if not already done.
+///
+/// Without the `dlopen` feature the library is linked at build time and this always succeeds.
+#[cfg(not(feature = "dlopen"))]
+#[inline]
+pub fn load() -> Result<(), LoadError> {
+    Ok(())
+}
+
+/// Loads the library, if not already done.
+///
+/// Without the `dlopen` feature the library is linked at build time and this always succeeds.
+#[cfg(feature = "dlopen")]
+pub fn load() -> Result<(), LoadError> {
+    loader::handle().map(|_| ())
+}
+
+/// Checks whether the library is available, loading it if not already done.
+///
+/// Without the `dlopen` feature the library is linked at build time and this always returns
+/// `true`.
+#[inline]
+pub fn is_available() -> bool {
+    load().is_ok()
+}
+
+/// Looks up a library function by name, returning `None` if not present.
+///
+/// This allows for checking at runtime for functions that only exist in newer versions of the
+/// library than the minimum targeted at build time. With the `dlopen` feature, this also returns
+/// `None` if the library could not be loaded. The name must not include a trailing nul.
+pub fn find_symbol(name: &str) -> Option<*mut std::os::raw::c_void> {
+    let c_name = std::ffi::CString::new(name).ok()?;
+    #[cfg(feature = "dlopen")]
+    let handle = loader::handle().ok()?;
+    #[cfg(all(unix, not(feature = "dlopen")))]
+    let handle = libc::RTLD_DEFAULT;
+    #[cfg(unix)]
+    {
+        let ptr = unsafe { libc::dlsym(handle, c_name.as_ptr()) };
+        match ptr.is_null() {
+            true => None,
+            false => Some(ptr),
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = c_name;
+        None
+    }
+}
+
+/// Resolves a symbol, caching it in the given location. Panics if it cannot be resolved.
+#[cfg(feature = "dlopen")]
+#[doc(hidden)]
+pub fn resolve(cache: &std::sync::atomic::AtomicPtr<std::os::raw::c_void>, name: &[u8])
+    -> *mut std::os::raw::c_void
+{
+    use std::sync::atomic::Ordering;
+
+    let ptr = cache.load(Ordering::Acquire);
+    if !ptr.is_null() {
+        return ptr;
+    }
+    let handle = match loader::handle() {
+        Ok(h) => h,
+        Err(e) => panic!("{}", e),
+    };
+    let ptr = unsafe { libc::dlsym(handle, name.as_ptr() as *const _) };
+    if ptr.is_null() {
+        let name = String::from_utf8_lossy(&name[..name.len() - 1]);
+        panic!("Symbol {} not found in {} (library too old?)", name, LIBRARY_NAME);
+    }
+    cache.store(ptr, Ordering::Release);
+    ptr
+}
+
+#[cfg(all(feature = "dlopen", not(unix)))]
+compile_error!("The `dlopen` feature is only supported on Unix platforms");
+
+#[cfg(feature = "dlopen")]
+mod loader {
+    use std::ffi::CStr;
+    use std::os::raw::c_void;
+    use std::sync::Once;
+    use super::{LoadError, LIBRARY_NAME};
+
+    static LOAD: Once = Once::new();
+    static mut RESULT: Option<Result<usize, LoadError>> = None;
+
+    /// Gets the library handle, loading it upon first use.
+    pub(super) fn handle() -> Result<*mut c_void, LoadError> {
+        LOAD.call_once(|| {
+            let name = format!("{}\0", LIBRARY_NAME);
+            let handle = unsafe {
+                libc::dlopen(name.as_ptr() as *const _, libc::RTLD_NOW | libc::RTLD_LOCAL)
+            };
+            let result = match handle.is_null() {
+                false => Ok(handle as usize),
+                true => {
+                    let msg = unsafe { libc::dlerror() };
+                    Err(LoadError(match msg.is_null() {
+                        true => "unknown error".to_string(),
+                        false => unsafe { CStr::from_ptr(msg) }.to_string_lossy().into_owned(),
+                    }))
+                },
+            };
+            // Safe, only written once, within the `Once`, before any read
+            unsafe { RESULT = Some(result) };
+        });
+        // Safe, the `Once` guarantees the write above completed
+        match unsafe { &*std::ptr::addr_of!(RESULT) } {
+            Some(Ok(h)) => Ok(*h as *mut c_void),
+            Some(Err(e)) => Err(e.clone()),
+            None => unreachable!(),
+        }
+    }
+}
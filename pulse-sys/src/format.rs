@@ -112,8 +112,7 @@ impl Default for pa_prop_type_t {
 }
 
 #[rustfmt::skip]
-#[link(name = "pulse")]
-extern "C" {
+pa_extern! {
     pub fn pa_encoding_to_string(e: pa_encoding_t) -> *const c_char;
     #[cfg(any(doc, feature = "pa_v12"))]
     #[cfg_attr(docsrs, doc(cfg(feature = "pa_v12")))]
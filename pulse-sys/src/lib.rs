@@ -22,6 +22,17 @@
 //! “higher-level” binding. Please see either the equivalent documentation in that, or the
 //! documentation of the actual PulseAudio C header files, if you need documentation.
 //!
+//! # Runtime loading
+//!
+//! By default the library is linked at build time, thus a program using this crate fails to start
+//! on a system without the library installed. With the `dlopen` feature (Unix only), the library
+//! (named [`LIBRARY_NAME`]) is instead loaded at runtime upon first use, allowing for PulseAudio
+//! support to be optional. Use [`is_available()`] (or [`load()`], for the reason for failure) to
+//! check that it can be loaded before making any other calls, since calling a function when the
+//! library could not be loaded (or lacks the function) panics. Note that the variadic
+//! `pa_proplist_setf()` function cannot be wrapped, and so with this feature is only available
+//! through [`pa_proplist_setf_ptr()`].
+//!
 //! [`libpulse-binding`]: https://docs.rs/libpulse-binding
 //! [PulseAudio]: https://en.wikipedia.org/wiki/PulseAudio
 
@@ -34,6 +45,9 @@
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+#[macro_use]
+mod dynamic;
+
 pub mod channelmap;
 pub mod context;
 pub mod def;
@@ -57,6 +71,7 @@ pub mod xmalloc;
 pub use self::channelmap::*;
 pub use self::context::*;
 pub use self::def::*;
+pub use self::dynamic::{find_symbol, is_available, load, LoadError, LIBRARY_NAME};
 pub use self::direction::*;
 pub use self::error::*;
 pub use self::format::*;
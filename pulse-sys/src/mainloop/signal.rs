@@ -26,8 +26,7 @@ pub type pa_signal_cb_t = Option<extern "C" fn(api: *const pa_mainloop_api, e: *
 pub type pa_signal_destroy_cb_t = Option<extern "C" fn(api: *const pa_mainloop_api, e: *mut pa_signal_event, userdata: *mut c_void)>;
 
 #[rustfmt::skip]
-#[link(name = "pulse")]
-extern "C" {
+pa_extern! {
     pub fn pa_signal_init(api: *const pa_mainloop_api) -> i32;
     pub fn pa_signal_done();
     pub fn pa_signal_new(sig: i32, callback: pa_signal_cb_t, userdata: *mut c_void) -> *mut pa_signal_event;
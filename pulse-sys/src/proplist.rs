@@ -335,14 +335,12 @@ pub const PA_UPDATE_MERGE:   pa_update_mode_t = pa_update_mode_t::Merge;
 pub const PA_UPDATE_REPLACE: pa_update_mode_t = pa_update_mode_t::Replace;
 
 #[rustfmt::skip]
-#[link(name = "pulse")]
-extern "C" {
+pa_extern! {
     pub fn pa_proplist_new() -> *mut pa_proplist;
     pub fn pa_proplist_free(p: *mut pa_proplist);
     pub fn pa_proplist_key_valid(key: *const c_char) -> i32;
     pub fn pa_proplist_sets(p: *mut pa_proplist, key: *const c_char, value: *const c_char) -> i32;
     pub fn pa_proplist_setp(p: *mut pa_proplist, pair: *const c_char) -> i32;
-    pub fn pa_proplist_setf(p: *mut pa_proplist, key: *const c_char, format: *const c_char, ...) -> i32;
     pub fn pa_proplist_set(p: *mut pa_proplist, key: *const c_char, data: *const c_void, nbytes: usize) -> i32;
     pub fn pa_proplist_gets(p: *const pa_proplist, key: *const c_char) -> *const c_char;
     pub fn pa_proplist_get(p: *const pa_proplist, key: *const c_char, data: *mut *const c_void, nbytes: *mut usize) -> i32;
@@ -360,3 +358,40 @@ extern "C" {
     pub fn pa_proplist_isempty(p: *const pa_proplist) -> i32;
     pub fn pa_proplist_equal(a: *const pa_proplist, b: *const pa_proplist) -> i32;
 }
+
+#[rustfmt::skip]
+#[cfg(not(feature = "dlopen"))]
+#[link(name = "pulse")]
+extern "C" {
+    /// Note, this is not available with the `dlopen` feature, since variadic functions cannot be
+    /// wrapped; use [`pa_proplist_setf_ptr()`] instead, which is available either way.
+    pub fn pa_proplist_setf(p: *mut pa_proplist, key: *const c_char, format: *const c_char, ...) -> i32;
+}
+
+/// Type of the variadic `pa_proplist_setf()` function.
+#[rustfmt::skip]
+pub type pa_proplist_setf_t = unsafe extern "C" fn(p: *mut pa_proplist, key: *const c_char, format: *const c_char, ...) -> i32;
+
+/// Gets a pointer to the variadic `pa_proplist_setf()` function.
+///
+/// Variadic functions cannot be wrapped, thus with the `dlopen` feature `pa_proplist_setf()` itself
+/// is not available, and this resolves it through the dynamic loader instead (panicking if the
+/// library could not be loaded). Without that feature this simply gives the function linked.
+#[cfg(not(feature = "dlopen"))]
+#[inline]
+pub fn pa_proplist_setf_ptr() -> pa_proplist_setf_t {
+    pa_proplist_setf
+}
+
+/// Gets a pointer to the variadic `pa_proplist_setf()` function.
+///
+/// Variadic functions cannot be wrapped, thus with the `dlopen` feature `pa_proplist_setf()` itself
+/// is not available, and this resolves it through the dynamic loader instead (panicking if the
+/// library could not be loaded). Without that feature this simply gives the function linked.
+#[cfg(feature = "dlopen")]
+pub fn pa_proplist_setf_ptr() -> pa_proplist_setf_t {
+    static SYMBOL: std::sync::atomic::AtomicPtr<std::os::raw::c_void> =
+        std::sync::atomic::AtomicPtr::new(std::ptr::null_mut());
+    let ptr = crate::dynamic::resolve(&SYMBOL, b"pa_proplist_setf\0");
+    unsafe { std::mem::transmute::<*mut std::os::raw::c_void, pa_proplist_setf_t>(ptr) }
+}
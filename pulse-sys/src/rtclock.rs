@@ -13,7 +13,6 @@
 
 //! Monotonic clock utilities.
 
-#[link(name = "pulse")]
-extern "C" {
+pa_extern! {
     pub fn pa_rtclock_now() -> crate::sample::pa_usec_t;
 }
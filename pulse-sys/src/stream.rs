@@ -135,8 +135,7 @@ pub type pa_stream_notify_cb_t = Option<extern "C" fn(p: *mut pa_stream, userdat
 pub type pa_stream_event_cb_t = Option<extern "C" fn(p: *mut pa_stream, name: *const c_char, pl: *mut pa_proplist, userdata: *mut c_void)>;
 
 #[rustfmt::skip]
-#[link(name = "pulse")]
-extern "C" {
+pa_extern! {
     pub fn pa_stream_connect_upload(s: *mut pa_stream, length: usize) -> i32;
     pub fn pa_stream_finish_upload(s: *mut pa_stream) -> i32;
 
@@ -27,8 +27,7 @@ pub const PA_USEC_INVALID: pa_usec_t = std::u64::MAX;
 
 pub const PA_USEC_MAX: pa_usec_t = std::u64::MAX - 1;
 
-#[link(name = "pulse")]
-extern "C" {
+pa_extern! {
     pub fn pa_gettimeofday(tv: *mut timeval) -> *mut timeval;
     pub fn pa_timeval_diff(a: *const timeval, b: *const timeval) -> pa_usec_t;
     pub fn pa_timeval_cmp(a: *const timeval, b: *const timeval) -> i32;
@@ -15,8 +15,7 @@
 
 use std::os::raw::c_char;
 
-#[link(name = "pulse")]
-extern "C" {
+pa_extern! {
     pub fn pa_utf8_valid(s: *const c_char) -> *mut c_char;
     pub fn pa_ascii_valid(s: *const c_char) -> *mut c_char;
     pub fn pa_utf8_filter(s: *const c_char) -> *mut c_char;
@@ -175,7 +175,6 @@ pub const fn pa_check_version(major: u8, minor: u8, _micro: u8) -> bool {
     (TARGET_VERSION.0 > major) || ((TARGET_VERSION.0 == major) && (TARGET_VERSION.1 > minor))
 }
 
-#[link(name = "pulse")]
-extern "C" {
+pa_extern! {
     pub fn pa_get_library_version() -> *const c_char;
 }
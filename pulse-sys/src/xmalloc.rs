@@ -43,8 +43,7 @@ pub unsafe fn pa_xrenew(p: *mut c_void, n: usize, k: usize) -> *mut c_void {
     pa_xrealloc(p, n * k)
 }
 
-#[link(name = "pulse")]
-extern "C" {
+pa_extern! {
     /// Allocates the specified number of bytes, just like `malloc()` does.
     /// However, in case of OOM, terminate.
     pub fn pa_xmalloc(l: usize) -> *mut c_void;
@@ -0,0 +1,151 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! Command-line argument handling.
+//!
+//! Options take the forms `-x`, `-x VALUE`, `--name`, `--name=VALUE` and `--name VALUE`. Anything
+//! else, including negative numbers such as relative volumes, and everything following `--`, is a
+//! positional argument.
+
+use crate::Error;
+
+/// A command-line argument.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Arg {
+    /// An option, by name (without dashes), with any value attached with `=`.
+    Option(String, Option<String>),
+    /// A positional argument.
+    Positional(String),
+}
+
+/// An iterator over command-line arguments.
+pub struct Args {
+    args: std::vec::IntoIter<String>,
+    /// Whether `--` has been seen.
+    positional_only: bool,
+}
+
+impl Args {
+    /// Takes the arguments of the process, skipping the program name.
+    pub fn from_env() -> Self {
+        Self::new(std::env::args().skip(1).collect())
+    }
+
+    /// Takes the given arguments.
+    pub fn new(args: Vec<String>) -> Self {
+        Self { args: args.into_iter(), positional_only: false }
+    }
+
+    /// Gets the value of an option, either attached to it or as the following argument.
+    pub fn value(&mut self, name: &str, attached: Option<String>) -> Result<String, Error> {
+        match attached.or_else(|| self.args.next()) {
+            Some(value) => Ok(value),
+            None => Err(format!("option `{}` requires a value", name).into()),
+        }
+    }
+}
+
+impl Iterator for Args {
+    type Item = Arg;
+
+    fn next(&mut self) -> Option<Arg> {
+        let arg = self.args.next()?;
+        if self.positional_only || arg == "-" || !arg.starts_with('-')
+            || arg[1..].starts_with(|c: char| c.is_ascii_digit() || c == '.')
+        {
+            return Some(Arg::Positional(arg));
+        }
+        if arg == "--" {
+            self.positional_only = true;
+            return self.next();
+        }
+        let name = arg.trim_start_matches('-');
+        match name.split_once('=') {
+            Some((name, value)) => Some(Arg::Option(name.to_owned(), Some(value.to_owned()))),
+            None => Some(Arg::Option(name.to_owned(), None)),
+        }
+    }
+}
+
+/// Output format.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    /// Human readable text.
+    Text,
+    /// JSON.
+    Json,
+}
+
+/// Options common to all of the tools.
+#[derive(Debug, Clone)]
+pub struct Common {
+    /// The server to connect to, if not the default.
+    pub server: Option<String>,
+    /// The client name to use.
+    pub client_name: String,
+    /// The output format.
+    pub format: Format,
+}
+
+/// Usage text for the common options.
+pub const COMMON_USAGE: &str = "\
+  -s, --server=SERVER       The server to connect to
+  -n, --client-name=NAME    How to call this client on the server
+  -f, --format=FORMAT       Output format, `text` or `json`
+  -h, --help                Show this help";
+
+impl Common {
+    /// Creates the default options for the given tool.
+    pub fn new(client_name: &str) -> Self {
+        Self { server: None, client_name: client_name.to_owned(), format: Format::Text }
+    }
+
+    /// Handles the given option if a common one, returning whether it was.
+    pub fn handle(&mut self, args: &mut Args, name: &str, attached: Option<String>)
+        -> Result<bool, Error>
+    {
+        match name {
+            "s" | "server" => self.server = Some(args.value(name, attached)?),
+            "n" | "client-name" => self.client_name = args.value(name, attached)?,
+            "f" | "format" => {
+                self.format = match args.value(name, attached)?.as_str() {
+                    "text" => Format::Text,
+                    "json" => Format::Json,
+                    f => return Err(format!("unknown format `{}`", f).into()),
+                };
+            },
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+#[test]
+fn parse() {
+    let args = ["-s", "unix:/x", "--format=json", "list", "-5%", "--", "--sinks"];
+    let mut args = Args::new(args.iter().map(|s| s.to_string()).collect());
+    let mut common = Common::new("test");
+    let mut positional = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg {
+            Arg::Option(name, attached) => {
+                assert!(common.handle(&mut args, &name, attached).unwrap());
+            },
+            Arg::Positional(p) => positional.push(p),
+        }
+    }
+    assert_eq!(common.server.as_deref(), Some("unix:/x"));
+    assert_eq!(common.format, Format::Json);
+    assert_eq!(positional, ["list", "-5%", "--sinks"]);
+
+    let mut args = Args::new(vec!["-s".into()]);
+    assert_eq!(args.next(), Some(Arg::Option("s".into(), None)));
+    assert!(args.value("s", None).is_err());
+}
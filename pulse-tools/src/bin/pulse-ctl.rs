@@ -0,0 +1,384 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! `pulse-ctl`: Lists and controls server objects, in the style of `pactl`.
+
+extern crate libpulse_binding as pulse;
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use pulse::callbacks::ListResult;
+use pulse::context::introspect::DeviceRef;
+use pulse::def::INVALID_INDEX;
+use pulse_tools::args::{Arg, Args, Common, Format, COMMON_USAGE};
+use pulse_tools::json::Value;
+use pulse_tools::session::Session;
+use pulse_tools::{describe, text, volume, Error};
+
+const NAME: &str = "pulse-ctl";
+
+const USAGE: &str = "\
+Usage: pulse-ctl [OPTIONS] COMMAND [ARGS...]
+
+Commands:
+  info                                      Show server information
+  list [KIND]                               List objects of one kind, or of all kinds: sinks,
+                                            sources, sink-inputs, source-outputs, cards,
+                                            modules, clients or samples
+  set-sink-volume SINK VOLUME...            Set the volume of a sink
+  set-source-volume SOURCE VOLUME...        Set the volume of a source
+  set-sink-input-volume INDEX VOLUME...     Set the volume of a sink input
+  set-source-output-volume INDEX VOLUME...  Set the volume of a source output
+  set-sink-mute SINK MUTE                   Set the mute state of a sink
+  set-source-mute SOURCE MUTE               Set the mute state of a source
+  set-sink-input-mute INDEX MUTE            Set the mute state of a sink input
+  set-source-output-mute INDEX MUTE         Set the mute state of a source output
+  set-default-sink SINK                     Set the default sink
+  set-default-source SOURCE                 Set the default source
+  move-sink-input INDEX SINK                Move a sink input to another sink
+  move-source-output INDEX SOURCE           Move a source output to another source
+  suspend-sink SINK BOOL                    Suspend or resume a sink
+  suspend-source SOURCE BOOL                Suspend or resume a source
+  set-card-profile CARD PROFILE             Set the active profile of a card
+  load-module NAME [ARGUMENTS...]           Load a module, printing its index
+  unload-module INDEX|NAME                  Unload a module, or all modules of a name
+  send-message RECIPIENT MESSAGE [PARAMS]   Send a message to an object, printing the response
+
+Devices are given by index or by name, with `@DEFAULT_SINK@`, `@DEFAULT_SOURCE@` and
+`@DEFAULT_MONITOR@` referring to the defaults. A volume is given either for all channels at once, or
+for each channel; each may be a percentage (`50%`), a decibel value (`0dB`), a linear factor (`0.5`)
+or a raw value (`32768`), optionally prefixed with `+` or `-` to change the current volume
+relatively (`-6dB` lowers it by 6 dB). Negative absolute decibel values thus cannot be given; use a
+percentage or linear factor instead. A mute state is one of `yes`, `no`, `on`, `off`, `true`,
+`false`, `1`, `0` or `toggle`.
+
+Options:
+";
+
+/// Fetches a single object, mapping it to the value wanted from it.
+///
+/// Expands to the value, returning failure from the enclosing function if the object was not found.
+macro_rules! fetch {
+    ($session:expr, $method:ident($($arg:expr),*), $what:expr, |$i:ident| $map:expr) => {{
+        let found = Rc::new(RefCell::new(None));
+        let found_ref = Rc::clone(&found);
+        let op = $session.context.introspect().$method($($arg,)* move |r| {
+            if let ListResult::Item($i) = r {
+                *found_ref.borrow_mut() = Some($map);
+            }
+        });
+        $session.wait(op)?;
+        let value = found.borrow_mut().take();
+        value.ok_or_else(|| format!("no such {}", $what))?
+    }};
+}
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("{}: {}", NAME, e);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Error> {
+    let mut args = Args::from_env();
+    let mut common = Common::new(NAME);
+    let mut positional = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg {
+            Arg::Option(name, _) if name == "h" || name == "help" => {
+                println!("{}{}", USAGE, COMMON_USAGE);
+                return Ok(());
+            },
+            Arg::Option(name, attached) => {
+                if !common.handle(&mut args, &name, attached)? {
+                    return Err(format!("unknown option `{}`, see --help", name).into());
+                }
+            },
+            Arg::Positional(p) => positional.push(p),
+        }
+    }
+    let (command, params) = match positional.split_first() {
+        Some((command, params)) => (command.as_str(), params),
+        None => return Err("no command given, see --help".into()),
+    };
+
+    let mut session = Session::connect(&common.client_name, common.server.as_deref())?;
+    match (command, params) {
+        ("info", []) => info(&mut session, common.format),
+        ("list", []) => list_all(&mut session, common.format),
+        ("list", [kind]) => list_one(&mut session, common.format, kind),
+        ("set-sink-volume", [sink, volume @ ..]) if !volume.is_empty() => {
+            let device = DeviceRef::parse(sink);
+            let current = fetch!(session, get_sink_info(device.clone()), "sink",
+                |i| i.volume);
+            let volume = volume::parse_channels(volume, &current)?;
+            session.apply("set sink volume", |c, cb| {
+                c.introspect().set_sink_volume(device, &volume, Some(cb))
+            })
+        },
+        ("set-source-volume", [source, volume @ ..]) if !volume.is_empty() => {
+            let device = DeviceRef::parse(source);
+            let current = fetch!(session, get_source_info(device.clone()), "source",
+                |i| i.volume);
+            let volume = volume::parse_channels(volume, &current)?;
+            session.apply("set source volume", |c, cb| {
+                c.introspect().set_source_volume(device, &volume, Some(cb))
+            })
+        },
+        ("set-sink-input-volume", [index, volume @ ..]) if !volume.is_empty() => {
+            let index = parse_index(index)?;
+            let current = fetch!(session, get_sink_input_info(index), "sink input",
+                |i| i.volume);
+            let volume = volume::parse_channels(volume, &current)?;
+            session.apply("set sink input volume", |c, cb| {
+                c.introspect().set_sink_input_volume(index, &volume, Some(cb))
+            })
+        },
+        ("set-source-output-volume", [index, volume @ ..]) if !volume.is_empty() => {
+            let index = parse_index(index)?;
+            let current = fetch!(session, get_source_output_info(index), "source output",
+                |i| i.volume);
+            let volume = volume::parse_channels(volume, &current)?;
+            session.apply("set source output volume", |c, cb| {
+                c.introspect().set_source_output_volume(index, &volume, Some(cb))
+            })
+        },
+        ("set-sink-mute", [sink, mute]) => {
+            let device = DeviceRef::parse(sink);
+            let mute = match parse_mute(mute)? {
+                Some(mute) => mute,
+                None => !fetch!(session, get_sink_info(device.clone()), "sink", |i| i.mute),
+            };
+            session.apply("set sink mute", |c, cb| {
+                c.introspect().set_sink_mute(device, mute, Some(cb))
+            })
+        },
+        ("set-source-mute", [source, mute]) => {
+            let device = DeviceRef::parse(source);
+            let mute = match parse_mute(mute)? {
+                Some(mute) => mute,
+                None => !fetch!(session, get_source_info(device.clone()), "source", |i| i.mute),
+            };
+            session.apply("set source mute", |c, cb| {
+                c.introspect().set_source_mute(device, mute, Some(cb))
+            })
+        },
+        ("set-sink-input-mute", [index, mute]) => {
+            let index = parse_index(index)?;
+            let mute = match parse_mute(mute)? {
+                Some(mute) => mute,
+                None => !fetch!(session, get_sink_input_info(index), "sink input", |i| i.mute),
+            };
+            session.apply("set sink input mute", |c, cb| {
+                c.introspect().set_sink_input_mute(index, mute, Some(cb))
+            })
+        },
+        ("set-source-output-mute", [index, mute]) => {
+            let index = parse_index(index)?;
+            let mute = match parse_mute(mute)? {
+                Some(mute) => mute,
+                None => !fetch!(session, get_source_output_info(index), "source output",
+                    |i| i.mute),
+            };
+            session.apply("set source output mute", |c, cb| {
+                c.introspect().set_source_output_mute(index, mute, Some(cb))
+            })
+        },
+        ("set-default-sink", [sink]) => {
+            session.apply("set default sink", |c, cb| c.set_default_sink(sink, cb))
+        },
+        ("set-default-source", [source]) => {
+            session.apply("set default source", |c, cb| c.set_default_source(source, cb))
+        },
+        ("move-sink-input", [index, sink]) => {
+            let index = parse_index(index)?;
+            session.apply("move sink input", |c, cb| {
+                c.introspect().move_sink_input(index, DeviceRef::parse(sink), Some(cb))
+            })
+        },
+        ("move-source-output", [index, source]) => {
+            let index = parse_index(index)?;
+            session.apply("move source output", |c, cb| {
+                c.introspect().move_source_output(index, DeviceRef::parse(source), Some(cb))
+            })
+        },
+        ("suspend-sink", [sink, suspend]) => {
+            let suspend = parse_bool(suspend)?;
+            session.apply("suspend sink", |c, cb| {
+                c.introspect().suspend_sink(DeviceRef::parse(sink), suspend, Some(cb))
+            })
+        },
+        ("suspend-source", [source, suspend]) => {
+            let suspend = parse_bool(suspend)?;
+            session.apply("suspend source", |c, cb| {
+                c.introspect().suspend_source(DeviceRef::parse(source), suspend, Some(cb))
+            })
+        },
+        ("set-card-profile", [card, profile]) => {
+            session.apply("set card profile", |c, cb| {
+                c.introspect().set_card_profile(DeviceRef::parse(card), profile, Some(cb))
+            })
+        },
+        ("load-module", [name, arguments @ ..]) => {
+            load_module(&mut session, common.format, name, &arguments.join(" "))
+        },
+        ("unload-module", [module]) => unload_module(&mut session, module),
+        ("send-message", [recipient, message]) => {
+            send_message(&mut session, common.format, recipient, message, "")
+        },
+        ("send-message", [recipient, message, parameters]) => {
+            send_message(&mut session, common.format, recipient, message, parameters)
+        },
+        _ => Err(format!("invalid command or arguments: `{}`, see --help", command).into()),
+    }
+}
+
+fn info(session: &mut Session, format: Format) -> Result<(), Error> {
+    let info = Rc::new(RefCell::new(Value::Null));
+    let info_ref = Rc::clone(&info);
+    let op = session.context.introspect().get_server_info(move |i| {
+        *info_ref.borrow_mut() = describe::server(i);
+    });
+    session.wait(op)?;
+    let info = info.replace(Value::Null);
+    match format {
+        Format::Json => println!("{:#}", info),
+        Format::Text => print!("{}", text::render("Server", &info)),
+    }
+    Ok(())
+}
+
+fn list_one(session: &mut Session, format: Format, kind: &str) -> Result<(), Error> {
+    let kind = describe::kind(kind)?;
+    let items = describe::list(session, kind)?;
+    match format {
+        Format::Json => println!("{:#}", Value::Array(items)),
+        Format::Text => print_items(kind.heading, &items),
+    }
+    Ok(())
+}
+
+fn list_all(session: &mut Session, format: Format) -> Result<(), Error> {
+    let mut all = Value::object();
+    for kind in describe::KINDS {
+        let items = describe::list(session, kind)?;
+        match format {
+            Format::Json => all.insert(kind.key, Value::Array(items)),
+            Format::Text => print_items(kind.heading, &items),
+        }
+    }
+    if format == Format::Json {
+        println!("{:#}", all);
+    }
+    Ok(())
+}
+
+fn print_items(heading: &str, items: &[Value]) {
+    for item in items {
+        let index = item.get("index").map_or_else(String::new, text::scalar);
+        println!("{}", text::render(&format!("{} #{}", heading, index), item));
+    }
+}
+
+fn load_module(session: &mut Session, format: Format, name: &str, arguments: &str)
+    -> Result<(), Error>
+{
+    let index = Rc::new(Cell::new(INVALID_INDEX));
+    let index_ref = Rc::clone(&index);
+    let op = session.context.introspect().load_module(name, arguments,
+        move |i| index_ref.set(i));
+    session.wait(op)?;
+    match index.get() {
+        INVALID_INDEX => Err(session.failure("load module").into()),
+        i if format == Format::Json => {
+            println!("{}", Value::object().with("index", i));
+            Ok(())
+        },
+        i => {
+            println!("{}", i);
+            Ok(())
+        },
+    }
+}
+
+fn unload_module(session: &mut Session, module: &str) -> Result<(), Error> {
+    let indexes = match module.parse::<u32>() {
+        Ok(index) => vec![index],
+        Err(_) => {
+            let modules = describe::list(session, describe::kind("modules")?)?;
+            let matching: Vec<u32> = modules.iter()
+                .filter(|m| m.get("name") == Some(&Value::from(module)))
+                .filter_map(|m| match m.get("index") {
+                    Some(Value::UInt(i)) => Some(*i as u32),
+                    _ => None,
+                })
+                .collect();
+            if matching.is_empty() {
+                return Err(format!("no module named `{}` is loaded", module).into());
+            }
+            matching
+        },
+    };
+    for index in indexes {
+        session.apply("unload module", |c, cb| c.introspect().unload_module(index, cb))?;
+    }
+    Ok(())
+}
+
+fn send_message(session: &mut Session, format: Format, recipient: &str, message: &str,
+    parameters: &str) -> Result<(), Error>
+{
+    let response = Rc::new(RefCell::new(None));
+    let response_ref = Rc::clone(&response);
+    let op = session.context.introspect().try_send_message_to_object(recipient, message,
+        parameters, move |success, r| *response_ref.borrow_mut() = Some((success, r)))
+        .ok_or("the client library does not support messaging")?;
+    session.wait(op)?;
+    let response = response.borrow_mut().take();
+    match response {
+        Some((true, r)) => {
+            match format {
+                Format::Json => println!("{}", Value::object().with("response", r)),
+                Format::Text => println!("{}", r.unwrap_or_default()),
+            }
+            Ok(())
+        },
+        _ => Err(session.failure("send message").into()),
+    }
+}
+
+fn parse_index(s: &str) -> Result<u32, Error> {
+    s.parse().map_err(|_| format!("invalid index `{}`", s).into())
+}
+
+fn parse_bool(s: &str) -> Result<bool, Error> {
+    match s {
+        "1" | "yes" | "true" | "on" => Ok(true),
+        "0" | "no" | "false" | "off" => Ok(false),
+        _ => Err(format!("invalid boolean `{}`", s).into()),
+    }
+}
+
+/// Parses a mute state, giving `None` for `toggle`.
+fn parse_mute(s: &str) -> Result<Option<bool>, Error> {
+    match s {
+        "toggle" => Ok(None),
+        s => parse_bool(s).map(Some),
+    }
+}
+
+#[test]
+fn mute_parsing() {
+    assert_eq!(parse_mute("toggle").unwrap(), None);
+    assert_eq!(parse_mute("on").unwrap(), Some(true));
+    assert_eq!(parse_mute("0").unwrap(), Some(false));
+    assert!(parse_mute("maybe").is_err());
+}
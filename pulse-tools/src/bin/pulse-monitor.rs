@@ -0,0 +1,430 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! `pulse-monitor`: Reports changes to server objects, in the style of `pactl subscribe`.
+//!
+//! Each event is resolved to the current state of the object concerned, and reported along with
+//! those of its members that changed since it was last seen, one event per line.
+
+extern crate libpulse_binding as pulse;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use pulse::callbacks::ListResult;
+use pulse::context::introspect::Introspector;
+use pulse::context::subscribe::{Facility, InterestMaskSet, Operation};
+use pulse::context::{ext_device_manager, ext_device_restore, ext_stream_restore};
+use pulse::mainloop::standard::IterateResult;
+use pulse_tools::args::{Arg, Args, Common, Format, COMMON_USAGE};
+use pulse_tools::json::Value;
+use pulse_tools::session::Session;
+use pulse_tools::{describe, text, Error};
+
+const NAME: &str = "pulse-monitor";
+
+const USAGE: &str = "\
+Usage: pulse-monitor [OPTIONS]
+
+Reports changes to server objects, along with those of the databases of the stream restore,
+device restore and device manager modules where loaded, until interrupted. Each event is given
+on its own line, with its time, the facility and operation, the index and name of the object
+concerned, and those of its members that changed. JSON is the default output format.
+
+Options:
+";
+
+/// Facility name of the stream restore database.
+const STREAM_RESTORE: &str = "stream_restore";
+/// Facility name of the device restore database.
+const DEVICE_RESTORE: &str = "device_restore";
+/// Facility name of the device manager database.
+const DEVICE_MANAGER: &str = "device_manager";
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("{}: {}", NAME, e);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Error> {
+    let mut args = Args::from_env();
+    let mut common = Common::new(NAME);
+    common.format = Format::Json;
+    while let Some(arg) = args.next() {
+        match arg {
+            Arg::Option(name, _) if name == "h" || name == "help" => {
+                println!("{}{}", USAGE, COMMON_USAGE);
+                return Ok(());
+            },
+            Arg::Option(name, attached) => {
+                if !common.handle(&mut args, &name, attached)? {
+                    return Err(format!("unknown option `{}`, see --help", name).into());
+                }
+            },
+            Arg::Positional(p) => return Err(format!("unexpected argument `{}`", p).into()),
+        }
+    }
+
+    let mut session = Session::connect(&common.client_name, common.server.as_deref())?;
+    let monitor = Rc::new(RefCell::new(Monitor::new(common.format)));
+
+    // Subscribe before taking stock, such that nothing is missed in between
+    let introspector = session.context.introspect();
+    let monitor_ref = Rc::clone(&monitor);
+    session.context.set_subscribe_callback(Some(Box::new(move |facility, operation, index| {
+        if let (Some(facility), Some(operation)) = (facility, operation) {
+            resolve(&introspector, &monitor_ref, facility, operation, index);
+        }
+    })));
+    session.apply("subscribe", |c, cb| c.subscribe(InterestMaskSet::ALL, cb))?;
+    for kind in describe::KINDS {
+        for item in describe::list(&mut session, kind)? {
+            let index = item.get("index").map_or_else(String::new, text::scalar);
+            monitor.borrow_mut().cache.insert((facility_name(kind.facility), index), item);
+        }
+    }
+
+    // The extensions are only available where their modules are loaded
+    let _stream_restore = watch_stream_restore(&mut session, &monitor);
+    let _device_restore = watch_device_restore(&mut session, &monitor);
+    let _device_manager = watch_device_manager(&mut session, &monitor);
+
+    loop {
+        match session.mainloop.iterate(true) {
+            IterateResult::Success(_) => {},
+            IterateResult::Quit(_) => return Ok(()),
+            IterateResult::Err(e) => return Err(e.into()),
+        }
+        if !session.context.get_state().is_good() {
+            return Err(session.failure("monitoring").into());
+        }
+    }
+}
+
+/// Looks up the object concerned by an event, reporting it once known.
+///
+/// Objects removed before being looked up are reported upon removal only.
+fn resolve(introspector: &Introspector, monitor: &Rc<RefCell<Monitor>>, facility: Facility,
+    operation: Operation, index: u32)
+{
+    let event = Event { timestamp: timestamp(SystemTime::now()), facility, operation, index };
+    if operation == Operation::Removed {
+        monitor.borrow_mut().report(&event, None);
+        return;
+    }
+    let monitor = Rc::clone(monitor);
+    macro_rules! lookup {
+        ($method:ident, $describe:path) => {{
+            let _ = introspector.$method(index, move |r| {
+                if let ListResult::Item(i) = r {
+                    monitor.borrow_mut().report(&event, Some($describe(i)));
+                }
+            });
+        }};
+    }
+    match facility {
+        Facility::Sink => lookup!(get_sink_info_by_index, describe::sink),
+        Facility::Source => lookup!(get_source_info_by_index, describe::source),
+        Facility::SinkInput => lookup!(get_sink_input_info, describe::sink_input),
+        Facility::SourceOutput => lookup!(get_source_output_info, describe::source_output),
+        Facility::Module => lookup!(get_module_info, describe::module),
+        Facility::Client => lookup!(get_client_info, describe::client),
+        Facility::SampleCache => lookup!(get_sample_info_by_index, describe::sample),
+        Facility::Card => lookup!(get_card_info_by_index, describe::card),
+        Facility::Server => {
+            let _ = introspector.get_server_info(move |i| {
+                monitor.borrow_mut().report(&event, Some(describe::server(i)));
+            });
+        },
+    }
+}
+
+/// Watches the stream restore database, if available.
+fn watch_stream_restore(session: &mut Session, monitor: &Rc<RefCell<Monitor>>)
+    -> Option<ext_stream_restore::StreamRestore>
+{
+    let mut reader = session.context.stream_restore();
+    let monitor = Rc::clone(monitor);
+    let mut sync = move || {
+        let monitor = Rc::clone(&monitor);
+        let timestamp = timestamp(SystemTime::now());
+        let mut entries = Vec::new();
+        let _ = reader.read(move |r| match r {
+            ListResult::Item(i) => entries.push(describe::stream_restore_entry(i)),
+            ListResult::End => {
+                let entries = std::mem::take(&mut entries).into_iter()
+                    .map(|e| (e.get("name").map_or_else(String::new, text::scalar), e));
+                monitor.borrow_mut().sync(&timestamp, STREAM_RESTORE, entries.collect());
+            },
+            ListResult::Error => {},
+        });
+    };
+    sync();
+    let mut ext = session.context.stream_restore();
+    ext.set_subscribe_cb(sync);
+    session.apply("subscribe to stream restore", |c, cb| c.stream_restore().subscribe(true, cb))
+        .ok().map(|_| ext)
+}
+
+/// Watches the device restore database, if available.
+fn watch_device_restore(session: &mut Session, monitor: &Rc<RefCell<Monitor>>)
+    -> Option<ext_device_restore::DeviceRestore>
+{
+    let mut reader = session.context.device_restore();
+    let monitor = Rc::clone(monitor);
+    let mut sync = move || {
+        let monitor = Rc::clone(&monitor);
+        let timestamp = timestamp(SystemTime::now());
+        let mut entries = Vec::new();
+        let _ = reader.read_formats_all(move |r| match r {
+            ListResult::Item(i) => entries.push(describe::device_restore_entry(i)),
+            ListResult::End => {
+                let entries = std::mem::take(&mut entries).into_iter().map(|e| {
+                    let key = |k| e.get(k).map_or_else(String::new, text::scalar);
+                    (format!("{}:{}", key("type"), key("index")), e)
+                });
+                monitor.borrow_mut().sync(&timestamp, DEVICE_RESTORE, entries.collect());
+            },
+            ListResult::Error => {},
+        });
+    };
+    sync();
+    let mut ext = session.context.device_restore();
+    ext.set_subscribe_cb(move |_, _| sync());
+    session.apply("subscribe to device restore", |c, cb| c.device_restore().subscribe(true, cb))
+        .ok().map(|_| ext)
+}
+
+/// Watches the device manager database, if available.
+fn watch_device_manager(session: &mut Session, monitor: &Rc<RefCell<Monitor>>)
+    -> Option<ext_device_manager::DeviceManager>
+{
+    let mut reader = session.context.device_manager();
+    let monitor = Rc::clone(monitor);
+    let mut sync = move || {
+        let monitor = Rc::clone(&monitor);
+        let timestamp = timestamp(SystemTime::now());
+        let mut entries = Vec::new();
+        let _ = reader.read(move |r| match r {
+            ListResult::Item(i) => entries.push(describe::device_manager_entry(i)),
+            ListResult::End => {
+                let entries = std::mem::take(&mut entries).into_iter()
+                    .map(|e| (e.get("name").map_or_else(String::new, text::scalar), e));
+                monitor.borrow_mut().sync(&timestamp, DEVICE_MANAGER, entries.collect());
+            },
+            ListResult::Error => {},
+        });
+    };
+    sync();
+    let mut ext = session.context.device_manager();
+    ext.set_subscribe_cb(sync);
+    session.apply("subscribe to device manager", |c, cb| c.device_manager().subscribe(true, cb))
+        .ok().map(|_| ext)
+}
+
+/// An event on a server object.
+struct Event {
+    /// When the event was received.
+    timestamp: String,
+    facility: Facility,
+    operation: Operation,
+    index: u32,
+}
+
+/// Reports events, remembering the last seen state of each object.
+struct Monitor {
+    format: Format,
+    /// The last seen state of each object, by facility name and index or name.
+    cache: HashMap<(&'static str, String), Value>,
+    /// Whether the extension databases have been read at least once, by facility name.
+    synced: Vec<&'static str>,
+}
+
+impl Monitor {
+    fn new(format: Format) -> Self {
+        Self { format, cache: HashMap::new(), synced: Vec::new() }
+    }
+
+    /// Reports an event, given the current state of the object if not removed.
+    fn report(&mut self, event: &Event, current: Option<Value>) {
+        let facility = facility_name(event.facility);
+        let operation = match event.operation {
+            Operation::New => "new",
+            Operation::Changed => "change",
+            Operation::Removed => "remove",
+        };
+        let key = (facility, event.index.to_string());
+        let previous = match current.clone() {
+            Some(current) => self.cache.insert(key, current),
+            None => self.cache.remove(&key),
+        };
+        self.emit(&event.timestamp, facility, operation, Some(event.index), previous, current);
+    }
+
+    /// Reports the differences between the given entries of an extension database and those last
+    /// seen, as events by entry, with the first reading taken silently.
+    fn sync(&mut self, timestamp: &str, facility: &'static str, entries: Vec<(String, Value)>) {
+        let report = self.synced.contains(&facility);
+        if !report {
+            self.synced.push(facility);
+        }
+        let mut removed: Vec<String> = self.cache.keys().filter(|(f, _)| *f == facility)
+            .map(|(_, name)| name.clone()).collect();
+        for (name, entry) in entries {
+            removed.retain(|n| *n != name);
+            let previous = self.cache.insert((facility, name), entry.clone());
+            let operation = match &previous {
+                None => "new",
+                Some(previous) if *previous != entry => "change",
+                Some(_) => continue,
+            };
+            if report {
+                let index = entry_index(&entry);
+                self.emit(timestamp, facility, operation, index, previous, Some(entry));
+            }
+        }
+        removed.sort();
+        for name in removed {
+            let previous = self.cache.remove(&(facility, name));
+            if report {
+                let index = previous.as_ref().and_then(entry_index);
+                self.emit(timestamp, facility, "remove", index, previous, None);
+            }
+        }
+    }
+
+    fn emit(&self, timestamp: &str, facility: &str, operation: &str, index: Option<u32>,
+        previous: Option<Value>, current: Option<Value>)
+    {
+        let name = current.as_ref().or(previous.as_ref()).and_then(object_name);
+        let changed = match &current {
+            Some(current) => changes(previous.as_ref(), current),
+            None => Value::object(),
+        };
+        let line = Value::object()
+            .with("timestamp", timestamp)
+            .with("facility", facility)
+            .with("operation", operation)
+            .with("index", index)
+            .with("name", name)
+            .with("changed", changed);
+        match self.format {
+            Format::Json => println!("{}", line),
+            Format::Text => print_text(&line),
+        }
+    }
+}
+
+fn print_text(line: &Value) {
+    let get = |k| line.get(k).map_or_else(String::new, text::scalar);
+    let mut out = format!("{} {} {} #{}", get("timestamp"), get("operation"), get("facility"),
+        get("index"));
+    if let Some(Value::String(name)) = line.get("name") {
+        out.push_str(&format!(" ({})", name));
+    }
+    if let Some(Value::Object(members)) = line.get("changed") {
+        let names: Vec<&str> = members.iter().map(|(k, _)| k.as_str()).collect();
+        if !names.is_empty() {
+            out.push_str(&format!(": {}", names.join(", ")));
+        }
+    }
+    println!("{}", out);
+}
+
+/// Gets the members of an object which differ from those of its previous state, or all of them if
+/// not previously seen.
+fn changes(previous: Option<&Value>, current: &Value) -> Value {
+    let mut changed = Value::object();
+    if let Value::Object(members) = current {
+        for (key, value) in members {
+            if previous.and_then(|p| p.get(key)) != Some(value) {
+                changed.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    changed
+}
+
+fn object_name(value: &Value) -> Option<String> {
+    match value.get("name").or_else(|| value.get("server_name")) {
+        Some(Value::String(name)) => Some(name.clone()),
+        _ => None,
+    }
+}
+
+fn entry_index(value: &Value) -> Option<u32> {
+    match value.get("index") {
+        Some(Value::UInt(i)) => Some(*i as u32),
+        _ => None,
+    }
+}
+
+fn facility_name(facility: Facility) -> &'static str {
+    match facility {
+        Facility::Sink => "sink",
+        Facility::Source => "source",
+        Facility::SinkInput => "sink_input",
+        Facility::SourceOutput => "source_output",
+        Facility::Module => "module",
+        Facility::Client => "client",
+        Facility::SampleCache => "sample_cache",
+        Facility::Server => "server",
+        Facility::Card => "card",
+    }
+}
+
+/// Formats a time as an RFC 3339 UTC timestamp, with microsecond precision.
+fn timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (days, secs_of_day) = ((secs / 86_400) as i64, secs % 86_400);
+    // Civil date from days since the epoch, per Howard Hinnant’s algorithm
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:06}Z", year, month, day, secs_of_day / 3600,
+        secs_of_day / 60 % 60, secs_of_day % 60, since_epoch.subsec_micros())
+}
+
+#[test]
+fn timestamps() {
+    use std::time::Duration;
+    assert_eq!(timestamp(UNIX_EPOCH), "1970-01-01T00:00:00.000000Z");
+    assert_eq!(timestamp(UNIX_EPOCH + Duration::from_micros(951_782_400_000_001)),
+        "2000-02-29T00:00:00.000001Z");
+    assert_eq!(timestamp(UNIX_EPOCH + Duration::from_secs(1_792_410_645)),
+        "2026-10-19T11:50:45.000000Z");
+}
+
+#[test]
+fn changed_members() {
+    let old = Value::object().with("name", "out").with("mute", false).with("volume", 1u32);
+    let new = Value::object().with("name", "out").with("mute", true).with("volume", 1u32);
+    assert_eq!(changes(Some(&old), &new), Value::object().with("mute", true));
+    assert_eq!(changes(None, &new), new);
+
+    let mut monitor = Monitor::new(Format::Json);
+    let entry = |mute: bool| Value::object().with("name", "x").with("mute", mute);
+    monitor.sync("t", STREAM_RESTORE, vec![("x".into(), entry(false))]);
+    assert_eq!(monitor.cache.get(&(STREAM_RESTORE, "x".to_owned())), Some(&entry(false)));
+    monitor.sync("t", STREAM_RESTORE, vec![("x".into(), entry(true))]);
+    assert_eq!(monitor.cache.get(&(STREAM_RESTORE, "x".to_owned())), Some(&entry(true)));
+    monitor.sync("t", STREAM_RESTORE, Vec::new());
+    assert!(monitor.cache.is_empty());
+}
@@ -0,0 +1,239 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! `pulse-play`: Plays a WAV file or raw audio data, in the style of `paplay`.
+//!
+//! Data is written from the stream write callback, directly into memory obtained with
+//! `begin_write()`, as much as the server requests at a time, in whole frames. Once the input is
+//! exhausted, the stream is drained before disconnecting, such that nothing is cut off.
+
+extern crate libpulse_binding as pulse;
+
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::rc::Rc;
+use pulse::proplist::properties;
+use pulse::sample::{Format, Spec};
+use pulse::stream::{SeekMode, Stream};
+use pulse::wav;
+use pulse_tools::args::{Arg, Args, Common, COMMON_USAGE};
+use pulse_tools::session::Session;
+use pulse_tools::stream::{StreamOptions, STREAM_USAGE};
+use pulse_tools::Error;
+
+const NAME: &str = "pulse-play";
+
+const USAGE: &str = "\
+Usage: pulse-play [OPTIONS] [FILE]
+
+Plays a WAV file, or raw data with `--raw`, from the given file or else standard input. The sample
+specification and channel map of a WAV file are taken from it, though the channel map may be
+overridden, while raw data defaults to `s16le`, 44100 Hz, stereo.
+
+Options:
+";
+
+/// The default specification of raw data.
+const RAW_SPEC: Spec = Spec { format: Format::S16le, rate: 44100, channels: 2 };
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("{}: {}", NAME, e);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Error> {
+    let mut args = Args::from_env();
+    let mut common = Common::new(NAME);
+    let mut options = StreamOptions::default();
+    let mut file = None;
+    while let Some(arg) = args.next() {
+        match arg {
+            Arg::Option(name, _) if name == "h" || name == "help" => {
+                println!("{}{}\n{}", USAGE, STREAM_USAGE, COMMON_USAGE);
+                return Ok(());
+            },
+            Arg::Option(name, attached) => {
+                if !options.handle(&mut args, &name, attached.clone())?
+                    && !common.handle(&mut args, &name, attached)?
+                {
+                    return Err(format!("unknown option `{}`, see --help", name).into());
+                }
+            },
+            Arg::Positional(p) if file.is_none() => file = Some(p),
+            Arg::Positional(p) => return Err(format!("unexpected argument `{}`", p).into()),
+        }
+    }
+
+    let file = file.filter(|f| f != "-");
+    let input: Box<dyn Read> = match &file {
+        Some(path) => Box::new(BufReader::new(File::open(path)
+            .map_err(|e| format!("failed to open `{}`: {}", path, e))?)),
+        None => Box::new(io::stdin()),
+    };
+    let (input, spec, map): (Box<dyn Read>, _, _) = match options.raw {
+        true => {
+            let spec = options.spec(RAW_SPEC)?;
+            let map = options.map(&spec, None)?;
+            (input, spec, map)
+        },
+        false => {
+            if options.format.is_some() || options.rate.is_some() || options.channels.is_some() {
+                return Err("the sample specification is taken from WAV files".into());
+            }
+            let reader = wav::Reader::new(input)?;
+            let spec = *reader.spec();
+            let map = options.map(&spec, Some(reader.map()))?;
+            (Box::new(reader), spec, map)
+        },
+    };
+
+    let media_name = file.as_deref().unwrap_or("Standard input");
+    let mut proplist = options.proplist(media_name, "music")?;
+    if let Some(path) = &file {
+        let _ = proplist.set_str(properties::MEDIA_FILENAME, path);
+    }
+
+    let mut session = Session::connect(&common.client_name, common.server.as_deref())?;
+    let stream_name = options.name.as_deref().unwrap_or(media_name);
+    let stream = Stream::new_with_proplist(&mut session.context, stream_name, &spec, Some(&map),
+        &mut proplist).ok_or("failed to create stream")?;
+    let stream = Rc::new(RefCell::new(stream));
+
+    let feeder = Rc::new(RefCell::new(Feeder::new(input, spec.frame_size())));
+    {
+        let feeder = Rc::clone(&feeder);
+        let weak_stream = Rc::downgrade(&stream);
+        stream.borrow_mut().set_write_callback(Some(Box::new(move |nbytes| {
+            if let Some(stream) = weak_stream.upgrade() {
+                feeder.borrow_mut().feed(&mut stream.borrow_mut(), nbytes);
+            }
+        })));
+    }
+
+    let (attr, flags) = options.buffer_attr(&spec, true);
+    let volume = options.channel_volumes(spec.channels);
+    stream.borrow_mut().connect_playback(options.device.as_deref(), attr.as_ref(), flags,
+        volume.as_ref(), None).map_err(|_| failed(&stream, "connect playback"))?;
+
+    let finished = session.mainloop.run_until(|| {
+        feeder.borrow().done || !stream.borrow().get_state().is_good()
+    }, None);
+    stream.borrow_mut().set_write_callback(None);
+    finished?;
+    if let Some(e) = feeder.borrow_mut().error.take() {
+        return Err(e);
+    }
+    if !stream.borrow().get_state().is_good() {
+        return Err(failed(&stream, "playback"));
+    }
+
+    // Let everything written be played before disconnecting
+    let drain = stream.borrow_mut().drain(None);
+    session.wait(drain).map_err(|_| failed(&stream, "drain"))?;
+    let _ = stream.borrow_mut().disconnect();
+    Ok(())
+}
+
+fn failed(stream: &Rc<RefCell<Stream>>, what: &'static str) -> Error {
+    pulse::error::Error::from_stream(&stream.borrow(), what).into()
+}
+
+/// Feeds the input to the stream, upon request.
+struct Feeder {
+    input: Box<dyn Read>,
+    frame_size: usize,
+    /// Whether the input is exhausted, or feeding failed.
+    done: bool,
+    /// The failure, if any.
+    error: Option<Error>,
+}
+
+impl Feeder {
+    fn new(input: Box<dyn Read>, frame_size: usize) -> Self {
+        Self { input, frame_size, done: false, error: None }
+    }
+
+    /// Writes up to the requested number of bytes, in whole frames.
+    fn feed(&mut self, stream: &mut Stream, mut nbytes: usize) {
+        while nbytes > 0 && !self.done {
+            if let Err(e) = self.feed_once(stream, &mut nbytes) {
+                self.error = Some(e);
+                self.done = true;
+            }
+        }
+    }
+
+    fn feed_once(&mut self, stream: &mut Stream, nbytes: &mut usize) -> Result<(), Error> {
+        // Write directly into memory provided by the server, saving a copy
+        let buffer = match stream.begin_write(Some(*nbytes))? {
+            Some(buffer) => buffer,
+            None => {
+                *nbytes = 0;
+                return Ok(());
+            },
+        };
+        let len = match read_full(&mut self.input, buffer) {
+            Ok(len) => len,
+            Err(e) => {
+                stream.cancel_write()?;
+                return Err(e.into());
+            },
+        };
+        if len < buffer.len() {
+            self.done = true;
+        }
+        // Any trailing partial frame is dropped, since writes must be of whole frames
+        let len = len - len % self.frame_size;
+        match len {
+            0 => stream.cancel_write()?,
+            len => stream.write(&buffer[..len], None, 0, SeekMode::Relative)?,
+        }
+        *nbytes = nbytes.saturating_sub(len);
+        Ok(())
+    }
+}
+
+/// Reads until the buffer is full or the end of the input, returning the length read.
+fn read_full(input: &mut dyn Read, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+    while len < buffer.len() {
+        match input.read(&mut buffer[len..]) {
+            Ok(0) => break,
+            Ok(n) => len += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {},
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(len)
+}
+
+#[test]
+fn full_reads() {
+    // A reader yielding at most three bytes at a time
+    struct Trickle(Vec<u8>);
+    impl Read for Trickle {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = buf.len().min(self.0.len()).min(3);
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0.drain(..n);
+            Ok(n)
+        }
+    }
+    let mut input = Trickle((0..10).collect());
+    let mut buffer = [0u8; 8];
+    assert_eq!(read_full(&mut input, &mut buffer).unwrap(), 8);
+    assert_eq!(read_full(&mut input, &mut buffer).unwrap(), 2);
+    assert_eq!(&buffer[..2], &[8, 9]);
+    assert_eq!(read_full(&mut input, &mut buffer).unwrap(), 0);
+}
+
@@ -0,0 +1,240 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! `pulse-record`: Records to a WAV file or raw audio data, in the style of `parec`.
+//!
+//! Data is taken from the stream read callback, fragment by fragment with `peek()`, each being
+//! discarded once written out. Holes in the record buffer are written as silence, such that the
+//! timing of what follows is preserved. Recording continues until interrupted.
+
+extern crate libpulse_binding as pulse;
+
+use std::cell::{Cell, RefCell};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::rc::Rc;
+use pulse::mainloop::signal::{Event, MainloopSignals};
+use pulse::proplist::properties;
+use pulse::sample::{Format, Spec};
+use pulse::stream::{PeekResult, Stream};
+use pulse::wav;
+use pulse_tools::args::{Arg, Args, Common, COMMON_USAGE};
+use pulse_tools::session::Session;
+use pulse_tools::stream::{StreamOptions, STREAM_USAGE};
+use pulse_tools::Error;
+
+const NAME: &str = "pulse-record";
+
+const USAGE: &str = "\
+Usage: pulse-record [OPTIONS] [FILE]
+
+Records to a WAV file, or raw data with `--raw`, at the given path or else standard output, until
+interrupted. The sample specification defaults to `s16le`, 44100 Hz, stereo. A WAV file written to
+standard output is left with its lengths unknown, since they cannot be filled in afterwards.
+
+Options:
+";
+
+/// The default specification to record with.
+const DEFAULT_SPEC: Spec = Spec { format: Format::S16le, rate: 44100, channels: 2 };
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("{}: {}", NAME, e);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Error> {
+    let mut args = Args::from_env();
+    let mut common = Common::new(NAME);
+    let mut options = StreamOptions::default();
+    let mut file = None;
+    while let Some(arg) = args.next() {
+        match arg {
+            Arg::Option(name, _) if name == "h" || name == "help" => {
+                println!("{}{}\n{}", USAGE, STREAM_USAGE, COMMON_USAGE);
+                return Ok(());
+            },
+            Arg::Option(name, attached) => {
+                if !options.handle(&mut args, &name, attached.clone())?
+                    && !common.handle(&mut args, &name, attached)?
+                {
+                    return Err(format!("unknown option `{}`, see --help", name).into());
+                }
+            },
+            Arg::Positional(p) if file.is_none() => file = Some(p),
+            Arg::Positional(p) => return Err(format!("unexpected argument `{}`", p).into()),
+        }
+    }
+
+    let spec = options.spec(DEFAULT_SPEC)?;
+    let map = options.map(&spec, None)?;
+    let file = file.filter(|f| f != "-");
+    let output = match &file {
+        Some(path) => {
+            let file = File::create(path)
+                .map_err(|e| format!("failed to create `{}`: {}", path, e))?;
+            let file = BufWriter::new(file);
+            match options.raw {
+                true => Output::Raw(Box::new(file)),
+                false => Output::WavFile(wav::Writer::new(file, &spec, &map)?),
+            }
+        },
+        None => match options.raw {
+            true => Output::Raw(Box::new(io::stdout())),
+            false => Output::WavStream(wav::Writer::new(io::stdout(), &spec, &map)?),
+        },
+    };
+
+    let media_name = file.as_deref().unwrap_or("Standard output");
+    let mut proplist = options.proplist(media_name, "production")?;
+    if let Some(path) = &file {
+        let _ = proplist.set_str(properties::MEDIA_FILENAME, path);
+    }
+
+    let mut session = Session::connect(&common.client_name, common.server.as_deref())?;
+    let stream_name = options.name.as_deref().unwrap_or(media_name);
+    let stream = Stream::new_with_proplist(&mut session.context, stream_name, &spec, Some(&map),
+        &mut proplist).ok_or("failed to create stream")?;
+    let stream = Rc::new(RefCell::new(stream));
+
+    let output = Rc::new(RefCell::new(output));
+    let error = Rc::new(RefCell::new(None));
+    {
+        let output = Rc::clone(&output);
+        let error = Rc::clone(&error);
+        let weak_stream = Rc::downgrade(&stream);
+        let silence = silence_byte(spec.format);
+        stream.borrow_mut().set_read_callback(Some(Box::new(move |_| {
+            let stream = match weak_stream.upgrade() {
+                Some(stream) => stream,
+                None => return,
+            };
+            let mut error = error.borrow_mut();
+            if error.is_none() {
+                let result =
+                    drain(&mut stream.borrow_mut(), output.borrow_mut().writer(), silence);
+                *error = result.err();
+            }
+        })));
+    }
+
+    let (attr, flags) = options.buffer_attr(&spec, false);
+    stream.borrow_mut().connect_record(options.device.as_deref(), attr.as_ref(), flags)
+        .map_err(|_| failed(&stream, "connect record"))?;
+
+    // Stop recording cleanly upon interruption, such that the output is finalised
+    session.mainloop.init_signals().map_err(|_| "failed to set up signal handling")?;
+    let stop = Rc::new(Cell::new(false));
+    let _events: Vec<Event> = [libc::SIGINT, libc::SIGTERM].iter().map(|&signal| {
+        let stop = Rc::clone(&stop);
+        Event::new(signal, move |_| stop.set(true))
+    }).collect();
+
+    let finished = session.mainloop.run_until(|| {
+        stop.get() || error.borrow().is_some() || !stream.borrow().get_state().is_good()
+    }, None);
+    stream.borrow_mut().set_read_callback(None);
+    finished?;
+    if let Some(e) = error.borrow_mut().take() {
+        return Err(e);
+    }
+    if !stream.borrow().get_state().is_good() {
+        return Err(failed(&stream, "recording"));
+    }
+    let _ = stream.borrow_mut().disconnect();
+
+    match Rc::try_unwrap(output).map_err(|_| "output still in use")?.into_inner() {
+        Output::WavFile(writer) => writer.finish()?.flush()?,
+        Output::WavStream(writer) => writer.into_inner()?.flush()?,
+        Output::Raw(mut writer) => writer.flush()?,
+    }
+    Ok(())
+}
+
+fn failed(stream: &Rc<RefCell<Stream>>, what: &'static str) -> Error {
+    pulse::error::Error::from_stream(&stream.borrow(), what).into()
+}
+
+/// Where recorded data goes.
+enum Output {
+    /// A WAV file, the lengths of which are filled in once finished.
+    WavFile(wav::Writer<BufWriter<File>>),
+    /// A WAV file written to a stream, the lengths of which are left unknown.
+    WavStream(wav::Writer<io::Stdout>),
+    /// Raw data.
+    Raw(Box<dyn Write>),
+}
+
+impl Output {
+    fn writer(&mut self) -> &mut dyn Write {
+        match self {
+            Output::WavFile(writer) => writer,
+            Output::WavStream(writer) => writer,
+            Output::Raw(writer) => writer,
+        }
+    }
+}
+
+/// Writes out all data readable from the stream, discarding each fragment once written.
+///
+/// Holes are written out with the given silence byte.
+fn drain(stream: &mut Stream, output: &mut dyn Write, silence: u8) -> Result<(), Error> {
+    loop {
+        match stream.peek()? {
+            PeekResult::Empty => return Ok(()),
+            PeekResult::Hole(len) => {
+                write_silence(output, len, silence)?;
+                stream.discard()?;
+            },
+            PeekResult::Data(data) => {
+                output.write_all(data)?;
+                stream.discard()?;
+            },
+        }
+    }
+}
+
+/// Gets the byte with which to fill silence in the given sample format.
+fn silence_byte(format: Format) -> u8 {
+    match format {
+        Format::U8 => 0x80,
+        Format::ALaw => 0xd5,
+        Format::ULaw => 0xff,
+        _ => 0,
+    }
+}
+
+/// Writes the given number of silence bytes.
+fn write_silence(output: &mut dyn Write, mut len: usize, silence: u8) -> io::Result<()> {
+    let buf = [silence; 4096];
+    while len > 0 {
+        let n = len.min(buf.len());
+        output.write_all(&buf[..n])?;
+        len -= n;
+    }
+    Ok(())
+}
+
+#[test]
+fn silence() {
+    let mut output = Vec::new();
+    write_silence(&mut output, 10000, silence_byte(Format::S16le)).unwrap();
+    assert_eq!(output.len(), 10000);
+    assert!(output.iter().all(|&b| b == 0));
+
+    for &(format, byte) in &[(Format::U8, 0x80), (Format::ALaw, 0xd5), (Format::ULaw, 0xff)] {
+        let mut output = Vec::new();
+        write_silence(&mut output, 5000, silence_byte(format)).unwrap();
+        assert_eq!(output.len(), 5000);
+        assert!(output.iter().all(|&b| b == byte));
+    }
+}
@@ -0,0 +1,402 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! Descriptions of server objects, as JSON values.
+//!
+//! Member names are in `snake_case`, following the fields of the introspection structures, while
+//! property list and channel names are kept as given by the server.
+
+use std::cell::{Cell, RefCell};
+use std::fmt::Debug;
+use std::rc::Rc;
+use pulse::callbacks::ListResult;
+use pulse::channelmap::{self, Position};
+use pulse::context::introspect::*;
+use pulse::context::subscribe::Facility;
+use pulse::context::{ext_device_manager, ext_device_restore, ext_stream_restore};
+use pulse::format;
+use pulse::proplist::Proplist;
+use pulse::sample;
+use pulse::time::MicroSeconds;
+use pulse::volume::{ChannelVolumes, Volume, VolumeDB};
+use crate::json::Value;
+use crate::session::Session;
+use crate::Error;
+
+/// A kind of server object that may be listed.
+#[derive(Debug)]
+pub struct Kind {
+    /// The name used on the command line, such as `sink-inputs`.
+    pub name: &'static str,
+    /// The name used as a JSON key, such as `sink_inputs`.
+    pub key: &'static str,
+    /// The heading of an object of this kind in text output, such as `Sink Input`.
+    pub heading: &'static str,
+    /// The subscription facility of objects of this kind.
+    pub facility: Facility,
+}
+
+/// The kinds of server object that may be listed.
+pub const KINDS: &[Kind] = &[
+    Kind { name: "sinks", key: "sinks", heading: "Sink", facility: Facility::Sink },
+    Kind { name: "sources", key: "sources", heading: "Source", facility: Facility::Source },
+    Kind { name: "sink-inputs", key: "sink_inputs", heading: "Sink Input",
+        facility: Facility::SinkInput },
+    Kind { name: "source-outputs", key: "source_outputs", heading: "Source Output",
+        facility: Facility::SourceOutput },
+    Kind { name: "cards", key: "cards", heading: "Card", facility: Facility::Card },
+    Kind { name: "modules", key: "modules", heading: "Module", facility: Facility::Module },
+    Kind { name: "clients", key: "clients", heading: "Client", facility: Facility::Client },
+    Kind { name: "samples", key: "samples", heading: "Sample", facility: Facility::SampleCache },
+];
+
+/// Gets a kind of object by its command-line name.
+pub fn kind(name: &str) -> Result<&'static Kind, Error> {
+    KINDS.iter().find(|k| k.name == name)
+        .ok_or_else(|| format!("unknown kind of object `{}`", name).into())
+}
+
+/// Collects the descriptions of all objects listed by the given introspection method.
+///
+/// Expands to the descriptions, returning failure from the enclosing function upon error.
+macro_rules! collect {
+    ($session:expr, $method:ident, $describe:path) => {{
+        let items = Rc::new(RefCell::new(Vec::new()));
+        let failed = Rc::new(Cell::new(false));
+        let (items_ref, failed_ref) = (Rc::clone(&items), Rc::clone(&failed));
+        let op = $session.context.introspect().$method(move |r| match r {
+            ListResult::Item(i) => items_ref.borrow_mut().push($describe(i)),
+            ListResult::Error => failed_ref.set(true),
+            ListResult::End => {},
+        });
+        $session.wait(op)?;
+        if failed.get() {
+            return Err($session.failure(stringify!($method)).into());
+        }
+        items.replace(Vec::new())
+    }};
+}
+
+/// Lists and describes all objects of the given kind.
+pub fn list(session: &mut Session, kind: &Kind) -> Result<Vec<Value>, Error> {
+    Ok(match kind.facility {
+        Facility::Sink => collect!(session, get_sink_info_list, sink),
+        Facility::Source => collect!(session, get_source_info_list, source),
+        Facility::SinkInput => collect!(session, get_sink_input_info_list, sink_input),
+        Facility::SourceOutput => collect!(session, get_source_output_info_list, source_output),
+        Facility::Card => collect!(session, get_card_info_list, card),
+        Facility::Module => collect!(session, get_module_info_list, module),
+        Facility::Client => collect!(session, get_client_info_list, client),
+        Facility::SampleCache => collect!(session, get_sample_info_list, sample),
+        Facility::Server => return Err("the server is not a kind of object to list".into()),
+    })
+}
+
+/// Describes the server.
+pub fn server(i: &ServerInfo) -> Value {
+    Value::object()
+        .with("server_name", i.server_name.clone())
+        .with("server_version", i.server_version.clone())
+        .with("user_name", i.user_name.clone())
+        .with("host_name", i.host_name.clone())
+        .with("cookie", format!("{:04x}:{:04x}", i.cookie >> 16, i.cookie & 0xffff))
+        .with("sample_spec", spec(&i.sample_spec))
+        .with("channel_map", map(&i.channel_map))
+        .with("default_sink_name", i.default_sink_name.clone())
+        .with("default_source_name", i.default_source_name.clone())
+}
+
+/// Describes a sink.
+pub fn sink(i: &SinkInfo) -> Value {
+    let ports = i.ports.iter().map(|p| port(&p.name, &p.description, p.priority, p.available));
+    Value::object()
+        .with("index", i.index)
+        .with("name", i.name.clone())
+        .with("description", i.description.clone())
+        .with("state", state(&i.state))
+        .with("driver", i.driver.clone())
+        .with("owner_module", i.owner_module)
+        .with("card", i.card)
+        .with("sample_spec", spec(&i.sample_spec))
+        .with("channel_map", map(&i.channel_map))
+        .with("mute", i.mute)
+        .with("volume", volume(&i.volume, &i.channel_map))
+        .with("base_volume", single_volume(i.base_volume))
+        .with("monitor_source", i.monitor_source)
+        .with("monitor_source_name", i.monitor_source_name.clone())
+        .with("latency_usec", micros(i.latency))
+        .with("configured_latency_usec", micros(i.configured_latency))
+        .with("flags", flags(&i.flags))
+        .with("ports", Value::Array(ports.collect()))
+        .with("active_port", i.active_port.as_ref().and_then(|p| p.name.clone()))
+        .with("formats", formats(&i.formats))
+        .with("properties", proplist(&i.proplist))
+}
+
+/// Describes a source.
+pub fn source(i: &SourceInfo) -> Value {
+    let ports = i.ports.iter().map(|p| port(&p.name, &p.description, p.priority, p.available));
+    Value::object()
+        .with("index", i.index)
+        .with("name", i.name.clone())
+        .with("description", i.description.clone())
+        .with("state", state(&i.state))
+        .with("driver", i.driver.clone())
+        .with("owner_module", i.owner_module)
+        .with("card", i.card)
+        .with("sample_spec", spec(&i.sample_spec))
+        .with("channel_map", map(&i.channel_map))
+        .with("mute", i.mute)
+        .with("volume", volume(&i.volume, &i.channel_map))
+        .with("base_volume", single_volume(i.base_volume))
+        .with("monitor_of_sink", i.monitor_of_sink)
+        .with("monitor_of_sink_name", i.monitor_of_sink_name.clone())
+        .with("latency_usec", micros(i.latency))
+        .with("configured_latency_usec", micros(i.configured_latency))
+        .with("flags", flags(&i.flags))
+        .with("ports", Value::Array(ports.collect()))
+        .with("active_port", i.active_port.as_ref().and_then(|p| p.name.clone()))
+        .with("formats", formats(&i.formats))
+        .with("properties", proplist(&i.proplist))
+}
+
+/// Describes a sink input.
+pub fn sink_input(i: &SinkInputInfo) -> Value {
+    Value::object()
+        .with("index", i.index)
+        .with("name", i.name.clone())
+        .with("driver", i.driver.clone())
+        .with("owner_module", i.owner_module)
+        .with("client", i.client)
+        .with("sink", i.sink)
+        .with("sample_spec", spec(&i.sample_spec))
+        .with("channel_map", map(&i.channel_map))
+        .with("format", i.format.print())
+        .with("corked", i.corked)
+        .with("mute", i.mute)
+        .with("volume", match i.has_volume {
+            true => volume(&i.volume, &i.channel_map),
+            false => Value::Null,
+        })
+        .with("volume_writable", i.volume_writable)
+        .with("buffer_latency_usec", micros(i.buffer_usec))
+        .with("sink_latency_usec", micros(i.sink_usec))
+        .with("resample_method", i.resample_method.clone())
+        .with("properties", proplist(&i.proplist))
+}
+
+/// Describes a source output.
+pub fn source_output(i: &SourceOutputInfo) -> Value {
+    Value::object()
+        .with("index", i.index)
+        .with("name", i.name.clone())
+        .with("driver", i.driver.clone())
+        .with("owner_module", i.owner_module)
+        .with("client", i.client)
+        .with("source", i.source)
+        .with("sample_spec", spec(&i.sample_spec))
+        .with("channel_map", map(&i.channel_map))
+        .with("format", i.format.print())
+        .with("corked", i.corked)
+        .with("mute", i.mute)
+        .with("volume", match i.has_volume {
+            true => volume(&i.volume, &i.channel_map),
+            false => Value::Null,
+        })
+        .with("volume_writable", i.volume_writable)
+        .with("buffer_latency_usec", micros(i.buffer_usec))
+        .with("source_latency_usec", micros(i.source_usec))
+        .with("resample_method", i.resample_method.clone())
+        .with("properties", proplist(&i.proplist))
+}
+
+/// Describes a card.
+pub fn card(i: &CardInfo) -> Value {
+    let ports = i.ports.iter().map(|p| {
+        port(&p.name, &p.description, p.priority, p.available)
+            .with("latency_offset_usec", p.latency_offset)
+            .with("profiles", Value::Array(p.profiles.iter()
+                .map(|p| Value::from(p.name.clone())).collect()))
+            .with("properties", proplist(&p.proplist))
+    });
+    Value::object()
+        .with("index", i.index)
+        .with("name", i.name.clone())
+        .with("driver", i.driver.clone())
+        .with("owner_module", i.owner_module)
+        .with("profiles", Value::Array(i.profiles.iter().map(profile).collect()))
+        .with("active_profile", i.active_profile.as_ref().and_then(|p| p.name.clone()))
+        .with("ports", Value::Array(ports.collect()))
+        .with("properties", proplist(&i.proplist))
+}
+
+/// Describes a module.
+pub fn module(i: &ModuleInfo) -> Value {
+    Value::object()
+        .with("index", i.index)
+        .with("name", i.name.clone())
+        .with("argument", i.argument.clone())
+        .with("n_used", i.n_used)
+        .with("properties", proplist(&i.proplist))
+}
+
+/// Describes a client.
+pub fn client(i: &ClientInfo) -> Value {
+    Value::object()
+        .with("index", i.index)
+        .with("name", i.name.clone())
+        .with("driver", i.driver.clone())
+        .with("owner_module", i.owner_module)
+        .with("properties", proplist(&i.proplist))
+}
+
+/// Describes a sample cache entry.
+pub fn sample(i: &SampleInfo) -> Value {
+    Value::object()
+        .with("index", i.index)
+        .with("name", i.name.clone())
+        .with("sample_spec", spec(&i.sample_spec))
+        .with("channel_map", map(&i.channel_map))
+        .with("volume", volume(&i.volume, &i.channel_map))
+        .with("duration_usec", micros(i.duration))
+        .with("bytes", i.bytes)
+        .with("lazy", i.lazy)
+        .with("filename", i.filename.clone())
+        .with("properties", proplist(&i.proplist))
+}
+
+/// Describes an entry of the stream database of `module-stream-restore`.
+pub fn stream_restore_entry(i: &ext_stream_restore::Info) -> Value {
+    Value::object()
+        .with("name", i.name.clone())
+        .with("device", i.device.clone())
+        .with("mute", i.mute)
+        .with("volume", volume(&i.volume, &i.channel_map))
+}
+
+/// Describes an entry of the device database of `module-device-restore`.
+pub fn device_restore_entry(i: &ext_device_restore::Info) -> Value {
+    Value::object()
+        .with("type", format!("{:?}", i.dtype).to_lowercase())
+        .with("index", i.index)
+        .with("formats", formats(&i.formats))
+}
+
+/// Describes an entry of the device database of `module-device-manager`.
+pub fn device_manager_entry(i: &ext_device_manager::Info) -> Value {
+    let mut priorities = Value::object();
+    for rp in &i.role_priorities {
+        priorities.insert(rp.role.clone().unwrap_or_default(), rp.priority);
+    }
+    Value::object()
+        .with("name", i.name.clone())
+        .with("description", i.description.clone())
+        .with("icon", i.icon.clone())
+        .with("index", i.index)
+        .with("role_priorities", priorities)
+}
+
+/// Describes a property list, with each property as a string.
+pub fn proplist(p: &Proplist) -> Value {
+    let mut v = Value::object();
+    for key in p.iter() {
+        if let Some(value) = p.get_str(&key) {
+            v.insert(key, value);
+        }
+    }
+    v
+}
+
+/// Describes a volume, per channel, by position name.
+pub fn volume(cv: &ChannelVolumes, map: &channelmap::Map) -> Value {
+    let mut v = Value::object();
+    for (i, vol) in cv.get().iter().enumerate() {
+        let position = map.get().get(i).copied().unwrap_or(Position::Invalid);
+        let name = Position::to_string(position).map_or_else(|| i.to_string(), |s| s.into_owned());
+        v.insert(name, single_volume(*vol));
+    }
+    v
+}
+
+/// Describes a single volume, as a raw value, a percentage and in decibels.
+pub fn single_volume(v: Volume) -> Value {
+    let db = VolumeDB::from(v).0;
+    Value::object()
+        .with("value", v.0)
+        .with("percent", percent(v))
+        .with("db", match db.is_finite() {
+            true => Value::from((db * 100.0).round() / 100.0),
+            false => Value::Null,
+        })
+}
+
+/// Gets a volume as a rounded percentage of the normal volume.
+pub fn percent(v: Volume) -> u64 {
+    (v.0 as u64 * 100 + Volume::NORMAL.0 as u64 / 2) / Volume::NORMAL.0 as u64
+}
+
+fn spec(s: &sample::Spec) -> Value {
+    s.print().into()
+}
+
+fn map(m: &channelmap::Map) -> Value {
+    m.print().into()
+}
+
+fn formats(f: &[format::Info]) -> Value {
+    Value::Array(f.iter().map(|f| f.print().into()).collect())
+}
+
+fn micros(t: MicroSeconds) -> Value {
+    t.0.into()
+}
+
+/// Describes an enumeration variant, in upper case.
+fn state<T: Debug>(s: &T) -> Value {
+    format!("{:?}", s).to_uppercase().into()
+}
+
+/// Describes a set of flags by name, in lower case.
+fn flags<T: Debug>(f: &T) -> Value {
+    let names = format!("{:?}", f);
+    Value::Array(names.split('|').map(str::trim)
+        .filter(|n| !n.is_empty() && *n != "(empty)" && *n != "NOFLAGS")
+        .map(|n| Value::from(n.to_lowercase())).collect())
+}
+
+fn port(name: &Option<std::borrow::Cow<str>>, description: &Option<std::borrow::Cow<str>>,
+    priority: u32, available: pulse::def::PortAvailable) -> Value
+{
+    Value::object()
+        .with("name", name.clone())
+        .with("description", description.clone())
+        .with("priority", priority)
+        .with("available", format!("{:?}", available).to_lowercase())
+}
+
+fn profile(p: &CardProfileInfo) -> Value {
+    Value::object()
+        .with("name", p.name.clone())
+        .with("description", p.description.clone())
+        .with("n_sinks", p.n_sinks)
+        .with("n_sources", p.n_sources)
+        .with("priority", p.priority)
+        .with("available", p.available)
+}
+
+#[test]
+fn volumes() {
+    assert_eq!(percent(Volume::NORMAL), 100);
+    assert_eq!(percent(Volume::MUTED), 0);
+    assert_eq!(percent(Volume(Volume::NORMAL.0 / 2)), 50);
+    let set = pulse::def::SinkFlagSet::HW_MUTE_CTRL | pulse::def::SinkFlagSet::LATENCY;
+    assert_eq!(flags(&set), Value::from(vec!["latency", "hw_mute_ctrl"]));
+    assert_eq!(flags(&pulse::def::SinkFlagSet::empty()), Value::Array(Vec::new()));
+}
@@ -0,0 +1,245 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! A minimal JSON value type and writer.
+//!
+//! Values are written compactly with `{}` formatting, and indented with `{:#}` formatting. Object
+//! members keep the order in which they were added.
+
+use std::fmt::{self, Write};
+
+/// A JSON value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// Null.
+    Null,
+    /// A boolean.
+    Bool(bool),
+    /// A signed integer.
+    Int(i64),
+    /// An unsigned integer.
+    UInt(u64),
+    /// A floating point number, written as null if not finite.
+    Float(f64),
+    /// A string.
+    String(String),
+    /// An array.
+    Array(Vec<Value>),
+    /// An object, with members in order.
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Creates an empty object.
+    pub fn object() -> Self {
+        Value::Object(Vec::new())
+    }
+
+    /// Adds a member to an object, returning it.
+    ///
+    /// Panics if not an object.
+    pub fn with<K: Into<String>, V: Into<Value>>(mut self, key: K, value: V) -> Self {
+        self.insert(key, value);
+        self
+    }
+
+    /// Adds a member to an object.
+    ///
+    /// Panics if not an object.
+    pub fn insert<K: Into<String>, V: Into<Value>>(&mut self, key: K, value: V) {
+        match self {
+            Value::Object(members) => members.push((key.into(), value.into())),
+            _ => panic!("not an object"),
+        }
+    }
+
+    /// Gets a member of an object by key.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Checks whether this is a scalar (neither an array nor an object).
+    pub fn is_scalar(&self) -> bool {
+        !matches!(self, Value::Array(_) | Value::Object(_))
+    }
+
+    fn write(&self, f: &mut fmt::Formatter, indent: Option<usize>) -> fmt::Result {
+        match self {
+            Value::Null => f.write_str("null"),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Int(i) => write!(f, "{}", i),
+            Value::UInt(u) => write!(f, "{}", u),
+            Value::Float(x) if x.is_finite() => write!(f, "{}", x),
+            Value::Float(_) => f.write_str("null"),
+            Value::String(s) => write_string(f, s),
+            Value::Array(items) => {
+                f.write_char('[')?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    newline(f, indent.map(|n| n + 1))?;
+                    item.write(f, indent.map(|n| n + 1))?;
+                }
+                if !items.is_empty() {
+                    newline(f, indent)?;
+                }
+                f.write_char(']')
+            },
+            Value::Object(members) => {
+                f.write_char('{')?;
+                for (i, (key, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    newline(f, indent.map(|n| n + 1))?;
+                    write_string(f, key)?;
+                    f.write_str(if indent.is_some() { ": " } else { ":" })?;
+                    value.write(f, indent.map(|n| n + 1))?;
+                }
+                if !members.is_empty() {
+                    newline(f, indent)?;
+                }
+                f.write_char('}')
+            },
+        }
+    }
+}
+
+fn newline(f: &mut fmt::Formatter, indent: Option<usize>) -> fmt::Result {
+    match indent {
+        Some(n) => write!(f, "\n{:1$}", "", n * 2),
+        None => Ok(()),
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write(f, if f.alternate() { Some(0) } else { None })
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Bool(b)
+    }
+}
+
+impl From<u8> for Value {
+    fn from(u: u8) -> Self {
+        Value::UInt(u as u64)
+    }
+}
+
+impl From<u32> for Value {
+    fn from(u: u32) -> Self {
+        Value::UInt(u as u64)
+    }
+}
+
+impl From<u64> for Value {
+    fn from(u: u64) -> Self {
+        Value::UInt(u)
+    }
+}
+
+impl From<usize> for Value {
+    fn from(u: usize) -> Self {
+        Value::UInt(u as u64)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(i: i32) -> Self {
+        Value::Int(i as i64)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(i: i64) -> Self {
+        Value::Int(i)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(x: f64) -> Self {
+        Value::Float(x)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::String(s.to_owned())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::String(s)
+    }
+}
+
+impl From<std::borrow::Cow<'_, str>> for Value {
+    fn from(s: std::borrow::Cow<'_, str>) -> Self {
+        Value::String(s.into_owned())
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(o: Option<T>) -> Self {
+        o.map_or(Value::Null, Into::into)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(v: Vec<T>) -> Self {
+        Value::Array(v.into_iter().map(Into::into).collect())
+    }
+}
+
+#[test]
+fn write() {
+    let v = Value::object()
+        .with("name", "a \"quoted\"\tname\u{1}")
+        .with("index", 3u32)
+        .with("offset", -2i64)
+        .with("ratio", 0.5)
+        .with("nan", f64::NAN)
+        .with("muted", false)
+        .with("card", None::<u32>)
+        .with("ports", vec!["x", "y"])
+        .with("empty", Value::object());
+    assert_eq!(v.to_string(), "{\"name\":\"a \\\"quoted\\\"\\tname\\u0001\",\"index\":3,\
+        \"offset\":-2,\"ratio\":0.5,\"nan\":null,\"muted\":false,\"card\":null,\
+        \"ports\":[\"x\",\"y\"],\"empty\":{}}");
+    assert_eq!(v.get("index"), Some(&Value::UInt(3)));
+
+    let v = Value::object().with("a", vec![1u32]).with("b", Value::Array(Vec::new()));
+    assert_eq!(format!("{:#}", v), "{\n  \"a\": [\n    1\n  ],\n  \"b\": []\n}");
+}
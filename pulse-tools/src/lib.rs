@@ -0,0 +1,31 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! Command-line tools for PulseAudio, built upon the PulseAudio Rust language binding.
+//!
+//! # About
+//!
+//! This crate holds the tools themselves, as binaries, along with the few pieces they share:
+//! command-line argument handling ([`args`]), connecting to the server and waiting upon operations
+//! ([`session`]), and describing server objects ([`describe`]) as JSON values ([`json`]), either
+//! written as such or rendered as text ([`text`]).
+
+extern crate libpulse_binding as pulse;
+
+pub mod args;
+pub mod describe;
+pub mod json;
+pub mod session;
+pub mod stream;
+pub mod text;
+pub mod volume;
+
+/// Error type of the tools.
+pub type Error = Box<dyn std::error::Error>;
@@ -0,0 +1,86 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! A connection to the server, with a standard mainloop to drive it.
+
+use pulse::context::{Context, FlagSet};
+use pulse::error::Error as PulseError;
+use pulse::mainloop::standard::{Mainloop, WaitError};
+use pulse::operation::{Batch, Operation};
+use pulse::proplist::{properties, Proplist};
+use pulse::time::MicroSeconds;
+use crate::Error;
+
+/// How long to wait for the connection to be established.
+const CONNECT_TIMEOUT_SECS: u64 = 10;
+
+/// A connected context, along with the mainloop driving it.
+pub struct Session {
+    /// The context, declared first such that it is dropped before the mainloop.
+    pub context: Context,
+    /// The mainloop.
+    pub mainloop: Mainloop,
+}
+
+impl Session {
+    /// Connects to the given server, or the default one, with the given client name.
+    pub fn connect(client_name: &str, server: Option<&str>) -> Result<Self, Error> {
+        let mut mainloop = Mainloop::new().ok_or("failed to create mainloop")?;
+        let mut proplist = Proplist::new().ok_or("failed to create proplist")?;
+        let _ = proplist.set_str(properties::APPLICATION_NAME, client_name);
+        let _ = proplist.set_str(properties::APPLICATION_ID, "org.pulse-binding-rust.tools");
+        let mut context = Context::new_with_proplist(&mainloop, client_name, &proplist)
+            .ok_or("failed to create context")?;
+        context.connect_blocking(&mut mainloop, server, FlagSet::NOAUTOSPAWN,
+            MicroSeconds::from_secs(CONNECT_TIMEOUT_SECS))
+            .map_err(|e| format!("connection failure: {}", e))?;
+        Ok(Self { context, mainloop })
+    }
+
+    /// Waits for the given operation to complete.
+    pub fn wait<C: ?Sized>(&mut self, operation: Operation<C>) -> Result<(), Error> {
+        self.mainloop.wait_for_operation(&operation).map_err(|e| self.wait_error(e))
+    }
+
+    /// Issues an operation reporting success through a callback, and waits for it to complete,
+    /// failing unless successful.
+    ///
+    /// The name of the operation is used to describe failure.
+    pub fn apply<F>(&mut self, operation: &'static str, issue: F) -> Result<(), Error>
+        where F: FnOnce(&mut Context, Box<dyn FnMut(bool)>) -> Operation<dyn FnMut(bool)>
+    {
+        let mut batch = Batch::new();
+        batch.add(|cb| issue(&mut self.context, cb));
+        match self.mainloop.wait_for_batch(&mut batch, None) {
+            Ok(()) => Ok(()),
+            Err(WaitError::Batch(_)) => Err(self.failure(operation).into()),
+            Err(e) => Err(self.wait_error(e)),
+        }
+    }
+
+    /// Describes the failure of the given operation, from the last error of the context.
+    pub fn failure(&self, operation: &'static str) -> PulseError {
+        PulseError::from_context(&self.context, operation)
+    }
+
+    /// Converts an error from waiting, describing failure of the context if it was lost.
+    fn wait_error(&self, e: WaitError) -> Error {
+        match e {
+            WaitError::Cancelled => self.failure("operation").into(),
+            e => e.into(),
+        }
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        self.context.disconnect();
+    }
+}
@@ -0,0 +1,192 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! Options shared by the tools playing and recording streams.
+
+use pulse::channelmap::{Map, MapDef};
+use pulse::def::BufferAttr;
+use pulse::proplist::{properties, Proplist};
+use pulse::sample::{Format, Spec};
+use pulse::stream::FlagSet;
+use pulse::time::MicroSeconds;
+use pulse::volume::{ChannelVolumes, Volume};
+use crate::args::Args;
+use crate::{volume, Error};
+
+/// Usage text for the stream options.
+pub const STREAM_USAGE: &str = "\
+  -d, --device=DEVICE       The sink or source to connect to, if not the default
+      --stream-name=NAME    How to call the stream on the server
+      --sample-format=FMT   Sample format, such as `s16le` or `float32le`
+      --rate=RATE           Sample rate in Hz
+      --channels=CHANNELS   Number of channels
+      --channel-map=MAP     Channel map, such as `stereo` or `front-left,front-right`
+      --latency-msec=MSEC   Requested latency, in milliseconds
+      --process-msec=MSEC   Requested processing time per request, in milliseconds
+      --volume=VOLUME       Initial volume, such as `80%` or `-6dB`
+      --property=KEY=VALUE  Sets a property of the stream, such as `media.role=music`
+      --raw                 Raw data rather than a WAV file";
+
+/// Options of a stream.
+#[derive(Debug, Clone, Default)]
+pub struct StreamOptions {
+    /// The device to connect to, if not the default.
+    pub device: Option<String>,
+    /// The stream name, if not the default.
+    pub name: Option<String>,
+    /// The sample format, if given.
+    pub format: Option<Format>,
+    /// The sample rate, if given.
+    pub rate: Option<u32>,
+    /// The number of channels, if given.
+    pub channels: Option<u8>,
+    /// The channel map, if given.
+    pub map: Option<Map>,
+    /// The requested latency, if any.
+    pub latency: Option<MicroSeconds>,
+    /// The requested processing time, if any.
+    pub process_time: Option<MicroSeconds>,
+    /// The initial volume, if given.
+    pub volume: Option<Volume>,
+    /// Properties of the stream, as `key=value` pairs.
+    pub properties: Vec<String>,
+    /// Whether data is raw rather than a WAV file.
+    pub raw: bool,
+}
+
+impl StreamOptions {
+    /// Handles the given option if a stream one, returning whether it was.
+    pub fn handle(&mut self, args: &mut Args, name: &str, attached: Option<String>)
+        -> Result<bool, Error>
+    {
+        match name {
+            "d" | "device" => self.device = Some(args.value(name, attached)?),
+            "stream-name" => self.name = Some(args.value(name, attached)?),
+            "sample-format" => {
+                let value = args.value(name, attached)?;
+                match Format::parse(&value) {
+                    Format::Invalid => {
+                        return Err(format!("invalid sample format `{}`", value).into());
+                    },
+                    f => self.format = Some(f),
+                }
+            },
+            "rate" => self.rate = Some(number(name, &args.value(name, attached)?)?),
+            "channels" => self.channels = Some(number(name, &args.value(name, attached)?)?),
+            "channel-map" => {
+                let value = args.value(name, attached)?;
+                let map = Map::new_from_string(&value)
+                    .map_err(|_| format!("invalid channel map `{}`", value))?;
+                self.map = Some(map);
+            },
+            "latency-msec" => self.latency = Some(millis(name, &args.value(name, attached)?)?),
+            "process-msec" => {
+                self.process_time = Some(millis(name, &args.value(name, attached)?)?);
+            },
+            "volume" => {
+                self.volume = Some(volume::parse(&args.value(name, attached)?, Volume::NORMAL)?);
+            },
+            "property" => {
+                let value = args.value(name, attached)?;
+                if !value.contains('=') {
+                    return Err(format!("invalid property `{}`, expected KEY=VALUE", value).into());
+                }
+                self.properties.push(value);
+            },
+            "raw" => self.raw = true,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Gets the sample specification, as given by the options over the given base.
+    pub fn spec(&self, base: Spec) -> Result<Spec, Error> {
+        let spec = Spec {
+            format: self.format.unwrap_or(base.format),
+            rate: self.rate.unwrap_or(base.rate),
+            channels: self.channels.unwrap_or(base.channels),
+        };
+        match spec.is_valid() {
+            true => Ok(spec),
+            false => Err("invalid sample specification".into()),
+        }
+    }
+
+    /// Gets the channel map for the given sample specification, as given by the options or else
+    /// the given base, if compatible, or else the default one.
+    pub fn map(&self, spec: &Spec, base: Option<&Map>) -> Result<Map, Error> {
+        match self.map.as_ref().or(base) {
+            Some(map) if map.is_compatible_with_sample_spec(spec) => Ok(*map),
+            Some(_) if self.map.is_some() => {
+                Err("channel map does not match the number of channels".into())
+            },
+            _ => {
+                let mut map = Map::default();
+                map.init_extend(spec.channels, MapDef::WAVEEx);
+                Ok(map)
+            },
+        }
+    }
+
+    /// Gets the buffering attributes for the requested latency, along with the flags to connect
+    /// the stream with, for a playback stream if `playback`, or else a record stream.
+    ///
+    /// With no latency requested, the server defaults are used.
+    pub fn buffer_attr(&self, spec: &Spec, playback: bool) -> (Option<BufferAttr>, FlagSet) {
+        let latency = match self.latency {
+            Some(latency) => spec.usec_to_bytes(latency) as u32,
+            None => return (None, FlagSet::NOFLAGS),
+        };
+        let minreq = self.process_time.map_or(u32::MAX, |t| spec.usec_to_bytes(t) as u32);
+        let attr = match playback {
+            true => BufferAttr {
+                maxlength: u32::MAX,
+                tlength: latency,
+                prebuf: u32::MAX,
+                minreq,
+                fragsize: u32::MAX,
+            },
+            false => BufferAttr {
+                maxlength: u32::MAX,
+                tlength: u32::MAX,
+                prebuf: u32::MAX,
+                minreq: u32::MAX,
+                fragsize: latency,
+            },
+        };
+        (Some(attr), FlagSet::ADJUST_LATENCY)
+    }
+
+    /// Gets the initial volume for the given number of channels, if given.
+    pub fn channel_volumes(&self, channels: u8) -> Option<ChannelVolumes> {
+        let mut cv = ChannelVolumes::default();
+        self.volume.map(|v| *cv.set(channels, v))
+    }
+
+    /// Gets the property list of the stream, with the media name and role given defaults.
+    pub fn proplist(&self, media_name: &str, media_role: &str) -> Result<Proplist, Error> {
+        let mut proplist = Proplist::new().ok_or("failed to create proplist")?;
+        let _ = proplist.set_str(properties::MEDIA_NAME, media_name);
+        let _ = proplist.set_str(properties::MEDIA_ROLE, media_role);
+        for pair in &self.properties {
+            proplist.set_pl(pair).map_err(|_| format!("invalid property `{}`", pair))?;
+        }
+        Ok(proplist)
+    }
+}
+
+fn number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, Error> {
+    value.parse().map_err(|_| format!("invalid value `{}` for option `{}`", value, name).into())
+}
+
+fn millis(name: &str, value: &str) -> Result<MicroSeconds, Error> {
+    let ms: u64 = number(name, value)?;
+    Ok(MicroSeconds(ms.saturating_mul(1000)))
+}
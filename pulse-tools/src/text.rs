@@ -0,0 +1,141 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! Rendering of object descriptions as human readable text.
+//!
+//! Descriptions are rendered in the style of `pactl`: a heading, followed by one tab-indented
+//! `Key: value` line per member, with nested objects and arrays further indented. Member names
+//! given in `snake_case` are shown in title case, while others, such as property names, are kept
+//! as they are.
+
+use crate::json::Value;
+
+/// Renders a description under the given heading, such as `Sink #3`.
+pub fn render(heading: &str, value: &Value) -> String {
+    let mut out = String::new();
+    out.push_str(heading);
+    out.push('\n');
+    write_members(&mut out, value, 1);
+    out
+}
+
+/// Converts a `snake_case` member name to title case, leaving other names as they are.
+pub fn title(key: &str) -> String {
+    if !key.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_') {
+        return key.to_owned();
+    }
+    key.split('_').filter(|w| !w.is_empty()).map(|w| {
+        let mut chars = w.chars();
+        let first = chars.next().map(|c| c.to_ascii_uppercase());
+        first.into_iter().chain(chars).collect::<String>()
+    }).collect::<Vec<_>>().join(" ")
+}
+
+/// Renders a scalar value.
+pub fn scalar(value: &Value) -> String {
+    match value {
+        Value::Null => "n/a".to_owned(),
+        Value::Bool(true) => "yes".to_owned(),
+        Value::Bool(false) => "no".to_owned(),
+        Value::String(s) => s.clone(),
+        v => v.to_string(),
+    }
+}
+
+fn write_members(out: &mut String, value: &Value, depth: usize) {
+    match value {
+        Value::Object(members) => {
+            for (key, value) in members {
+                write_member(out, &title(key), value, depth);
+            }
+        },
+        Value::Array(items) => {
+            for item in items {
+                write_member(out, "-", item, depth);
+            }
+        },
+        v => write_line(out, depth, &scalar(v)),
+    }
+}
+
+fn write_member(out: &mut String, label: &str, value: &Value, depth: usize) {
+    match value {
+        Value::Object(members) if is_volume(value) => {
+            write_line(out, depth, &format!("{}: {}", label, inline(members)));
+        },
+        Value::Object(members) if members.is_empty() => {
+            write_line(out, depth, &format!("{}: (none)", label));
+        },
+        Value::Array(items) if items.is_empty() => {
+            write_line(out, depth, &format!("{}: (none)", label));
+        },
+        Value::Array(items) if items.iter().all(Value::is_scalar) => {
+            let items: Vec<String> = items.iter().map(scalar).collect();
+            write_line(out, depth, &format!("{}: {}", label, items.join(", ")));
+        },
+        Value::Object(_) | Value::Array(_) => {
+            write_line(out, depth, &format!("{}:", label));
+            write_members(out, value, depth + 1);
+        },
+        v if label == "-" => write_line(out, depth, &scalar(v)),
+        v => write_line(out, depth, &format!("{}: {}", label, scalar(v))),
+    }
+}
+
+/// Checks whether an object is a single volume, as produced by
+/// [`describe::single_volume()`](crate::describe::single_volume).
+fn is_volume(value: &Value) -> bool {
+    value.get("value").is_some() && value.get("percent").is_some() && value.get("db").is_some()
+}
+
+/// Renders a single volume inline, in the style of `pactl`.
+fn inline(members: &[(String, Value)]) -> String {
+    let get = |k: &str| members.iter().find(|(key, _)| key == k).map(|(_, v)| v);
+    let db = match get("db") {
+        Some(Value::Float(db)) => format!("{:.2} dB", db),
+        _ => "-inf dB".to_owned(),
+    };
+    format!("{} / {}% / {}", get("value").map_or_else(String::new, scalar),
+        get("percent").map_or_else(String::new, scalar), db)
+}
+
+fn write_line(out: &mut String, depth: usize, line: &str) {
+    for _ in 0..depth {
+        out.push('\t');
+    }
+    out.push_str(line);
+    out.push('\n');
+}
+
+#[test]
+fn render_text() {
+    let volume = Value::object().with("value", 32768u32).with("percent", 50u32)
+        .with("db", -18.06);
+    let v = Value::object()
+        .with("name", "out")
+        .with("mute", false)
+        .with("card", None::<u32>)
+        .with("volume", Value::object().with("front-left", volume))
+        .with("flags", vec!["latency", "hardware"])
+        .with("ports", Value::Array(Vec::new()))
+        .with("properties", Value::object().with("device.class", "sound"));
+    assert_eq!(render("Sink #1", &v), "Sink #1\n\
+        \tName: out\n\
+        \tMute: no\n\
+        \tCard: n/a\n\
+        \tVolume:\n\
+        \t\tfront-left: 32768 / 50% / -18.06 dB\n\
+        \tFlags: latency, hardware\n\
+        \tPorts: (none)\n\
+        \tProperties:\n\
+        \t\tdevice.class: sound\n");
+    assert_eq!(title("n_sinks"), "N Sinks");
+    assert_eq!(title("device.class"), "device.class");
+}
@@ -0,0 +1,96 @@
+// Copyright 2026 Lyndon Brown
+//
+// This file is part of the PulseAudio Rust language binding.
+//
+// Licensed under the MIT license or the Apache license (version 2.0), at your option. You may not
+// copy, modify, or distribute this file except in compliance with said license. You can find copies
+// of these licenses either in the LICENSE-MIT and LICENSE-APACHE files, or alternatively at
+// <http://opensource.org/licenses/MIT> and <http://www.apache.org/licenses/LICENSE-2.0>
+// respectively.
+
+//! Parsing of volumes given on the command line.
+//!
+//! A volume may be given as a percentage (`50%`), a decibel value (`0dB`), a linear factor (`0.5`)
+//! or a raw value (`32768`), optionally prefixed with `+` or `-` to change a current volume
+//! relatively. A leading `-` always means a relative change, such that `-6dB` lowers the current
+//! volume by 6 dB, and negative absolute decibel values cannot be given.
+
+use pulse::volume::{ChannelVolumes, Volume, VolumeDB, VolumeLinear};
+use crate::Error;
+
+/// Parses a volume, either one value for all channels or one per channel, relative to the current
+/// volume.
+pub fn parse_channels(values: &[String], current: &ChannelVolumes)
+    -> Result<ChannelVolumes, Error>
+{
+    let channels = current.len() as usize;
+    if values.len() != 1 && values.len() != channels {
+        return Err(format!("expected one volume, or one for each of {} channels", channels)
+            .into());
+    }
+    let mut volume = *current;
+    for (i, v) in volume.get_mut().iter_mut().enumerate() {
+        *v = parse(&values[i % values.len()], *v)?;
+    }
+    Ok(volume)
+}
+
+/// Parses a single volume, relative to the current one.
+pub fn parse(s: &str, current: Volume) -> Result<Volume, Error> {
+    let invalid = || format!("invalid volume `{}`", s);
+    let (sign, value) = match s.as_bytes().first() {
+        Some(b'+') => (1.0, &s[1..]),
+        Some(b'-') => (-1.0, &s[1..]),
+        _ => (0.0, s),
+    };
+    let relative = sign != 0.0;
+    let sign = if relative { sign } else { 1.0 };
+    let number = |n: &str| n.trim().parse::<f64>().map_err(|_| invalid());
+    let normal = Volume::NORMAL.0 as f64;
+    let raw = if let Some(n) = value.strip_suffix('%') {
+        let delta = sign * number(n)? / 100.0 * normal;
+        if relative { current.0 as f64 + delta } else { delta }
+    } else if let Some(n) = value.strip_suffix("dB").or_else(|| value.strip_suffix("db")) {
+        let db = sign * number(n)?;
+        let db = if relative { VolumeDB::from(current).0 + db } else { db };
+        Volume::from(VolumeDB(db)).0 as f64
+    } else if value.contains('.') {
+        let factor = sign * number(value)?;
+        let factor = if relative { VolumeLinear::from(current).0 + factor } else { factor };
+        Volume::from(VolumeLinear(factor.max(0.0))).0 as f64
+    } else {
+        let delta = sign * value.parse::<u32>().map_err(|_| invalid())? as f64;
+        if relative { current.0 as f64 + delta } else { delta }
+    };
+    if !relative && raw < 0.0 {
+        return Err(invalid().into());
+    }
+    Ok(Volume(raw.round().max(Volume::MUTED.0 as f64).min(Volume::MAX.0 as f64) as u32))
+}
+
+#[test]
+fn parsing() {
+    let normal = Volume::NORMAL;
+    let half = Volume(normal.0 / 2);
+    assert_eq!(parse("100%", half).unwrap(), normal);
+    assert_eq!(parse("50%", normal).unwrap(), half);
+    assert_eq!(parse("+50%", half).unwrap(), normal);
+    assert_eq!(parse("+50%", normal).unwrap(), Volume(normal.0 / 2 * 3));
+    assert_eq!(parse("-100%", half).unwrap(), Volume::MUTED);
+    assert_eq!(parse("0dB", half).unwrap(), normal);
+    assert_eq!(parse("-6dB", normal).unwrap(), Volume::from(VolumeDB(-6.0)));
+    assert_eq!(parse("1.0", half).unwrap(), normal);
+    assert_eq!(parse("32768", normal).unwrap(), half);
+    assert_eq!(parse("-1024", Volume(1000)).unwrap(), Volume::MUTED);
+    assert!(parse("loud", normal).is_err());
+    assert!(parse("%", normal).is_err());
+
+    let mut current = ChannelVolumes::default();
+    current.set(2, normal);
+    let both = parse_channels(&["50%".to_owned()], &current).unwrap();
+    assert_eq!(both.get(), &[half, half]);
+    let each = parse_channels(&["50%".to_owned(), "+0%".to_owned()], &current).unwrap();
+    assert_eq!(each.get(), &[half, normal]);
+    let three: Vec<String> = ["1%", "2%", "3%"].iter().map(|s| s.to_string()).collect();
+    assert!(parse_channels(&three, &current).is_err());
+}